@@ -32,6 +32,7 @@ use jj_lib::backend::Commit;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::Conflict;
 use jj_lib::backend::ConflictId;
+use jj_lib::backend::CopyDetectionOptions;
 use jj_lib::backend::CopyRecord;
 use jj_lib::backend::FileId;
 use jj_lib::backend::SigningFn;
@@ -198,8 +199,9 @@ impl Backend for JitBackend {
         paths: Option<&[RepoPathBuf]>,
         root: &CommitId,
         head: &CommitId,
+        options: &CopyDetectionOptions,
     ) -> BackendResult<BoxStream<BackendResult<CopyRecord>>> {
-        self.inner.get_copy_records(paths, root, head)
+        self.inner.get_copy_records(paths, root, head, options)
     }
 
     fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::borrow::Cow;
+use std::cell::Cell;
 use std::cell::OnceCell;
 use std::collections::BTreeMap;
 use std::collections::HashSet;
@@ -35,6 +36,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::SystemTime;
 
+use bstr::ByteSlice as _;
 use bstr::ByteVec as _;
 use chrono::TimeZone;
 use clap::builder::MapValueParser;
@@ -152,10 +154,17 @@ use crate::diff_util::DiffRenderer;
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
 use crate::formatter::PlainTextFormatter;
+use crate::git_util::get_git_import_globs;
 use crate::git_util::is_colocated_git_workspace;
+use crate::git_util::is_git_ref_in_import_scope;
+use crate::git_util::open_colocated_git_repo;
 use crate::git_util::print_failed_git_export;
 use crate::git_util::print_git_import_stats;
+use crate::git_util::print_git_worktree_link_warning;
+use crate::merge_tools::get_external_tool_config;
+use crate::merge_tools::get_git_mergetools;
 use crate::merge_tools::DiffEditor;
+use crate::merge_tools::ExternalMergeTool;
 use crate::merge_tools::MergeEditor;
 use crate::merge_tools::MergeToolConfigError;
 use crate::operation_templater::OperationTemplateLanguage;
@@ -280,6 +289,9 @@ struct CommandHelperData {
     maybe_workspace_loader: Result<Box<dyn WorkspaceLoader>, CommandError>,
     store_factories: StoreFactories,
     working_copy_factories: WorkingCopyFactories,
+    // Whether the .git file of the workspace has been checked. The workspace
+    // may be loaded more than once per command.
+    checked_git_worktree_link: Cell<bool>,
 }
 
 impl CommandHelper {
@@ -838,6 +850,10 @@ pub struct WorkspaceCommandHelper {
     op_summary_template_text: String,
     may_update_working_copy: bool,
     working_copy_shared_with_git: bool,
+    // Root of the linked Git worktree if the colocated workspace isn't the
+    // main working directory of the backing Git repo.
+    git_worktree_root: Option<PathBuf>,
+    ran_pre_commit_hook: bool,
 }
 
 impl WorkspaceCommandHelper {
@@ -856,6 +872,20 @@ impl WorkspaceCommandHelper {
         let may_update_working_copy =
             loaded_at_head && !env.command.global_args().ignore_working_copy;
         let working_copy_shared_with_git = is_colocated_git_workspace(&workspace, &repo);
+        if !working_copy_shared_with_git
+            && !env.command.data.checked_git_worktree_link.replace(true)
+        {
+            print_git_worktree_link_warning(ui, &workspace, &repo)?;
+        }
+        let git_worktree_root = repo
+            .store()
+            .backend_impl()
+            .downcast_ref::<GitBackend>()
+            .filter(|git_backend| {
+                working_copy_shared_with_git
+                    && git_backend.git_workdir() != Some(workspace.workspace_root())
+            })
+            .map(|_| workspace.workspace_root().to_owned());
         let helper = Self {
             workspace,
             user_repo: ReadonlyUserRepo::new(repo),
@@ -864,6 +894,8 @@ impl WorkspaceCommandHelper {
             op_summary_template_text,
             may_update_working_copy,
             working_copy_shared_with_git,
+            git_worktree_root,
+            ran_pre_commit_hook: false,
         };
         // Parse commit_summary template early to report error before starting
         // mutable operation.
@@ -928,8 +960,14 @@ impl WorkspaceCommandHelper {
     fn import_git_head(&mut self, ui: &Ui) -> Result<(), CommandError> {
         assert!(self.may_update_working_copy);
         let command = self.env.command.clone();
+        let workspace_id = self.workspace_id().to_owned();
+        let git_worktree_root = self.git_worktree_root.clone();
         let mut tx = self.start_transaction();
-        git::import_head(tx.repo_mut())?;
+        if let Some(worktree_root) = &git_worktree_root {
+            git::import_worktree_head(tx.repo_mut(), worktree_root, &workspace_id)?;
+        } else {
+            git::import_workspace_head(tx.repo_mut(), &workspace_id)?;
+        }
         if !tx.repo().has_changes() {
             return Ok(());
         }
@@ -945,10 +983,9 @@ impl WorkspaceCommandHelper {
         //   out yet.
 
         let mut tx = tx.into_inner();
-        let old_git_head = self.repo().view().git_head().clone();
-        let new_git_head = tx.repo().view().git_head().clone();
+        let old_git_head = self.repo().view().workspace_git_head(&workspace_id).clone();
+        let new_git_head = tx.repo().view().workspace_git_head(&workspace_id).clone();
         if let Some(new_git_head_id) = new_git_head.as_normal() {
-            let workspace_id = self.workspace_id().to_owned();
             let new_git_head_commit = tx.repo().store().get_commit(new_git_head_id)?;
             tx.repo_mut()
                 .check_out(workspace_id, command.settings(), &new_git_head_commit)?;
@@ -986,16 +1023,18 @@ impl WorkspaceCommandHelper {
     #[instrument(skip_all)]
     fn import_git_refs(&mut self, ui: &Ui) -> Result<(), CommandError> {
         let git_settings = self.settings().git_settings();
+        let import_globs = get_git_import_globs(self.settings())?;
         let mut tx = self.start_transaction();
         // Automated import shouldn't fail because of reserved remote name.
         let stats = git::import_some_refs(tx.repo_mut(), &git_settings, |ref_name| {
             !git::is_reserved_git_remote_ref(ref_name)
+                && is_git_ref_in_import_scope(&import_globs, ref_name)
         })?;
         if !tx.repo().has_changes() {
             return Ok(());
         }
 
-        print_git_import_stats(ui, tx.repo(), &stats, false)?;
+        print_git_import_stats(ui, tx.repo(), &stats, false, true)?;
         let mut tx = tx.into_inner();
         // Rebase here to show slightly different status message.
         let num_rebased = tx.repo_mut().rebase_descendants(self.settings())?;
@@ -1074,6 +1113,52 @@ impl WorkspaceCommandHelper {
         self.working_copy_shared_with_git
     }
 
+    /// Root of the linked Git worktree this workspace is colocated with, if
+    /// any.
+    pub fn git_worktree_root(&self) -> Option<&Path> {
+        self.git_worktree_root.as_deref()
+    }
+
+    /// Opens the Git repo colocated with this workspace. This is a linked
+    /// worktree of the backing repo if the workspace isn't its main working
+    /// directory.
+    pub fn open_colocated_git_repo(&self) -> Result<git2::Repository, CommandError> {
+        let git_backend = self.git_backend().unwrap();
+        Ok(open_colocated_git_repo(
+            git_backend,
+            self.git_worktree_root.as_deref(),
+        )?)
+    }
+
+    fn should_run_git_hooks(&self) -> bool {
+        self.working_copy_shared_with_git && self.settings().git_settings().run_hooks
+    }
+
+    /// Runs the `pre-commit` hook of the colocated Git repo if `git.run-hooks`
+    /// is enabled and the hook hasn't already been run by this command.
+    ///
+    /// The hook is given the working-copy commit as the staged changes. The
+    /// working copy is snapshotted again afterwards, since the hook may modify
+    /// files.
+    pub fn run_pre_commit_hook(&mut self, ui: &Ui) -> Result<(), CommandError> {
+        if !self.should_run_git_hooks() || self.ran_pre_commit_hook {
+            return Ok(());
+        }
+        let Some(wc_commit_id) = self.get_wc_commit_id() else {
+            return Ok(());
+        };
+        let wc_commit = self.repo().store().get_commit(wc_commit_id)?;
+        self.run_pre_commit_hook_with_tree(&wc_commit.tree()?)?;
+        self.maybe_snapshot(ui)
+    }
+
+    fn run_pre_commit_hook_with_tree(&mut self, tree: &MergedTree) -> Result<(), CommandError> {
+        let git_repo = self.open_colocated_git_repo()?;
+        self.ran_pre_commit_hook = true;
+        git::run_pre_commit_hook(&git_repo, tree)?;
+        Ok(())
+    }
+
     pub fn format_file_path(&self, file: &RepoPath) -> String {
         self.path_converter().format_file_path(file)
     }
@@ -1108,18 +1193,44 @@ impl WorkspaceCommandHelper {
     }
 
     /// Parses the given fileset expressions and concatenates them all.
+    ///
+    /// Like Git pathspecs, arguments prefixed with `:!` or `:^` are excluded
+    /// from the union. If there are only exclusions, they are subtracted from
+    /// all files.
     pub fn parse_union_filesets(
         &self,
         ui: &Ui,
         file_args: &[String], // TODO: introduce FileArg newtype?
     ) -> Result<FilesetExpression, CommandError> {
         let mut diagnostics = FilesetDiagnostics::new();
-        let expressions: Vec<_> = file_args
-            .iter()
-            .map(|arg| fileset::parse_maybe_bare(&mut diagnostics, arg, self.path_converter()))
-            .try_collect()?;
+        let mut includes = Vec::new();
+        let mut excludes = Vec::new();
+        for arg in file_args {
+            if let Some(text) = arg.strip_prefix(":!").or_else(|| arg.strip_prefix(":^")) {
+                excludes.push(fileset::parse_maybe_bare(
+                    &mut diagnostics,
+                    text,
+                    self.path_converter(),
+                )?);
+            } else {
+                includes.push(fileset::parse_maybe_bare(
+                    &mut diagnostics,
+                    arg,
+                    self.path_converter(),
+                )?);
+            }
+        }
         print_parse_diagnostics(ui, "In fileset expression", &diagnostics)?;
-        Ok(FilesetExpression::union_all(expressions))
+        let expression = if includes.is_empty() && !excludes.is_empty() {
+            FilesetExpression::all()
+        } else {
+            FilesetExpression::union_all(includes)
+        };
+        if excludes.is_empty() {
+            Ok(expression)
+        } else {
+            Ok(expression.difference(FilesetExpression::union_all(excludes)))
+        }
     }
 
     pub fn auto_tracking_matcher(&self, ui: &Ui) -> Result<Box<dyn Matcher>, CommandError> {
@@ -1253,6 +1364,28 @@ impl WorkspaceCommandHelper {
         ui: &Ui,
         tool_name: Option<&str>,
     ) -> Result<MergeEditor, MergeToolConfigError> {
+        let configured_editor = self
+            .settings()
+            .config()
+            .get::<CommandNameAndArgs>("ui.merge-editor")
+            .optional()?;
+        let name = match (tool_name, &configured_editor) {
+            (Some(name), _) => Some(name.to_owned()),
+            // Only a plain tool name can refer to a Git merge tool.
+            (None, Some(CommandNameAndArgs::String(name))) => Some(name.clone()),
+            (None, Some(_)) => None,
+            // Git's default merge tool is used if there's none in the jj config.
+            (None, None) => self.git_merge_tool_name(),
+        };
+        if let Some(name) = &name {
+            if get_external_tool_config(self.settings(), name)?.is_some() {
+                if configured_editor.is_none() {
+                    return MergeEditor::with_name(name, self.settings());
+                }
+            } else if let Some(tool) = self.git_mergetools().remove(name) {
+                return MergeEditor::with_external_tool(name, tool);
+            }
+        }
         if let Some(name) = tool_name {
             MergeEditor::with_name(name, self.settings())
         } else {
@@ -1260,6 +1393,29 @@ impl WorkspaceCommandHelper {
         }
     }
 
+    /// Loads merge tools from the `mergetool` section of the Git config.
+    ///
+    /// Returns an empty map unless the workspace is colocated with Git.
+    pub fn git_mergetools(&self) -> BTreeMap<String, ExternalMergeTool> {
+        match self.git_backend() {
+            Some(git_backend) if self.working_copy_shared_with_git => {
+                get_git_mergetools(&git_backend.git_repo().config_snapshot())
+            }
+            _ => BTreeMap::new(),
+        }
+    }
+
+    /// Returns the default merge tool configured by `merge.tool` in the Git
+    /// config, if the workspace is colocated with Git.
+    fn git_merge_tool_name(&self) -> Option<String> {
+        let git_backend = self
+            .git_backend()
+            .filter(|_| self.working_copy_shared_with_git)?;
+        let git_repo = git_backend.git_repo();
+        let name = git_repo.config_snapshot().string("merge.tool")?;
+        Some(name.to_str().ok()?.to_owned())
+    }
+
     pub fn resolve_single_op(&self, op_str: &str) -> Result<Operation, OpsetEvaluationError> {
         op_walk::resolve_op_with_repo(self.repo(), op_str)
     }
@@ -1545,12 +1701,6 @@ impl WorkspaceCommandHelper {
                 .map(|id| repo.store().get_commit(id))
                 .transpose()
         };
-        let repo = self.repo().clone();
-        let Some(wc_commit) = get_wc_commit(&repo)? else {
-            // If the workspace has been deleted, it's unclear what to do, so we just skip
-            // committing the working copy.
-            return Ok(());
-        };
         let base_ignores = self.base_ignores()?;
         let auto_tracking_matcher = self.auto_tracking_matcher(ui)?;
 
@@ -1558,92 +1708,117 @@ impl WorkspaceCommandHelper {
         let fsmonitor_settings = self.settings().fsmonitor_settings()?;
         let max_new_file_size = self.settings().max_new_file_size()?;
         let command = self.env.command.clone();
-        let mut locked_ws = self.workspace.start_working_copy_mutation()?;
-        let old_op_id = locked_ws.locked_wc().old_operation_id().clone();
-        let (repo, wc_commit) =
-            match check_stale_working_copy(locked_ws.locked_wc(), &wc_commit, &repo) {
-                Ok(WorkingCopyFreshness::Fresh) => (repo, wc_commit),
-                Ok(WorkingCopyFreshness::Updated(wc_operation)) => {
-                    let repo = repo.reload_at(&wc_operation)?;
-                    let wc_commit = if let Some(wc_commit) = get_wc_commit(&repo)? {
-                        wc_commit
-                    } else {
-                        return Ok(()); // The workspace has been deleted (see
-                                       // above)
-                    };
-                    (repo, wc_commit)
-                }
-                Ok(WorkingCopyFreshness::WorkingCopyStale) => {
-                    return Err(user_error_with_hint(
-                        format!(
-                            "The working copy is stale (not updated since operation {}).",
+        let run_hooks = self.should_run_git_hooks();
+        // The working copy is snapshotted again after running the pre-commit
+        // hook, which may have modified files.
+        loop {
+            let repo = self.repo().clone();
+            let Some(wc_commit) = get_wc_commit(&repo)? else {
+                // If the workspace has been deleted, it's unclear what to do, so we just skip
+                // committing the working copy.
+                return Ok(());
+            };
+            let mut locked_ws = self.workspace.start_working_copy_mutation()?;
+            let old_op_id = locked_ws.locked_wc().old_operation_id().clone();
+            let (repo, wc_commit) =
+                match check_stale_working_copy(locked_ws.locked_wc(), &wc_commit, &repo) {
+                    Ok(WorkingCopyFreshness::Fresh) => (repo, wc_commit),
+                    Ok(WorkingCopyFreshness::Updated(wc_operation)) => {
+                        let repo = repo.reload_at(&wc_operation)?;
+                        let wc_commit = if let Some(wc_commit) = get_wc_commit(&repo)? {
+                            wc_commit
+                        } else {
+                            return Ok(()); // The workspace has been deleted (see
+                                           // above)
+                        };
+                        (repo, wc_commit)
+                    }
+                    Ok(WorkingCopyFreshness::WorkingCopyStale) => {
+                        return Err(user_error_with_hint(
+                            format!(
+                                "The working copy is stale (not updated since operation {}).",
+                                short_operation_hash(&old_op_id)
+                            ),
+                            "Run `jj workspace update-stale` to update it.
+See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
+                             for more information.",
+                        ));
+                    }
+                    Ok(WorkingCopyFreshness::SiblingOperation) => {
+                        return Err(internal_error(format!(
+                            "The repo was loaded at operation {}, which seems to be a sibling of \
+                             the working copy's operation {}",
+                            short_operation_hash(repo.op_id()),
                             short_operation_hash(&old_op_id)
-                        ),
-                        "Run `jj workspace update-stale` to update it.
+                        )));
+                    }
+                    Err(OpStoreError::ObjectNotFound { .. }) => {
+                        return Err(user_error_with_hint(
+                            "Could not read working copy's operation.",
+                            "Run `jj workspace update-stale` to recover.
 See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
-                         for more information.",
-                    ));
-                }
-                Ok(WorkingCopyFreshness::SiblingOperation) => {
-                    return Err(internal_error(format!(
-                        "The repo was loaded at operation {}, which seems to be a sibling of the \
-                         working copy's operation {}",
-                        short_operation_hash(repo.op_id()),
-                        short_operation_hash(&old_op_id)
-                    )));
+                             for more information.",
+                        ));
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+            self.user_repo = ReadonlyUserRepo::new(repo);
+            let progress = crate::progress::snapshot_progress(ui);
+            let new_tree_id = locked_ws.locked_wc().snapshot(&SnapshotOptions {
+                base_ignores: base_ignores.clone(),
+                fsmonitor_settings: fsmonitor_settings.clone(),
+                progress: progress.as_ref().map(|x| x as _),
+                start_tracking_matcher: &auto_tracking_matcher,
+                max_new_file_size,
+            })?;
+            drop(progress);
+            if new_tree_id != *wc_commit.tree_id() && run_hooks && !self.ran_pre_commit_hook {
+                // The hook may run jj, so the working copy must not be locked.
+                // Nothing has been recorded yet, so the snapshot is discarded.
+                drop(locked_ws);
+                let new_tree = self.repo().store().get_root_tree(&new_tree_id)?;
+                self.run_pre_commit_hook_with_tree(&new_tree)
+                    .map_err(|err| {
+                        err.hinted(
+                            "The working copy was not snapshotted. Use --ignore-working-copy to \
+                             run the command anyway.",
+                        )
+                    })?;
+                continue;
+            }
+            if new_tree_id != *wc_commit.tree_id() {
+                let mut tx = start_repo_transaction(
+                    &self.user_repo.repo,
+                    command.settings(),
+                    command.string_args(),
+                );
+                tx.set_is_snapshot(true);
+                let mut_repo = tx.repo_mut();
+                let commit = mut_repo
+                    .rewrite_commit(command.settings(), &wc_commit)
+                    .set_tree_id(new_tree_id)
+                    .write()?;
+                mut_repo.set_wc_commit(workspace_id, commit.id().clone())?;
+
+                // Rebase descendants
+                let num_rebased = mut_repo.rebase_descendants(command.settings())?;
+                if num_rebased > 0 {
+                    writeln!(
+                        ui.status(),
+                        "Rebased {num_rebased} descendant commits onto updated working copy"
+                    )?;
                 }
-                Err(OpStoreError::ObjectNotFound { .. }) => {
-                    return Err(user_error_with_hint(
-                        "Could not read working copy's operation.",
-                        "Run `jj workspace update-stale` to recover.
-See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
-                         for more information.",
-                    ));
+
+                if self.working_copy_shared_with_git {
+                    let refs = git::export_refs(mut_repo)?;
+                    print_failed_git_export(ui, &refs)?;
                 }
-                Err(e) => return Err(e.into()),
-            };
-        self.user_repo = ReadonlyUserRepo::new(repo);
-        let progress = crate::progress::snapshot_progress(ui);
-        let new_tree_id = locked_ws.locked_wc().snapshot(&SnapshotOptions {
-            base_ignores,
-            fsmonitor_settings,
-            progress: progress.as_ref().map(|x| x as _),
-            start_tracking_matcher: &auto_tracking_matcher,
-            max_new_file_size,
-        })?;
-        drop(progress);
-        if new_tree_id != *wc_commit.tree_id() {
-            let mut tx = start_repo_transaction(
-                &self.user_repo.repo,
-                command.settings(),
-                command.string_args(),
-            );
-            tx.set_is_snapshot(true);
-            let mut_repo = tx.repo_mut();
-            let commit = mut_repo
-                .rewrite_commit(command.settings(), &wc_commit)
-                .set_tree_id(new_tree_id)
-                .write()?;
-            mut_repo.set_wc_commit(workspace_id, commit.id().clone())?;
-
-            // Rebase descendants
-            let num_rebased = mut_repo.rebase_descendants(command.settings())?;
-            if num_rebased > 0 {
-                writeln!(
-                    ui.status(),
-                    "Rebased {num_rebased} descendant commits onto updated working copy"
-                )?;
-            }
 
-            if self.working_copy_shared_with_git {
-                let refs = git::export_refs(mut_repo)?;
-                print_failed_git_export(ui, &refs)?;
+                self.user_repo = ReadonlyUserRepo::new(tx.commit("snapshot working copy"));
             }
-
-            self.user_repo = ReadonlyUserRepo::new(tx.commit("snapshot working copy"));
+            locked_ws.finish(self.user_repo.repo.op_id().clone())?;
+            return Ok(());
         }
-        locked_ws.finish(self.user_repo.repo.op_id().clone())?;
-        Ok(())
     }
 
     fn update_working_copy(
@@ -1749,10 +1924,20 @@ See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
             .map(|commit_id| tx.repo().store().get_commit(commit_id))
             .transpose()?;
 
+        let old_git_head = tx
+            .repo()
+            .view()
+            .workspace_git_head(self.workspace_id())
+            .clone();
         if self.working_copy_shared_with_git {
-            let git_repo = self.git_backend().unwrap().open_git_repo()?;
+            let git_repo = self.open_colocated_git_repo()?;
             if let Some(wc_commit) = &maybe_new_wc_commit {
-                git::reset_head(tx.repo_mut(), &git_repo, wc_commit)?;
+                git::reset_workspace_head(
+                    tx.repo_mut(),
+                    &git_repo,
+                    self.workspace_id(),
+                    wc_commit,
+                )?;
             }
             let refs = git::export_refs(tx.repo_mut())?;
             print_failed_git_export(ui, &refs)?;
@@ -1770,6 +1955,29 @@ See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
                 // It seems the workspace was deleted, so we shouldn't try to
                 // update it.
             }
+            // Rewriting the working-copy commit (e.g. by `jj describe`)
+            // doesn't check out anything, so only run the hook if the files or
+            // Git HEAD changed.
+            let new_git_head = self
+                .repo()
+                .view()
+                .workspace_git_head(self.workspace_id())
+                .clone();
+            let is_checkout = match (&maybe_old_wc_commit, &maybe_new_wc_commit) {
+                (Some(old_commit), Some(new_commit)) => {
+                    old_commit.tree_id() != new_commit.tree_id() || old_git_head != new_git_head
+                }
+                (None, Some(_)) => true,
+                (_, None) => false,
+            };
+            if self.should_run_git_hooks() && is_checkout {
+                let git_repo = self.open_colocated_git_repo()?;
+                git::run_post_checkout_hook(
+                    &git_repo,
+                    old_git_head.as_normal(),
+                    new_git_head.as_normal(),
+                )?;
+            }
         }
 
         self.report_repo_changes(ui, &old_repo)?;
@@ -3366,6 +3574,7 @@ impl CliRunner {
             maybe_workspace_loader,
             store_factories: self.store_factories,
             working_copy_factories: self.working_copy_factories,
+            checked_git_worktree_link: Cell::new(false),
         };
         let command_helper = CommandHelper {
             data: Rc::new(command_helper_data),
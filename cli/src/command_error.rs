@@ -28,6 +28,7 @@ use jj_lib::fileset::FilesetParseError;
 use jj_lib::fileset::FilesetParseErrorKind;
 use jj_lib::git::GitConfigParseError;
 use jj_lib::git::GitExportError;
+use jj_lib::git::GitHookError;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitRemoteManagementError;
 use jj_lib::gitignore::GitIgnoreError;
@@ -60,6 +61,7 @@ use crate::diff_util::DiffRenderError;
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
 use crate::merge_tools::ConflictResolveError;
+use crate::merge_tools::DiffCheckoutError;
 use crate::merge_tools::DiffEditError;
 use crate::merge_tools::MergeToolConfigError;
 use crate::revset_util::UserRevsetEvaluationError;
@@ -401,6 +403,12 @@ impl From<DiffEditError> for CommandError {
     }
 }
 
+impl From<DiffCheckoutError> for CommandError {
+    fn from(err: DiffCheckoutError) -> Self {
+        user_error_with_message("Failed to write trees", err)
+    }
+}
+
 impl From<DiffRenderError> for CommandError {
     fn from(err: DiffRenderError) -> Self {
         match err {
@@ -474,6 +482,15 @@ impl From<GitExportError> for CommandError {
     }
 }
 
+impl From<GitHookError> for CommandError {
+    fn from(err: GitHookError) -> Self {
+        match err {
+            GitHookError::InternalGitError(err) => err.into(),
+            _ => user_error(err),
+        }
+    }
+}
+
 impl From<GitRemoteManagementError> for CommandError {
     fn from(err: GitRemoteManagementError) -> Self {
         user_error(err)
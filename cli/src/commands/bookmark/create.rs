@@ -13,14 +13,20 @@
 // limitations under the License.
 
 use clap::builder::NonEmptyStringValueParser;
+use jj_lib::git;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RemoteRefState;
+use jj_lib::repo::Repo as _;
+use jj_lib::str_util::StringPattern;
 
 use super::has_tracked_remote_bookmarks;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
+use crate::git_util::get_git_repo;
 use crate::ui::Ui;
 
 /// Create a new bookmark
@@ -36,6 +42,15 @@ pub struct BookmarkCreateArgs {
     /// The bookmarks to create
     #[arg(required = true, value_parser = NonEmptyStringValueParser::new())]
     names: Vec<String>,
+
+    /// Track the bookmarks of the same names on this remote
+    ///
+    /// Existing remote bookmarks are tracked without moving the new local
+    /// bookmarks. Remote bookmarks that don't exist yet aren't tracked, since
+    /// there's nothing to track. They will be tracked once the bookmarks are
+    /// pushed with `jj git push --bookmark`.
+    #[arg(long, value_name = "REMOTE")]
+    tracking: Option<String>,
 }
 
 pub fn cmd_bookmark_create(
@@ -48,6 +63,18 @@ pub fn cmd_bookmark_create(
         .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
     let view = workspace_command.repo().view();
     let bookmark_names = &args.names;
+    if let Some(remote_name) = &args.tracking {
+        if remote_name == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+            return Err(user_error(format!(
+                "Bookmarks of the {} remote can't be tracked",
+                git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+            )));
+        }
+        let git_repo = get_git_repo(workspace_command.repo().store())?;
+        if git_repo.find_remote(remote_name).is_err() {
+            return Err(user_error(format!("No git remote named '{remote_name}'")));
+        }
+    }
     for name in bookmark_names {
         if view.get_local_bookmark(name).is_present() {
             return Err(user_error_with_hint(
@@ -66,6 +93,23 @@ pub fn cmd_bookmark_create(
         }
     }
 
+    // Untracked remote bookmarks of the same names, which the user might want
+    // to associate with the new local bookmarks.
+    let mut untracked_remote_bookmarks = vec![];
+    if args.tracking.is_none() {
+        for name in bookmark_names {
+            let bookmark_pattern = StringPattern::exact(name);
+            untracked_remote_bookmarks.extend(
+                view.remote_bookmarks_matching(&bookmark_pattern, &StringPattern::everything())
+                    .filter(|&((_, remote_name), _)| {
+                        remote_name != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                    })
+                    .filter(|(_, remote_ref)| remote_ref.is_present() && !remote_ref.is_tracking())
+                    .map(|((name, remote_name), _)| (name.to_owned(), remote_name.to_owned())),
+            );
+        }
+    }
+
     let mut tx = workspace_command.start_transaction();
     for bookmark_name in bookmark_names {
         tx.repo_mut().set_local_bookmark_target(
@@ -86,6 +130,43 @@ pub fn cmd_bookmark_create(
     if bookmark_names.len() > 1 && args.revision.is_none() {
         writeln!(ui.hint_default(), "Use -r to specify the target revision.")?;
     }
+    for (bookmark_name, remote_name) in &untracked_remote_bookmarks {
+        writeln!(
+            ui.warning_default(),
+            "Remote bookmark {bookmark_name}@{remote_name} exists but is not tracked."
+        )?;
+        writeln!(
+            ui.hint_default(),
+            "Run `jj bookmark track {bookmark_name}@{remote_name}` to track it."
+        )?;
+    }
+    if let Some(remote_name) = &args.tracking {
+        let mut num_tracked = 0;
+        for bookmark_name in bookmark_names {
+            let mut remote_ref = tx.repo().get_remote_bookmark(bookmark_name, remote_name);
+            if remote_ref.is_present() {
+                // Unlike `MutableRepo::track_remote_bookmark()`, the remote
+                // target isn't merged into the new local bookmark.
+                remote_ref.state = RemoteRefState::Tracking;
+                tx.repo_mut()
+                    .set_remote_bookmark(bookmark_name, remote_name, remote_ref);
+                num_tracked += 1;
+            } else {
+                writeln!(
+                    ui.hint_default(),
+                    "Remote bookmark {bookmark_name}@{remote_name} doesn't exist yet. Run `jj git \
+                     push --remote {remote_name} --bookmark {bookmark_name}` to create and track \
+                     it."
+                )?;
+            }
+        }
+        if num_tracked > 0 {
+            writeln!(
+                ui.status(),
+                "Started tracking {num_tracked} remote bookmarks."
+            )?;
+        }
+    }
 
     tx.finish(
         ui,
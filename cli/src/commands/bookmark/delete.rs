@@ -13,11 +13,16 @@
 // limitations under the License.
 
 use itertools::Itertools as _;
+use jj_lib::git;
 use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RemoteRef;
 use jj_lib::str_util::StringPattern;
 
 use super::find_local_bookmarks;
+use super::find_remote_bookmarks;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RemoteBookmarkNamePattern;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -32,6 +37,14 @@ pub struct BookmarkDeleteArgs {
     /// https://martinvonz.github.io/jj/latest/revsets/#string-patterns.       
     #[arg(required = true, value_parser = StringPattern::parse)]
     names: Vec<StringPattern>,
+    /// Only delete the remote bookmarks of the given remote
+    ///
+    /// The matching `<name>@<remote>` entries are removed from the repo, but
+    /// the local bookmarks and the commits they point to are left untouched.
+    /// The remote bookmarks will be recreated on future fetches if they still
+    /// exist on the remote.
+    #[arg(long)]
+    remote: Option<String>,
 }
 
 pub fn cmd_bookmark_delete(
@@ -39,6 +52,9 @@ pub fn cmd_bookmark_delete(
     command: &CommandHelper,
     args: &BookmarkDeleteArgs,
 ) -> Result<(), CommandError> {
+    if let Some(remote) = &args.remote {
+        return delete_remote_bookmarks(ui, command, &args.names, remote);
+    }
     let mut workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo().clone();
     let matched_bookmarks = find_local_bookmarks(repo.view(), &args.names)?;
@@ -61,3 +77,44 @@ pub fn cmd_bookmark_delete(
     )?;
     Ok(())
 }
+
+fn delete_remote_bookmarks(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    bookmark_patterns: &[StringPattern],
+    remote: &str,
+) -> Result<(), CommandError> {
+    if remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        return Err(user_error(format!(
+            "Git-tracking bookmarks cannot be deleted with --remote {remote}"
+        )));
+    }
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().clone();
+    let name_patterns = bookmark_patterns
+        .iter()
+        .map(|pattern| RemoteBookmarkNamePattern {
+            bookmark: pattern.clone(),
+            remote: StringPattern::exact(remote),
+        })
+        .collect_vec();
+    let matched_bookmarks = find_remote_bookmarks(repo.view(), &name_patterns)?;
+    let mut tx = workspace_command.start_transaction();
+    for (name, _) in &matched_bookmarks {
+        tx.repo_mut()
+            .set_remote_bookmark(&name.bookmark, &name.remote, RemoteRef::absent());
+    }
+    writeln!(
+        ui.status(),
+        "Deleted {} remote bookmarks.",
+        matched_bookmarks.len()
+    )?;
+    tx.finish(
+        ui,
+        format!(
+            "delete remote bookmark {}",
+            matched_bookmarks.iter().map(|(name, _)| name).join(", ")
+        ),
+    )?;
+    Ok(())
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use itertools::Itertools as _;
+use jj_lib::git;
 use jj_lib::op_store::BookmarkTarget;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
@@ -31,6 +32,12 @@ use crate::ui::Ui;
 /// recreated on future pulls if it still exists in the remote.
 #[derive(clap::Args, Clone, Debug)]
 pub struct BookmarkForgetArgs {
+    /// Report each remote bookmark that is forgotten
+    ///
+    /// The corresponding remote bookmarks are always forgotten along with the
+    /// local bookmark. This flag only adds a line of output for each of them.
+    #[arg(long)]
+    include_remotes: bool,
     /// The bookmarks to forget
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
@@ -49,15 +56,24 @@ pub fn cmd_bookmark_forget(
     let repo = workspace_command.repo().clone();
     let matched_bookmarks = find_forgettable_bookmarks(repo.view(), &args.names)?;
     let mut tx = workspace_command.start_transaction();
+    let mut forgotten_remotes = Vec::new();
     for (name, bookmark_target) in &matched_bookmarks {
         tx.repo_mut()
             .set_local_bookmark_target(name, RefTarget::absent());
         for (remote_name, _) in &bookmark_target.remote_refs {
             tx.repo_mut()
                 .set_remote_bookmark(name, remote_name, RemoteRef::absent());
+            if *remote_name != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+                forgotten_remotes.push(format!("{name}@{remote_name}"));
+            }
         }
     }
     writeln!(ui.status(), "Forgot {} bookmarks.", matched_bookmarks.len())?;
+    if args.include_remotes {
+        for name in &forgotten_remotes {
+            writeln!(ui.status(), "Forgot remote bookmark {name}")?;
+        }
+    }
     tx.finish(
         ui,
         format!(
@@ -16,6 +16,7 @@ use std::collections::HashSet;
 
 use itertools::Itertools;
 use jj_lib::git;
+use jj_lib::op_store::RefTarget;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::str_util::StringPattern;
 
@@ -67,6 +68,16 @@ pub struct BookmarkListArgs {
     #[arg(long, short)]
     revisions: Vec<RevisionArg>,
 
+    /// Show only bookmarks, local or remote, whose targets are in the given
+    /// revisions
+    ///
+    /// Unlike `--revisions`, this is a filter applied on top of the other
+    /// options, and remote bookmarks are matched by their own targets. A
+    /// remote bookmark that matches is listed under its local bookmark even if
+    /// the local target doesn't match.
+    #[arg(long, value_name = "REVSETS")]
+    points_at: Vec<RevisionArg>,
+
     /// Render each bookmark using the given template
     ///
     /// All 0-argument methods of the `RefName` type are available as keywords.
@@ -116,6 +127,27 @@ pub fn cmd_bookmark_list(
         None
     };
 
+    let points_at_targets: Option<HashSet<_>> = if args.points_at.is_empty() {
+        None
+    } else {
+        let mut expression = workspace_command.parse_union_revsets(ui, &args.points_at)?;
+        expression.intersect_with(
+            &RevsetExpression::bookmarks(StringPattern::everything()).union(
+                &RevsetExpression::remote_bookmarks(
+                    StringPattern::everything(),
+                    StringPattern::everything(),
+                    None,
+                ),
+            ),
+        );
+        Some(expression.evaluate_to_commit_ids()?.try_collect()?)
+    };
+    let points_at = |target: &RefTarget| {
+        points_at_targets
+            .as_ref()
+            .map_or(true, |ids| target.added_ids().any(|id| ids.contains(id)))
+    };
+
     let template = {
         let language = workspace_command.commit_template_language();
         let text = match &args.template {
@@ -144,6 +176,7 @@ pub fn cmd_bookmark_list(
         let (mut tracking_remote_refs, untracked_remote_refs) = remote_refs
             .iter()
             .copied()
+            .filter(|&(_, remote_ref)| points_at(&remote_ref.target))
             .partition::<Vec<_>, _>(|&(_, remote_ref)| remote_ref.is_tracking());
 
         if args.tracked {
@@ -153,7 +186,9 @@ pub fn cmd_bookmark_list(
             tracking_remote_refs.retain(|&(_, remote_ref)| remote_ref.target != *local_target);
         }
 
-        if !args.tracked && local_target.is_present() || !tracking_remote_refs.is_empty() {
+        if !args.tracked && local_target.is_present() && points_at(local_target)
+            || !tracking_remote_refs.is_empty()
+        {
             let ref_name = RefName::local(
                 name,
                 local_target.clone(),
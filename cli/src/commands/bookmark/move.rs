@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use itertools::Itertools as _;
+use jj_lib::git;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
 use jj_lib::str_util::StringPattern;
@@ -57,6 +58,17 @@ pub struct BookmarkMoveArgs {
     #[arg(long, short = 'B')]
     allow_backwards: bool,
 
+    /// Also move the tracked remote bookmarks
+    ///
+    /// The remote bookmarks are recorded as pointing to the target revision,
+    /// as if the bookmarks had been pushed. The next `jj git push` uses the
+    /// target revision as the expected location of the bookmarks on the
+    /// remote, like `git push --force-with-lease`. Use this if the bookmarks
+    /// were already moved on the remote by other means. If they weren't, the
+    /// next push will be rejected.
+    #[arg(long)]
+    expand: bool,
+
     /// Move bookmarks matching the given name patterns
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
@@ -139,12 +151,35 @@ pub fn cmd_bookmark_move(
         tx.repo_mut()
             .set_local_bookmark_target(name, RefTarget::normal(target_commit.id().clone()));
     }
+    let mut moved_remote_bookmarks = vec![];
+    if args.expand {
+        let tracked_remote_bookmarks = repo
+            .view()
+            .all_remote_bookmarks()
+            .filter(|((name, remote), remote_ref)| {
+                *remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                    && remote_ref.is_tracking()
+                    && matched_bookmarks.iter().any(|(matched, _)| matched == name)
+            })
+            .map(|((name, remote), _)| (name, remote));
+        for (name, remote) in tracked_remote_bookmarks {
+            git::set_expected_remote_bookmark(tx.repo_mut(), remote, name, target_commit.id());
+            moved_remote_bookmarks.push(format!("{name}@{remote}"));
+        }
+    }
 
     if let Some(mut formatter) = ui.status_formatter() {
         write!(formatter, "Moved {} bookmarks to ", matched_bookmarks.len())?;
         tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
         writeln!(formatter)?;
     }
+    if !moved_remote_bookmarks.is_empty() {
+        writeln!(
+            ui.status(),
+            "Also moved remote bookmarks: {}",
+            moved_remote_bookmarks.join(", ")
+        )?;
+    }
     if matched_bookmarks.len() > 1 && args.names.is_empty() {
         writeln!(
             ui.hint_default(),
@@ -19,6 +19,7 @@ use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
@@ -62,6 +63,11 @@ pub(crate) struct CommitArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Commit even if the change has no modifications
+    ///
+    /// This overrides the `ui.refuse-empty-commit` setting.
+    #[arg(long, alias = "no-empty-check")]
+    allow_empty: bool,
 }
 
 #[instrument(skip_all)]
@@ -71,7 +77,14 @@ pub(crate) fn cmd_commit(
     args: &CommitArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
+    let refuse_empty = !args.allow_empty
+        && args.message_paragraphs.is_empty()
+        && command
+            .settings()
+            .config()
+            .get_bool("ui.refuse-empty-commit")?;
 
+    workspace_command.run_pre_commit_hook(ui)?;
     let commit_id = workspace_command
         .get_wc_commit_id()
         .ok_or_else(|| user_error("This command requires a working copy"))?;
@@ -109,6 +122,12 @@ new working-copy commit.
             args.paths.join(" ")
         )?;
     }
+    if refuse_empty && tree_id == base_tree.id() {
+        return Err(user_error_with_hint(
+            "Refusing to commit a change without modifications",
+            "Use --allow-empty to commit it anyway, or pass a message with -m.",
+        ));
+    }
 
     let mut commit_builder = tx
         .repo_mut()
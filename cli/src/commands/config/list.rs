@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+
 use tracing::instrument;
 
 use super::ConfigLevelArgs;
@@ -38,9 +40,15 @@ pub struct ConfigListArgs {
     /// Allow printing overridden values.
     #[arg(long)]
     pub include_overridden: bool,
+    /// Show the origin of each value
+    ///
+    /// Values read from config files are prefixed with `file:` and the file
+    /// path. Other values are prefixed with `default`, `env`, or
+    /// `command-line`.
+    #[arg(long)]
+    pub show_origin: bool,
     #[command(flatten)]
     pub level: ConfigLevelArgs,
-    // TODO(#1047): Support --show-origin using LayeredConfigs.
     /// Render each variable using the given template
     ///
     /// The following keywords are defined:
@@ -95,6 +103,9 @@ pub fn cmd_config_list(
             continue;
         }
 
+        if args.show_origin {
+            write!(formatter, "{}\t", format_origin(&annotated))?;
+        }
         template.format(&annotated, formatter.as_mut())?;
         wrote_values = true;
     }
@@ -110,6 +121,16 @@ pub fn cmd_config_list(
     Ok(())
 }
 
+fn format_origin(annotated: &AnnotatedValue) -> String {
+    match (&annotated.source, &annotated.origin) {
+        (_, Some(path)) => format!("file:{}", path.display()),
+        (ConfigSource::Default, None) => "default".to_owned(),
+        (ConfigSource::Env, None) => "env".to_owned(),
+        (ConfigSource::User | ConfigSource::Repo, None) => "file".to_owned(),
+        (ConfigSource::CommandArg, None) => "command-line".to_owned(),
+    }
+}
+
 // AnnotatedValue will be cloned internally in the templater. If the cloning
 // cost matters, wrap it with Rc.
 fn config_template_language() -> GenericTemplateLanguage<'static, AnnotatedValue> {
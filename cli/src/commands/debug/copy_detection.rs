@@ -17,6 +17,7 @@ use std::io::Write as _;
 
 use futures::executor::block_on_stream;
 use jj_lib::backend::Backend;
+use jj_lib::backend::CopyDetectionOptions;
 use jj_lib::backend::CopyRecord;
 
 use crate::cli_util::CommandHelper;
@@ -44,9 +45,13 @@ pub fn cmd_debug_copy_detection(
     };
     let commit = ws.resolve_single_rev(ui, &args.revision)?;
     for parent_id in commit.parent_ids() {
-        for CopyRecord { target, source, .. } in
-            block_on_stream(git.get_copy_records(None, parent_id, commit.id())?)
-                .filter_map(|r| r.ok())
+        for CopyRecord { target, source, .. } in block_on_stream(git.get_copy_records(
+            None,
+            parent_id,
+            commit.id(),
+            &CopyDetectionOptions::default(),
+        )?)
+        .filter_map(|r| r.ok())
         {
             writeln!(
                 ui.stdout(),
@@ -16,6 +16,7 @@ pub mod copy_detection;
 pub mod fileset;
 pub mod index;
 pub mod local_working_copy;
+pub mod op_view;
 pub mod operation;
 pub mod reindex;
 pub mod revset;
@@ -39,6 +40,8 @@ use self::index::cmd_debug_index;
 use self::index::DebugIndexArgs;
 use self::local_working_copy::cmd_debug_local_working_copy;
 use self::local_working_copy::DebugLocalWorkingCopyArgs;
+use self::op_view::cmd_debug_op_view;
+use self::op_view::DebugOpViewArgs;
 use self::operation::cmd_debug_operation;
 use self::operation::DebugOperationArgs;
 use self::reindex::cmd_debug_reindex;
@@ -68,6 +71,7 @@ pub enum DebugCommand {
     Fileset(DebugFilesetArgs),
     Index(DebugIndexArgs),
     LocalWorkingCopy(DebugLocalWorkingCopyArgs),
+    OpView(DebugOpViewArgs),
     #[command(visible_alias = "view")]
     Operation(DebugOperationArgs),
     Reindex(DebugReindexArgs),
@@ -89,6 +93,7 @@ pub fn cmd_debug(
         DebugCommand::Fileset(args) => cmd_debug_fileset(ui, command, args),
         DebugCommand::Index(args) => cmd_debug_index(ui, command, args),
         DebugCommand::LocalWorkingCopy(args) => cmd_debug_local_working_copy(ui, command, args),
+        DebugCommand::OpView(args) => cmd_debug_op_view(ui, command, args),
         DebugCommand::Operation(args) => cmd_debug_operation(ui, command, args),
         DebugCommand::Reindex(args) => cmd_debug_reindex(ui, command, args),
         DebugCommand::CopyDetection(args) => cmd_debug_copy_detection(ui, command, args),
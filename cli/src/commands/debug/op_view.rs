@@ -0,0 +1,196 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::io::Write as _;
+
+use itertools::Itertools as _;
+use jj_lib::object_id::ObjectId;
+use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RemoteRefState;
+use jj_lib::op_store::View;
+use jj_lib::op_walk;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Show the full view (heads, bookmarks, refs, working copies) at an operation
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugOpViewArgs {
+    #[arg(default_value = "@")]
+    operation: String,
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OpViewFormat,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OpViewFormat {
+    /// Human-readable listing
+    Text,
+    /// Machine-readable JSON object
+    Json,
+}
+
+pub fn cmd_debug_op_view(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugOpViewArgs,
+) -> Result<(), CommandError> {
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op = op_walk::resolve_op_for_load(repo_loader, &args.operation)?;
+    let view = op.view()?;
+    let view = view.store_view();
+    let mut formatter = ui.stdout_formatter();
+    match args.format {
+        OpViewFormat::Text => {
+            writeln!(formatter, "Operation: {}", op.id().hex())?;
+            write_view_text(formatter.as_mut(), view)?;
+        }
+        OpViewFormat::Json => {
+            let mut value = view_to_json(view);
+            value["operation"] = op.id().hex().into();
+            serde_json::to_writer_pretty(formatter.as_mut(), &value).map_err(io::Error::from)?;
+            writeln!(formatter)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_view_text(out: &mut dyn io::Write, view: &View) -> io::Result<()> {
+    writeln!(out, "Heads:")?;
+    for id in view.head_ids.iter().sorted() {
+        writeln!(out, "  {}", id.hex())?;
+    }
+    writeln!(out, "Working copies:")?;
+    for (workspace_id, id) in view.wc_commit_ids.iter().sorted() {
+        writeln!(out, "  {}: {}", workspace_id.as_str(), id.hex())?;
+    }
+    writeln!(out, "Bookmarks:")?;
+    for (name, target) in &view.local_bookmarks {
+        writeln!(out, "  {name}: {}", ref_target_to_text(target))?;
+    }
+    writeln!(out, "Remote bookmarks:")?;
+    for (remote_name, remote_view) in &view.remote_views {
+        for (name, remote_ref) in &remote_view.bookmarks {
+            writeln!(
+                out,
+                "  {name}@{remote_name}: {} ({})",
+                ref_target_to_text(&remote_ref.target),
+                remote_ref_state_name(remote_ref.state)
+            )?;
+        }
+    }
+    writeln!(out, "Tags:")?;
+    for (name, target) in &view.tags {
+        writeln!(out, "  {name}: {}", ref_target_to_text(target))?;
+    }
+    writeln!(out, "Git refs:")?;
+    for (name, target) in &view.git_refs {
+        writeln!(out, "  {name}: {}", ref_target_to_text(target))?;
+    }
+    writeln!(out, "Git HEADs:")?;
+    for (workspace_id, target) in &view.git_heads {
+        writeln!(
+            out,
+            "  {}: {}",
+            workspace_id.as_str(),
+            ref_target_to_text(target)
+        )?;
+    }
+    Ok(())
+}
+
+fn ref_target_to_text(target: &RefTarget) -> String {
+    if target.is_absent() {
+        "(absent)".to_owned()
+    } else if let Some(id) = target.as_normal() {
+        id.hex()
+    } else {
+        let removes = target.removed_ids().map(|id| format!("-{}", id.hex()));
+        let adds = target.added_ids().map(|id| format!("+{}", id.hex()));
+        format!("conflict({})", removes.chain(adds).join(" "))
+    }
+}
+
+fn view_to_json(view: &View) -> serde_json::Value {
+    let ref_targets_to_json = |targets: &BTreeMap<String, RefTarget>| {
+        targets
+            .iter()
+            .map(|(name, target)| (name.clone(), ref_target_to_json(target)))
+            .collect::<serde_json::Map<_, _>>()
+    };
+    let remote_bookmarks = view
+        .remote_views
+        .iter()
+        .map(|(remote_name, remote_view)| {
+            let bookmarks = remote_view
+                .bookmarks
+                .iter()
+                .map(|(name, remote_ref)| {
+                    let value = serde_json::json!({
+                        "target": ref_target_to_json(&remote_ref.target),
+                        "state": remote_ref_state_name(remote_ref.state),
+                    });
+                    (name.clone(), value)
+                })
+                .collect::<serde_json::Map<_, _>>();
+            (remote_name.clone(), bookmarks.into())
+        })
+        .collect::<serde_json::Map<_, _>>();
+    serde_json::json!({
+        "heads": view.head_ids.iter().map(|id| id.hex()).sorted().collect_vec(),
+        "working_copies": view
+            .wc_commit_ids
+            .iter()
+            .map(|(workspace_id, id)| (workspace_id.as_str().to_owned(), id.hex().into()))
+            .collect::<serde_json::Map<_, _>>(),
+        "bookmarks": ref_targets_to_json(&view.local_bookmarks),
+        "remote_bookmarks": remote_bookmarks,
+        "tags": ref_targets_to_json(&view.tags),
+        "git_refs": ref_targets_to_json(&view.git_refs),
+        "git_heads": view
+            .git_heads
+            .iter()
+            .map(|(workspace_id, target)| {
+                (workspace_id.as_str().to_owned(), ref_target_to_json(target))
+            })
+            .collect::<serde_json::Map<_, _>>(),
+    })
+}
+
+/// Absent target is `null`, normal target is the commit ID, and conflicted
+/// target is an object of removed and added commit IDs.
+fn ref_target_to_json(target: &RefTarget) -> serde_json::Value {
+    if target.is_absent() {
+        serde_json::Value::Null
+    } else if let Some(id) = target.as_normal() {
+        id.hex().into()
+    } else {
+        serde_json::json!({
+            "removes": target.removed_ids().map(|id| id.hex()).collect_vec(),
+            "adds": target.added_ids().map(|id| id.hex()).collect_vec(),
+        })
+    }
+}
+
+fn remote_ref_state_name(state: RemoteRefState) -> &'static str {
+    match state {
+        RemoteRefState::New => "new",
+        RemoteRefState::Tracking => "tracking",
+    }
+}
@@ -17,7 +17,9 @@ use std::io::Write as _;
 
 use jj_lib::default_index::AsCompositeIndex as _;
 use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::default_index::DefaultReadonlyIndex;
 
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::command_error::internal_error;
 use crate::command_error::user_error;
@@ -25,6 +27,10 @@ use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// Rebuild commit index
+///
+/// The index is rebuilt from scratch from the operation log and the commit
+/// store. This is safe to run at any time. If the rebuilt index differs from
+/// the previous one, the fixed inconsistencies are reported.
 #[derive(clap::Args, Clone, Debug)]
 pub struct DebugReindexArgs {}
 
@@ -40,15 +46,61 @@ pub fn cmd_debug_reindex(
     let op = command.resolve_operation(ui, repo_loader)?;
     let index_store = repo_loader.index_store();
     if let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() {
+        // Remember the stats of the current index to detect inconsistencies
+        // fixed by reindexing. The current index might not even be loadable.
+        let old_stats = index_store
+            .get_index_at_op(&op, repo_loader.store())
+            .ok()
+            .and_then(|index| {
+                let index = index.as_any().downcast_ref::<DefaultReadonlyIndex>()?;
+                Some(index.as_composite().stats())
+            });
+        writeln!(
+            ui.status(),
+            "Reindexing commits at operation {}...",
+            short_operation_hash(op.id())
+        )?;
         default_index_store.reinit().map_err(internal_error)?;
         let default_index = default_index_store
             .build_index_at_operation(&op, repo_loader.store())
             .map_err(internal_error)?;
+        let new_stats = default_index.as_composite().stats();
         writeln!(
             ui.status(),
             "Finished indexing {:?} commits.",
-            default_index.as_composite().stats().num_commits
+            new_stats.num_commits
         )?;
+        match old_stats {
+            Some(old_stats)
+                if (
+                    old_stats.num_commits,
+                    old_stats.num_changes,
+                    old_stats.num_heads,
+                ) == (
+                    new_stats.num_commits,
+                    new_stats.num_changes,
+                    new_stats.num_heads,
+                ) => {}
+            Some(old_stats) => {
+                writeln!(
+                    ui.status(),
+                    "Fixed inconsistencies in the index: it had {} commits, {} changes, and {} \
+                     heads; it now has {} commits, {} changes, and {} heads.",
+                    old_stats.num_commits,
+                    old_stats.num_changes,
+                    old_stats.num_heads,
+                    new_stats.num_commits,
+                    new_stats.num_changes,
+                    new_stats.num_heads
+                )?;
+            }
+            None => {
+                writeln!(
+                    ui.status(),
+                    "Replaced the previous index, which couldn't be loaded."
+                )?;
+            }
+        }
     } else {
         return Err(user_error(format!(
             "Cannot reindex indexes of type '{}'",
@@ -14,6 +14,14 @@
 
 use std::fmt::Debug;
 use std::io::Write as _;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use jj_lib::fsmonitor::FsmonitorSettings;
+use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::working_copy::SnapshotOptions;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -21,17 +29,68 @@ use crate::ui::Ui;
 
 /// Show information about the working copy state
 #[derive(clap::Args, Clone, Debug)]
-pub struct DebugWorkingCopyArgs {}
+pub struct DebugWorkingCopyArgs {
+    /// Also time a snapshot of the working copy
+    ///
+    /// The snapshot isn't recorded, so the working-copy commit and the
+    /// working-copy state are left unchanged. This helps to diagnose slow
+    /// snapshots, e.g. whether the filesystem monitor reduces the number of
+    /// files that have to be scanned.
+    #[arg(long)]
+    snapshot: bool,
+}
 
 pub fn cmd_debug_working_copy(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &DebugWorkingCopyArgs,
+    args: &DebugWorkingCopyArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper_no_snapshot(ui)?;
     let wc = workspace_command.working_copy();
+    let fsmonitor_settings = command.settings().fsmonitor_settings()?;
     writeln!(ui.stdout(), "Type: {:?}", wc.name())?;
     writeln!(ui.stdout(), "Current operation: {:?}", wc.operation_id())?;
     writeln!(ui.stdout(), "Current tree: {:?}", wc.tree_id()?)?;
+    let fsmonitor_name = match &fsmonitor_settings {
+        FsmonitorSettings::Watchman(_) => "watchman",
+        FsmonitorSettings::Test { .. } => "test",
+        FsmonitorSettings::None => "none",
+    };
+    writeln!(ui.stdout(), "Fsmonitor: {fsmonitor_name}")?;
+    if let Some(wc) = wc.as_any().downcast_ref::<LocalWorkingCopy>() {
+        writeln!(
+            ui.stdout(),
+            "Tracked files: {}",
+            wc.file_states()?.paths().len()
+        )?;
+    }
+    if args.snapshot {
+        let base_ignores = workspace_command.base_ignores()?;
+        let auto_tracking_matcher = workspace_command.auto_tracking_matcher(ui)?;
+        let max_new_file_size = command.settings().max_new_file_size()?;
+        let scanned_files = AtomicUsize::new(0);
+        let progress = |_: &RepoPath| {
+            scanned_files.fetch_add(1, Ordering::Relaxed);
+        };
+        // The lock is released without finishing the mutation, so nothing is
+        // recorded.
+        let mut locked_wc = wc.start_mutation()?;
+        let start_time = Instant::now();
+        locked_wc.snapshot(&SnapshotOptions {
+            base_ignores,
+            fsmonitor_settings,
+            progress: Some(&progress),
+            start_tracking_matcher: &auto_tracking_matcher,
+            max_new_file_size,
+        })?;
+        let duration = start_time.elapsed();
+        drop(locked_wc);
+        writeln!(ui.stdout(), "Snapshot duration: {duration:?}")?;
+        writeln!(
+            ui.stdout(),
+            "Files scanned: {}",
+            scanned_files.load(Ordering::Relaxed)
+        )?;
+    }
     Ok(())
 }
@@ -12,18 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::PathBuf;
+
 use itertools::Itertools;
+use jj_lib::backend::CopyDetectionOptions;
+use jj_lib::commit::Commit;
 use jj_lib::copies::CopyRecords;
 use jj_lib::repo::Repo;
+use jj_lib::revset;
+use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::rewrite::merge_commit_trees;
 use tracing::instrument;
 
 use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::print_parse_diagnostics;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::diff_util::get_copy_records;
 use crate::diff_util::DiffFormatArgs;
+use crate::merge_tools::dump_trees;
+use crate::revset_util;
+use crate::revset_util::RevsetExpressionEvaluator;
 use crate::ui::Ui;
 
 /// Compare file contents between two revisions
@@ -37,6 +50,12 @@ use crate::ui::Ui;
 /// given revisions. If either is left out, it defaults to the working-copy
 /// commit. For example, `jj diff --from main` shows the changes from "main"
 /// (perhaps a bookmark name) to the working-copy commit.
+///
+/// With the `--between-ops` option, shows how the revision given by `-r`
+/// changed between two operations, e.g. how `jj squash` changed it.
+///
+/// With the `--root` option, shows the full contents of the revision given by
+/// `-r` as additions to the empty tree.
 #[derive(clap::Args, Clone, Debug)]
 #[command(mut_arg("ignore_all_space", |a| a.short('w')))]
 #[command(mut_arg("ignore_space_change", |a| a.short('b')))]
@@ -54,9 +73,65 @@ pub(crate) struct DiffArgs {
     /// Show changes to this revision
     #[arg(long, conflicts_with = "revision")]
     to: Option<RevisionArg>,
+    /// Show how the revision changed between these two operations
+    ///
+    /// The revision is resolved at each of the operations, and the contents
+    /// at the first operation are compared to the contents at the second
+    /// operation.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["FROM_OP", "TO_OP"],
+        conflicts_with_all = ["from", "to"]
+    )]
+    between_ops: Vec<String>,
+    /// Show the changes compared to the empty tree instead of the parent(s)
+    ///
+    /// All files in the revision are shown as added.
+    #[arg(long, conflicts_with_all = ["from", "to", "between_ops"])]
+    root: bool,
     /// Restrict the diff to these paths
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Detect renames of files that are at least this similar, in percent
+    ///
+    /// Unlike the default, copies aren't detected. If no value is given, the
+    /// threshold is 50%.
+    #[arg(
+        long,
+        short = 'M',
+        value_name = "PERCENT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "50",
+        value_parser = clap::value_parser!(u8).range(..=100)
+    )]
+    find_renames: Option<u8>,
+    /// Detect copies and renames of files that are at least this similar, in
+    /// percent
+    ///
+    /// This is the default, with a threshold of 50%.
+    #[arg(
+        long,
+        short = 'C',
+        value_name = "PERCENT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "50",
+        value_parser = clap::value_parser!(u8).range(..=100),
+        conflicts_with = "find_renames"
+    )]
+    find_copies: Option<u8>,
+    /// Write the two sides of the diff to this directory instead of showing
+    /// the diff
+    ///
+    /// All files in the left and right trees (restricted to the given paths,
+    /// if any) are written to the `left` and `right` subdirectories, the same
+    /// way they are materialized for an external diff tool. The paths of the
+    /// two directories are then printed, one per line. The directory is not
+    /// cleaned up afterwards; removing it is up to the caller.
+    #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    dump_trees: Option<PathBuf>,
     #[command(flatten)]
     format: DiffFormatArgs,
 }
@@ -75,17 +150,44 @@ pub(crate) fn cmd_diff(
         workspace_command.resolve_single_rev(ui, r.as_ref().unwrap_or(&RevisionArg::AT))
     };
 
+    let copy_options = match (args.find_renames, args.find_copies) {
+        (Some(percent), _) => CopyDetectionOptions {
+            find_copies: false,
+            similarity_threshold: f32::from(percent) / 100.0,
+        },
+        (None, Some(percent)) => CopyDetectionOptions {
+            find_copies: true,
+            similarity_threshold: f32::from(percent) / 100.0,
+        },
+        (None, None) => CopyDetectionOptions::default(),
+    };
+
     let from_tree;
     let to_tree;
     let mut copy_records = CopyRecords::default();
-    if args.from.is_some() || args.to.is_some() {
+    if let [from_op_str, to_op_str] = &args.between_ops[..] {
+        let revision_arg = args.revision.as_ref().unwrap_or(&RevisionArg::AT);
+        let from =
+            resolve_single_rev_at_op(ui, command, &workspace_command, revision_arg, from_op_str)?;
+        let to =
+            resolve_single_rev_at_op(ui, command, &workspace_command, revision_arg, to_op_str)?;
+        from_tree = from.tree()?;
+        to_tree = to.tree()?;
+
+        let records = get_copy_records(repo.store(), from.id(), to.id(), &matcher, &copy_options)?;
+        copy_records.add_records(records)?;
+    } else if args.from.is_some() || args.to.is_some() {
         let from = resolve_revision(&args.from)?;
         let to = resolve_revision(&args.to)?;
         from_tree = from.tree()?;
         to_tree = to.tree()?;
 
-        let records = get_copy_records(repo.store(), from.id(), to.id(), &matcher)?;
+        let records = get_copy_records(repo.store(), from.id(), to.id(), &matcher, &copy_options)?;
         copy_records.add_records(records)?;
+    } else if args.root {
+        let to = resolve_revision(&args.revision)?;
+        from_tree = repo.store().root_commit().tree()?;
+        to_tree = to.tree()?;
     } else {
         let to = resolve_revision(&args.revision)?;
         let parents: Vec<_> = to.parents().try_collect()?;
@@ -93,11 +195,21 @@ pub(crate) fn cmd_diff(
         to_tree = to.tree()?;
 
         for p in &parents {
-            let records = get_copy_records(repo.store(), p.id(), to.id(), &matcher)?;
+            let records = get_copy_records(repo.store(), p.id(), to.id(), &matcher, &copy_options)?;
             copy_records.add_records(records)?;
         }
     }
 
+    if let Some(output_dir) = &args.dump_trees {
+        let output_dir = command.cwd().join(output_dir);
+        let (left_dir, right_dir) =
+            dump_trees(repo.store(), &from_tree, &to_tree, &matcher, &output_dir)?;
+        let mut formatter = ui.stdout_formatter();
+        writeln!(formatter, "{}", left_dir.display())?;
+        writeln!(formatter, "{}", right_dir.display())?;
+        return Ok(());
+    }
+
     let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     ui.request_pager();
     diff_renderer.show_diff(
@@ -117,3 +229,40 @@ pub(crate) fn cmd_diff(
     )?;
     Ok(())
 }
+
+/// Resolves a revset to a single revision in the repo as of the given
+/// operation.
+pub(crate) fn resolve_single_rev_at_op(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    revision_arg: &RevisionArg,
+    op_str: &str,
+) -> Result<Commit, CommandError> {
+    let op = workspace_command.resolve_single_op(op_str)?;
+    let repo = workspace_command.repo().loader().load_at(&op)?;
+    let mut diagnostics = RevsetDiagnostics::new();
+    let context = workspace_command.revset_parse_context();
+    let expression = revset::parse(&mut diagnostics, revision_arg.as_ref(), &context)?;
+    print_parse_diagnostics(ui, "In revset expression", &diagnostics)?;
+    let id_prefix_context = workspace_command.env().new_id_prefix_context();
+    let expression = RevsetExpressionEvaluator::new(
+        repo.as_ref(),
+        command.revset_extensions().clone(),
+        &id_prefix_context,
+        expression,
+    );
+    if expression.evaluate_to_commit_ids()?.next().is_none() {
+        return Err(user_error(format!(
+            r#"Revset "{revision_arg}" didn't resolve to any revisions at operation {}"#,
+            short_operation_hash(op.id())
+        )));
+    }
+    let should_hint_about_all_prefix = false;
+    revset_util::evaluate_revset_to_single_commit(
+        revision_arg.as_ref(),
+        &expression,
+        || workspace_command.commit_summary_template(),
+        should_hint_about_all_prefix,
+    )
+}
@@ -16,8 +16,9 @@ use std::io;
 use std::io::Write;
 
 use jj_lib::backend::BackendResult;
-use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_merge_result_with_style;
 use jj_lib::conflicts::materialize_tree_value;
+use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::fileset::FilePattern;
 use jj_lib::fileset::FilesetExpression;
@@ -33,6 +34,8 @@ use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::diff_util::conflict_marker_style_for;
+use crate::diff_util::ConflictStyleArg;
 use crate::ui::Ui;
 
 /// Print contents of files in a revision
@@ -47,6 +50,11 @@ pub(crate) struct FileShowArgs {
     /// Paths to print
     #[arg(required = true, value_hint = clap::ValueHint::FilePath)]
     paths: Vec<String>,
+    /// How to render conflicted files
+    ///
+    /// Defaults to the `ui.conflict-style` setting, or `jj` if unset.
+    #[arg(long, value_enum, value_name = "STYLE")]
+    conflict_style: Option<ConflictStyleArg>,
 }
 
 #[instrument(skip_all)]
@@ -61,6 +69,7 @@ pub(crate) fn cmd_file_show(
     // TODO: No need to add special case for empty paths when switching to
     // parse_union_filesets(). paths = [] should be "none()" if supported.
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let conflict_marker_style = conflict_marker_style_for(command.settings(), args.conflict_style)?;
 
     // Try fast path for single file entry
     if let Some(path) = get_single_path(&fileset_expression) {
@@ -71,7 +80,12 @@ pub(crate) fn cmd_file_show(
         }
         if !value.is_tree() {
             ui.request_pager();
-            write_tree_entries(ui, &workspace_command, [(path, Ok(value))])?;
+            write_tree_entries(
+                ui,
+                &workspace_command,
+                [(path, Ok(value))],
+                conflict_marker_style,
+            )?;
             return Ok(());
         }
     }
@@ -82,6 +96,7 @@ pub(crate) fn cmd_file_show(
         ui,
         &workspace_command,
         tree.entries_matching(matcher.as_ref()),
+        conflict_marker_style,
     )?;
     print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
     Ok(())
@@ -103,6 +118,7 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
     ui: &Ui,
     workspace_command: &WorkspaceCommandHelper,
     entries: impl IntoIterator<Item = (P, BackendResult<MergedTreeValue>)>,
+    conflict_marker_style: ConflictMarkerStyle,
 ) -> Result<(), CommandError> {
     let repo = workspace_command.repo();
     for (path, result) in entries {
@@ -121,7 +137,11 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
                 io::copy(&mut reader, &mut ui.stdout_formatter().as_mut())?;
             }
             MaterializedTreeValue::FileConflict { contents, .. } => {
-                materialize_merge_result(&contents, &mut ui.stdout_formatter())?;
+                materialize_merge_result_with_style(
+                    &contents,
+                    conflict_marker_style,
+                    &mut ui.stdout_formatter(),
+                )?;
             }
             MaterializedTreeValue::OtherConflict { id } => {
                 ui.stdout_formatter().write_all(id.describe().as_bytes())?;
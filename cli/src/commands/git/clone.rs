@@ -241,8 +241,14 @@ fn do_git_clone(
         GitFetchError::InvalidBranchPattern => {
             unreachable!("we didn't provide any globs")
         }
+        GitFetchError::RemoteReservedForAnonymousFetch => {
+            unreachable!("we didn't fetch from a URL")
+        }
+        GitFetchError::GitCommandFailed(_) | GitFetchError::GitCommandDisabled => {
+            unreachable!("we didn't fetch with the git executable")
+        }
     })?;
-    print_git_import_stats(ui, fetch_tx.repo(), &stats.import_stats, true)?;
+    print_git_import_stats(ui, fetch_tx.repo(), &stats.import_stats, true, true)?;
     fetch_tx.finish(ui, "fetch from git remote into empty repo")?;
     Ok((workspace_command, stats))
 }
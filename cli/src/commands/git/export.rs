@@ -12,26 +12,154 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write;
+
+use jj_lib::backend::CommitId;
 use jj_lib::git;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo;
 
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::git_util::print_failed_git_export;
 use crate::ui::Ui;
 
 /// Update the underlying Git repo with changes made in the repo
 #[derive(clap::Args, Clone, Debug)]
-pub struct GitExportArgs {}
+pub struct GitExportArgs {
+    /// Delete Git branches that no longer have a corresponding bookmark
+    ///
+    /// This deletes branches that jj still remembers from an earlier import or
+    /// export. Branches that had been modified in Git since are reported as
+    /// failed instead, so work added in Git isn't lost. Git branches that jj
+    /// has never imported or exported are left alone.
+    #[arg(long)]
+    prune: bool,
+    /// Also move Git HEAD to the working-copy parent of this workspace
+    ///
+    /// Git HEAD is normally kept in sync with the working-copy parent after
+    /// each command. This resets it explicitly, e.g. if it was changed in Git
+    /// without being imported. The Git index is reset to match as well. Only
+    /// supported in a workspace that shares its working copy with Git.
+    #[arg(long)]
+    update_head: bool,
+    /// Only display the refs that would be created, moved, or deleted, and
+    /// those that would fail to export
+    ///
+    /// Nothing is written to the Git repo.
+    #[arg(long, conflicts_with_all = ["prune", "update_head"])]
+    dry_run: bool,
+}
 
 pub fn cmd_git_export(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &GitExportArgs,
+    args: &GitExportArgs,
 ) -> Result<(), CommandError> {
-    let mut workspace_command = command.workspace_helper(ui)?;
+    // In a colocated repo, the snapshot would export refs to Git, so don't
+    // take one if nothing should be written.
+    let mut workspace_command = if args.dry_run {
+        command.workspace_helper_no_snapshot(ui)?
+    } else {
+        command.workspace_helper(ui)?
+    };
+    if args.update_head && !workspace_command.working_copy_shared_with_git() {
+        return Err(user_error(
+            "--update-head requires a workspace colocated with Git",
+        ));
+    }
+    if args.dry_run {
+        return print_export_preview(ui, workspace_command.repo().as_ref());
+    }
     let mut tx = workspace_command.start_transaction();
-    let failed_refs = git::export_refs(tx.repo_mut())?;
-    tx.finish(ui, "export git refs")?;
+    let mut failed_refs = git::export_refs(tx.repo_mut())?;
+    if args.prune {
+        let pruned = git::prune_git_branches(tx.repo_mut())?;
+        failed_refs.retain(|failed| {
+            !pruned.pruned.contains(&failed.name)
+                && !pruned.failed.iter().any(|other| other.name == failed.name)
+        });
+        failed_refs.extend(pruned.failed);
+        for name in &pruned.pruned {
+            writeln!(ui.status(), "Pruned Git branch {name}")?;
+        }
+    }
+    let new_git_head = if args.update_head {
+        update_git_head(&mut tx)?
+    } else {
+        None
+    };
+    // The recorded Git HEAD might not change, so moving the actual Git HEAD is
+    // reported separately instead of as "Nothing changed."
+    if new_git_head.is_none() || tx.repo().has_changes() {
+        tx.finish(ui, "export git refs")?;
+    }
+    match new_git_head {
+        Some(Some(id)) => writeln!(ui.status(), "Moved Git HEAD to {}", short_commit_hash(&id))?,
+        Some(None) => writeln!(ui.status(), "Unset Git HEAD")?,
+        None => {}
+    }
     print_failed_git_export(ui, &failed_refs)?;
     Ok(())
 }
+
+fn print_export_preview(ui: &Ui, repo: &dyn Repo) -> Result<(), CommandError> {
+    let preview = git::preview_export_refs(repo)?;
+    if preview.updated_refs.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
+    } else {
+        let mut formatter = ui.stdout_formatter();
+        writeln!(formatter, "Changes to export to Git:")?;
+        for update in &preview.updated_refs {
+            let name = &update.git_ref_name;
+            match (&update.old_target, &update.new_target) {
+                (None, Some(new)) => {
+                    writeln!(formatter, "  Create {name} at {}", short_commit_hash(new))?;
+                }
+                (Some(old), Some(new)) => writeln!(
+                    formatter,
+                    "  Move {name} from {} to {}",
+                    short_commit_hash(old),
+                    short_commit_hash(new)
+                )?,
+                (Some(old), None) => {
+                    writeln!(formatter, "  Delete {name} from {}", short_commit_hash(old))?;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+    print_failed_git_export(ui, &preview.failed_refs)?;
+    writeln!(ui.status(), "Dry-run requested, not exporting.")?;
+    Ok(())
+}
+
+/// Moves Git HEAD to the working-copy parent, comparing against the actual
+/// Git HEAD rather than the one recorded in the view. Returns the new Git HEAD
+/// if it was moved.
+fn update_git_head(
+    tx: &mut WorkspaceCommandTransaction,
+) -> Result<Option<Option<CommitId>>, CommandError> {
+    let workspace_id = tx.base_workspace_helper().workspace_id().clone();
+    let Some(wc_commit_id) = tx.repo().view().get_wc_commit_id(&workspace_id).cloned() else {
+        return Ok(None);
+    };
+    let wc_commit = tx.repo().store().get_commit(&wc_commit_id)?;
+    let git_repo = tx.base_workspace_helper().open_colocated_git_repo()?;
+    let actual_head = git_repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| CommitId::from_bytes(oid.as_bytes()));
+    let first_parent_id = &wc_commit.parent_ids()[0];
+    let expected_head =
+        (first_parent_id != tx.repo().store().root_commit_id()).then(|| first_parent_id.clone());
+    let is_in_sync = actual_head == expected_head;
+    tx.repo_mut()
+        .set_workspace_git_head_target(&workspace_id, RefTarget::resolved(actual_head));
+    git::reset_workspace_head(tx.repo_mut(), &git_repo, &workspace_id, &wc_commit)?;
+    Ok((!is_in_sync).then_some(expected_head))
+}
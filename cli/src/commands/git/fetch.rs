@@ -12,23 +12,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs;
+use std::time::SystemTime;
+
 use itertools::Itertools;
+use jj_lib::backend::CommitId;
+use jj_lib::default_index::AsCompositeIndex as _;
+use jj_lib::default_index::DefaultIndexStore;
 use jj_lib::git;
+use jj_lib::git::GitCommandFetchOptions;
+use jj_lib::git::GitCommitGraphError;
 use jj_lib::git::GitFetchError;
 use jj_lib::repo::Repo;
+use jj_lib::rewrite::rebase_commit;
 use jj_lib::settings::ConfigResultExt as _;
+use jj_lib::settings::GitAutoGcSettings;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::internal_error;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
+use crate::commands::git::git_settings_with_abandon_policy;
 use crate::commands::git::map_git_error;
+use crate::commands::git::AbandonPolicyArg;
+use crate::commands::util::DEFAULT_GC_EXPIRATION;
+use crate::config::write_config_value_to_file;
+use crate::config::ConfigNamePathBuf;
+use crate::config::ConfigSource;
 use crate::git_util::get_git_repo;
 use crate::git_util::print_git_import_stats;
+use crate::git_util::print_git_import_stats_porcelain;
 use crate::git_util::with_remote_git_callbacks;
 use crate::ui::Ui;
 
@@ -44,13 +64,101 @@ pub struct GitFetchArgs {
     /// expand `*` as a glob. The other wildcard characters aren't supported.
     #[arg(long, short, alias="bookmark", default_value = "glob:*", value_parser = StringPattern::parse)]
     branch: Vec<StringPattern>,
-    /// The remote to fetch from (only named remotes are supported, can be
-    /// repeated)
+    /// The remote to fetch from (can be repeated)
+    ///
+    /// This can also be a URL or path of a Git repository that isn't
+    /// configured as a remote. Its branches are imported as remote bookmarks
+    /// of the temporary `anonymous` remote, replacing the ones from any
+    /// previous fetch from a URL. They are never tracked automatically.
     #[arg(long = "remote", value_name = "remote")]
     remotes: Vec<String>,
     /// Fetch from all remotes
     #[arg(long, conflicts_with = "remotes")]
     all_remotes: bool,
+    /// Which commits to abandon after importing refs
+    ///
+    /// Defaults to `unreachable`, or `never` if
+    /// `git.abandon-unreachable-commits` is disabled.
+    #[arg(long, value_enum, value_name = "POLICY")]
+    abandon: Option<AbandonPolicyArg>,
+    /// Fetch all branches, tags, and Git notes as local refs
+    ///
+    /// The remote branches are imported as if the local branches had been
+    /// updated in the underlying Git repo, so they replace the local bookmarks
+    /// of the same name. Local Git branches and tags that don't exist on the
+    /// remote are deleted. This bypasses the usual tracking-bookmark model, and
+    /// is meant for maintaining a backup mirror.
+    #[arg(long, conflicts_with = "branch")]
+    mirror: bool,
+    /// Move the working copy onto the new target of its parent's bookmark
+    ///
+    /// If the parent of the working-copy commit had a bookmark tracking one of
+    /// the fetched remotes, and the fetch fast-forwarded that bookmark, the
+    /// working-copy commit is rebased onto the bookmark's new target.
+    #[arg(long)]
+    update_head: bool,
+    /// Write Git's commit-graph file after fetching
+    ///
+    /// The commit-graph file makes reading the history faster, both for git
+    /// and for jj's Git backend. This runs `git commit-graph write
+    /// --reachable`, so `git` must be installed, and `git.subprocess` must be
+    /// enabled.
+    #[arg(long)]
+    write_commit_graph: bool,
+    /// Print the ref updates in a stable, machine-readable format
+    ///
+    /// Each updated ref is printed to stdout as a line of tab-separated fields:
+    /// the ref kind (`bookmark` or `tag`), the ref name, the old and new commit
+    /// IDs (`-` if absent), the update kind (`new`, `deleted`, `updated`, or
+    /// `forced`), and the tracking status (`tracked`, `untracked`, or `-` for
+    /// tags).
+    #[arg(long)]
+    porcelain: bool,
+    /// Mark non-fast-forward updates as `[forced]` (the default)
+    ///
+    /// A bookmark is force-updated if its new target isn't a descendant of
+    /// the old target, e.g. because the history was rewritten on the remote.
+    /// This overrides an earlier `--no-show-forced-updates`.
+    #[arg(long, overrides_with = "no_show_forced_updates")]
+    show_forced_updates: bool,
+    /// Report non-fast-forward updates as `[updated]`
+    #[arg(long)]
+    no_show_forced_updates: bool,
+    /// Only report these commits and their ancestors to the remote as already
+    /// present (can be repeated)
+    ///
+    /// This can make the fetch negotiation converge faster in large repos,
+    /// like `git fetch --negotiation-tip`. This runs the `git` executable, so
+    /// `git.subprocess` must be enabled.
+    /// The number of negotiation rounds is logged with `--debug`.
+    #[arg(long, value_name = "REVSETS", conflicts_with = "mirror")]
+    negotiation_tip: Vec<RevisionArg>,
+    /// Fetch only the objects matching this filter spec, e.g. `blob:none`
+    ///
+    /// This makes a partial fetch like `git fetch --filter`, which runs the
+    /// `git` executable, so `git.subprocess` must be enabled. The remote is
+    /// then marked as a promisor remote, and missing file contents are fetched
+    /// from it with `git` on demand, e.g. when they are diffed or checked out.
+    #[arg(long, value_name = "SPEC", conflicts_with = "mirror")]
+    filter: Option<String>,
+    /// Record the default branch of each remote
+    ///
+    /// The branch that the remote's `HEAD` points to is recorded in
+    /// `refs/remotes/<remote>/HEAD` of the underlying Git repo, like `git
+    /// remote set-head --auto`. The repository-level `trunk()` revset alias
+    /// is also set to `<branch>@<remote>`, as `jj git clone` does, when
+    /// fetching from a single remote or if it already points to a branch of
+    /// the remote. A customized alias is kept.
+    #[arg(long, conflicts_with_all = ["mirror", "negotiation_tip", "filter"])]
+    set_head: bool,
+    /// Fetch the full history of a shallow repo
+    ///
+    /// This removes the shallow boundary of a repo cloned with `--depth`, like
+    /// `git fetch --unshallow`. This runs the `git` executable, so
+    /// `git.subprocess` must be enabled. The commit index is then rebuilt, so
+    /// that the fetched ancestors show up in the history.
+    #[arg(long, conflicts_with_all = ["mirror", "set_head"])]
+    unshallow: bool,
 }
 
 #[tracing::instrument(skip(ui, command))]
@@ -68,18 +176,96 @@ pub fn cmd_git_fetch(
     } else {
         args.remotes.clone()
     };
+    let git_settings = git_settings_with_abandon_policy(command, args.abandon);
+    let auto_gc_settings = command.settings().git_auto_gc_settings()?;
+    let negotiation_tips: Vec<CommitId> = if args.negotiation_tip.is_empty() {
+        vec![]
+    } else {
+        workspace_command
+            .parse_union_revsets(ui, &args.negotiation_tip)?
+            .evaluate_to_commit_ids()?
+            .try_collect()?
+    };
     let mut tx = workspace_command.start_transaction();
+    let mut fetched_remote_names = vec![];
+    let mut default_branches = vec![];
+    let mut num_unshallowed_commits = 0;
     for remote in &remotes {
+        let is_url = is_anonymous_remote(&git_repo, remote);
+        if is_url && args.mirror {
+            return Err(user_error(format!(
+                "Cannot mirror {remote} because it isn't a configured remote"
+            )));
+        }
+        if is_url && !args.negotiation_tip.is_empty() {
+            return Err(user_error(format!(
+                "Cannot use --negotiation-tip with {remote} because it isn't a configured remote"
+            )));
+        }
+        if is_url && args.filter.is_some() {
+            return Err(user_error(format!(
+                "Cannot use --filter with {remote} because it isn't a configured remote"
+            )));
+        }
+        if is_url && args.set_head {
+            return Err(user_error(format!(
+                "Cannot use --set-head with {remote} because it isn't a configured remote"
+            )));
+        }
+        if is_url && args.unshallow {
+            return Err(user_error(format!(
+                "Cannot use --unshallow with {remote} because it isn't a configured remote"
+            )));
+        }
+        if is_url {
+            writeln!(
+                ui.status(),
+                "Fetching {remote} into temporary remote {}",
+                git::REMOTE_NAME_FOR_ANONYMOUS_FETCH
+            )?;
+            fetched_remote_names.push(git::REMOTE_NAME_FOR_ANONYMOUS_FETCH);
+        } else {
+            fetched_remote_names.push(remote.as_str());
+        }
         let stats = with_remote_git_callbacks(ui, None, |cb| {
-            git::fetch(
-                tx.repo_mut(),
-                &git_repo,
-                remote,
-                &args.branch,
-                cb,
-                &command.settings().git_settings(),
-                None,
-            )
+            if args.mirror {
+                git::fetch_mirror(tx.repo_mut(), &git_repo, remote, cb, &git_settings)
+            } else if is_url {
+                git::fetch_url(
+                    tx.repo_mut(),
+                    &git_repo,
+                    remote,
+                    &args.branch,
+                    cb,
+                    &git_settings,
+                    None,
+                )
+            } else if !args.negotiation_tip.is_empty() || args.filter.is_some() || args.unshallow {
+                let options = GitCommandFetchOptions {
+                    negotiation_tips: &negotiation_tips,
+                    filter: args.filter.as_deref(),
+                    unshallow: args.unshallow,
+                };
+                git::fetch_with_git_command(
+                    tx.repo_mut(),
+                    &git_repo,
+                    remote,
+                    &args.branch,
+                    &options,
+                    cb,
+                    &git_settings,
+                )
+            } else {
+                git::fetch(
+                    tx.repo_mut(),
+                    &git_repo,
+                    remote,
+                    &args.branch,
+                    cb,
+                    &git_settings,
+                    None,
+                )
+            }
         })
         .map_err(|err| match err {
             GitFetchError::InvalidBranchPattern => {
@@ -96,27 +282,299 @@ pub fn cmd_git_fetch(
                     user_error(err)
                 }
             }
+            GitFetchError::GitCommandDisabled => user_error_with_hint(
+                err,
+                "--negotiation-tip, --filter and --unshallow need the `git` executable. Set \
+                 `git.subprocess = true` to allow running it.",
+            ),
             GitFetchError::GitImportError(err) => err.into(),
             GitFetchError::InternalGitError(err) => map_git_error(err),
             _ => user_error(err),
         })?;
-        print_git_import_stats(ui, tx.repo(), &stats.import_stats, true)?;
+        num_unshallowed_commits += stats.unshallowed_commits.len();
+        let show_forced_updates = !args.no_show_forced_updates;
+        if args.porcelain {
+            print_git_import_stats_porcelain(
+                ui,
+                tx.repo(),
+                &stats.import_stats,
+                show_forced_updates,
+            )?;
+        }
+        print_git_import_stats(
+            ui,
+            tx.repo(),
+            &stats.import_stats,
+            !args.porcelain,
+            show_forced_updates,
+        )?;
+        if args.set_head {
+            if let Some(branch) = &stats.default_branch {
+                if git::get_remote_head(&git_repo, remote).as_ref() != Some(branch) {
+                    git::set_remote_head(&git_repo, remote, branch).map_err(map_git_error)?;
+                    writeln!(
+                        ui.status(),
+                        "Set the default branch of remote {remote} to {branch}"
+                    )?;
+                }
+                default_branches.push((remote.as_str(), branch.clone()));
+            } else {
+                writeln!(
+                    ui.warning_default(),
+                    "Couldn't determine the default branch of remote {remote}"
+                )?;
+            }
+        }
+    }
+    if !args.mirror {
+        warn_if_branches_not_found(
+            ui,
+            &tx,
+            &args.branch,
+            &fetched_remote_names
+                .iter()
+                .copied()
+                .map(StringPattern::exact)
+                .collect_vec(),
+        )?;
+    }
+    if args.update_head {
+        update_working_copy_parent(ui, &mut tx, &fetched_remote_names)?;
+    }
+    // Deepening the history doesn't change the view, so don't report
+    // "Nothing changed."
+    if num_unshallowed_commits == 0 || tx.repo().has_changes() {
+        tx.finish(
+            ui,
+            format!("fetch from git remote(s) {}", remotes.iter().join(",")),
+        )?;
+    }
+    if num_unshallowed_commits > 0 {
+        writeln!(
+            ui.status(),
+            "Fetched the full history of {num_unshallowed_commits} shallow commits"
+        )?;
+        reindex_unshallowed_history(ui, command, &workspace_command)?;
+    }
+    for (remote, branch) in &default_branches {
+        let replace_builtin = remotes.len() == 1;
+        set_trunk_alias(
+            ui,
+            command,
+            &workspace_command,
+            remote,
+            branch,
+            replace_builtin,
+        )?;
+    }
+    if args.write_commit_graph {
+        git::write_commit_graph(&git_repo, &git_settings).map_err(|err| match err {
+            GitCommitGraphError::GitCommandDisabled => user_error_with_hint(
+                err,
+                "--write-commit-graph needs the `git` executable. Set `git.subprocess = true` to \
+                 allow running it.",
+            ),
+            GitCommitGraphError::GitCommandFailed(_) => user_error(err),
+        })?;
+        writeln!(ui.status(), "Wrote the commit-graph file")?;
+    }
+    if command.is_at_head_operation() {
+        run_auto_gc_if_needed(ui, &workspace_command, &git_repo, &auto_gc_settings)?;
     }
-    warn_if_branches_not_found(
-        ui,
-        &tx,
-        &args.branch,
-        &remotes.iter().map(StringPattern::exact).collect_vec(),
+    Ok(())
+}
+
+/// Rebuilds the commit index, which recorded the commits at the former shallow
+/// boundary as root commits.
+fn reindex_unshallowed_history(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<(), CommandError> {
+    // The commits read by this command are cached with their shallow parents,
+    // so read them again from a newly loaded store.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let Some(default_index_store) = repo_loader
+        .index_store()
+        .as_any()
+        .downcast_ref::<DefaultIndexStore>()
+    else {
+        return Ok(());
+    };
+    default_index_store.reinit().map_err(internal_error)?;
+    let index = default_index_store
+        .build_index_at_operation(workspace_command.repo().operation(), repo_loader.store())
+        .map_err(internal_error)?;
+    writeln!(
+        ui.status(),
+        "Reindexed {} commits",
+        index.as_composite().stats().num_commits
+    )?;
+    Ok(())
+}
+
+/// Sets the repository-level `trunk()` alias to the default branch of the
+/// remote, unless it already is. An alias configured by the user is kept, so
+/// this only replaces one set by `jj git clone` or an earlier fetch from the
+/// same remote, or the built-in alias if `replace_builtin` is set.
+fn set_trunk_alias(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    remote: &str,
+    branch: &str,
+    replace_builtin: bool,
+) -> Result<(), CommandError> {
+    let name = ConfigNamePathBuf::from_iter(["revset-aliases", "trunk()"]);
+    let alias = format!("{branch}@{remote}");
+    let values = command.resolved_config_values(&name)?;
+    let Some(current) = values.iter().find(|value| !value.is_overridden) else {
+        return Ok(());
+    };
+    let current_alias = current.value.clone().into_string().ok();
+    let is_replaceable = match current.source {
+        ConfigSource::Default => replace_builtin,
+        ConfigSource::Repo => current_alias
+            .as_deref()
+            .and_then(|value| value.strip_suffix(&format!("@{remote}")))
+            .is_some_and(|branch| !branch.is_empty() && !branch.contains(['@', '(', ' '])),
+        ConfigSource::Env | ConfigSource::User | ConfigSource::CommandArg => false,
+    };
+    if !is_replaceable || current_alias.as_ref() == Some(&alias) {
+        return Ok(());
+    }
+    let config_path = workspace_command.repo_path().join("config.toml");
+    write_config_value_to_file(&name, alias.clone().into(), &config_path)?;
+    writeln!(
+        ui.status(),
+        "Setting the revset alias \"trunk()\" to \"{alias}\""
     )?;
-    tx.finish(
-        ui,
-        format!("fetch from git remote(s) {}", remotes.iter().join(",")),
+    Ok(())
+}
+
+/// File in the repo directory counting the fetches since the last automatic
+/// garbage collection.
+const AUTO_GC_FETCH_COUNT_FILE: &str = "git_auto_gc_fetch_count";
+
+/// Runs garbage collection if `git.auto-gc` is configured and enough fetches
+/// or loose objects have accumulated.
+fn run_auto_gc_if_needed(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    git_repo: &git2::Repository,
+    auto_gc_settings: &GitAutoGcSettings,
+) -> Result<(), CommandError> {
+    if !auto_gc_settings.is_enabled() {
+        return Ok(());
+    }
+    let count_path = workspace_command.repo_path().join(AUTO_GC_FETCH_COUNT_FILE);
+    let fetch_count = fs::read_to_string(&count_path)
+        .ok()
+        .and_then(|text| text.trim().parse::<u64>().ok())
+        .unwrap_or(0)
+        + 1;
+    let reason = if auto_gc_settings
+        .fetches
+        .is_some_and(|max_fetches| fetch_count >= max_fetches)
+    {
+        Some(format!("after {fetch_count} fetches"))
+    } else if let Some(max_loose_objects) = auto_gc_settings.loose_objects {
+        let loose_objects = git::count_loose_objects(git_repo);
+        (loose_objects > max_loose_objects)
+            .then(|| format!("since there were {loose_objects} loose objects"))
+    } else {
+        None
+    };
+    let Some(reason) = reason else {
+        fs::write(&count_path, fetch_count.to_string())?;
+        return Ok(());
+    };
+    // Objects reachable from jj's commits are kept by the backend.
+    let repo = workspace_command.repo();
+    let keep_newer = SystemTime::now() - DEFAULT_GC_EXPIRATION;
+    repo.store().gc(repo.index(), keep_newer)?;
+    fs::write(&count_path, "0")?;
+    writeln!(ui.status(), "Ran automatic garbage collection {reason}")?;
+    Ok(())
+}
+
+/// Rebases the working-copy commit onto the new target of a bookmark that
+/// pointed to its parent before the fetch, if the bookmark was fast-forwarded.
+fn update_working_copy_parent(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    remotes: &[&str],
+) -> Result<(), CommandError> {
+    let Some(wc_commit_id) = tx.base_workspace_helper().get_wc_commit_id().cloned() else {
+        return Ok(());
+    };
+    let wc_commit = tx.repo().store().get_commit(&wc_commit_id)?;
+    let [parent_id] = wc_commit.parent_ids() else {
+        return Ok(());
+    };
+    let base_view = tx.base_repo().view();
+    let mut new_parents: Vec<(String, CommitId)> = vec![];
+    for (name, _) in base_view.local_bookmarks_for_commit(parent_id) {
+        let is_tracked = remotes
+            .iter()
+            .any(|remote| base_view.get_remote_bookmark(name, remote).is_tracking());
+        if !is_tracked {
+            continue;
+        }
+        let new_target = tx.repo().view().get_local_bookmark(name);
+        match new_target.as_normal() {
+            Some(id) if id == parent_id => {}
+            Some(id) if tx.repo().index().is_ancestor(parent_id, id) => {
+                new_parents.push((name.to_owned(), id.clone()));
+            }
+            _ => {
+                writeln!(
+                    ui.warning_default(),
+                    "Not moving the working copy because bookmark {name} wasn't fast-forwarded"
+                )?;
+                return Ok(());
+            }
+        }
+    }
+    let Some((name, new_parent_id)) = new_parents.first() else {
+        return Ok(());
+    };
+    if new_parents.iter().any(|(_, id)| id != new_parent_id) {
+        writeln!(
+            ui.warning_default(),
+            "Not moving the working copy because bookmarks {} moved to different commits",
+            new_parents.iter().map(|(name, _)| name).join(", ")
+        )?;
+        return Ok(());
+    }
+    tx.base_workspace_helper()
+        .check_rewritable([wc_commit.id()])?;
+    let settings = tx.settings().clone();
+    rebase_commit(
+        &settings,
+        tx.repo_mut(),
+        wc_commit,
+        vec![new_parent_id.clone()],
     )?;
+    tx.repo_mut().rebase_descendants(&settings)?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        let new_parent = tx.repo().store().get_commit(new_parent_id)?;
+        write!(formatter, "Moved working copy onto bookmark {name}: ")?;
+        tx.write_commit_summary(formatter.as_mut(), &new_parent)?;
+        writeln!(formatter)?;
+    }
     Ok(())
 }
 
 const DEFAULT_REMOTE: &str = "origin";
 
+/// Returns true if `remote` isn't a configured remote but looks like a URL or
+/// path of a Git repository.
+fn is_anonymous_remote(git_repo: &git2::Repository, remote: &str) -> bool {
+    git_repo.find_remote(remote).is_err() && (remote.contains(':') || remote.contains('/'))
+}
+
 fn get_default_fetch_remotes(
     ui: &Ui,
     settings: &UserSettings,
@@ -12,11 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
+use std::sync::Arc;
+
 use jj_lib::git;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::str_util::StringPattern;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::LogContentFormat;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
+use crate::commands::git::git_settings_with_abandon_policy;
+use crate::commands::git::AbandonPolicyArg;
+use crate::commands::operation::diff::show_op_diff;
+use crate::commit_templater::CommitTemplateLanguage;
+use crate::git_util::get_git_import_globs;
+use crate::git_util::is_git_ref_in_import_scope;
 use crate::git_util::print_git_import_stats;
+use crate::graphlog::GraphStyle;
 use crate::ui::Ui;
 
 /// Update repo with changes made in the underlying Git repo
@@ -24,20 +39,141 @@ use crate::ui::Ui;
 /// If a working-copy commit gets abandoned, it will be given a new, empty
 /// commit. This is true in general; it is not specific to this command.
 #[derive(clap::Args, Clone, Debug)]
-pub struct GitImportArgs {}
+pub struct GitImportArgs {
+    /// Which commits to abandon after importing refs
+    ///
+    /// Defaults to `unreachable`, or `never` if
+    /// `git.abandon-unreachable-commits` is disabled.
+    #[arg(long, value_enum, value_name = "POLICY")]
+    abandon: Option<AbandonPolicyArg>,
+
+    /// Only show what would change, without updating the repo
+    ///
+    /// The changed bookmarks and tags, the commits that would be abandoned or
+    /// rebased, and the new Git HEAD and working-copy commit are shown.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Only import Git refs whose full name matches this glob pattern
+    ///
+    /// The pattern is matched against the full ref name, e.g.
+    /// `refs/heads/team-foo/*` or `refs/remotes/origin/*`. Refs outside the
+    /// patterns are left untouched. Bookmarks and tags within the patterns
+    /// that were deleted in Git are deleted in jj, too. This option can be
+    /// repeated. Defaults to the `git.import-globs` setting, or to all refs.
+    #[arg(long, value_name = "GLOB", value_parser = StringPattern::glob)]
+    glob: Vec<StringPattern>,
+}
 
 pub fn cmd_git_import(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &GitImportArgs,
+    args: &GitImportArgs,
 ) -> Result<(), CommandError> {
-    let mut workspace_command = command.workspace_helper(ui)?;
+    // In a colocated repo, the snapshot would import Git HEAD and refs, so
+    // don't take one if nothing should be changed.
+    let mut workspace_command = if args.dry_run {
+        command.workspace_helper_no_snapshot(ui)?
+    } else {
+        command.workspace_helper(ui)?
+    };
+    let workspace_id = workspace_command.workspace_id().clone();
+    let git_worktree_root = workspace_command.git_worktree_root().map(Path::to_owned);
     let mut tx = workspace_command.start_transaction();
     // In non-colocated repo, Git HEAD will never be moved internally by jj.
     // That's why cmd_git_export() doesn't export the HEAD ref.
-    git::import_head(tx.repo_mut())?;
-    let stats = git::import_refs(tx.repo_mut(), &command.settings().git_settings())?;
-    print_git_import_stats(ui, tx.repo(), &stats, true)?;
+    if let Some(worktree_root) = &git_worktree_root {
+        git::import_worktree_head(tx.repo_mut(), worktree_root, &workspace_id)?;
+    } else {
+        git::import_workspace_head(tx.repo_mut(), &workspace_id)?;
+    }
+    let git_settings = git_settings_with_abandon_policy(command, args.abandon);
+    let import_globs = if args.glob.is_empty() {
+        get_git_import_globs(command.settings())?
+    } else {
+        args.glob.clone()
+    };
+    let stats = git::import_some_refs(tx.repo_mut(), &git_settings, |ref_name| {
+        is_git_ref_in_import_scope(&import_globs, ref_name)
+    })?;
+    if args.dry_run {
+        tx.repo_mut().rebase_descendants(command.settings())?;
+        let base_repo = tx.base_repo().clone();
+        // Write the operation without publishing it, so the op log is
+        // unchanged.
+        let new_repo = tx.into_inner().write("import git refs").leave_unpublished();
+        return print_import_plan(ui, command, &workspace_command, &base_repo, &new_repo);
+    }
+    print_git_import_stats(ui, tx.repo(), &stats, true, true)?;
     tx.finish(ui, "import git refs")?;
     Ok(())
 }
+
+/// Shows the changes from `base_repo` to the unpublished `new_repo`.
+fn print_import_plan(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    base_repo: &Arc<ReadonlyRepo>,
+    new_repo: &Arc<ReadonlyRepo>,
+) -> Result<(), CommandError> {
+    let workspace_env = workspace_command.env();
+    let id_prefix_context = workspace_env.new_id_prefix_context();
+    let commit_summary_template = {
+        let language =
+            workspace_env.commit_template_language(new_repo.as_ref(), &id_prefix_context);
+        let text = command
+            .settings()
+            .config()
+            .get_string("templates.commit_summary")?;
+        workspace_env.parse_template(ui, &language, &text, CommitTemplateLanguage::wrap_commit)?
+    };
+    let graph_style = GraphStyle::from_settings(command.settings())?;
+    let with_content_format = LogContentFormat::new(ui, command.settings())?;
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    show_op_diff(
+        ui,
+        formatter.as_mut(),
+        new_repo.as_ref(),
+        base_repo.view(),
+        new_repo.view(),
+        &commit_summary_template,
+        Some(graph_style),
+        &with_content_format,
+        None,
+    )?;
+    let workspace_id = workspace_command.workspace_id();
+    let old_git_head = base_repo.view().workspace_git_head(workspace_id);
+    let new_git_head = new_repo.view().workspace_git_head(workspace_id);
+    if new_git_head != old_git_head {
+        writeln!(formatter)?;
+        match new_git_head.as_normal() {
+            Some(commit_id) => {
+                let commit = new_repo.store().get_commit(commit_id)?;
+                write!(formatter, "Git HEAD would move to: ")?;
+                commit_summary_template.format(&commit, formatter.as_mut())?;
+                writeln!(formatter)?;
+            }
+            None => writeln!(formatter, "Git HEAD would be unset")?,
+        }
+    }
+    let old_wc_commit_id = base_repo.view().get_wc_commit_id(workspace_id);
+    let new_wc_commit_id = new_repo.view().get_wc_commit_id(workspace_id);
+    if new_wc_commit_id != old_wc_commit_id {
+        writeln!(formatter)?;
+        match new_wc_commit_id {
+            Some(commit_id) => {
+                let commit = new_repo.store().get_commit(commit_id)?;
+                write!(formatter, "Working copy would move to: ")?;
+                commit_summary_template.format(&commit, formatter.as_mut())?;
+                writeln!(formatter)?;
+            }
+            None => writeln!(formatter, "Working copy would be removed")?,
+        }
+    }
+    drop(formatter);
+    writeln!(ui.status(), "Dry-run requested, not importing.")?;
+    Ok(())
+}
@@ -17,12 +17,15 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use jj_lib::backend::CommitId;
 use jj_lib::file_util;
 use jj_lib::git;
 use jj_lib::git::parse_git_ref;
 use jj_lib::git::RefName;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::settings::GitAbandonPolicy;
+use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 
 use crate::cli_util::print_trackable_remote_bookmarks;
@@ -76,6 +79,24 @@ pub struct GitInitArgs {
     /// This option is mutually exclusive with `--colocate`.
     #[arg(long, conflicts_with = "colocate", value_hint = clap::ValueHint::DirPath)]
     git_repo: Option<String>,
+
+    /// Base the initial working-copy commit on the given Git ref instead of
+    /// the Git HEAD
+    ///
+    /// The ref can be given in full (e.g. `refs/heads/dev`) or as a short
+    /// name that Git can resolve (e.g. `dev`).
+    #[arg(long, requires = "git_repo")]
+    at_ref: Option<String>,
+
+    /// Import only the Git refs matching the given glob pattern (can be
+    /// repeated)
+    ///
+    /// The pattern is matched against the full ref name, e.g.
+    /// `refs/heads/main` or `refs/remotes/origin/*`. The other refs are left
+    /// out of the initial import, which can be much faster for large repos.
+    /// They can be brought in later by `jj git fetch` or `jj git import`.
+    #[arg(long, requires = "git_repo", value_name = "GLOB", value_parser = StringPattern::glob)]
+    import_refs: Vec<StringPattern>,
 }
 
 pub fn cmd_git_init(
@@ -101,6 +122,8 @@ pub fn cmd_git_init(
         &wc_path,
         args.colocate,
         args.git_repo.as_deref(),
+        args.at_ref.as_deref(),
+        &args.import_refs,
     )?;
 
     let relative_wc_path = file_util::relative_path(cwd, &wc_path);
@@ -119,6 +142,8 @@ pub fn do_init(
     workspace_root: &Path,
     colocate: bool,
     git_repo: Option<&str>,
+    at_ref: Option<&str>,
+    import_refs: &[StringPattern],
 ) -> Result<(), CommandError> {
     #[derive(Clone, Debug)]
     enum GitInitMode {
@@ -163,20 +188,37 @@ pub fn do_init(
             maybe_add_gitignore(&workspace_command)?;
         }
         GitInitMode::External(git_repo_path) => {
+            // Resolve the ref before creating anything so a typo doesn't leave
+            // a half-initialized repo behind.
+            let at_commit_id = at_ref
+                .map(|ref_name| resolve_git_ref(git_repo_path, ref_name))
+                .transpose()?;
             let (workspace, repo) =
                 Workspace::init_external_git(command.settings(), workspace_root, git_repo_path)?;
             // Import refs first so all the reachable commits are indexed in
             // chronological order.
             let colocated = is_colocated_git_workspace(&workspace, &repo);
-            let repo = init_git_refs(ui, command, repo, colocated)?;
+            let repo = init_git_refs(ui, command, repo, colocated, import_refs)?;
             let mut workspace_command = command.for_workable_repo(ui, workspace, repo)?;
             maybe_add_gitignore(&workspace_command)?;
             workspace_command.maybe_snapshot(ui)?;
             maybe_set_repository_level_trunk_alias(ui, &workspace_command)?;
-            if !workspace_command.working_copy_shared_with_git() {
+            if let (Some(ref_name), Some(commit_id)) = (at_ref, &at_commit_id) {
+                let commit = workspace_command.repo().store().get_commit(commit_id)?;
+                let mut tx = workspace_command.start_transaction();
+                tx.check_out(&commit)?;
+                tx.finish(ui, format!("check out git ref {ref_name}"))?;
+            } else if !workspace_command.working_copy_shared_with_git() {
                 let mut tx = workspace_command.start_transaction();
-                jj_lib::git::import_head(tx.repo_mut())?;
-                if let Some(git_head_id) = tx.repo().view().git_head().as_normal().cloned() {
+                let workspace_id = tx.base_workspace_helper().workspace_id().clone();
+                jj_lib::git::import_workspace_head(tx.repo_mut(), &workspace_id)?;
+                if let Some(git_head_id) = tx
+                    .repo()
+                    .view()
+                    .workspace_git_head(&workspace_id)
+                    .as_normal()
+                    .cloned()
+                {
                     let git_head_commit = tx.repo().store().get_commit(&git_head_id)?;
                     tx.check_out(&git_head_commit)?;
                 }
@@ -193,9 +235,24 @@ pub fn do_init(
     Ok(())
 }
 
+/// Looks up the commit the given Git ref points to in the Git repo at
+/// `git_repo_path`.
+fn resolve_git_ref(git_repo_path: &Path, ref_name: &str) -> Result<CommitId, CommandError> {
+    let git_repo = git2::Repository::open(git_repo_path)
+        .map_err(|err| user_error_with_message("Failed to access the repository", err))?;
+    let git_commit = git_repo
+        .resolve_reference_from_short_name(ref_name)
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(|err| user_error_with_message(format!("Git ref {ref_name} not found"), err))?;
+    Ok(CommitId::from_bytes(git_commit.id().as_bytes()))
+}
+
 /// Imports branches and tags from the underlying Git repo, exports changes if
 /// the repo is colocated.
 ///
+/// If `import_refs` isn't empty, only the refs matching any of the patterns
+/// are imported.
+///
 /// This is similar to `WorkspaceCommandHelper::import_git_refs()`, but never
 /// moves the Git HEAD to the working copy parent.
 fn init_git_refs(
@@ -203,21 +260,41 @@ fn init_git_refs(
     command: &CommandHelper,
     repo: Arc<ReadonlyRepo>,
     colocated: bool,
+    import_refs: &[StringPattern],
 ) -> Result<Arc<ReadonlyRepo>, CommandError> {
+    // Initial import shouldn't fail because of reserved remote name.
+    let git_ref_filter = |ref_name: &RefName| {
+        !git::is_reserved_git_remote_ref(ref_name)
+            && (import_refs.is_empty()
+                || git::to_git_ref_name(ref_name).is_some_and(|full_name| {
+                    import_refs
+                        .iter()
+                        .any(|pattern| pattern.matches(&full_name))
+                }))
+    };
+    if !import_refs.is_empty() {
+        let git_repo = get_git_repo(repo.store())?;
+        let (imported, skipped): (Vec<_>, Vec<_>) = git_repo
+            .references()?
+            .filter_map(|reference| parse_git_ref(reference.ok()?.name()?))
+            .filter(|ref_name| !git::is_reserved_git_remote_ref(ref_name))
+            .partition(git_ref_filter);
+        writeln!(
+            ui.status(),
+            "Importing {} Git refs, skipped {} refs not matching --import-refs",
+            imported.len(),
+            skipped.len()
+        )?;
+    }
     let mut tx = start_repo_transaction(&repo, command.settings(), command.string_args());
     // There should be no old refs to abandon, but enforce it.
     let mut git_settings = command.settings().git_settings();
-    git_settings.abandon_unreachable_commits = false;
-    let stats = git::import_some_refs(
-        tx.repo_mut(),
-        &git_settings,
-        // Initial import shouldn't fail because of reserved remote name.
-        |ref_name| !git::is_reserved_git_remote_ref(ref_name),
-    )?;
+    git_settings.abandon_policy = GitAbandonPolicy::Never;
+    let stats = git::import_some_refs(tx.repo_mut(), &git_settings, git_ref_filter)?;
     if !tx.repo().has_changes() {
         return Ok(repo);
     }
-    print_git_import_stats(ui, tx.repo(), &stats, false)?;
+    print_git_import_stats(ui, tx.repo(), &stats, false, true)?;
     if colocated {
         // If git.auto-local-branch = true, local bookmarks could be created for
         // the imported remote branches.
@@ -22,6 +22,8 @@ pub mod remote;
 pub mod submodule;
 
 use clap::Subcommand;
+use jj_lib::settings::GitAbandonPolicy;
+use jj_lib::settings::GitSettings;
 
 use self::clone::cmd_git_clone;
 use self::clone::GitCloneArgs;
@@ -82,6 +84,33 @@ pub fn cmd_git(
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum AbandonPolicyArg {
+    /// Keep all commits
+    Never,
+    /// Abandon commits that are no longer reachable from any ref
+    Unreachable,
+    /// Also abandon commits left behind by the local bookmarks and tags that
+    /// were updated by the import
+    All,
+}
+
+/// Returns the Git settings with the abandon policy overridden by `abandon`.
+fn git_settings_with_abandon_policy(
+    command: &CommandHelper,
+    abandon: Option<AbandonPolicyArg>,
+) -> GitSettings {
+    let mut git_settings = command.settings().git_settings();
+    if let Some(abandon) = abandon {
+        git_settings.abandon_policy = match abandon {
+            AbandonPolicyArg::Never => GitAbandonPolicy::Never,
+            AbandonPolicyArg::Unreachable => GitAbandonPolicy::Unreachable,
+            AbandonPolicyArg::All => GitAbandonPolicy::All,
+        };
+    }
+    git_settings
+}
+
 fn map_git_error(err: git2::Error) -> CommandError {
     if err.class() == git2::ErrorClass::Ssh {
         let hint =
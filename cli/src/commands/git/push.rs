@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::io;
 use std::io::Write;
+use std::thread;
+use std::time::Duration;
 
 use clap::ArgGroup;
 use itertools::Itertools;
@@ -23,6 +26,7 @@ use jj_lib::backend::CommitId;
 use jj_lib::git;
 use jj_lib::git::GitBranchPushTargets;
 use jj_lib::git::GitPushError;
+use jj_lib::git::GitSignTagsError;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::refs::classify_bookmark_push_action;
@@ -32,6 +36,7 @@ use jj_lib::refs::LocalAndRemoteRef;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::settings::ConfigResultExt as _;
+use jj_lib::settings::SignSettings;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
@@ -49,17 +54,23 @@ use crate::commands::git::get_single_remote;
 use crate::commands::git::map_git_error;
 use crate::formatter::Formatter;
 use crate::git_util::get_git_repo;
+use crate::git_util::print_failed_git_export;
+use crate::git_util::print_git_import_stats;
 use crate::git_util::with_remote_git_callbacks;
 use crate::git_util::GitSidebandProgressMessageWriter;
 use crate::ui::Ui;
 
 /// Push to a Git remote
 ///
-/// By default, pushes any bookmarks pointing to
+/// By default, pushes any tracked bookmarks pointing to
 /// `remote_bookmarks(remote=<remote>)..@`. Use `--bookmark` to push specific
 /// bookmarks. Use `--all` to push all bookmarks. Use `--change` to generate
 /// bookmark names based on the change IDs of specific commits.
 ///
+/// Pushed bookmarks start tracking the corresponding remote bookmarks, so
+/// there's no need to run `jj bookmark track` after pushing a new bookmark.
+/// Each remote bookmark that wasn't tracked before is reported.
+///
 /// Before the command actually moves, creates, or deletes a remote bookmark, it
 /// makes several [safety checks]. If there is a problem, you may need to run
 /// `jj git fetch --remote <remote name>` and/or resolve some [bookmark
@@ -72,8 +83,9 @@ use crate::ui::Ui;
 ///     https://martinvonz.github.io/jj/latest/bookmarks/#conflicts
 
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions"]).multiple(true)))]
+#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions", "create"]).multiple(true)))]
 #[command(group(ArgGroup::new("what").args(&["all", "deleted", "tracked"]).conflicts_with("specific")))]
+#[command(group(ArgGroup::new("mirror_mode").args(&["mirror"]).conflicts_with_all(["specific", "what", "atomic", "include_untracked_bookmarks", "prune", "options"])))]
 pub struct GitPushArgs {
     /// The remote to push to (only named remotes are supported)
     #[arg(long)]
@@ -103,12 +115,50 @@ pub struct GitPushArgs {
     /// correspond to missing local bookmarks.
     #[arg(long)]
     deleted: bool,
+    /// Also push untracked bookmarks selected by revisions
+    ///
+    /// Bookmarks selected by the default revset or `--revisions` are only
+    /// pushed if they track the remote, so that local-only bookmarks aren't
+    /// published by accident. Bookmarks named by `--bookmark` or created by
+    /// `--change` are always pushed.
+    #[arg(long, conflicts_with = "what")]
+    include_untracked_bookmarks: bool,
+    /// Also delete remote bookmarks that were deleted locally
+    ///
+    /// Only tracked remote bookmarks whose local bookmark was deleted are
+    /// deleted on the remote. You will be asked to confirm the deletion.
+    /// Unlike `--deleted`, this can be combined with the other ways of
+    /// selecting bookmarks.
+    #[arg(long, conflicts_with = "what")]
+    prune: bool,
+    /// Only delete remote bookmarks if they point to this commit on the remote
+    ///
+    /// By default, a bookmark is only deleted if the remote bookmark is still
+    /// where it was last fetched. With this option, the deletion is refused
+    /// unless the remote bookmark is at the given full commit ID instead. Only
+    /// one bookmark can be deleted when this option is used.
+    #[arg(long, value_name = "COMMIT_ID", value_parser = CommitId::try_from_hex)]
+    expect: Option<CommitId>,
     /// Allow pushing commits with empty descriptions
     #[arg(long)]
     allow_empty_description: bool,
     /// Allow pushing commits that are private
     #[arg(long)]
     allow_private: bool,
+    /// Refuse to push unless all commits to push are in these revisions
+    ///
+    /// For example, `--pre-push-check '~empty()'` blocks pushing empty
+    /// commits. All commits that fail the check are reported.
+    #[arg(long, alias = "require", value_name = "REVSET")]
+    pre_push_check: Option<RevisionArg>,
+    /// Push existing commits on the remote instead of equivalent local ones
+    ///
+    /// A local commit to push is equivalent to a commit that's only reachable
+    /// from the remote's mutable bookmarks if they have the same tree,
+    /// description, author, and (equivalent) parents. The local commit is
+    /// replaced by the remote one, and its descendants are rebased onto it.
+    #[arg(long)]
+    deduplicate_commits: bool,
     /// Push bookmarks pointing to these commits (can be repeated)
     #[arg(long, short)]
     revisions: Vec<RevisionArg>,
@@ -116,9 +166,67 @@ pub struct GitPushArgs {
     /// repeated)
     #[arg(long, short)]
     change: Vec<RevisionArg>,
+    /// Create a bookmark with this name at the working-copy parent and push it
+    ///
+    /// In a colocated repo, the working-copy parent is the commit the
+    /// detached Git HEAD points to. The bookmark must not exist yet.
+    #[arg(long, value_name = "NAME")]
+    create: Option<String>,
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
+    /// Don't run the `pre-push` hook of the Git repo
+    ///
+    /// The hook is only run if `git.run-hooks` is enabled. It gets the same
+    /// arguments and input as with `git push`, and the push is aborted if it
+    /// fails.
+    #[arg(long)]
+    no_verify: bool,
+    /// Retry the push up to this many times on transient network errors
+    ///
+    /// Retries are delayed with exponential backoff, starting at
+    /// `git.push-retry-delay` seconds (one second by default).
+    /// Errors such as rejected ref updates are never retried.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    retry: u32,
+    /// Apply either all or none of the bookmark updates on the remote
+    ///
+    /// This runs the `git` executable, since atomic pushes aren't supported
+    /// otherwise, so `git.subprocess` must be enabled. Nothing is pushed if
+    /// the remote doesn't support atomic pushes.
+    #[arg(long)]
+    atomic: bool,
+    /// Send this option to the server (can be repeated)
+    ///
+    /// The options are transmitted with Git's push-options capability, and
+    /// can be used to trigger server-side automation. The push fails if the
+    /// remote doesn't support push options.
+    #[arg(long = "option", short = 'o', value_name = "OPTION")]
+    options: Vec<String>,
+    /// Report detailed reasons for refs rejected by the remote
+    ///
+    /// This runs the `git` executable, so `git.subprocess` must be enabled.
+    /// `git` negotiates Git's report-status-v2 protocol if the remote supports
+    /// it. The remote then reports why each rejected ref was refused, e.g.
+    /// "remote rejected: hook declined".
+    #[arg(long)]
+    report_status_v2: bool,
+    /// Push all bookmarks, tags, and Git notes, overwriting the remote refs
+    ///
+    /// The bookmarks are exported to the underlying Git repo first, then all
+    /// local Git branches, tags, and notes are force-pushed. Remote branches
+    /// that were deleted locally are deleted on the remote. Nothing is pushed
+    /// if a remote branch moved since it was last fetched. This bypasses the
+    /// tracking-bookmark model, and is meant for maintaining a backup mirror.
+    #[arg(long, conflicts_with_all = ["dry_run", "retry"])]
+    mirror: bool,
+    /// Sign the given tag before mirroring it to the remote
+    ///
+    /// The lightweight tag is replaced with an annotated tag signed with the
+    /// configured signing backend and `signing.key`. Annotated tags can't be
+    /// signed. If the push fails, the tag is restored. Can be repeated.
+    #[arg(long, value_name = "TAG", requires = "mirror")]
+    sign_tags: Vec<String>,
 }
 
 fn make_bookmark_term(bookmark_names: &[impl fmt::Display]) -> String {
@@ -151,10 +259,22 @@ pub fn cmd_git_push(
         get_default_push_remote(ui, command.settings(), &git_repo)?
     };
 
+    if args.mirror {
+        return push_mirror(
+            ui,
+            command,
+            &mut workspace_command,
+            &git_repo,
+            &remote,
+            &args.sign_tags,
+        );
+    }
+
     let repo = workspace_command.repo().clone();
     let mut tx = workspace_command.start_transaction();
     let tx_description;
     let mut bookmark_updates = vec![];
+    let mut pruned_bookmark_names = vec![];
     if args.all {
         for (bookmark_name, targets) in repo.view().local_remote_bookmarks(&remote) {
             match classify_bookmark_update(bookmark_name, &remote, targets) {
@@ -203,8 +323,12 @@ pub fn cmd_git_push(
         } else {
             command.settings().push_bookmark_prefix()
         };
-        let change_bookmark_names =
+        let mut change_bookmark_names =
             update_change_bookmarks(ui, &mut tx, &args.change, &bookmark_prefix)?;
+        if let Some(bookmark_name) = &args.create {
+            create_wc_parent_bookmark(ui, &mut tx, bookmark_name)?;
+            change_bookmark_names.push(bookmark_name.clone());
+        }
         let change_bookmarks = change_bookmark_names.iter().map(|bookmark_name| {
             let targets = LocalAndRemoteRef {
                 local_target: tx.repo().view().get_local_bookmark(bookmark_name),
@@ -227,8 +351,10 @@ pub fn cmd_git_push(
             }
         }
 
-        let use_default_revset =
-            args.bookmark.is_empty() && args.change.is_empty() && args.revisions.is_empty();
+        let use_default_revset = args.bookmark.is_empty()
+            && args.change.is_empty()
+            && args.revisions.is_empty()
+            && args.create.is_none();
         let bookmarks_targeted = find_bookmarks_targeted_by_revisions(
             ui,
             tx.base_workspace_helper(),
@@ -236,16 +362,91 @@ pub fn cmd_git_push(
             &args.revisions,
             use_default_revset,
         )?;
+        let mut skipped_untracked_names = vec![];
         for &(bookmark_name, targets) in &bookmarks_targeted {
             if !seen_bookmarks.insert(bookmark_name) {
                 continue;
             }
+            if !args.include_untracked_bookmarks && !targets.remote_ref.is_tracking() {
+                skipped_untracked_names.push(bookmark_name);
+                continue;
+            }
             match classify_bookmark_update(bookmark_name, &remote, targets) {
                 Ok(Some(update)) => bookmark_updates.push((bookmark_name.to_owned(), update)),
                 Ok(None) => {}
                 Err(reason) => reason.print(ui)?,
             }
         }
+        if !skipped_untracked_names.is_empty() {
+            writeln!(
+                ui.warning_default(),
+                "Not pushing bookmarks that don't track {remote}:"
+            )?;
+            for name in &skipped_untracked_names {
+                writeln!(ui.warning_no_heading(), "  {name}")?;
+            }
+            writeln!(
+                ui.hint_default(),
+                "Use --include-untracked-bookmarks to push them, or name them with --bookmark."
+            )?;
+        }
+        // In a colocated repo, Git HEAD is detached at the working-copy parent,
+        // so make it clear that only bookmarks are pushed.
+        if use_default_revset
+            && !bookmark_updates.is_empty()
+            && tx.base_workspace_helper().working_copy_shared_with_git()
+        {
+            writeln!(
+                ui.status(),
+                "Pushing {} rather than the detached Git HEAD",
+                make_bookmark_term(
+                    &bookmark_updates
+                        .iter()
+                        .map(|(bookmark, _)| bookmark.as_str())
+                        .collect_vec()
+                )
+            )?;
+        }
+
+        if args.prune {
+            let pruned_bookmarks = repo
+                .view()
+                .local_remote_bookmarks(&remote)
+                .filter(|(bookmark_name, targets)| {
+                    targets.local_target.is_absent()
+                        && targets.remote_ref.is_present()
+                        && targets.remote_ref.is_tracking()
+                        && !seen_bookmarks.contains(bookmark_name)
+                })
+                .collect_vec();
+            if !pruned_bookmarks.is_empty() {
+                let bookmark_term = make_bookmark_term(
+                    &pruned_bookmarks
+                        .iter()
+                        .map(|(bookmark_name, _)| *bookmark_name)
+                        .collect_vec(),
+                );
+                let confirmed = args.dry_run
+                    || ui.prompt_yes_no(
+                        &format!("Delete {bookmark_term} on {remote}?"),
+                        Some(false),
+                    )?;
+                if confirmed {
+                    for (bookmark_name, targets) in pruned_bookmarks {
+                        match classify_bookmark_update(bookmark_name, &remote, targets) {
+                            Ok(Some(update)) => {
+                                bookmark_updates.push((bookmark_name.to_owned(), update));
+                                pruned_bookmark_names.push(bookmark_name);
+                            }
+                            Ok(None) => {}
+                            Err(reason) => reason.print(ui)?,
+                        }
+                    }
+                } else {
+                    writeln!(ui.status(), "Not deleting {bookmark_term} on {remote}.")?;
+                }
+            }
+        }
 
         tx_description = format!(
             "push {} to git remote {}",
@@ -262,11 +463,45 @@ pub fn cmd_git_push(
         writeln!(ui.status(), "Nothing changed.")?;
         return Ok(());
     }
+    if let Some(expected_id) = &args.expect {
+        if expected_id.as_bytes().len() != repo.store().commit_id_length() {
+            return Err(user_error(format!(
+                "--expect requires a full commit ID, got {}",
+                expected_id.hex()
+            )));
+        }
+        let mut deletions = bookmark_updates
+            .iter_mut()
+            .filter(|(_, update)| update.new_target.is_none());
+        match (deletions.next(), deletions.next()) {
+            (Some((_, update)), None) => update.old_target = Some(expected_id.clone()),
+            (None, _) => {
+                return Err(user_error(
+                    "--expect was given, but no bookmarks are being deleted",
+                ));
+            }
+            (Some(_), Some(_)) => {
+                return Err(user_error(
+                    "--expect can only be used when deleting a single bookmark",
+                ));
+            }
+        }
+    }
 
     validate_commits_ready_to_push(ui, &bookmark_updates, &remote, &tx, command, args)?;
+    if args.deduplicate_commits {
+        deduplicate_commits(ui, command, &mut tx, &remote, &mut bookmark_updates)?;
+        if bookmark_updates.is_empty() {
+            writeln!(ui.status(), "Nothing changed.")?;
+            if !args.dry_run {
+                tx.finish(ui, tx_description)?;
+            }
+            return Ok(());
+        }
+    }
     if let Some(mut formatter) = ui.status_formatter() {
         writeln!(formatter, "Changes to push to {remote}:")?;
-        print_commits_ready_to_push(formatter.as_mut(), repo.as_ref(), &bookmark_updates)?;
+        print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &bookmark_updates)?;
     }
 
     if args.dry_run {
@@ -277,14 +512,80 @@ pub fn cmd_git_push(
     let targets = GitBranchPushTargets {
         branch_updates: bookmark_updates,
     };
+    let git_settings = command.settings().git_settings();
+    let initial_retry_delay = get_push_retry_delay(command.settings())?;
+    if git_settings.run_hooks && !args.no_verify {
+        git::run_pre_push_hook(&git_repo, &remote, &targets).map_err(|err| match err {
+            GitPushError::HookFailed(_) => {
+                user_error_with_hint(err, "Use --no-verify to skip the hook.")
+            }
+            GitPushError::InternalGitError(err) => map_git_error(err),
+            _ => user_error(err),
+        })?;
+    }
     let mut writer = GitSidebandProgressMessageWriter::new(ui);
     let mut sideband_progress_callback = |progress_message: &[u8]| {
         _ = writer.write(ui, progress_message);
     };
-    with_remote_git_callbacks(ui, Some(&mut sideband_progress_callback), |cb| {
-        git::push_branches(tx.repo_mut(), &git_repo, &remote, &targets, cb)
-    })
-    .map_err(|err| match err {
+    let mut attempt = 0;
+    let push_result = loop {
+        attempt += 1;
+        let result = with_remote_git_callbacks(ui, Some(&mut sideband_progress_callback), |cb| {
+            if args.atomic {
+                git::push_branches_atomic(
+                    tx.repo_mut(),
+                    &git_repo,
+                    &remote,
+                    &targets,
+                    &args.options,
+                    cb,
+                    &git_settings,
+                )
+            } else if args.report_status_v2 {
+                git::push_branches_with_git(
+                    tx.repo_mut(),
+                    &git_repo,
+                    &remote,
+                    &targets,
+                    &args.options,
+                    cb,
+                    &git_settings,
+                )
+            } else if !args.options.is_empty() {
+                git::push_branches_with_options(
+                    tx.repo_mut(),
+                    &git_repo,
+                    &remote,
+                    &targets,
+                    &args.options,
+                    cb,
+                )
+            } else {
+                git::push_branches(tx.repo_mut(), &git_repo, &remote, &targets, cb)
+            }
+        });
+        match result {
+            Err(err) if err.is_transient() && attempt <= args.retry => {
+                let delay = initial_retry_delay * (1 << (attempt - 1).min(6));
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to push (attempt {attempt} of {total}): {err}",
+                    total = args.retry + 1,
+                    err = match &err {
+                        GitPushError::InternalGitError(err) => err.message().to_owned(),
+                        _ => err.to_string(),
+                    },
+                )?;
+                writeln!(ui.status(), "Retrying in {}s...", delay.as_secs())?;
+                thread::sleep(delay);
+            }
+            result => break result,
+        }
+    };
+    push_result.map_err(|err| match err {
+        GitPushError::InternalGitError(err) if attempt > 1 => {
+            map_git_error(err).hinted(format!("Gave up after {attempt} attempts."))
+        }
         GitPushError::InternalGitError(err) => map_git_error(err),
         GitPushError::RefInUnexpectedLocation(refs) => user_error_with_hint(
             format!(
@@ -295,13 +596,136 @@ pub fn cmd_git_push(
             "Try fetching from the remote, then make the bookmark point to where you want it to \
              be, and push again.",
         ),
+        GitPushError::AtomicPushNotSupported(_) => user_error_with_hint(
+            err,
+            "Push without --atomic to update the bookmarks one by one.",
+        ),
+        GitPushError::GitCommandDisabled => user_error_with_hint(
+            err,
+            "--atomic and --report-status-v2 need the `git` executable. Set \
+             `git.subprocess = true` to allow running it.",
+        ),
         _ => user_error(err),
     })?;
     writer.flush(ui)?;
+    if args.atomic {
+        writeln!(ui.status(), "Pushed atomically to {remote}")?;
+    }
+    if !pruned_bookmark_names.is_empty() {
+        writeln!(
+            ui.status(),
+            "Pruned {} from {remote}",
+            make_bookmark_term(&pruned_bookmark_names)
+        )?;
+    }
+    let base_view = tx.base_repo().view();
+    for (bookmark_name, update) in &targets.branch_updates {
+        // Pushed bookmarks are marked as tracking, deleted ones are gone.
+        if update.new_target.is_some()
+            && !base_view
+                .get_remote_bookmark(bookmark_name, &remote)
+                .is_tracking()
+        {
+            writeln!(
+                ui.status(),
+                "Started tracking bookmark {bookmark_name}@{remote}"
+            )?;
+        }
+    }
     tx.finish(ui, tx_description)?;
     Ok(())
 }
 
+fn push_mirror(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &mut WorkspaceCommandHelper,
+    git_repo: &git2::Repository,
+    remote: &str,
+    sign_tags: &[String],
+) -> Result<(), CommandError> {
+    let mut tx = workspace_command.start_transaction();
+    let failed_refs = git::export_refs(tx.repo_mut())?;
+    print_failed_git_export(ui, &failed_refs)?;
+    let signed_tags = if !sign_tags.is_empty() {
+        let signer = tx.repo().store().signer();
+        if !signer.can_sign() {
+            return Err(user_error_with_hint(
+                "Cannot sign tags because no signing backend is configured",
+                "Set `signing.backend` to sign tags.",
+            ));
+        }
+        let key = SignSettings::from_settings(command.settings()).key;
+        git::sign_tags(
+            git_repo,
+            sign_tags,
+            &command.settings().signature(),
+            |data| signer.sign(data, key.as_deref()),
+        )
+        .map_err(|err| match err {
+            GitSignTagsError::InternalGitError(err) => map_git_error(err),
+            _ => user_error(err),
+        })?
+    } else {
+        vec![]
+    };
+    writeln!(ui.status(), "Mirroring all refs to {remote}")?;
+    let mut writer = GitSidebandProgressMessageWriter::new(ui);
+    let mut sideband_progress_callback = |progress_message: &[u8]| {
+        _ = writer.write(ui, progress_message);
+    };
+    let push_result = with_remote_git_callbacks(ui, Some(&mut sideband_progress_callback), |cb| {
+        git::push_mirror(git_repo, remote, cb)
+    });
+    if let Err(err) = push_result {
+        // Don't leave the signed tags behind if they weren't published.
+        git::unsign_tags(git_repo, &signed_tags).map_err(map_git_error)?;
+        // The Git refs were exported, so record that in the view. Otherwise,
+        // the next export would fail to update them.
+        if tx.repo().has_changes() {
+            tx.finish(ui, "export git refs")?;
+        }
+        return Err(match err {
+            GitPushError::InternalGitError(err) => map_git_error(err),
+            GitPushError::RefInUnexpectedLocation(refs) => user_error_with_hint(
+                format!(
+                    "Refusing to mirror refs that unexpectedly moved on the remote. Affected \
+                     refs: {}",
+                    refs.join(", ")
+                ),
+                "Try fetching from the remote, then push again.",
+            ),
+            _ => user_error(err),
+        });
+    }
+    writer.flush(ui)?;
+    if !signed_tags.is_empty() {
+        writeln!(
+            ui.status(),
+            "Pushed signed tags: {}",
+            signed_tags.iter().map(|tag| &tag.name).join(", ")
+        )?;
+    }
+    // Import the remote-tracking branches and signed tags updated by the push,
+    // and track the pushed bookmarks as a normal push would do.
+    let stats = git::import_refs(tx.repo_mut(), &command.settings().git_settings())?;
+    print_git_import_stats(ui, tx.repo(), &stats, false, true)?;
+    let untracked_names = tx
+        .repo()
+        .view()
+        .local_remote_bookmarks(remote)
+        .filter(|(_, targets)| {
+            targets.local_target.is_present() && !targets.remote_ref.is_tracking()
+        })
+        .map(|(name, _)| name.to_owned())
+        .collect_vec();
+    for name in &untracked_names {
+        tx.repo_mut().track_remote_bookmark(name, remote);
+    }
+    tx.finish(ui, format!("push all refs to git remote {remote}"))?;
+    Ok(())
+}
+
 /// Validates that the commits that will be pushed are ready (have authorship
 /// information, are not conflicted, etc.)
 fn validate_commits_ready_to_push(
@@ -338,12 +762,24 @@ fn validate_commits_ready_to_push(
     } else {
         Box::new(|_: &CommitId| Ok(false))
     };
+    let passes_check = if let Some(revset) = &args.pre_push_check {
+        workspace_helper
+            .parse_revset(ui, revset)?
+            .evaluate()?
+            .containing_fn()
+    } else {
+        Box::new(|_: &CommitId| Ok(true))
+    };
 
+    let mut failed_check = vec![];
     for commit in workspace_helper
         .attach_revset_evaluator(commits_to_push)
         .evaluate_to_commits()?
     {
         let commit = commit?;
+        if !passes_check(commit.id())? {
+            failed_check.push(commit.clone());
+        }
         let mut reasons = vec![];
         if commit.description().is_empty() && !args.allow_empty_description {
             reasons.push("it has no description");
@@ -373,6 +809,139 @@ fn validate_commits_ready_to_push(
             )));
         }
     }
+    if !failed_check.is_empty() {
+        let mut cmd_err = user_error(format!(
+            "Won't push {} commits since they don't satisfy the pre-push check",
+            failed_check.len()
+        ));
+        for commit in &failed_check {
+            cmd_err.add_formatted_hint_with(|formatter| {
+                write!(formatter, "Failing commit: ")?;
+                workspace_helper.write_commit_summary(formatter, commit)
+            });
+        }
+        return Err(cmd_err);
+    }
+    Ok(())
+}
+
+/// Replaces commits to push with equivalent commits that already exist on the
+/// remote, and updates the bookmark targets accordingly.
+fn deduplicate_commits(
+    ui: &Ui,
+    command: &CommandHelper,
+    tx: &mut WorkspaceCommandTransaction,
+    remote: &str,
+    bookmark_updates: &mut Vec<(String, BookmarkPushUpdate)>,
+) -> Result<(), CommandError> {
+    let workspace_helper = tx.base_workspace_helper();
+    let repo = workspace_helper.repo();
+    let new_heads = bookmark_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .collect_vec();
+    let old_heads = repo
+        .view()
+        .remote_bookmarks(remote)
+        .flat_map(|(_, old_head)| old_head.target.added_ids())
+        .cloned()
+        .collect_vec();
+    let immutable_heads = workspace_helper.env().immutable_heads_expression();
+    let commits_to_push = RevsetExpression::commits(old_heads)
+        .union(immutable_heads)
+        .range(&RevsetExpression::commits(new_heads.clone()));
+    let candidates = RevsetExpression::remote_bookmarks(
+        StringPattern::everything(),
+        StringPattern::exact(remote),
+        None,
+    )
+    .ancestors()
+    .minus(
+        &immutable_heads
+            .union(&RevsetExpression::commits(new_heads))
+            .ancestors(),
+    );
+    let mut candidates_by_description: HashMap<String, Vec<_>> = HashMap::new();
+    for commit in workspace_helper
+        .attach_revset_evaluator(candidates)
+        .evaluate_to_commits()?
+    {
+        let commit = commit?;
+        let key = commit.description().to_owned();
+        candidates_by_description
+            .entry(key)
+            .or_default()
+            .push(commit);
+    }
+
+    // Map local commits to remote ones, parents first, so that stacked
+    // commits can be matched.
+    let mut mapping: HashMap<CommitId, CommitId> = HashMap::new();
+    let local_commits: Vec<_> = workspace_helper
+        .attach_revset_evaluator(commits_to_push)
+        .evaluate_to_commits()?
+        .try_collect()?;
+    for commit in local_commits.iter().rev() {
+        let Some(candidates) = candidates_by_description.get(commit.description()) else {
+            continue;
+        };
+        let mapped_parent_ids = commit
+            .parent_ids()
+            .iter()
+            .map(|id| mapping.get(id).unwrap_or(id))
+            .collect_vec();
+        let equivalent = candidates.iter().find(|candidate| {
+            candidate.tree_id() == commit.tree_id()
+                && candidate.author() == commit.author()
+                && candidate
+                    .parent_ids()
+                    .iter()
+                    .eq(mapped_parent_ids.iter().copied())
+        });
+        if let Some(equivalent) = equivalent {
+            mapping.insert(commit.id().clone(), equivalent.id().clone());
+        }
+    }
+
+    let replaced = local_commits
+        .iter()
+        .filter_map(|commit| Some((commit.id().clone(), mapping.get(commit.id())?.clone())))
+        .collect_vec();
+    if replaced.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Replacing {} commits with equivalent commits on {remote}:",
+            replaced.len()
+        )?;
+        for (old_id, new_id) in &replaced {
+            writeln!(
+                formatter,
+                "  {} -> {}",
+                short_commit_hash(old_id),
+                short_commit_hash(new_id)
+            )?;
+        }
+    }
+    for (old_id, new_id) in replaced {
+        tx.repo_mut().set_rewritten_commit(old_id, new_id);
+    }
+    tx.repo_mut().rebase_descendants(command.settings())?;
+    // The bookmarks to push have been moved along with the rewritten commits
+    for (name, update) in bookmark_updates.iter_mut() {
+        if update.new_target.is_some() {
+            update.new_target = tx
+                .repo()
+                .view()
+                .get_local_bookmark(name)
+                .as_normal()
+                .cloned();
+        }
+    }
+    bookmark_updates.retain(|(_, update)| update.new_target != update.old_target);
     Ok(())
 }
 
@@ -438,6 +1007,17 @@ fn print_commits_ready_to_push(
     Ok(())
 }
 
+/// Returns the delay before the first retry of a failed push. The delay is
+/// doubled after each further attempt.
+fn get_push_retry_delay(settings: &UserSettings) -> Result<Duration, CommandError> {
+    let secs = settings
+        .config()
+        .get("git.push-retry-delay")
+        .optional()?
+        .unwrap_or(1);
+    Ok(Duration::from_secs(secs))
+}
+
 fn get_default_push_remote(
     ui: &Ui,
     settings: &UserSettings,
@@ -560,6 +1140,53 @@ fn update_change_bookmarks(
     Ok(bookmark_names)
 }
 
+/// Creates a new bookmark pointing to the working-copy parent.
+fn create_wc_parent_bookmark(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    bookmark_name: &str,
+) -> Result<(), CommandError> {
+    let workspace_command = tx.base_workspace_helper();
+    let Some(wc_commit_id) = workspace_command.get_wc_commit_id() else {
+        return Err(user_error("Nothing checked out in this workspace"));
+    };
+    if tx
+        .base_repo()
+        .view()
+        .get_local_bookmark(bookmark_name)
+        .is_present()
+    {
+        return Err(user_error_with_hint(
+            format!("Bookmark {bookmark_name} already exists"),
+            format!("Use `jj git push --bookmark {bookmark_name}` to push it."),
+        ));
+    }
+    let wc_commit = tx.base_repo().store().get_commit(wc_commit_id)?;
+    let parent = match wc_commit.parent_ids() {
+        [parent_id] if parent_id != tx.base_repo().store().root_commit_id() => {
+            tx.base_repo().store().get_commit(parent_id)?
+        }
+        [_] => {
+            return Err(user_error(
+                "Cannot create a bookmark because the working-copy parent is the root commit",
+            ))
+        }
+        _ => {
+            return Err(user_error(
+                "Cannot create a bookmark because the working-copy commit has multiple parents",
+            ))
+        }
+    };
+    writeln!(
+        ui.status(),
+        "Creating bookmark {bookmark_name} for revision {}",
+        short_change_hash(parent.change_id())
+    )?;
+    tx.repo_mut()
+        .set_local_bookmark_target(bookmark_name, RefTarget::normal(parent.id().clone()));
+    Ok(())
+}
+
 fn find_bookmarks_to_push<'a>(
     view: &'a View,
     bookmark_patterns: &[StringPattern],
@@ -621,6 +1248,13 @@ fn find_bookmarks_targeted_by_revisions<'a>(
                 "No bookmarks found in the default push revset: \
                  remote_bookmarks(remote={remote_name})..@"
             )?;
+            if workspace_command.working_copy_shared_with_git() {
+                writeln!(
+                    ui.hint_default(),
+                    "Git HEAD is detached and isn't pushed. Use `jj git push --create <NAME>` \
+                     to create a bookmark at the working-copy parent and push it."
+                )?;
+            }
         }
     }
     for rev_arg in revisions {
@@ -14,6 +14,7 @@
 
 use jj_lib::git;
 use jj_lib::repo::Repo;
+use jj_lib::str_util::StringPattern;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -27,6 +28,13 @@ pub struct GitRemoteAddArgs {
     remote: String,
     /// The remote's URL
     url: String,
+    /// Fetch only the branches matching this glob (can be repeated)
+    ///
+    /// The glob is stored in the fetch refspec of the remote in the Git
+    /// config, so it also applies to `git fetch`. By default, all branches are
+    /// fetched.
+    #[arg(long, value_name = "GLOB", value_parser = StringPattern::glob)]
+    track: Vec<StringPattern>,
 }
 
 pub fn cmd_git_remote_add(
@@ -37,6 +45,6 @@ pub fn cmd_git_remote_add(
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
     let git_repo = get_git_repo(repo.store())?;
-    git::add_remote(&git_repo, &args.remote, &args.url)?;
+    git::add_remote(&git_repo, &args.remote, &args.url, &args.track)?;
     Ok(())
 }
@@ -69,7 +69,15 @@ pub(crate) fn cmd_init(
     // a colocated repo.
     let colocate = false;
     if args.git || args.git_repo.is_some() {
-        git::init::do_init(ui, command, &wc_path, colocate, args.git_repo.as_deref())?;
+        git::init::do_init(
+            ui,
+            command,
+            &wc_path,
+            colocate,
+            args.git_repo.as_deref(),
+            None,
+            &[],
+        )?;
         writeln!(
             ui.warning_default(),
             "`--git` and `--git-repo` are deprecated.
@@ -12,8 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::io;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::graph::GraphEdge;
 use jj_lib::graph::GraphEdgeType;
+use jj_lib::graph::GraphNode;
 use jj_lib::graph::ReverseGraphIterator;
 use jj_lib::graph::TopoGroupedGraphIterator;
 use jj_lib::repo::Repo;
@@ -23,15 +33,19 @@ use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::RevsetIteratorExt;
 use jj_lib::settings::ConfigResultExt as _;
 use jj_lib::settings::UserSettings;
+use jj_lib::str_util::StringPattern;
+use jj_lib::str_util::StringPatternParseError;
 use tracing::instrument;
 
 use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::diff_util::DiffFormatArgs;
+use crate::formatter::Formatter;
 use crate::graphlog::get_graphlog;
 use crate::graphlog::Edge;
 use crate::graphlog::GraphStyle;
@@ -56,6 +70,19 @@ pub(crate) struct LogArgs {
     /// Show revisions modifying the given paths
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Only show revisions whose author matches the given pattern
+    ///
+    /// Matches a substring of the name or email, like the `author()` revset
+    /// function. Use a prefix such as `exact:` or `glob:` for other kinds of
+    /// patterns. Can be repeated to match any of the patterns. The filter is
+    /// applied to `--revisions` (or the default revset).
+    #[arg(long, value_name = "PATTERN", value_parser = parse_signature_pattern)]
+    author: Vec<StringPattern>,
+    /// Only show revisions whose committer matches the given pattern
+    ///
+    /// Works like `--author`, but matches the committer instead.
+    #[arg(long, value_name = "PATTERN", value_parser = parse_signature_pattern)]
+    committer: Vec<StringPattern>,
     /// Show revisions in the opposite order (older revisions first)
     #[arg(long)]
     reversed: bool,
@@ -73,6 +100,10 @@ pub(crate) struct LogArgs {
     )]
     deprecated_limit: Option<usize>,
     /// Don't show the graph, show a flat list of revisions
+    ///
+    /// Revisions are printed as they are found, without waiting for the whole
+    /// revset to be evaluated. With `--reversed`, the revset still has to be
+    /// evaluated before the first revision is printed.
     #[arg(long)]
     no_graph: bool,
     /// Render each revision using the given template
@@ -80,6 +111,28 @@ pub(crate) struct LogArgs {
     /// For the syntax, see https://martinvonz.github.io/jj/latest/templates/
     #[arg(long, short = 'T')]
     template: Option<String>,
+    /// Render each revision using the template read from the given file
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "template",
+        value_hint = clap::ValueHint::FilePath
+    )]
+    template_file: Option<PathBuf>,
+    /// Collapse long linear runs of commits into a single elided node
+    ///
+    /// Commits with a single parent and a single child in the graph are
+    /// collapsed unless they have bookmarks or tags, or are the working-copy
+    /// commit of a workspace. Only runs of at least `ui.log-elide-threshold`
+    /// commits are collapsed.
+    #[arg(long, alias = "elide-unchanged", conflicts_with_all = ["no_graph", "reversed"])]
+    elide: bool,
+    /// Show the signature verification status of each revision
+    ///
+    /// Signatures are verified by the configured signing backends. The status
+    /// is rendered by the `templates.show_signature` template.
+    #[arg(long)]
+    show_signature: bool,
     /// Show patch
     #[arg(long, short = 'p')]
     patch: bool,
@@ -87,6 +140,16 @@ pub(crate) struct LogArgs {
     diff_format: DiffFormatArgs,
 }
 
+/// Parses an author or committer pattern, which matches a substring by
+/// default.
+fn parse_signature_pattern(src: &str) -> Result<StringPattern, StringPatternParseError> {
+    if let Some((kind, pattern)) = src.split_once(':') {
+        StringPattern::from_str_kind(pattern, kind)
+    } else {
+        Ok(StringPattern::substring(src))
+    }
+}
+
 #[instrument(skip_all)]
 pub(crate) fn cmd_log(
     ui: &mut Ui,
@@ -113,6 +176,26 @@ pub(crate) fn cmd_log(
             let predicate = RevsetFilterPredicate::File(fileset_expression.clone());
             expression.intersect_with(&RevsetExpression::filter(predicate));
         }
+        if !args.author.is_empty() {
+            let filters = args
+                .author
+                .iter()
+                .map(|pattern| {
+                    RevsetExpression::filter(RevsetFilterPredicate::Author(pattern.clone()))
+                })
+                .collect_vec();
+            expression.intersect_with(&RevsetExpression::union_all(&filters));
+        }
+        if !args.committer.is_empty() {
+            let filters = args
+                .committer
+                .iter()
+                .map(|pattern| {
+                    RevsetExpression::filter(RevsetFilterPredicate::Committer(pattern.clone()))
+                })
+                .collect_vec();
+            expression.intersect_with(&RevsetExpression::union_all(&filters));
+        }
         expression
     };
 
@@ -131,12 +214,22 @@ pub(crate) fn cmd_log(
     let with_content_format = LogContentFormat::new(ui, command.settings())?;
 
     let template;
+    let signature_template;
     let node_template;
     {
         let language = workspace_command.commit_template_language();
-        let template_string = match &args.template {
-            Some(value) => value.to_string(),
-            None => command.settings().config().get_string("templates.log")?,
+        let template_string = match (&args.template, &args.template_file) {
+            (Some(value), _) => value.clone(),
+            (None, Some(path)) => {
+                let path = command.cwd().join(path);
+                std::fs::read_to_string(&path).map_err(|err| {
+                    user_error_with_message(
+                        format!("Failed to read template file {}", path.display()),
+                        err,
+                    )
+                })?
+            }
+            (None, None) => command.settings().config().get_string("templates.log")?,
         };
         template = workspace_command
             .parse_template(
@@ -146,6 +239,21 @@ pub(crate) fn cmd_log(
                 CommitTemplateLanguage::wrap_commit,
             )?
             .labeled("log");
+        signature_template = if args.show_signature {
+            let template_string = command
+                .settings()
+                .config()
+                .get_string("templates.show_signature")?;
+            let template = workspace_command.parse_template(
+                ui,
+                &language,
+                &template_string,
+                CommitTemplateLanguage::wrap_commit,
+            )?;
+            Some(template.labeled("log"))
+        } else {
+            None
+        };
         node_template = workspace_command
             .parse_template(
                 ui,
@@ -155,6 +263,13 @@ pub(crate) fn cmd_log(
             )?
             .labeled("node");
     }
+    let format_commit = |commit: &Commit, formatter: &mut dyn Formatter| -> io::Result<()> {
+        template.format(commit, formatter)?;
+        if let Some(template) = &signature_template {
+            template.format(commit, formatter)?;
+        }
+        Ok(())
+    };
 
     {
         ui.request_pager();
@@ -178,8 +293,53 @@ pub(crate) fn cmd_log(
             } else {
                 Box::new(forward_iter)
             };
+            let iter: Box<dyn Iterator<Item = _>> = if args.elide {
+                let threshold = command
+                    .settings()
+                    .config()
+                    .get::<usize>("ui.log-elide-threshold")?;
+                let view = repo.view();
+                let kept_ids = itertools::chain!(
+                    view.local_bookmarks()
+                        .flat_map(|(_, target)| target.added_ids()),
+                    view.all_remote_bookmarks()
+                        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids()),
+                    view.tags().values().flat_map(|target| target.added_ids()),
+                    view.wc_commit_ids().values(),
+                )
+                .cloned()
+                .collect();
+                Box::new(ElideLinearRuns::new(iter, threshold, kept_ids))
+            } else {
+                Box::new(iter.map_ok(LogGraphNode::Commit))
+            };
             for node in iter.take(limit) {
-                let (commit_id, edges) = node?;
+                let (commit_id, edges) = match node? {
+                    LogGraphNode::Commit(node) => node,
+                    LogGraphNode::ElidedRun { id, count, edges } => {
+                        let key = (id, false);
+                        let edges = edges
+                            .into_iter()
+                            .map(|edge| Edge::Direct((edge.target, false)))
+                            .collect_vec();
+                        let mut buffer = vec![];
+                        let within_graph = with_content_format.sub_width(graph.width(&key, &edges));
+                        within_graph.write(
+                            ui.new_formatter(&mut buffer).as_mut(),
+                            |formatter| {
+                                writeln!(formatter.labeled("elided"), "(elided {count} revisions)")
+                            },
+                        )?;
+                        let node_symbol = format_template(ui, &None, &node_template);
+                        graph.add_node(
+                            &key,
+                            &edges,
+                            &node_symbol,
+                            &String::from_utf8_lossy(&buffer),
+                        )?;
+                        continue;
+                    }
+                };
 
                 // The graph is keyed by (CommitId, is_synthetic)
                 let mut graphlog_edges = vec![];
@@ -215,7 +375,7 @@ pub(crate) fn cmd_log(
                 let within_graph =
                     with_content_format.sub_width(graph.width(&key, &graphlog_edges));
                 within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                    template.format(&commit, formatter)
+                    format_commit(&commit, formatter)
                 })?;
                 if !buffer.ends_with(b"\n") {
                     buffer.push(b'\n');
@@ -267,11 +427,14 @@ pub(crate) fn cmd_log(
             for commit_or_error in iter.commits(store).take(limit) {
                 let commit = commit_or_error?;
                 with_content_format
-                    .write(formatter, |formatter| template.format(&commit, formatter))?;
+                    .write(formatter, |formatter| format_commit(&commit, formatter))?;
                 if let Some(renderer) = &diff_renderer {
                     let width = ui.term_width();
                     renderer.show_patch(ui, formatter, &commit, matcher.as_ref(), width)?;
                 }
+                // Stdout is line-buffered, so flush explicitly to stream the
+                // entries even if the template doesn't end with a newline.
+                formatter.flush()?;
             }
         }
     }
@@ -303,6 +466,106 @@ pub(crate) fn cmd_log(
     Ok(())
 }
 
+enum LogGraphNode {
+    Commit(GraphNode<CommitId>),
+    /// Linear run of `count` commits, `id` being the one closest to the heads.
+    ElidedRun {
+        id: CommitId,
+        count: usize,
+        edges: Vec<GraphEdge<CommitId>>,
+    },
+}
+
+/// Collapses linear runs of graph nodes into `LogGraphNode::ElidedRun`.
+///
+/// Nodes must be emitted children first. A node can be part of a run if it
+/// has exactly one child and one direct parent in the graph, and isn't in
+/// `kept_ids`.
+struct ElideLinearRuns<I> {
+    iter: I,
+    threshold: usize,
+    kept_ids: HashSet<CommitId>,
+    child_counts: HashMap<CommitId, usize>,
+    run: Vec<GraphNode<CommitId>>,
+    ready: VecDeque<LogGraphNode>,
+}
+
+impl<I> ElideLinearRuns<I> {
+    fn new(iter: I, threshold: usize, kept_ids: HashSet<CommitId>) -> Self {
+        ElideLinearRuns {
+            iter,
+            threshold,
+            kept_ids,
+            child_counts: HashMap::new(),
+            run: vec![],
+            ready: VecDeque::new(),
+        }
+    }
+
+    fn is_elidable(&self, (id, edges): &GraphNode<CommitId>) -> bool {
+        matches!(edges.as_slice(), [edge] if edge.edge_type == GraphEdgeType::Direct)
+            && self.child_counts.get(id) == Some(&1)
+            && !self.kept_ids.contains(id)
+    }
+
+    fn flush_run(&mut self) {
+        if self.run.is_empty() {
+            return;
+        }
+        if self.run.len() >= self.threshold {
+            let count = self.run.len();
+            let id = self.run[0].0.clone();
+            let (_, edges) = self.run.pop().unwrap();
+            self.run.clear();
+            self.ready
+                .push_back(LogGraphNode::ElidedRun { id, count, edges });
+        } else {
+            self.ready
+                .extend(self.run.drain(..).map(LogGraphNode::Commit));
+        }
+    }
+}
+
+impl<I, E> Iterator for ElideLinearRuns<I>
+where
+    I: Iterator<Item = Result<GraphNode<CommitId>, E>>,
+{
+    type Item = Result<LogGraphNode, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = self.ready.pop_front() {
+                return Some(Ok(node));
+            }
+            let node = match self.iter.next() {
+                Some(Ok(node)) => node,
+                Some(Err(err)) => return Some(Err(err)),
+                None if self.run.is_empty() => return None,
+                None => {
+                    self.flush_run();
+                    continue;
+                }
+            };
+            for edge in &node.1 {
+                *self.child_counts.entry(edge.target.clone()).or_default() += 1;
+            }
+            let continues_run = self
+                .run
+                .last()
+                .map_or(true, |(_, edges)| edges[0].target == node.0);
+            if !continues_run {
+                self.flush_run();
+            }
+            if self.is_elidable(&node) {
+                self.run.push(node);
+            } else {
+                self.flush_run();
+                self.ready.push_back(LogGraphNode::Commit(node));
+            }
+        }
+    }
+}
+
 pub fn get_node_template(
     style: GraphStyle,
     settings: &UserSettings,
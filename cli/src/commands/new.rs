@@ -32,6 +32,7 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::commands::diff::resolve_single_rev_at_op;
 use crate::description_util::join_message_paragraphs;
 use crate::ui::Ui;
 
@@ -65,6 +66,9 @@ pub(crate) struct NewArgs {
     #[arg(long, hide = true)]
     _edit: bool,
     /// Insert the new change after the given commit(s)
+    ///
+    /// The children of the given commits are rebased onto the new change, so
+    /// it's inserted in the middle of the stack.
     #[arg(
         long,
         short = 'A',
@@ -80,6 +84,24 @@ pub(crate) struct NewArgs {
         conflicts_with = "revisions"
     )]
     insert_before: Vec<RevisionArg>,
+    /// Insert the new change between the given revisions and their children
+    ///
+    /// This is like `--insert-after`, but takes the revisions from the
+    /// arguments, e.g. `jj new --insert -r <revision>`.
+    #[arg(long, conflicts_with_all = ["insert_after", "insert_before"])]
+    insert: bool,
+    /// Resolve the parent revisions at this operation
+    ///
+    /// This can be used to create a new change on top of a commit that has
+    /// since been abandoned, e.g. by `jj git fetch`, which makes the commit
+    /// visible again. Each revision must resolve to a single commit at the
+    /// operation.
+    #[arg(
+        long,
+        value_name = "OPERATION",
+        conflicts_with_all = ["insert_after", "insert_before", "insert"]
+    )]
+    revisions_at_op: Option<String>,
 }
 
 #[instrument(skip_all)]
@@ -96,9 +118,14 @@ pub(crate) fn cmd_new(
     let mut advance_bookmarks_target = None;
     let mut advanceable_bookmarks = vec![];
 
-    if !args.insert_before.is_empty() && !args.insert_after.is_empty() {
+    let insert_after = if args.insert {
+        &args.revisions
+    } else {
+        &args.insert_after
+    };
+    if !args.insert_before.is_empty() && !insert_after.is_empty() {
         parent_commits = workspace_command
-            .resolve_some_revsets_default_single(ui, &args.insert_after)?
+            .resolve_some_revsets_default_single(ui, insert_after)?
             .into_iter()
             .collect_vec();
         parent_commit_ids = parent_commits.iter().ids().cloned().collect();
@@ -143,9 +170,9 @@ pub(crate) fn cmd_new(
             .iter()
             .map(|commit_id| workspace_command.repo().store().get_commit(commit_id))
             .try_collect()?;
-    } else if !args.insert_after.is_empty() {
+    } else if !insert_after.is_empty() {
         parent_commits = workspace_command
-            .resolve_some_revsets_default_single(ui, &args.insert_after)?
+            .resolve_some_revsets_default_single(ui, insert_after)?
             .into_iter()
             .collect_vec();
         parent_commit_ids = parent_commits.iter().ids().cloned().collect();
@@ -162,10 +189,19 @@ pub(crate) fn cmd_new(
             .commits(workspace_command.repo().store())
             .try_collect()?;
     } else {
-        parent_commits = workspace_command
-            .resolve_some_revsets_default_single(ui, &args.revisions)?
-            .into_iter()
-            .collect_vec();
+        parent_commits = if let Some(op_str) = &args.revisions_at_op {
+            args.revisions
+                .iter()
+                .map(|revision_arg| {
+                    resolve_single_rev_at_op(ui, command, &workspace_command, revision_arg, op_str)
+                })
+                .process_results(|commits| commits.unique().collect_vec())?
+        } else {
+            workspace_command
+                .resolve_some_revsets_default_single(ui, &args.revisions)?
+                .into_iter()
+                .collect_vec()
+        };
         parent_commit_ids = parent_commits.iter().ids().cloned().collect();
         children_commits = vec![];
 
@@ -14,7 +14,6 @@
 
 use std::collections::HashMap;
 use std::convert::Infallible;
-use std::sync::Arc;
 
 use indexmap::IndexMap;
 use itertools::Itertools;
@@ -31,10 +30,10 @@ use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::RemoteRefState;
 use jj_lib::refs::diff_named_ref_targets;
 use jj_lib::refs::diff_named_remote_refs;
-use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::revset;
 use jj_lib::revset::RevsetIteratorExt as _;
+use jj_lib::view::View;
 
 use crate::cli_util::short_change_hash;
 use crate::cli_util::CommandHelper;
@@ -137,8 +136,8 @@ pub fn cmd_op_diff(
         ui,
         formatter.as_mut(),
         merged_repo,
-        &from_repo,
-        &to_repo,
+        from_repo.view(),
+        to_repo.view(),
         &commit_summary_template,
         (!args.no_graph).then_some(graph_style),
         &with_content_format,
@@ -147,22 +146,22 @@ pub fn cmd_op_diff(
 }
 
 /// Computes and shows the differences between two operations, using the given
-/// `ReadonlyRepo`s for the operations.
-/// `current_repo` should contain a `Repo` with the indices of both repos merged
+/// `View`s for the operations.
+/// `current_repo` should contain a `Repo` with the indices of both views merged
 /// into it.
 #[allow(clippy::too_many_arguments)]
 pub fn show_op_diff(
     ui: &Ui,
     formatter: &mut dyn Formatter,
     current_repo: &dyn Repo,
-    from_repo: &Arc<ReadonlyRepo>,
-    to_repo: &Arc<ReadonlyRepo>,
+    from_view: &View,
+    to_view: &View,
     commit_summary_template: &TemplateRenderer<Commit>,
     graph_style: Option<GraphStyle>,
     with_content_format: &LogContentFormat,
     diff_renderer: Option<&DiffRenderer>,
 ) -> Result<(), CommandError> {
-    let changes = compute_operation_commits_diff(current_repo, from_repo, to_repo)?;
+    let changes = compute_operation_commits_diff(current_repo, from_view, to_view)?;
 
     let commit_id_change_id_map: HashMap<CommitId, ChangeId> = changes
         .iter()
@@ -272,11 +271,9 @@ pub fn show_op_diff(
         }
     }
 
-    let changed_local_bookmarks = diff_named_ref_targets(
-        from_repo.view().local_bookmarks(),
-        to_repo.view().local_bookmarks(),
-    )
-    .collect_vec();
+    let changed_local_bookmarks =
+        diff_named_ref_targets(from_view.local_bookmarks(), to_view.local_bookmarks())
+            .collect_vec();
     if !changed_local_bookmarks.is_empty() {
         writeln!(formatter)?;
         with_content_format.write(formatter, |formatter| {
@@ -305,8 +302,7 @@ pub fn show_op_diff(
         }
     }
 
-    let changed_tags =
-        diff_named_ref_targets(from_repo.view().tags(), to_repo.view().tags()).collect_vec();
+    let changed_tags = diff_named_ref_targets(from_view.tags(), to_view.tags()).collect_vec();
     if !changed_tags.is_empty() {
         writeln!(formatter)?;
         with_content_format.write(formatter, |formatter| writeln!(formatter, "Changed tags:"))?;
@@ -335,8 +331,8 @@ pub fn show_op_diff(
     }
 
     let changed_remote_bookmarks = diff_named_remote_refs(
-        from_repo.view().all_remote_bookmarks(),
-        to_repo.view().all_remote_bookmarks(),
+        from_view.all_remote_bookmarks(),
+        to_view.all_remote_bookmarks(),
     )
     // Skip updates to the local git repo, since they should typically be covered in
     // local branches.
@@ -494,16 +490,16 @@ struct ModifiedChange {
 /// and removed commits for the change ID.
 fn compute_operation_commits_diff(
     repo: &dyn Repo,
-    from_repo: &ReadonlyRepo,
-    to_repo: &ReadonlyRepo,
+    from_view: &View,
+    to_view: &View,
 ) -> Result<IndexMap<ChangeId, ModifiedChange>, CommandError> {
     let mut changes: IndexMap<ChangeId, ModifiedChange> = IndexMap::new();
 
-    let from_heads = from_repo.view().heads().iter().cloned().collect_vec();
-    let to_heads = to_repo.view().heads().iter().cloned().collect_vec();
+    let from_heads = from_view.heads().iter().cloned().collect_vec();
+    let to_heads = to_view.heads().iter().cloned().collect_vec();
 
-    // Find newly added commits in `to_repo` which were not present in
-    // `from_repo`.
+    // Find newly added commits in `to_view` which were not present in
+    // `from_view`.
     for commit in revset::walk_revs(repo, &to_heads, &from_heads)?
         .iter()
         .commits(repo.store())
@@ -518,7 +514,7 @@ fn compute_operation_commits_diff(
         modified_change.added_commits.push(commit);
     }
 
-    // Find commits which were hidden in `to_repo`.
+    // Find commits which were hidden in `to_view`.
     for commit in revset::walk_revs(repo, &from_heads, &to_heads)?
         .iter()
         .commits(repo.store())
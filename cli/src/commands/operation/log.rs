@@ -14,18 +14,21 @@
 
 use std::slice;
 
+use chrono::TimeZone as _;
 use itertools::Itertools as _;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
 use jj_lib::settings::ConfigResultExt as _;
 use jj_lib::settings::UserSettings;
+use jj_lib::time_util::DatePattern;
 
 use super::diff::show_op_diff;
 use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::WorkspaceCommandEnvironment;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::diff_util::diff_formats_for_log;
@@ -46,6 +49,8 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationLogArgs {
     /// Limit number of operations to show
+    ///
+    /// Applied after operations are filtered and reordered.
     #[arg(long, short = 'n')]
     limit: Option<usize>,
     // TODO: Delete `-l` alias in jj 0.25+
@@ -56,9 +61,28 @@ pub struct OperationLogArgs {
         value_name = "LIMIT"
     )]
     deprecated_limit: Option<usize>,
+    /// Only show operations that ended at or after the given time
+    ///
+    /// Accepts the same date formats as the `after:` date pattern in revsets,
+    /// e.g. `--since='1 hour ago'` or `--since=2024-02-01`.
+    #[arg(long, value_name = "DATE")]
+    since: Option<String>,
+    /// Only show operations that ended before the given time
+    ///
+    /// Accepts the same date formats as the `before:` date pattern in revsets,
+    /// e.g. `--until=yesterday` or `--until=now`.
+    #[arg(long, value_name = "DATE")]
+    until: Option<String>,
     /// Don't show the graph, show a flat list of operations
+    ///
+    /// Operations are printed as they are found, without waiting for the whole
+    /// operation log to be walked. With `--reversed`, all operations have to
+    /// be walked before the first one is printed.
     #[arg(long)]
     no_graph: bool,
+    /// Show operations in the opposite order (older operations first)
+    #[arg(long, alias = "reverse", requires = "no_graph")]
+    reversed: bool,
     /// Render each operation using the given template
     ///
     /// For the syntax, see https://martinvonz.github.io/jj/latest/templates/
@@ -173,8 +197,8 @@ fn do_op_log(
                 ui,
                 formatter,
                 repo.as_ref(),
-                &parent_repo,
-                &repo,
+                parent_repo.view(),
+                repo.view(),
                 &commit_summary_template,
                 (!args.no_graph).then_some(graph_style),
                 with_content_format,
@@ -196,15 +220,63 @@ fn do_op_log(
         )?;
     }
     let limit = args.limit.or(args.deprecated_limit).unwrap_or(usize::MAX);
-    let iter = op_walk::walk_ancestors(slice::from_ref(current_op)).take(limit);
+    let now = if let Some(timestamp) = settings.operation_timestamp() {
+        chrono::Local
+            .timestamp_millis_opt(timestamp.timestamp.0)
+            .unwrap()
+    } else {
+        chrono::Local::now()
+    };
+    let parse_date = |value: &Option<String>, kind: &str| {
+        value
+            .as_ref()
+            .map(|text| {
+                DatePattern::from_str_kind(text, kind, now)
+                    .map_err(|err| user_error_with_message(format!("Invalid date \"{text}\""), err))
+            })
+            .transpose()
+    };
+    let since = parse_date(&args.since, "after")?;
+    let until = parse_date(&args.until, "before")?;
+    let is_after_since = |op: &Operation| {
+        since
+            .as_ref()
+            .map_or(true, |pattern| pattern.matches(&op.metadata().end_time))
+    };
+    let is_before_until = |op: &Operation| {
+        until
+            .as_ref()
+            .map_or(true, |pattern| pattern.matches(&op.metadata().end_time))
+    };
+    let iter = op_walk::walk_ancestors(slice::from_ref(current_op))
+        .filter_ok(|op| is_after_since(op) && is_before_until(op));
+    let iter: Box<dyn Iterator<Item = _>> = if args.reversed {
+        let ops: Vec<_> = iter.try_collect()?;
+        Box::new(ops.into_iter().rev().map(Ok).take(limit))
+    } else {
+        Box::new(iter.take(limit))
+    };
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
         for op in iter {
             let op = op?;
             let mut edges = vec![];
-            for id in op.parent_ids() {
-                edges.push(Edge::Direct(id.clone()));
+            if since.is_some() {
+                // Parents older than --since aren't shown, so their edges
+                // shouldn't be drawn as continuing lines.
+                for parent_op in op.parents() {
+                    let parent_op = parent_op?;
+                    if is_after_since(&parent_op) {
+                        edges.push(Edge::Direct(parent_op.id().clone()));
+                    } else {
+                        edges.push(Edge::Missing);
+                    }
+                }
+            } else {
+                for id in op.parent_ids() {
+                    edges.push(Edge::Direct(id.clone()));
+                }
             }
             let mut buffer = vec![];
             let within_graph = with_content_format.sub_width(graph.width(op.id(), &edges));
@@ -12,13 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::slice;
+
+use itertools::Itertools as _;
+use jj_lib::git;
 use jj_lib::object_id::ObjectId;
+use jj_lib::op_store::RemoteRef;
+use jj_lib::op_store::View;
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+use jj_lib::refs::diff_named_ref_targets;
+use jj_lib::refs::diff_named_remote_refs;
+use jj_lib::repo::MutableRepo;
+use jj_lib::repo::Repo as _;
 
+use super::diff::show_op_diff;
 use super::view_with_desired_portions_restored;
 use super::UndoWhatToRestore;
 use super::DEFAULT_UNDO_WHAT;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::LogContentFormat;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateLanguage;
+use crate::graphlog::GraphStyle;
 use crate::ui::Ui;
 
 /// Create a new operation that restores the repo to an earlier state
@@ -39,6 +57,28 @@ pub struct OperationRestoreArgs {
     /// This option is EXPERIMENTAL.
     #[arg(long, value_enum, default_values_t = DEFAULT_UNDO_WHAT)]
     what: Vec<UndoWhatToRestore>,
+
+    /// Only show what would change, without creating a new operation
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Keep the current files in the working copy
+    ///
+    /// Instead of updating the working copy to the restored working-copy
+    /// commit, a new working-copy commit is created on top of it with the
+    /// current files.
+    #[arg(long, conflicts_with = "dry_run")]
+    preserve_working_copy: bool,
+
+    /// Replay the changes imported from Git after the operation
+    ///
+    /// Bookmarks, tags, and working-copy commits that were updated by "import
+    /// git refs" and "import git head" operations after the restored
+    /// operation are set to their imported targets again, so changes made by
+    /// external Git commands aren't lost. Changes made by jj commands are
+    /// still discarded.
+    #[arg(long)]
+    replay_git: bool,
 }
 
 pub fn cmd_op_restore(
@@ -55,13 +95,218 @@ pub fn cmd_op_restore(
         &args.what,
     );
     tx.repo_mut().set_view(new_view);
+    let replayed_changes = if args.replay_git {
+        let current_op = tx.base_repo().operation().clone();
+        replay_git_imports(tx.repo_mut(), &current_op, &target_op)?
+    } else {
+        vec![]
+    };
+    if args.dry_run {
+        // Compare the views in memory. The transaction is dropped without
+        // being committed, so nothing is written to the op store.
+        let base_repo = tx.base_repo().clone();
+        let new_repo = tx.repo();
+        let workspace_command = tx.base_workspace_helper();
+
+        let workspace_env = workspace_command.env();
+        let id_prefix_context = workspace_env.new_id_prefix_context();
+        let commit_summary_template = {
+            let language = workspace_env.commit_template_language(new_repo, &id_prefix_context);
+            let text = command
+                .settings()
+                .config()
+                .get_string("templates.commit_summary")?;
+            workspace_env.parse_template(
+                ui,
+                &language,
+                &text,
+                CommitTemplateLanguage::wrap_commit,
+            )?
+        };
+        let graph_style = GraphStyle::from_settings(command.settings())?;
+        let with_content_format = LogContentFormat::new(ui, command.settings())?;
+
+        if let Some(mut formatter) = ui.status_formatter() {
+            write!(formatter, "Would restore to operation: ")?;
+            let template = workspace_command.operation_summary_template();
+            template.format(&target_op, formatter.as_mut())?;
+            writeln!(formatter)?;
+        }
+        ui.request_pager();
+        let mut formatter = ui.stdout_formatter();
+        show_op_diff(
+            ui,
+            formatter.as_mut(),
+            new_repo,
+            base_repo.view(),
+            new_repo.view(),
+            &commit_summary_template,
+            Some(graph_style),
+            &with_content_format,
+            None,
+        )?;
+        let workspace_id = workspace_command.workspace_id();
+        let old_wc_commit_id = base_repo.view().get_wc_commit_id(workspace_id);
+        let new_wc_commit_id = new_repo.view().get_wc_commit_id(workspace_id);
+        if new_wc_commit_id != old_wc_commit_id {
+            writeln!(formatter)?;
+            match new_wc_commit_id {
+                Some(commit_id) => {
+                    let commit = new_repo.store().get_commit(commit_id)?;
+                    write!(formatter, "Working copy would move to: ")?;
+                    commit_summary_template.format(&commit, formatter.as_mut())?;
+                    writeln!(formatter)?;
+                }
+                None => writeln!(formatter, "Working copy would be removed")?,
+            }
+        }
+        drop(formatter);
+        writeln!(ui.status(), "Dry-run requested, not restoring.")?;
+        return Ok(());
+    }
+    if args.preserve_working_copy {
+        preserve_working_copy(ui, command, &mut tx)?;
+    }
     if let Some(mut formatter) = ui.status_formatter() {
         write!(formatter, "Restored to operation: ")?;
         let template = tx.base_workspace_helper().operation_summary_template();
         template.format(&target_op, formatter.as_mut())?;
         writeln!(formatter)?;
     }
+    if !replayed_changes.is_empty() {
+        let mut formatter = ui.status();
+        writeln!(formatter, "Replayed changes imported from Git:")?;
+        for change in &replayed_changes {
+            writeln!(formatter, "  {change}")?;
+        }
+    }
     tx.finish(ui, format!("restore to operation {}", target_op.id().hex()))?;
 
     Ok(())
 }
+
+/// Re-applies the view changes made by Git import operations between
+/// `target_op` (exclusive) and `current_op` (inclusive). Returns descriptions
+/// of the replayed changes.
+fn replay_git_imports(
+    mut_repo: &mut MutableRepo,
+    current_op: &Operation,
+    target_op: &Operation,
+) -> Result<Vec<String>, CommandError> {
+    let mut import_ops = vec![];
+    let mut found_target = false;
+    for op in op_walk::walk_ancestors(slice::from_ref(current_op)) {
+        let op = op?;
+        if op.id() == target_op.id() {
+            found_target = true;
+            break;
+        }
+        let description = &op.metadata().description;
+        if description == "import git refs" || description == "import git head" {
+            import_ops.push(op);
+        }
+    }
+    if !found_target {
+        return Err(user_error(
+            "--replay-git requires the operation to be an ancestor of the current operation",
+        ));
+    }
+
+    let mut changes = vec![];
+    // Replay the oldest import first so later imports take precedence.
+    for op in import_ops.iter().rev() {
+        let parent_ops: Vec<_> = op.parents().try_collect()?;
+        let [parent_op] = &parent_ops[..] else {
+            continue;
+        };
+        let old_view = parent_op.view()?;
+        let new_view = op.view()?;
+        let (old_view, new_view) = (old_view.store_view(), new_view.store_view());
+
+        let added_heads: Vec<_> = new_view
+            .head_ids
+            .difference(&old_view.head_ids)
+            .map(|id| mut_repo.store().get_commit(id))
+            .try_collect()?;
+        mut_repo.add_heads(&added_heads)?;
+        for id in old_view.head_ids.difference(&new_view.head_ids) {
+            mut_repo.remove_head(id);
+        }
+        for (name, (_, target)) in
+            diff_named_ref_targets(&old_view.local_bookmarks, &new_view.local_bookmarks)
+        {
+            mut_repo.set_local_bookmark_target(name, target.clone());
+            changes.push(format!("bookmark {name}"));
+        }
+        for ((remote_name, name), (_, remote_ref)) in
+            diff_named_remote_refs(flatten_remote_refs(old_view), flatten_remote_refs(new_view))
+        {
+            mut_repo.set_remote_bookmark(name, remote_name, remote_ref.clone());
+            // The @git refs mirror the local bookmarks reported above
+            if remote_name != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+                changes.push(format!("bookmark {name}@{remote_name}"));
+            }
+        }
+        for (name, (_, target)) in diff_named_ref_targets(&old_view.tags, &new_view.tags) {
+            mut_repo.set_tag_target(name, target.clone());
+            changes.push(format!("tag {name}"));
+        }
+        for (workspace_id, commit_id) in &new_view.wc_commit_ids {
+            if old_view.wc_commit_ids.get(workspace_id) != Some(commit_id) {
+                mut_repo.set_wc_commit(workspace_id.clone(), commit_id.clone())?;
+                changes.push(format!("working copy of {}", workspace_id.as_str()));
+            }
+        }
+    }
+    Ok(changes.into_iter().unique().collect())
+}
+
+fn flatten_remote_refs(view: &View) -> impl Iterator<Item = ((&str, &str), &RemoteRef)> {
+    view.remote_views
+        .iter()
+        .flat_map(|(remote_name, remote_view)| {
+            remote_view
+                .bookmarks
+                .iter()
+                .map(move |(name, remote_ref)| ((remote_name.as_str(), name.as_str()), remote_ref))
+        })
+}
+
+/// Creates a new working-copy commit on top of the restored one, with the
+/// files of the current working-copy commit.
+fn preserve_working_copy(
+    ui: &Ui,
+    command: &CommandHelper,
+    tx: &mut WorkspaceCommandTransaction,
+) -> Result<(), CommandError> {
+    let workspace_id = tx.base_workspace_helper().workspace_id().to_owned();
+    let (Some(old_wc_commit_id), Some(new_wc_commit_id)) = (
+        tx.base_repo()
+            .view()
+            .get_wc_commit_id(&workspace_id)
+            .cloned(),
+        tx.repo().view().get_wc_commit_id(&workspace_id).cloned(),
+    ) else {
+        return Ok(());
+    };
+    let old_wc_commit = tx.repo().store().get_commit(&old_wc_commit_id)?;
+    let new_wc_commit = tx.repo().store().get_commit(&new_wc_commit_id)?;
+    if old_wc_commit.tree_id() == new_wc_commit.tree_id() {
+        return Ok(());
+    }
+    let wc_commit = tx
+        .repo_mut()
+        .new_commit(
+            command.settings(),
+            vec![new_wc_commit_id],
+            old_wc_commit.tree_id().clone(),
+        )
+        .write()?;
+    tx.repo_mut()
+        .set_wc_commit(workspace_id, wc_commit.id().clone())?;
+    writeln!(
+        ui.status(),
+        "Preserved the working-copy files in a new commit on top of the restored working copy"
+    )?;
+    Ok(())
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId;
 use jj_lib::repo::Repo;
 
@@ -40,6 +41,19 @@ pub struct OperationUndoArgs {
     /// This option is EXPERIMENTAL.
     #[arg(long, value_enum, default_values_t = DEFAULT_UNDO_WHAT)]
     what: Vec<UndoWhatToRestore>,
+
+    /// Undo this many operations, ending with the given operation
+    ///
+    /// The operation and its N-1 ancestors are undone together, as if they
+    /// were a single operation.
+    #[arg(
+        long,
+        short = 'n',
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    repeat: u32,
 }
 
 pub fn cmd_op_undo(
@@ -49,13 +63,22 @@ pub fn cmd_op_undo(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let bad_op = workspace_command.resolve_single_op(&args.operation)?;
-    let mut parent_ops = bad_op.parents();
-    let Some(parent_op) = parent_ops.next().transpose()? else {
-        return Err(user_error("Cannot undo repo initialization"));
+    // The operations to undo, newest first
+    let mut bad_ops = vec![bad_op.clone()];
+    let parent_op = loop {
+        let parent_ops: Vec<_> = bad_ops.last().unwrap().parents().try_collect()?;
+        let parent_op = match <[_; 1]>::try_from(parent_ops) {
+            Ok([parent_op]) => parent_op,
+            Err(parent_ops) if parent_ops.is_empty() => {
+                return Err(user_error("Cannot undo repo initialization"));
+            }
+            Err(_) => return Err(user_error("Cannot undo a merge operation")),
+        };
+        if bad_ops.len() == args.repeat as usize {
+            break parent_op;
+        }
+        bad_ops.push(parent_op);
     };
-    if parent_ops.next().is_some() {
-        return Err(user_error("Cannot undo a merge operation"));
-    }
 
     let mut tx = workspace_command.start_transaction();
     let repo_loader = tx.base_repo().loader();
@@ -69,12 +92,30 @@ pub fn cmd_op_undo(
     );
     tx.repo_mut().set_view(new_view);
     if let Some(mut formatter) = ui.status_formatter() {
-        write!(formatter, "Undid operation: ")?;
         let template = tx.base_workspace_helper().operation_summary_template();
-        template.format(&bad_op, formatter.as_mut())?;
-        writeln!(formatter)?;
+        if let [op] = &bad_ops[..] {
+            write!(formatter, "Undid operation: ")?;
+            template.format(op, formatter.as_mut())?;
+            writeln!(formatter)?;
+        } else {
+            writeln!(formatter, "Undid {} operations:", bad_ops.len())?;
+            for op in &bad_ops {
+                write!(formatter, "  ")?;
+                template.format(op, formatter.as_mut())?;
+                writeln!(formatter)?;
+            }
+        }
     }
-    tx.finish(ui, format!("undo operation {}", bad_op.id().hex()))?;
+    let description = if let [op] = &bad_ops[..] {
+        format!("undo operation {}", op.id().hex())
+    } else {
+        format!(
+            "undo operations {}..{}",
+            parent_op.id().hex(),
+            bad_op.id().hex()
+        )
+    };
+    tx.finish(ui, description)?;
 
     Ok(())
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::io::Write;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -19,6 +20,7 @@ use std::sync::Arc;
 use clap::ArgGroup;
 use indexmap::IndexSet;
 use itertools::Itertools;
+use jj_lib::backend::BackendResult;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt;
@@ -180,6 +182,18 @@ pub(crate) struct RebaseArgs {
     /// parents.
     #[arg(long, conflicts_with = "revisions")]
     skip_emptied: bool,
+
+    /// Abandon commits whose change already exists in the destination
+    ///
+    /// If another commit with the same change ID is an ancestor of the
+    /// destination, e.g. because the change was rewritten and fetched from a
+    /// remote, the rebased commit would become divergent. With this option,
+    /// it's abandoned instead if both commits have the same description and
+    /// author, and the same content or the same diff against their parents.
+    /// Its descendants are rebased onto its parent. Commits that differ are
+    /// kept.
+    #[arg(long, conflicts_with = "revisions")]
+    skip_divergent: bool,
 }
 
 #[derive(clap::Args, Clone, Debug)]
@@ -265,6 +279,7 @@ pub(crate) fn cmd_rebase(
             &args.source,
             &args.destination,
             &rebase_options,
+            args.skip_divergent,
         )?;
     } else {
         let destination = args
@@ -288,6 +303,7 @@ pub(crate) fn cmd_rebase(
             new_parents,
             &branch_commits,
             rebase_options,
+            args.skip_divergent,
         )?;
     }
     Ok(())
@@ -337,6 +353,7 @@ fn rebase_source(
     source: &[RevisionArg],
     rebase_destination: &RebaseDestinationArgs,
     rebase_options: &RebaseOptions,
+    skip_divergent: bool,
 ) -> Result<(), CommandError> {
     let source_commits = workspace_command
         .resolve_some_revsets_default_single(ui, source)?
@@ -360,6 +377,7 @@ fn rebase_source(
         &new_children,
         &source_commits,
         rebase_options,
+        skip_divergent,
     )
 }
 
@@ -370,6 +388,7 @@ fn rebase_branch(
     new_parents: Vec<Commit>,
     branch_commits: &IndexSet<Commit>,
     rebase_options: RebaseOptions,
+    skip_divergent: bool,
 ) -> Result<(), CommandError> {
     let parent_ids = new_parents.iter().ids().cloned().collect_vec();
     let branch_commit_ids = branch_commits
@@ -398,9 +417,11 @@ fn rebase_branch(
         &[],
         &root_commits,
         &rebase_options,
+        skip_divergent,
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn rebase_descendants_transaction(
     ui: &mut Ui,
     settings: &UserSettings,
@@ -409,6 +430,7 @@ fn rebase_descendants_transaction(
     new_children: &[Commit],
     target_roots: &[Commit],
     rebase_options: &RebaseOptions,
+    skip_divergent: bool,
 ) -> Result<(), CommandError> {
     if target_roots.is_empty() {
         return Ok(());
@@ -435,6 +457,22 @@ fn rebase_descendants_transaction(
             .commits(tx.repo().store())
             .try_collect()?;
     let target_roots = target_roots.iter().ids().cloned().collect_vec();
+    let (divergent_commit_ids, modified_commit_ids) = if skip_divergent {
+        find_commits_in_destination(tx.repo(), new_parent_ids, &target_commits)?
+    } else {
+        (vec![], vec![])
+    };
+    // Abandon the duplicates before rebasing, so no copies of them are
+    // written. Their descendants end up on top of the destination.
+    for old_id in &divergent_commit_ids {
+        let parent_ids = if target_roots.contains(old_id) {
+            new_parent_ids.to_vec()
+        } else {
+            tx.repo().store().get_commit(old_id)?.parent_ids().to_vec()
+        };
+        tx.repo_mut()
+            .record_abandoned_commit_with_parents(old_id.clone(), parent_ids);
+    }
 
     let MoveCommitsStats {
         num_rebased_targets,
@@ -472,10 +510,99 @@ fn rebase_descendants_transaction(
             "Abandoned {num_abandoned} newly emptied commits"
         )?;
     }
+    if !divergent_commit_ids.is_empty() {
+        if let Some(mut formatter) = ui.status_formatter() {
+            let template = tx.base_workspace_helper().commit_summary_template();
+            writeln!(
+                formatter,
+                "Abandoned {} commits that already exist in the destination:",
+                divergent_commit_ids.len()
+            )?;
+            for old_id in &divergent_commit_ids {
+                let commit = tx.base_repo().store().get_commit(old_id)?;
+                write!(formatter, "  ")?;
+                template.format(&commit, formatter.as_mut())?;
+                writeln!(formatter)?;
+            }
+        }
+    }
+    if !modified_commit_ids.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "Kept {} commits that differ from their change in the destination:",
+            modified_commit_ids.len()
+        )?;
+        let template = tx.base_workspace_helper().commit_summary_template();
+        let mut formatter = ui.stderr_formatter();
+        for old_id in &modified_commit_ids {
+            let commit = tx.base_repo().store().get_commit(old_id)?;
+            write!(formatter, "  ")?;
+            template.format(&commit, formatter.as_mut())?;
+            writeln!(formatter)?;
+        }
+    }
 
     tx.finish(ui, tx_description)
 }
 
+/// Finds the commits in `target_commits` whose change ID is also held by an
+/// ancestor of the destination `new_parent_ids`.
+///
+/// Returns the commits that duplicate the commit in the destination, i.e. that
+/// have the same description and author, and the same tree or the same diff
+/// against their parents, and the commits that differ from it.
+fn find_commits_in_destination(
+    repo: &dyn Repo,
+    new_parent_ids: &[CommitId],
+    target_commits: &[Commit],
+) -> BackendResult<(Vec<CommitId>, Vec<CommitId>)> {
+    let target_ids: HashSet<_> = target_commits.iter().ids().collect();
+    let index = repo.index();
+    let mut duplicate_ids = vec![];
+    let mut modified_ids = vec![];
+    for commit in target_commits {
+        let other_ids = repo
+            .resolve_change_id(commit.change_id())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|other_id| {
+                !target_ids.contains(other_id)
+                    && new_parent_ids
+                        .iter()
+                        .any(|parent_id| index.is_ancestor(other_id, parent_id))
+            })
+            .collect_vec();
+        if other_ids.is_empty() {
+            continue;
+        }
+        let mut is_duplicate = false;
+        for other_id in &other_ids {
+            let other = repo.store().get_commit(other_id)?;
+            if other.description() != commit.description() || other.author() != commit.author() {
+                continue;
+            }
+            if other.tree_id() == commit.tree_id() {
+                is_duplicate = true;
+                break;
+            }
+            // Apply the other commit's diff onto this commit's parents
+            let parent_tree = commit.parent_tree(repo)?;
+            let other_on_parent_tree =
+                parent_tree.merge(&other.parent_tree(repo)?, &other.tree()?)?;
+            if other_on_parent_tree.id() == *commit.tree_id() {
+                is_duplicate = true;
+                break;
+            }
+        }
+        if is_duplicate {
+            duplicate_ids.push(commit.id().clone());
+        } else {
+            modified_ids.push(commit.id().clone());
+        }
+    }
+    Ok((duplicate_ids, modified_ids))
+}
+
 /// Computes the new parents and children for the given
 /// [`RebaseDestinationArgs`].
 fn compute_rebase_destination(
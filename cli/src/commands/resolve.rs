@@ -15,14 +15,19 @@
 use std::io::Write;
 
 use itertools::Itertools;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::object_id::ObjectId;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::cli_error;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::merge_tools::get_external_tool_config;
 use crate::ui::Ui;
 
 /// Resolve a conflicted file with an external merge tool
@@ -30,6 +35,9 @@ use crate::ui::Ui;
 /// Only conflicts that can be resolved with a 3-way merge are supported. See
 /// docs for merge tool configuration instructions.
 ///
+/// With `--ours` or `--theirs`, all conflicts at the given paths are instead
+/// resolved by taking one side of the conflict.
+///
 /// Note that conflicts can also be resolved without using this command. You may
 /// edit the conflict markers in the conflicted file directly with a text
 /// editor.
@@ -52,6 +60,25 @@ pub(crate) struct ResolveArgs {
     /// Specify 3-way merge tool to be used
     #[arg(long, conflicts_with = "list", value_name = "NAME")]
     tool: Option<String>,
+    /// List the available merge tools and how they are invoked
+    ///
+    /// In a workspace colocated with Git, merge tools defined by
+    /// `mergetool.<name>.cmd` in the Git config are also listed, unless a tool
+    /// of the same name is configured in `[merge-tools.<name>]`.
+    #[arg(long, conflicts_with_all = ["list", "tool", "paths"])]
+    tool_list: bool,
+    /// Resolve the conflicts by taking the first side
+    ///
+    /// When a commit is rebased, this is the side of the destination it was
+    /// rebased onto. Only conflicts with two sides are supported.
+    #[arg(long, conflicts_with_all = ["list", "tool", "tool_list", "theirs"])]
+    ours: bool,
+    /// Resolve the conflicts by taking the second side
+    ///
+    /// When a commit is rebased, this is the side of the commit being rebased.
+    /// Only conflicts with two sides are supported.
+    #[arg(long, conflicts_with_all = ["list", "tool", "tool_list"])]
+    theirs: bool,
     /// Restrict to these paths when searching for a conflict to resolve. We
     /// will attempt to resolve the first conflict we can find. You can use
     /// the `--list` argument to find paths to use here.
@@ -67,6 +94,9 @@ pub(crate) fn cmd_resolve(
     args: &ResolveArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
+    if args.tool_list {
+        return print_merge_tools(ui, &workspace_command);
+    }
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
@@ -91,16 +121,53 @@ pub(crate) fn cmd_resolve(
         );
     };
 
-    let (repo_path, _) = conflicts.first().unwrap();
     workspace_command.check_rewritable([commit.id()])?;
-    let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
-    writeln!(
-        ui.status(),
-        "Resolving conflicts in: {}",
-        workspace_command.format_file_path(repo_path)
-    )?;
+    let side = if args.ours {
+        Some((0, "ours"))
+    } else if args.theirs {
+        Some((1, "theirs"))
+    } else {
+        None
+    };
+    let new_tree_id = if let Some((side_index, side_name)) = side {
+        let mut tree_builder = MergedTreeBuilder::new(commit.tree_id().clone());
+        for (repo_path, value) in &conflicts {
+            let ui_path = workspace_command.format_file_path(repo_path);
+            if value.num_sides() != 2 {
+                return Err(user_error(format!(
+                    "Cannot resolve {ui_path} with --{side_name} because the conflict has {} \
+                     sides",
+                    value.num_sides()
+                )));
+            }
+            let Some(side_value) = value.get_add(side_index).unwrap() else {
+                return Err(user_error(format!(
+                    "Cannot resolve {ui_path} with --{side_name} because the file doesn't exist \
+                     on that side"
+                )));
+            };
+            tree_builder.set_or_remove(repo_path.clone(), Merge::normal(side_value.clone()));
+        }
+        writeln!(
+            ui.status(),
+            "Resolving conflicts in: {}",
+            conflicts
+                .iter()
+                .map(|(repo_path, _)| workspace_command.format_file_path(repo_path))
+                .join(", ")
+        )?;
+        tree_builder.write_tree(commit.store())?
+    } else {
+        let (repo_path, _) = conflicts.first().unwrap();
+        let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
+        writeln!(
+            ui.status(),
+            "Resolving conflicts in: {}",
+            workspace_command.format_file_path(repo_path)
+        )?;
+        merge_editor.edit_file(&tree, repo_path)?
+    };
     let mut tx = workspace_command.start_transaction();
-    let new_tree_id = merge_editor.edit_file(&tree, repo_path)?;
     let new_commit = tx
         .repo_mut()
         .rewrite_commit(command.settings(), &commit)
@@ -129,3 +196,34 @@ pub(crate) fn cmd_resolve(
     }
     Ok(())
 }
+
+fn print_merge_tools(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<(), CommandError> {
+    let settings = workspace_command.settings();
+    let mut tools = workspace_command.git_mergetools();
+    for name in settings.config().get_table("merge-tools")?.keys() {
+        let tool = get_external_tool_config(settings, name)?.unwrap();
+        tools.insert(name.clone(), tool);
+    }
+    let mut formatter = ui.stdout_formatter();
+    writeln!(formatter, ":builtin: (built-in merge editor)")?;
+    for (name, tool) in &tools {
+        if tool.merge_args.is_empty() {
+            continue;
+        }
+        let args = tool.merge_args.iter().map(|arg| quote_arg(arg)).join(" ");
+        writeln!(formatter, "{name}: {} {args}", quote_arg(&tool.program))?;
+    }
+    Ok(())
+}
+
+/// Quotes the argument for display if it contains whitespace or quotes.
+fn quote_arg(arg: &str) -> String {
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"') {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    } else {
+        arg.to_owned()
+    }
+}
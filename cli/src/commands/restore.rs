@@ -14,6 +14,7 @@
 
 use std::io::Write;
 
+use futures::executor::block_on_stream;
 use jj_lib::object_id::ObjectId;
 use jj_lib::rewrite::restore_tree;
 use tracing::instrument;
@@ -38,6 +39,9 @@ use crate::ui::Ui;
 /// to `jj abandon`, except that it leaves an empty revision with its
 /// description and other metadata preserved.
 ///
+/// With `--apply`, the changes in the given revision are applied to the
+/// destination instead, similar to `git cherry-pick --no-commit`.
+///
 /// See `jj diffedit` if you'd like to restore portions of files rather than
 /// entire files.
 #[derive(clap::Args, Clone, Debug)]
@@ -48,6 +52,13 @@ pub(crate) struct RestoreArgs {
     /// Revision to restore from (source)
     #[arg(long)]
     from: Option<RevisionArg>,
+    /// Restore from the parent(s) of the destination
+    ///
+    /// This is a shorthand for `--from @-` when restoring into the working
+    /// copy. If the destination is a merge commit, the paths are restored
+    /// from the merge of its parents.
+    #[arg(long, conflicts_with_all = ["from", "changes_in", "apply"])]
+    from_parent: bool,
     /// Revision to restore into (destination)
     #[arg(long)]
     to: Option<RevisionArg>,
@@ -61,6 +72,14 @@ pub(crate) struct RestoreArgs {
     /// --changes-in @`.
     #[arg(long, short, value_name="REVISION", conflicts_with_all=["to", "from"])]
     changes_in: Option<RevisionArg>,
+    /// Apply the changes in a revision as compared to the merge of its
+    /// parents
+    ///
+    /// This applies the changes that can be seen with `jj diff -r REVISION` to
+    /// the destination (`--to`, or the working copy by default). Files that
+    /// the changes don't apply cleanly to are left with conflicts.
+    #[arg(long, value_name = "REVISION", conflicts_with_all = ["from", "changes_in"])]
+    apply: Option<RevisionArg>,
     /// Prints an error. DO NOT USE.
     ///
     /// If we followed the pattern of `jj diff` and `jj diffedit`, we would use
@@ -90,7 +109,19 @@ pub(crate) fn cmd_restore(
              revision,\nuse `--to` or `--changes-in`.",
         ));
     }
-    if args.from.is_some() || args.to.is_some() {
+    if let Some(source_arg) = &args.apply {
+        to_commit = workspace_command
+            .resolve_single_rev(ui, args.to.as_ref().unwrap_or(&RevisionArg::AT))?;
+        let source = workspace_command.resolve_single_rev(ui, source_arg)?;
+        let source_parent_tree = source.parent_tree(workspace_command.repo().as_ref())?;
+        from_tree = to_commit
+            .tree()?
+            .merge(&source_parent_tree, &source.tree()?)?;
+    } else if args.from_parent {
+        to_commit = workspace_command
+            .resolve_single_rev(ui, args.to.as_ref().unwrap_or(&RevisionArg::AT))?;
+        from_tree = to_commit.parent_tree(workspace_command.repo().as_ref())?;
+    } else if args.from.is_some() || args.to.is_some() {
         to_commit = workspace_command
             .resolve_single_rev(ui, args.to.as_ref().unwrap_or(&RevisionArg::AT))?;
         from_tree = workspace_command
@@ -131,6 +162,26 @@ pub(crate) fn cmd_restore(
             write!(formatter, "Created ")?;
             tx.write_commit_summary(formatter.as_mut(), &new_commit)?;
             writeln!(formatter)?;
+            if args.apply.is_some() {
+                let new_tree = new_commit.tree()?;
+                let mut num_files = 0;
+                let mut num_conflicted = 0;
+                for entry in block_on_stream(to_tree.diff_stream(&new_tree, matcher.as_ref())) {
+                    let (_, after) = entry.values?;
+                    num_files += 1;
+                    if !after.is_resolved() {
+                        num_conflicted += 1;
+                    }
+                }
+                if num_conflicted > 0 {
+                    writeln!(
+                        formatter,
+                        "Applied changes to {num_files} files ({num_conflicted} with conflicts)"
+                    )?;
+                } else {
+                    writeln!(formatter, "Applied changes to {num_files} files")?;
+                }
+            }
             if num_rebased > 0 {
                 writeln!(
                     formatter,
@@ -35,6 +35,15 @@ pub(crate) struct ShowArgs {
     /// For the syntax, see https://martinvonz.github.io/jj/latest/templates/
     #[arg(long, short = 'T')]
     template: Option<String>,
+    /// Don't show the changes, only the commit description and metadata
+    #[arg(long)]
+    no_patch: bool,
+    /// Show the signature verification status of the revision
+    ///
+    /// Signatures are verified by the configured signing backends. The status
+    /// is rendered by the `templates.show_signature` template.
+    #[arg(long)]
+    show_signature: bool,
     #[command(flatten)]
     format: DiffFormatArgs,
 }
@@ -52,11 +61,25 @@ pub(crate) fn cmd_show(
         None => command.settings().config().get_string("templates.show")?,
     };
     let template = workspace_command.parse_commit_template(ui, &template_string)?;
+    let signature_template = if args.show_signature {
+        let template_string = command
+            .settings()
+            .config()
+            .get_string("templates.show_signature")?;
+        Some(workspace_command.parse_commit_template(ui, &template_string)?)
+    } else {
+        None
+    };
     let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
     template.format(&commit, formatter)?;
-    diff_renderer.show_patch(ui, formatter, &commit, &EverythingMatcher, ui.term_width())?;
+    if let Some(template) = &signature_template {
+        template.format(&commit, formatter)?;
+    }
+    if !args.no_patch {
+        diff_renderer.show_patch(ui, formatter, &commit, &EverythingMatcher, ui.term_width())?;
+    }
     Ok(())
 }
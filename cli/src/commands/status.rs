@@ -12,8 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io;
+
+use futures::executor::block_on_stream;
 use itertools::Itertools;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::CopyDetectionOptions;
+use jj_lib::commit::Commit;
+use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
+use jj_lib::matchers::Matcher;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
@@ -21,6 +30,7 @@ use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
 use crate::diff_util::get_copy_records;
 use crate::diff_util::DiffFormat;
@@ -39,6 +49,20 @@ pub(crate) struct StatusArgs {
     /// Restrict the status display to these paths
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Output format
+    ///
+    /// The `json` format is meant for tools such as editor integrations. Paths
+    /// in it are relative to the workspace root.
+    #[arg(long, value_enum, default_value = "text")]
+    format: StatusFormat,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum StatusFormat {
+    /// Human-readable summary
+    Text,
+    /// Machine-readable JSON object
+    Json,
 }
 
 #[instrument(skip_all)]
@@ -56,6 +80,14 @@ pub(crate) fn cmd_status(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
+    if args.format == StatusFormat::Json {
+        return print_status_json(
+            ui,
+            &workspace_command,
+            maybe_wc_commit.as_ref(),
+            matcher.as_ref(),
+        );
+    }
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
@@ -69,7 +101,13 @@ pub(crate) fn cmd_status(
             writeln!(formatter, "Working copy changes:")?;
             let mut copy_records = CopyRecords::default();
             for parent in wc_commit.parent_ids() {
-                let records = get_copy_records(repo.store(), parent, wc_commit.id(), &matcher)?;
+                let records = get_copy_records(
+                    repo.store(),
+                    parent,
+                    wc_commit.id(),
+                    &matcher,
+                    &CopyDetectionOptions::default(),
+                )?;
                 copy_records.add_records(records)?;
             }
             let diff_renderer = workspace_command.diff_renderer(vec![DiffFormat::Summary]);
@@ -188,3 +226,112 @@ pub(crate) fn cmd_status(
 
     Ok(())
 }
+
+fn print_status_json(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    maybe_wc_commit: Option<&Commit>,
+    matcher: &dyn Matcher,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let commit_to_json = |commit: &Commit| {
+        serde_json::json!({
+            "commit_id": commit.id().hex(),
+            "change_id": commit.change_id().reverse_hex(),
+            "description": commit.description(),
+        })
+    };
+
+    let working_copy = if let Some(wc_commit) = maybe_wc_commit {
+        let parent_tree = wc_commit.parent_tree(repo.as_ref())?;
+        let tree = wc_commit.tree()?;
+        let mut copy_records = CopyRecords::default();
+        for parent in wc_commit.parent_ids() {
+            let records = get_copy_records(
+                repo.store(),
+                parent,
+                wc_commit.id(),
+                matcher,
+                &CopyDetectionOptions::default(),
+            )?;
+            copy_records.add_records(records)?;
+        }
+        let mut changes = vec![];
+        let tree_diff = parent_tree.diff_stream_with_copies(&tree, matcher, &copy_records);
+        for entry in block_on_stream(tree_diff) {
+            let (before, after) = entry.values?;
+            let status = match entry.path.copy_operation() {
+                Some(CopyOperation::Copy) => "copied",
+                Some(CopyOperation::Rename) => "renamed",
+                None => match (before.is_present(), after.is_present()) {
+                    (true, true) => "modified",
+                    (false, true) => "added",
+                    (true, false) => "removed",
+                    (false, false) => unreachable!(),
+                },
+            };
+            let mut change = serde_json::json!({
+                "path": entry.path.target().as_internal_file_string(),
+                "status": status,
+            });
+            if entry.path.copy_operation().is_some() {
+                change["source"] = entry.path.source().as_internal_file_string().into();
+            }
+            changes.push(change);
+        }
+        let conflicts = tree
+            .conflicts()
+            .filter(|(path, _)| matcher.matches(path))
+            .map(|(path, _)| path.as_internal_file_string().to_owned())
+            .collect_vec();
+        let mut working_copy = commit_to_json(wc_commit);
+        working_copy["changes"] = changes.into();
+        working_copy["conflicts"] = conflicts.into();
+        working_copy["parents"] = wc_commit
+            .parents()
+            .map_ok(|parent| commit_to_json(&parent))
+            .try_collect::<_, Vec<_>, _>()?
+            .into();
+        working_copy
+    } else {
+        serde_json::Value::Null
+    };
+
+    let conflicted_bookmarks = repo
+        .view()
+        .local_bookmarks()
+        .filter(|(_, target)| target.has_conflict())
+        .map(|(bookmark_name, target)| {
+            serde_json::json!({
+                "name": bookmark_name,
+                "targets": commit_ids_to_json(target.added_ids()),
+            })
+        })
+        .collect_vec();
+    let conflicted_remote_bookmarks = repo
+        .view()
+        .all_remote_bookmarks()
+        .filter(|(_, remote_ref)| remote_ref.target.has_conflict())
+        .map(|((bookmark_name, remote_name), remote_ref)| {
+            serde_json::json!({
+                "name": bookmark_name,
+                "remote": remote_name,
+                "targets": commit_ids_to_json(remote_ref.target.added_ids()),
+            })
+        })
+        .collect_vec();
+
+    let status = serde_json::json!({
+        "working_copy": working_copy,
+        "conflicted_bookmarks": conflicted_bookmarks,
+        "conflicted_remote_bookmarks": conflicted_remote_bookmarks,
+    });
+    let mut formatter = ui.stdout_formatter();
+    serde_json::to_writer_pretty(formatter.as_mut(), &status).map_err(io::Error::from)?;
+    writeln!(formatter)?;
+    Ok(())
+}
+
+fn commit_ids_to_json<'a>(ids: impl IntoIterator<Item = &'a CommitId>) -> serde_json::Value {
+    ids.into_iter().map(|id| id.hex()).collect_vec().into()
+}
@@ -12,18 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs;
 use std::io::Write;
+use std::path::Path;
 use std::slice;
 use std::time::Duration;
 use std::time::SystemTime;
 
 use clap::Command;
 use clap::Subcommand;
+use jj_lib::backend::MillisSinceEpoch;
+use jj_lib::git_backend::GitBackend;
 use jj_lib::repo::Repo;
+use jj_lib::settings::HumanByteSize;
+use jj_lib::time_util::DatePattern;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -72,17 +79,20 @@ pub(crate) struct UtilCompletionArgs {
     zsh: bool,
 }
 
+/// How old obsolete objects and operations must be to be pruned by default.
+pub(crate) const DEFAULT_GC_EXPIRATION: Duration = Duration::from_secs(14 * 86400);
+
 /// Run backend-dependent garbage collection.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct UtilGcArgs {
     /// Time threshold
     ///
     /// By default, only obsolete objects and operations older than 2 weeks are
-    /// pruned.
+    /// pruned. Objects reachable from commits and operations newer than the
+    /// threshold are always kept.
     ///
-    /// Only the string "now" can be passed to this parameter. Support for
-    /// arbitrary absolute and relative timestamps will come in a subsequent
-    /// release.
+    /// Accepts "now", a relative time such as "3 days ago" or Git's
+    /// "2.weeks.ago", or an absolute date such as "2024-01-01".
     #[arg(long)]
     expire: Option<String>,
 }
@@ -182,19 +192,62 @@ fn cmd_util_gc(
         ));
     }
     let keep_newer = match args.expire.as_deref() {
-        None => SystemTime::now() - Duration::from_secs(14 * 86400),
+        None => SystemTime::now() - DEFAULT_GC_EXPIRATION,
         Some("now") => SystemTime::now() - Duration::ZERO,
-        _ => return Err(user_error("--expire only accepts 'now'")),
+        Some(text) => parse_expire_time(text)?,
     };
     let workspace_command = command.workspace_helper(ui)?;
 
     let repo = workspace_command.repo();
+    let mut store_paths = vec![workspace_command.repo_path().to_owned()];
+    if let Some(git_backend) = repo.store().backend_impl().downcast_ref::<GitBackend>() {
+        if !git_backend
+            .git_repo_path()
+            .starts_with(workspace_command.repo_path())
+        {
+            store_paths.push(git_backend.git_repo_path().to_owned());
+        }
+    }
+    let size_before: u64 = store_paths.iter().map(|path| dir_size(path)).sum();
     repo.op_store()
         .gc(slice::from_ref(repo.op_id()), keep_newer)?;
     repo.store().gc(repo.index(), keep_newer)?;
+    let size_after: u64 = store_paths.iter().map(|path| dir_size(path)).sum();
+    writeln!(
+        ui.status(),
+        "Reclaimed {}",
+        HumanByteSize(size_before.saturating_sub(size_after))
+    )?;
     Ok(())
 }
 
+/// Parses `--expire` time, also accepting Git-style "2.weeks.ago".
+fn parse_expire_time(text: &str) -> Result<SystemTime, CommandError> {
+    let normalized = text.replace('.', " ");
+    let pattern = DatePattern::from_str_kind(&normalized, "before", chrono::Local::now())
+        .map_err(|err| user_error_with_message(format!("Invalid --expire time \"{text}\""), err))?;
+    let DatePattern::Before(MillisSinceEpoch(millis)) = pattern else {
+        unreachable!()
+    };
+    let millis = u64::try_from(millis).unwrap_or(0);
+    Ok(SystemTime::UNIX_EPOCH + Duration::from_millis(millis))
+}
+
+/// Returns the total size of the files in the directory, ignoring errors.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
 fn cmd_util_mangen(
     ui: &mut Ui,
     command: &CommandHelper,
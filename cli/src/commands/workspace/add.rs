@@ -18,6 +18,7 @@ use itertools::Itertools;
 use jj_lib::commit::CommitIteratorExt;
 use jj_lib::file_util;
 use jj_lib::file_util::IoResultExt;
+use jj_lib::git;
 use jj_lib::op_store::WorkspaceId;
 use jj_lib::repo::Repo;
 use jj_lib::rewrite::merge_commit_trees;
@@ -29,6 +30,7 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::commands::git::maybe_add_gitignore;
 use crate::ui::Ui;
 
 /// How to handle sparse patterns when creating a new workspace.
@@ -73,6 +75,13 @@ pub struct WorkspaceAddArgs {
     /// How to handle sparse patterns when creating a new workspace.
     #[arg(long, value_enum, default_value_t = SparseInheritance::Copy)]
     sparse_patterns: SparseInheritance,
+    /// Colocate the new workspace with a Git worktree
+    ///
+    /// The new workspace is registered as a linked worktree of the backing Git
+    /// repo. It has its own Git `HEAD`, so the directory can be used by both
+    /// `jj` and `git`.
+    #[arg(long)]
+    colocate: bool,
 }
 
 #[instrument(skip_all)]
@@ -82,6 +91,14 @@ pub fn cmd_workspace_add(
     args: &WorkspaceAddArgs,
 ) -> Result<(), CommandError> {
     let old_workspace_command = command.workspace_helper(ui)?;
+    let git_repo = if args.colocate {
+        let git_backend = old_workspace_command
+            .git_backend()
+            .ok_or_else(|| user_error("The repo is not backed by a git repo"))?;
+        Some(git_backend.git_repo())
+    } else {
+        None
+    };
     let destination_path = command.cwd().join(&args.destination);
     if destination_path.exists() {
         return Err(user_error("Workspace already exists"));
@@ -116,6 +133,9 @@ pub fn cmd_workspace_add(
         working_copy_factory,
         workspace_id,
     )?;
+    if let Some(git_repo) = &git_repo {
+        git::add_worktree(git_repo, &destination_path)?;
+    }
     writeln!(
         ui.status(),
         "Created workspace in \"{}\"",
@@ -132,6 +152,7 @@ pub fn cmd_workspace_add(
     }
 
     let mut new_workspace_command = command.for_workable_repo(ui, new_workspace, repo)?;
+    maybe_add_gitignore(&new_workspace_command)?;
 
     let sparsity = match args.sparse_patterns {
         SparseInheritance::Full => None,
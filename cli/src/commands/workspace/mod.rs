@@ -14,6 +14,7 @@
 
 mod add;
 mod forget;
+mod id;
 mod list;
 mod rename;
 mod root;
@@ -26,6 +27,8 @@ use self::add::cmd_workspace_add;
 use self::add::WorkspaceAddArgs;
 use self::forget::cmd_workspace_forget;
 use self::forget::WorkspaceForgetArgs;
+use self::id::cmd_workspace_id;
+use self::id::WorkspaceIdArgs;
 use self::list::cmd_workspace_list;
 use self::list::WorkspaceListArgs;
 use self::rename::cmd_workspace_rename;
@@ -53,6 +56,7 @@ use crate::ui::Ui;
 pub(crate) enum WorkspaceCommand {
     Add(WorkspaceAddArgs),
     Forget(WorkspaceForgetArgs),
+    Id(WorkspaceIdArgs),
     List(WorkspaceListArgs),
     Rename(WorkspaceRenameArgs),
     Root(WorkspaceRootArgs),
@@ -68,6 +72,7 @@ pub(crate) fn cmd_workspace(
     match subcommand {
         WorkspaceCommand::Add(args) => cmd_workspace_add(ui, command, args),
         WorkspaceCommand::Forget(args) => cmd_workspace_forget(ui, command, args),
+        WorkspaceCommand::Id(args) => cmd_workspace_id(ui, command, args),
         WorkspaceCommand::List(args) => cmd_workspace_list(ui, command, args),
         WorkspaceCommand::Rename(args) => cmd_workspace_rename(ui, command, args),
         WorkspaceCommand::Root(args) => cmd_workspace_root(ui, command, args),
@@ -23,6 +23,7 @@ use tracing::instrument;
 use crate::cli_util::check_stale_working_copy;
 use crate::cli_util::print_checkout_stats;
 use crate::cli_util::short_commit_hash;
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkingCopyFreshness;
 use crate::cli_util::WorkspaceCommandHelper;
@@ -80,6 +81,7 @@ pub fn cmd_workspace_update_stale(
             if known_wc_commit.tree_id() != locked_ws.locked_wc().old_tree_id() {
                 return Err(user_error("Concurrent working copy operation. Try again."));
             }
+            let old_op_id = locked_ws.locked_wc().old_operation_id().clone();
             let stats = locked_ws
                 .locked_wc()
                 .check_out(&desired_wc_commit)
@@ -94,6 +96,12 @@ pub fn cmd_workspace_update_stale(
                 })?;
             locked_ws.finish(repo.op_id().clone())?;
             if let Some(mut formatter) = ui.status_formatter() {
+                writeln!(
+                    formatter,
+                    "Updated working copy from operation {} to {}",
+                    short_operation_hash(&old_op_id),
+                    short_operation_hash(repo.op_id())
+                )?;
                 write!(formatter, "Working copy now at: ")?;
                 formatter.with_label("working_copy", |fmt| {
                     workspace_command.write_commit_summary(fmt, &desired_wc_commit)
@@ -17,13 +17,16 @@ use std::cmp::max;
 use std::collections::HashMap;
 use std::io;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use futures::stream::BoxStream;
 use itertools::Itertools as _;
 use jj_lib::backend::BackendResult;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::CopyDetectionOptions;
 use jj_lib::commit::Commit;
+use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopyRecords;
 use jj_lib::extensions_map::ExtensionsMap;
@@ -47,6 +50,9 @@ use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetModifier;
 use jj_lib::revset::RevsetParseContext;
+use jj_lib::signing::SigStatus;
+use jj_lib::signing::SignError;
+use jj_lib::signing::Verification;
 use jj_lib::store::Store;
 use once_cell::unsync::OnceCell;
 
@@ -237,6 +243,19 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
+                let type_name = "CryptographicSignature";
+                let table = &self.build_fn_table.cryptographic_signature_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name);
+                build(
+                    self,
+                    diagnostics,
+                    build_ctx,
+                    Box::new(inner_property),
+                    function,
+                )
+            }
         }
     }
 }
@@ -313,6 +332,12 @@ impl<'repo> CommitTemplateLanguage<'repo> {
     ) -> CommitTemplatePropertyKind<'repo> {
         CommitTemplatePropertyKind::TreeDiff(Box::new(property))
     }
+
+    pub fn wrap_cryptographic_signature_opt(
+        property: impl TemplateProperty<Output = Option<CryptographicSignature>> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo> {
+        CommitTemplatePropertyKind::CryptographicSignatureOpt(Box::new(property))
+    }
 }
 
 pub enum CommitTemplatePropertyKind<'repo> {
@@ -326,6 +351,9 @@ pub enum CommitTemplatePropertyKind<'repo> {
     CommitOrChangeId(Box<dyn TemplateProperty<Output = CommitOrChangeId> + 'repo>),
     ShortestIdPrefix(Box<dyn TemplateProperty<Output = ShortestIdPrefix> + 'repo>),
     TreeDiff(Box<dyn TemplateProperty<Output = TreeDiff> + 'repo>),
+    CryptographicSignatureOpt(
+        Box<dyn TemplateProperty<Output = Option<CryptographicSignature>> + 'repo>,
+    ),
 }
 
 impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
@@ -341,6 +369,9 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             CommitTemplatePropertyKind::CommitOrChangeId(_) => "CommitOrChangeId",
             CommitTemplatePropertyKind::ShortestIdPrefix(_) => "ShortestIdPrefix",
             CommitTemplatePropertyKind::TreeDiff(_) => "TreeDiff",
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => {
+                "Option<CryptographicSignature>"
+            }
         }
     }
 
@@ -366,6 +397,9 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             // TODO: boolean cast could be implemented, but explicit
             // diff.empty() method might be better.
             CommitTemplatePropertyKind::TreeDiff(_) => None,
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
+                Some(Box::new(property.map(|opt| opt.is_some())))
+            }
         }
     }
 
@@ -402,6 +436,7 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
                 Some(property.into_template())
             }
             CommitTemplatePropertyKind::TreeDiff(_) => None,
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => None,
         }
     }
 }
@@ -418,6 +453,8 @@ pub struct CommitTemplateBuildFnTable<'repo> {
     pub commit_or_change_id_methods: CommitTemplateBuildMethodFnMap<'repo, CommitOrChangeId>,
     pub shortest_id_prefix_methods: CommitTemplateBuildMethodFnMap<'repo, ShortestIdPrefix>,
     pub tree_diff_methods: CommitTemplateBuildMethodFnMap<'repo, TreeDiff>,
+    pub cryptographic_signature_methods:
+        CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature>,
 }
 
 impl<'repo> CommitTemplateBuildFnTable<'repo> {
@@ -430,6 +467,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods: builtin_commit_or_change_id_methods(),
             shortest_id_prefix_methods: builtin_shortest_id_prefix_methods(),
             tree_diff_methods: builtin_tree_diff_methods(),
+            cryptographic_signature_methods: builtin_cryptographic_signature_methods(),
         }
     }
 
@@ -441,6 +479,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods: HashMap::new(),
             shortest_id_prefix_methods: HashMap::new(),
             tree_diff_methods: HashMap::new(),
+            cryptographic_signature_methods: HashMap::new(),
         }
     }
 
@@ -452,6 +491,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods,
             shortest_id_prefix_methods,
             tree_diff_methods,
+            cryptographic_signature_methods,
         } = extension;
 
         self.core.merge(core);
@@ -466,6 +506,10 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             shortest_id_prefix_methods,
         );
         merge_fn_map(&mut self.tree_diff_methods, tree_diff_methods);
+        merge_fn_map(
+            &mut self.cryptographic_signature_methods,
+            cryptographic_signature_methods,
+        );
     }
 }
 
@@ -567,6 +611,14 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(L::wrap_signature(out_property))
         },
     );
+    map.insert(
+        "signature",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(CryptographicSignature::new);
+            Ok(L::wrap_cryptographic_signature_opt(out_property))
+        },
+    );
     map.insert(
         "mine",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -703,8 +755,9 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
         |language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
             let repo = language.repo;
-            let out_property = self_property.map(|commit| {
-                let target = repo.view().git_head();
+            let workspace_id = language.workspace_id.clone();
+            let out_property = self_property.map(move |commit| {
+                let target = repo.view().workspace_git_head(&workspace_id);
                 target.added_ids().contains(commit.id())
             });
             Ok(L::wrap_boolean(out_property))
@@ -1440,8 +1493,13 @@ impl TreeDiff {
     ) -> BackendResult<Self> {
         let mut copy_records = CopyRecords::default();
         for parent in commit.parent_ids() {
-            let records =
-                diff_util::get_copy_records(repo.store(), parent, commit.id(), &*matcher)?;
+            let records = diff_util::get_copy_records(
+                repo.store(),
+                parent,
+                commit.id(),
+                &*matcher,
+                &CopyDetectionOptions::default(),
+            )?;
             copy_records.add_records(records)?;
         }
         Ok(TreeDiff {
@@ -1512,13 +1570,18 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                         context: context.unwrap_or(diff_util::DEFAULT_CONTEXT_LINES),
                         line_diff: diff_util::LineDiffOptions {
                             compare_mode: diff_util::LineCompareMode::Exact,
+                            ignore_blank_lines: false,
                         },
                         max_inline_alternation: Some(3),
+                        conflict_marker_style: ConflictMarkerStyle::default(),
                     };
-                    diff.into_formatted(move |formatter, store, tree_diff| {
+                    let from_tree = diff.from_tree.clone();
+                    let to_tree = diff.to_tree.clone();
+                    diff.into_formatted(move |formatter, _store, tree_diff| {
                         diff_util::show_color_words_diff(
                             formatter,
-                            store,
+                            &from_tree,
+                            &to_tree,
                             tree_diff,
                             path_converter,
                             &options,
@@ -1549,7 +1612,9 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                         context: context.unwrap_or(diff_util::DEFAULT_CONTEXT_LINES),
                         line_diff: diff_util::LineDiffOptions {
                             compare_mode: diff_util::LineCompareMode::Exact,
+                            ignore_blank_lines: false,
                         },
+                        conflict_marker_style: ConflictMarkerStyle::default(),
                     };
                     diff.into_formatted(move |formatter, store, tree_diff| {
                         diff_util::show_git_diff(formatter, store, tree_diff, &options)
@@ -1575,6 +1640,7 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                     let options = diff_util::DiffStatOptions {
                         line_diff: diff_util::LineDiffOptions {
                             compare_mode: diff_util::LineCompareMode::Exact,
+                            ignore_blank_lines: false,
                         },
                     };
                     diff.into_formatted(move |formatter, store, tree_diff| {
@@ -1612,3 +1678,72 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
     // TODO: add files() or map() to support custom summary-like formatting?
     map
 }
+
+/// Cryptographic signature of a commit, verified on demand.
+#[derive(Debug)]
+pub struct CryptographicSignature {
+    commit: Commit,
+    // The error is shared so that it can be reported more than once.
+    verification: OnceCell<Result<Verification, Arc<SignError>>>,
+}
+
+impl CryptographicSignature {
+    fn new(commit: Commit) -> Option<Self> {
+        commit.is_signed().then(|| Self {
+            commit,
+            verification: OnceCell::new(),
+        })
+    }
+
+    fn verify(&self) -> Result<&Verification, Arc<SignError>> {
+        self.verification
+            .get_or_init(|| {
+                // Unsigned commits are filtered out by new()
+                Ok(self.commit.verification()?.unwrap())
+            })
+            .as_ref()
+            .map_err(Arc::clone)
+    }
+}
+
+fn builtin_cryptographic_signature_methods<'repo>(
+) -> CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature> {
+    type L<'repo> = CommitTemplateLanguage<'repo>;
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<CryptographicSignature>::new();
+    map.insert(
+        "status",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| {
+                let status = match sig.verify()?.status {
+                    SigStatus::Good => "good",
+                    SigStatus::Unknown => "unknown",
+                    SigStatus::Bad => "bad",
+                };
+                Ok(status.to_owned())
+            });
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "key",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|sig| Ok(sig.verify()?.key.clone().unwrap_or_default()));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "display",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|sig| Ok(sig.verify()?.display.clone().unwrap_or_default()));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map
+}
@@ -202,6 +202,8 @@ pub struct AnnotatedValue {
     pub path: ConfigNamePathBuf,
     pub value: config::Value,
     pub source: ConfigSource,
+    /// Path to the config file the value was read from.
+    pub origin: Option<PathBuf>,
     pub is_overridden: bool,
 }
 
@@ -220,7 +222,10 @@ pub struct LayeredConfigs {
     default: config::Config,
     env_base: config::Config,
     user: Option<config::Config>,
+    /// User config files and their contents, used to track provenance.
+    user_files: Vec<(PathBuf, config::Config)>,
     repo: Option<config::Config>,
+    repo_file: Option<PathBuf>,
     env_overrides: config::Config,
     arg_overrides: Option<config::Config>,
 }
@@ -232,7 +237,9 @@ impl LayeredConfigs {
             default,
             env_base: env_base(),
             user: None,
+            user_files: vec![],
             repo: None,
+            repo_file: None,
             env_overrides: env_overrides(),
             arg_overrides: None,
         }
@@ -240,9 +247,20 @@ impl LayeredConfigs {
 
     #[instrument]
     pub fn read_user_config(&mut self) -> Result<(), ConfigError> {
-        self.user = existing_config_path()?
-            .map(|path| read_config_path(&path))
-            .transpose()?;
+        let Some(config_path) = existing_config_path()? else {
+            self.user = None;
+            self.user_files = vec![];
+            return Ok(());
+        };
+        self.user_files = read_config_path(&config_path)?;
+        self.user = Some(
+            self.user_files
+                .iter()
+                .fold(config::Config::builder(), |builder, (_, config)| {
+                    builder.add_source(config.clone())
+                })
+                .build()?,
+        );
         Ok(())
     }
 
@@ -252,7 +270,9 @@ impl LayeredConfigs {
 
     #[instrument]
     pub fn read_repo_config(&mut self, repo_path: &Path) -> Result<(), ConfigError> {
-        self.repo = Some(read_config_file(&self.repo_config_path(repo_path))?);
+        let path = self.repo_config_path(repo_path);
+        self.repo = Some(read_config_file(&path)?);
+        self.repo_file = Some(path);
         Ok(())
     }
 
@@ -320,10 +340,12 @@ impl LayeredConfigs {
                         }
                     }
                     _ => {
+                        let origin = self.config_origin(&source, &path)?;
                         config_vals.push(AnnotatedValue {
                             path,
                             value: value.to_owned(),
                             source: source.clone(),
+                            origin,
                             // Note: Value updated below.
                             is_overridden: false,
                         });
@@ -341,6 +363,27 @@ impl LayeredConfigs {
 
         Ok(config_vals)
     }
+
+    /// Looks up the config file which the value at `path` was read from.
+    fn config_origin(
+        &self,
+        source: &ConfigSource,
+        path: &ConfigNamePathBuf,
+    ) -> Result<Option<PathBuf>, ConfigError> {
+        match source {
+            ConfigSource::User => {
+                // Later files take precedence over earlier ones.
+                for (file_path, config) in self.user_files.iter().rev() {
+                    if path.lookup_value(config).optional()?.is_some() {
+                        return Ok(Some(file_path.clone()));
+                    }
+                }
+                Ok(None)
+            }
+            ConfigSource::Repo => Ok(self.repo_file.clone()),
+            ConfigSource::Default | ConfigSource::Env | ConfigSource::CommandArg => Ok(None),
+        }
+    }
 }
 
 enum ConfigPath {
@@ -467,7 +510,11 @@ pub fn new_config_path() -> Result<Option<PathBuf>, ConfigError> {
 /// Environment variables that should be overridden by config values
 fn env_base() -> config::Config {
     let mut builder = config::Config::builder();
-    if env::var("NO_COLOR").is_ok() {
+    if env::var("NO_COLOR").is_ok_and(|value| !value.is_empty()) {
+        // "Command-line software which adds ANSI color to its output by default
+        // should check for a NO_COLOR environment variable that, when present
+        // and not an empty string (regardless of its value), prevents the
+        // addition of ANSI color."
         // "User-level configuration files and per-instance command-line arguments
         // should override $NO_COLOR." https://no-color.org/
         builder = builder.set_override("ui.color", "never").unwrap();
@@ -553,7 +600,9 @@ fn read_config_file(path: &Path) -> Result<config::Config, config::ConfigError>
         .build()
 }
 
-fn read_config_path(config_path: &Path) -> Result<config::Config, config::ConfigError> {
+fn read_config_path(
+    config_path: &Path,
+) -> Result<Vec<(PathBuf, config::Config)>, config::ConfigError> {
     let mut files = vec![];
     if config_path.is_dir() {
         if let Ok(read_dir) = config_path.read_dir() {
@@ -570,17 +619,14 @@ fn read_config_path(config_path: &Path) -> Result<config::Config, config::Config
         files.push(config_path.to_owned());
     }
 
+    // TODO: Accept other formats and/or accept only certain file extensions?
     files
-        .iter()
-        .fold(config::Config::builder(), |builder, path| {
-            // TODO: Accept other formats and/or accept only certain file extensions?
-            builder.add_source(
-                config::File::from(path.as_ref())
-                    .required(false)
-                    .format(config::FileFormat::Toml),
-            )
+        .into_iter()
+        .map(|path| {
+            let config = read_config_file(&path)?;
+            Ok((path, config))
         })
-        .build()
+        .collect()
 }
 
 pub fn write_config_value_to_file(
@@ -892,7 +938,9 @@ mod tests {
             default: empty_config.clone(),
             env_base: empty_config.clone(),
             user: None,
+            user_files: vec![],
             repo: None,
+            repo_file: None,
             env_overrides: empty_config,
             arg_overrides: None,
         };
@@ -923,7 +971,9 @@ mod tests {
             default: empty_config.clone(),
             env_base: env_base_config,
             user: None,
+            user_files: vec![],
             repo: Some(repo_config),
+            repo_file: None,
             env_overrides: empty_config,
             arg_overrides: None,
         };
@@ -960,6 +1010,7 @@ mod tests {
                     ),
                 },
                 source: Env,
+                origin: None,
                 is_overridden: true,
             },
             AnnotatedValue {
@@ -990,6 +1041,7 @@ mod tests {
                     ),
                 },
                 source: Env,
+                origin: None,
                 is_overridden: false,
             },
             AnnotatedValue {
@@ -1020,6 +1072,7 @@ mod tests {
                     ),
                 },
                 source: Repo,
+                origin: None,
                 is_overridden: false,
             },
         ]
@@ -1046,7 +1099,9 @@ mod tests {
             default: empty_config.clone(),
             env_base: empty_config.clone(),
             user: Some(user_config),
+            user_files: vec![],
             repo: Some(repo_config),
+            repo_file: None,
             env_overrides: empty_config,
             arg_overrides: None,
         };
@@ -1084,6 +1139,7 @@ mod tests {
                     ),
                 },
                 source: User,
+                origin: None,
                 is_overridden: false,
             },
             AnnotatedValue {
@@ -1114,6 +1170,7 @@ mod tests {
                     ),
                 },
                 source: Repo,
+                origin: None,
                 is_overridden: false,
             },
         ]
@@ -14,12 +14,18 @@
 
 use std::borrow::Borrow;
 use std::cmp::max;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::fmt;
 use std::io;
+use std::io::Read as _;
+use std::iter;
 use std::mem;
 use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
+use std::str;
+use std::sync::Arc;
 
 use bstr::BStr;
 use futures::executor::block_on_stream;
@@ -29,11 +35,13 @@ use itertools::Itertools;
 use jj_lib::backend::BackendError;
 use jj_lib::backend::BackendResult;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::CopyDetectionOptions;
 use jj_lib::backend::CopyRecord;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
-use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_merge_result_with_style;
 use jj_lib::conflicts::materialized_diff_stream;
+use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::conflicts::MaterializedTreeDiffEntry;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::copies::CopiesTreeDiffEntry;
@@ -50,12 +58,15 @@ use jj_lib::diff::DiffHunkKind;
 use jj_lib::files::DiffLineHunkSide;
 use jj_lib::files::DiffLineIterator;
 use jj_lib::files::DiffLineNumber;
+use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::repo_path::RepoPathComponent;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::rewrite::rebase_to_dest_parent;
 use jj_lib::settings::ConfigResultExt as _;
@@ -126,6 +137,51 @@ pub struct DiffFormatArgs {
     /// Ignore changes in amount of whitespace when comparing lines.
     #[arg(long, conflicts_with = "ignore_all_space")] // short = 'b'
     ignore_space_change: bool,
+    /// Ignore changes whose lines are all blank.
+    #[arg(long)]
+    ignore_blank_lines: bool,
+    /// How to render conflicted files
+    ///
+    /// Defaults to the `ui.conflict-style` setting, or `jj` if unset.
+    #[arg(long, value_enum, value_name = "STYLE")]
+    conflict_style: Option<ConflictStyleArg>,
+}
+
+/// Conflict marker style to render conflicted files with.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictStyleArg {
+    /// Show a snapshot of one side and diffs to the other sides
+    Jj,
+    /// Show a snapshot of each side, like Git's "merge" style
+    Git,
+    /// Show a snapshot of each side and of the base, like Git's "diff3" style
+    Diff3,
+}
+
+impl From<ConflictStyleArg> for ConflictMarkerStyle {
+    fn from(arg: ConflictStyleArg) -> Self {
+        match arg {
+            ConflictStyleArg::Jj => ConflictMarkerStyle::Jj,
+            ConflictStyleArg::Git => ConflictMarkerStyle::Git,
+            ConflictStyleArg::Diff3 => ConflictMarkerStyle::Diff3,
+        }
+    }
+}
+
+/// Returns the conflict marker style specified by `arg`, falling back to the
+/// `ui.conflict-style` setting.
+pub fn conflict_marker_style_for(
+    settings: &UserSettings,
+    arg: Option<ConflictStyleArg>,
+) -> Result<ConflictMarkerStyle, config::ConfigError> {
+    if let Some(arg) = arg {
+        return Ok(arg.into());
+    }
+    Ok(settings
+        .config()
+        .get::<ConflictMarkerStyle>("ui.conflict-style")
+        .optional()?
+        .unwrap_or_default())
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -184,7 +240,7 @@ fn diff_formats_from_args(
         formats.push(DiffFormat::NameOnly);
     }
     if args.git {
-        let options = UnifiedDiffOptions::from_args(args);
+        let options = UnifiedDiffOptions::from_settings_and_args(settings, args)?;
         formats.push(DiffFormat::Git(Box::new(options)));
     }
     if args.color_words {
@@ -230,7 +286,7 @@ fn default_diff_format(
         "types" => Ok(DiffFormat::Types),
         "name-only" => Ok(DiffFormat::NameOnly),
         "git" => {
-            let options = UnifiedDiffOptions::from_args(args);
+            let options = UnifiedDiffOptions::from_settings_and_args(settings, args)?;
             Ok(DiffFormat::Git(Box::new(options)))
         }
         "color-words" => {
@@ -350,7 +406,14 @@ impl<'a> DiffRenderer<'a> {
                 DiffFormat::ColorWords(options) => {
                     let tree_diff =
                         from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
-                    show_color_words_diff(formatter, store, tree_diff, path_converter, options)?;
+                    show_color_words_diff(
+                        formatter,
+                        from_tree,
+                        to_tree,
+                        tree_diff,
+                        path_converter,
+                        options,
+                    )?;
                 }
                 DiffFormat::Tool(tool) => {
                     match tool.diff_invocation_mode {
@@ -417,7 +480,13 @@ impl<'a> DiffRenderer<'a> {
         let to_tree = commit.tree()?;
         let mut copy_records = CopyRecords::default();
         for parent_id in commit.parent_ids() {
-            let records = get_copy_records(self.repo.store(), parent_id, commit.id(), matcher)?;
+            let records = get_copy_records(
+                self.repo.store(),
+                parent_id,
+                commit.id(),
+                matcher,
+                &CopyDetectionOptions::default(),
+            )?;
             copy_records.add_records(records)?;
         }
         self.show_diff(
@@ -437,9 +506,10 @@ pub fn get_copy_records<'a>(
     root: &CommitId,
     head: &CommitId,
     matcher: &'a dyn Matcher,
+    options: &CopyDetectionOptions,
 ) -> BackendResult<impl Iterator<Item = BackendResult<CopyRecord>> + 'a> {
     // TODO: teach backend about matching path prefixes?
-    let stream = store.get_copy_records(None, root, head)?;
+    let stream = store.get_copy_records(None, root, head, options)?;
     // TODO: test record.source as well? should be AND-ed or OR-ed?
     Ok(block_on_stream(stream).filter_ok(|record| matcher.matches(&record.target)))
 }
@@ -448,7 +518,8 @@ pub fn get_copy_records<'a>(
 pub struct LineDiffOptions {
     /// How equivalence of lines is tested.
     pub compare_mode: LineCompareMode,
-    // TODO: add --ignore-blank-lines, etc. which aren't mutually exclusive.
+    /// Whether to ignore changes that only add or remove blank lines.
+    pub ignore_blank_lines: bool,
 }
 
 impl LineDiffOptions {
@@ -460,7 +531,10 @@ impl LineDiffOptions {
         } else {
             LineCompareMode::Exact
         };
-        LineDiffOptions { compare_mode }
+        LineDiffOptions {
+            compare_mode,
+            ignore_blank_lines: args.ignore_blank_lines,
+        }
     }
 }
 
@@ -478,11 +552,7 @@ fn diff_by_line<'input, T: AsRef<[u8]> + ?Sized + 'input>(
     inputs: impl IntoIterator<Item = &'input T>,
     options: &LineDiffOptions,
 ) -> Diff<'input> {
-    // TODO: If we add --ignore-blank-lines, its tokenizer will have to attach
-    // blank lines to the preceding range. Maybe it can also be implemented as a
-    // post-process (similar to refine_changed_regions()) that expands unchanged
-    // regions across blank lines.
-    match options.compare_mode {
+    let mut diff = match options.compare_mode {
         LineCompareMode::Exact => {
             Diff::for_tokenizer(inputs, find_line_ranges, CompareBytesExactly)
         }
@@ -492,7 +562,11 @@ fn diff_by_line<'input, T: AsRef<[u8]> + ?Sized + 'input>(
         LineCompareMode::IgnoreSpaceChange => {
             Diff::for_tokenizer(inputs, find_line_ranges, CompareBytesIgnoreWhitespaceAmount)
         }
+    };
+    if options.ignore_blank_lines {
+        diff.ignore_blank_line_changes();
     }
+    diff
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -503,6 +577,8 @@ pub struct ColorWordsDiffOptions {
     pub line_diff: LineDiffOptions,
     /// Maximum number of removed/added word alternation to inline.
     pub max_inline_alternation: Option<usize>,
+    /// How conflicted files are rendered.
+    pub conflict_marker_style: ConflictMarkerStyle,
 }
 
 impl ColorWordsDiffOptions {
@@ -524,6 +600,7 @@ impl ColorWordsDiffOptions {
             context: args.context.unwrap_or(DEFAULT_CONTEXT_LINES),
             line_diff: LineDiffOptions::from_args(args),
             max_inline_alternation,
+            conflict_marker_style: conflict_marker_style_for(settings, args.conflict_style)?,
         })
     }
 }
@@ -586,22 +663,18 @@ fn show_color_words_context_lines(
     num_before: usize,
 ) -> io::Result<DiffLineNumber> {
     const SKIPPED_CONTEXT_LINE: &str = "    ...\n";
-    let extract = |side: usize| -> (Vec<&[u8]>, Vec<&[u8]>, u32) {
-        let mut lines = contexts
-            .iter()
-            .flat_map(|contents| contents[side].split_inclusive(|b| *b == b'\n'))
-            .fuse();
-        let after_lines = lines.by_ref().take(num_after).collect();
-        let before_lines = lines.by_ref().rev().take(num_before + 1).collect();
-        let num_skipped: u32 = lines.count().try_into().unwrap();
-        (after_lines, before_lines, num_skipped)
-    };
+    let rows = align_context_lines(contexts, options);
     let show = |formatter: &mut dyn Formatter,
-                left_lines: &[&[u8]],
-                right_lines: &[&[u8]],
-                mut line_number: DiffLineNumber| {
-        if left_lines == right_lines {
-            for line in left_lines {
+                rows: &[ContextRow],
+                mut line_number: DiffLineNumber|
+     -> io::Result<DiffLineNumber> {
+        // Lines that differ only in ignored whitespace or blank lines are
+        // shown as a diff, and identical lines as they are.
+        let mut rows = rows.iter().peekable();
+        while rows.peek().is_some() {
+            let identical_rows =
+                rows.peeking_take_while(|(left, right)| left.is_some() && left == right);
+            for (line, _) in identical_rows {
                 show_color_words_line_number(
                     formatter,
                     Some(line_number.left),
@@ -609,44 +682,103 @@ fn show_color_words_context_lines(
                 )?;
                 show_color_words_inline_hunks(
                     formatter,
-                    &[(DiffLineHunkSide::Both, line.as_ref())],
+                    &[(DiffLineHunkSide::Both, line.unwrap().as_ref())],
                 )?;
                 line_number.left += 1;
                 line_number.right += 1;
             }
-            Ok(line_number)
-        } else {
-            let left = left_lines.concat();
-            let right = right_lines.concat();
-            show_color_words_diff_lines(
-                formatter,
-                &[BStr::new(&left), BStr::new(&right)],
-                line_number,
-                options,
-            )
+            let (left, right): (Vec<_>, Vec<_>) = rows
+                .peeking_take_while(|(left, right)| left.is_none() || left != right)
+                .map(|&(left, right)| (left.unwrap_or_default(), right.unwrap_or_default()))
+                .unzip();
+            if !left.is_empty() {
+                let left = left.concat();
+                let right = right.concat();
+                line_number = show_color_words_diff_lines(
+                    formatter,
+                    &[BStr::new(&left), BStr::new(&right)],
+                    line_number,
+                    options,
+                )?;
+            }
+        }
+        Ok(line_number)
+    };
+    let count_lines = |rows: &[ContextRow]| -> DiffLineNumber {
+        let count = |side: fn(&ContextRow) -> bool| -> u32 {
+            rows.iter()
+                .filter(|row| side(row))
+                .count()
+                .try_into()
+                .unwrap()
+        };
+        DiffLineNumber {
+            left: count(|(left, _)| left.is_some()),
+            right: count(|(_, right)| right.is_some()),
         }
     };
 
-    let (left_after, mut left_before, num_left_skipped) = extract(0);
-    let (right_after, mut right_before, num_right_skipped) = extract(1);
-    line_number = show(formatter, &left_after, &right_after, line_number)?;
-    if num_left_skipped > 0 || num_right_skipped > 0 {
+    let num_after = num_after.min(rows.len());
+    let (after_rows, rest_rows) = rows.split_at(num_after);
+    let num_before = if rest_rows.len() > num_before + 1 {
+        num_before
+    } else {
+        rest_rows.len()
+    };
+    let (skipped_rows, before_rows) = rest_rows.split_at(rest_rows.len() - num_before);
+    line_number = show(formatter, after_rows, line_number)?;
+    if !skipped_rows.is_empty() {
         write!(formatter, "{SKIPPED_CONTEXT_LINE}")?;
-        line_number.left += num_left_skipped;
-        line_number.right += num_right_skipped;
-        if left_before.len() > num_before {
-            left_before.pop();
-            line_number.left += 1;
+        let num_skipped = count_lines(skipped_rows);
+        line_number.left += num_skipped.left;
+        line_number.right += num_skipped.right;
+    }
+    line_number = show(formatter, before_rows, line_number)?;
+    Ok(line_number)
+}
+
+/// Pair of left and right lines of the diff context. Either side may be
+/// missing if blank lines are ignored.
+type ContextRow<'a> = (Option<&'a [u8]>, Option<&'a [u8]>);
+
+/// Splits the context hunks into rows of corresponding lines.
+fn align_context_lines<'a>(
+    contexts: &[DiffHunkContentVec<'a>],
+    options: &ColorWordsDiffOptions,
+) -> Vec<ContextRow<'a>> {
+    let split_lines = |text: &'a [u8]| text.split_inclusive(|b| *b == b'\n');
+    let mut rows = vec![];
+    for contents in contexts {
+        let [left, right]: [&'a BStr; 2] = contents[..].try_into().unwrap();
+        if !options.line_diff.ignore_blank_lines {
+            // Both sides have the same number of lines
+            rows.extend(
+                iter::zip(split_lines(left), split_lines(right))
+                    .map(|(left, right)| (Some(left), Some(right))),
+            );
+            continue;
         }
-        if right_before.len() > num_before {
-            right_before.pop();
-            line_number.right += 1;
+        let line_diff_options = LineDiffOptions {
+            ignore_blank_lines: false,
+            ..options.line_diff.clone()
+        };
+        for hunk in diff_by_line([left, right], &line_diff_options).hunks() {
+            let [left, right]: [&'a BStr; 2] = hunk.contents[..].try_into().unwrap();
+            match hunk.kind {
+                DiffHunkKind::Matching => {
+                    rows.extend(
+                        iter::zip(split_lines(left), split_lines(right))
+                            .map(|(left, right)| (Some(left), Some(right))),
+                    );
+                }
+                DiffHunkKind::Different => {
+                    rows.extend(split_lines(left).map(|line| (Some(line), None)));
+                    rows.extend(split_lines(right).map(|line| (None, Some(line))));
+                }
+            }
         }
     }
-    left_before.reverse();
-    right_before.reverse();
-    line_number = show(formatter, &left_before, &right_before, line_number)?;
-    Ok(line_number)
+    rows
 }
 
 fn show_color_words_diff_lines(
@@ -815,6 +947,160 @@ impl FileContent {
     }
 }
 
+/// A Git LFS pointer file, which is stored in place of the actual content of a
+/// file tracked by Git LFS.
+///
+/// See https://github.com/git-lfs/git-lfs/blob/main/docs/spec.md
+#[derive(Debug, PartialEq)]
+struct LfsPointer {
+    oid: String,
+    size: u64,
+}
+
+impl LfsPointer {
+    /// Pointer files are limited to this size by the spec.
+    const MAX_SIZE: usize = 1024;
+
+    /// Parses `contents` as a Git LFS pointer file, returning `None` if it
+    /// isn't one.
+    fn parse(contents: &[u8]) -> Option<Self> {
+        if contents.len() >= Self::MAX_SIZE {
+            return None;
+        }
+        let text = str::from_utf8(contents).ok()?;
+        let mut lines = text.strip_suffix('\n')?.split('\n');
+        let version = lines.next()?.strip_prefix("version ")?;
+        if !matches!(
+            version,
+            "https://git-lfs.github.com/spec/v1" | "https://hawser.github.com/spec/v1"
+        ) {
+            return None;
+        }
+        let mut oid = None;
+        let mut size = None;
+        for line in lines {
+            let (key, value) = line.split_once(' ')?;
+            match key {
+                "oid" => {
+                    let hex = value.strip_prefix("sha256:")?;
+                    if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                        return None;
+                    }
+                    oid = Some(hex.to_owned());
+                }
+                "size" => size = Some(value.parse().ok()?),
+                _ => {}
+            }
+        }
+        Some(LfsPointer {
+            oid: oid?,
+            size: size?,
+        })
+    }
+}
+
+impl fmt::Display for LfsPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes, sha256:{}", self.size, &self.oid[..12])
+    }
+}
+
+/// Looks up which paths are stored in Git LFS according to the
+/// `.gitattributes` files of a tree.
+///
+/// Only the `filter` attribute is considered. The patterns are matched with
+/// gitignore semantics, which is close enough for the usual `*.ext` patterns.
+struct LfsAttributes<'a> {
+    tree: &'a MergedTree,
+    dirs: HashMap<RepoPathBuf, Arc<GitIgnoreFile>>,
+}
+
+impl<'a> LfsAttributes<'a> {
+    fn new(tree: &'a MergedTree) -> Self {
+        LfsAttributes {
+            tree,
+            dirs: HashMap::new(),
+        }
+    }
+
+    /// Returns whether `path` has the `filter=lfs` attribute.
+    fn matches(&mut self, path: &RepoPath) -> BackendResult<bool> {
+        let Some(dir) = path.parent() else {
+            return Ok(false);
+        };
+        let patterns = self.patterns_for_dir(dir)?;
+        Ok(patterns.matches(path.as_internal_file_string()))
+    }
+
+    fn patterns_for_dir(&mut self, dir: &RepoPath) -> BackendResult<Arc<GitIgnoreFile>> {
+        if let Some(patterns) = self.dirs.get(dir) {
+            return Ok(patterns.clone());
+        }
+        let parent_patterns = match dir.parent() {
+            Some(parent) => self.patterns_for_dir(parent)?,
+            None => GitIgnoreFile::empty(),
+        };
+        let attributes_path = dir.join(RepoPathComponent::new(".gitattributes"));
+        let patterns = match self.tree.path_value(&attributes_path)?.into_resolved() {
+            Ok(Some(TreeValue::File { id, .. })) => {
+                let mut content = vec![];
+                self.tree
+                    .store()
+                    .read_file(&attributes_path, &id)?
+                    .read_to_end(&mut content)
+                    .map_err(|err| BackendError::ReadFile {
+                        path: attributes_path.clone(),
+                        id: id.clone(),
+                        source: err.into(),
+                    })?;
+                let lines = lfs_filter_patterns(&content);
+                // Malformed attributes don't prevent showing the diff
+                parent_patterns
+                    .chain(&dir.to_internal_dir_string(), lines.as_bytes())
+                    .unwrap_or(parent_patterns)
+            }
+            _ => parent_patterns,
+        };
+        self.dirs.insert(dir.to_owned(), patterns.clone());
+        Ok(patterns)
+    }
+}
+
+/// Translates `.gitattributes` content into gitignore patterns matching the
+/// paths with the `filter=lfs` attribute.
+fn lfs_filter_patterns(content: &[u8]) -> String {
+    let mut patterns = String::new();
+    for line in content.split(|b| *b == b'\n') {
+        let Ok(line) = str::from_utf8(line) else {
+            continue;
+        };
+        let mut fields = line.split_ascii_whitespace();
+        let Some(pattern) = fields.next() else {
+            continue;
+        };
+        // Negative patterns are forbidden in .gitattributes
+        if pattern.starts_with('#') || pattern.starts_with('!') {
+            continue;
+        }
+        let is_lfs = fields.fold(None, |is_lfs, attr| match attr {
+            "filter=lfs" => Some(true),
+            "-filter" | "!filter" => Some(false),
+            _ if attr.starts_with("filter=") => Some(false),
+            _ => is_lfs,
+        });
+        match is_lfs {
+            Some(true) => patterns.push_str(pattern),
+            Some(false) => {
+                patterns.push('!');
+                patterns.push_str(pattern);
+            }
+            None => continue,
+        }
+        patterns.push('\n');
+    }
+    patterns
+}
+
 fn file_content_for_diff(reader: &mut dyn io::Read) -> io::Result<FileContent> {
     // If this is a binary file, don't show the full contents.
     // Determine whether it's binary by whether the first 8k bytes contain a null
@@ -833,7 +1119,11 @@ fn file_content_for_diff(reader: &mut dyn io::Read) -> io::Result<FileContent> {
     })
 }
 
-fn diff_content(path: &RepoPath, value: MaterializedTreeValue) -> io::Result<FileContent> {
+fn diff_content(
+    path: &RepoPath,
+    value: MaterializedTreeValue,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> io::Result<FileContent> {
     match value {
         MaterializedTreeValue::Absent => Ok(FileContent::empty()),
         MaterializedTreeValue::AccessDenied(err) => Ok(FileContent {
@@ -859,7 +1149,7 @@ fn diff_content(path: &RepoPath, value: MaterializedTreeValue) -> io::Result<Fil
             executable: _,
         } => {
             let mut data = vec![];
-            materialize_merge_result(&contents, &mut data)
+            materialize_merge_result_with_style(&contents, conflict_marker_style, &mut data)
                 .expect("Failed to materialize conflict to in-memory buffer");
             Ok(FileContent {
                 is_binary: false,
@@ -899,12 +1189,15 @@ fn basic_diff_file_type(value: &MaterializedTreeValue) -> &'static str {
 
 pub fn show_color_words_diff(
     formatter: &mut dyn Formatter,
-    store: &Store,
+    from_tree: &MergedTree,
+    to_tree: &MergedTree,
     tree_diff: BoxStream<CopiesTreeDiffEntry>,
     path_converter: &RepoPathUiConverter,
     options: &ColorWordsDiffOptions,
 ) -> Result<(), DiffRenderError> {
-    let mut diff_stream = materialized_diff_stream(store, tree_diff);
+    let mut diff_stream = materialized_diff_stream(from_tree.store(), tree_diff);
+    let mut left_lfs_attributes = LfsAttributes::new(from_tree);
+    let mut right_lfs_attributes = LfsAttributes::new(to_tree);
     async {
         while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
             let left_path = path.source();
@@ -938,9 +1231,16 @@ pub fn show_color_words_diff(
                     formatter.labeled("header"),
                     "Added {description} {right_ui_path}:"
                 )?;
-                let right_content = diff_content(right_path, right_value)?;
+                let right_content =
+                    diff_content(right_path, right_value, options.conflict_marker_style)?;
                 if right_content.is_empty() {
                     writeln!(formatter.labeled("empty"), "    (empty)")?;
+                } else if let Some(pointer) = right_lfs_attributes
+                    .matches(right_path)?
+                    .then(|| LfsPointer::parse(&right_content.contents))
+                    .flatten()
+                {
+                    writeln!(formatter.labeled("lfs"), "    (Git LFS object: {pointer})")?;
                 } else if right_content.is_binary {
                     writeln!(formatter.labeled("binary"), "    (binary)")?;
                 } else {
@@ -1000,8 +1300,10 @@ pub fn show_color_words_diff(
                         )
                     }
                 };
-                let left_content = diff_content(left_path, left_value)?;
-                let right_content = diff_content(right_path, right_value)?;
+                let left_content =
+                    diff_content(left_path, left_value, options.conflict_marker_style)?;
+                let right_content =
+                    diff_content(right_path, right_value, options.conflict_marker_style)?;
                 if left_path == right_path {
                     writeln!(
                         formatter.labeled("header"),
@@ -1013,7 +1315,22 @@ pub fn show_color_words_diff(
                         "{description} {right_ui_path} ({left_ui_path} => {right_ui_path}):"
                     )?;
                 }
-                if left_content.is_binary || right_content.is_binary {
+                let lfs_pointers = (
+                    left_lfs_attributes
+                        .matches(left_path)?
+                        .then(|| LfsPointer::parse(&left_content.contents))
+                        .flatten(),
+                    right_lfs_attributes
+                        .matches(right_path)?
+                        .then(|| LfsPointer::parse(&right_content.contents))
+                        .flatten(),
+                );
+                if let (Some(left_pointer), Some(right_pointer)) = lfs_pointers {
+                    writeln!(
+                        formatter.labeled("lfs"),
+                        "    (Git LFS object: {left_pointer} => {right_pointer})"
+                    )?;
+                } else if left_content.is_binary || right_content.is_binary {
                     writeln!(formatter.labeled("binary"), "    (binary)")?;
                 } else {
                     show_color_words_diff_hunks(
@@ -1029,9 +1346,16 @@ pub fn show_color_words_diff(
                     formatter.labeled("header"),
                     "Removed {description} {right_ui_path}:"
                 )?;
-                let left_content = diff_content(left_path, left_value)?;
+                let left_content =
+                    diff_content(left_path, left_value, options.conflict_marker_style)?;
                 if left_content.is_empty() {
                     writeln!(formatter.labeled("empty"), "    (empty)")?;
+                } else if let Some(pointer) = left_lfs_attributes
+                    .matches(left_path)?
+                    .then(|| LfsPointer::parse(&left_content.contents))
+                    .flatten()
+                {
+                    writeln!(formatter.labeled("lfs"), "    (Git LFS object: {pointer})")?;
                 } else if left_content.is_binary {
                     writeln!(formatter.labeled("binary"), "    (binary)")?;
                 } else {
@@ -1059,7 +1383,7 @@ pub fn show_file_by_file_diff(
     ) -> Result<PathBuf, DiffRenderError> {
         let fs_path = path.to_fs_path(wc_dir);
         std::fs::create_dir_all(fs_path.parent().unwrap())?;
-        let content = diff_content(path, value)?;
+        let content = diff_content(path, value, ConflictMarkerStyle::default())?;
         std::fs::write(&fs_path, content.contents)?;
         Ok(fs_path)
     }
@@ -1125,6 +1449,7 @@ struct GitDiffPart {
 fn git_diff_part(
     path: &RepoPath,
     value: MaterializedTreeValue,
+    conflict_marker_style: ConflictMarkerStyle,
 ) -> Result<GitDiffPart, DiffRenderError> {
     const DUMMY_HASH: &str = "0000000000";
     let mode;
@@ -1176,7 +1501,7 @@ fn git_diff_part(
             mode = if executable { "100755" } else { "100644" };
             hash = DUMMY_HASH.to_owned();
             let mut data = vec![];
-            materialize_merge_result(&contents, &mut data)
+            materialize_merge_result_with_style(&contents, conflict_marker_style, &mut data)
                 .expect("Failed to materialize conflict to in-memory buffer");
             content = FileContent {
                 is_binary: false, // TODO: are we sure this is never binary?
@@ -1209,14 +1534,20 @@ pub struct UnifiedDiffOptions {
     pub context: usize,
     /// How lines are tokenized and compared.
     pub line_diff: LineDiffOptions,
+    /// How conflicted files are rendered.
+    pub conflict_marker_style: ConflictMarkerStyle,
 }
 
 impl UnifiedDiffOptions {
-    fn from_args(args: &DiffFormatArgs) -> Self {
-        UnifiedDiffOptions {
+    fn from_settings_and_args(
+        settings: &UserSettings,
+        args: &DiffFormatArgs,
+    ) -> Result<Self, config::ConfigError> {
+        Ok(UnifiedDiffOptions {
             context: args.context.unwrap_or(DEFAULT_CONTEXT_LINES),
             line_diff: LineDiffOptions::from_args(args),
-        }
+            conflict_marker_style: conflict_marker_style_for(settings, args.conflict_style)?,
+        })
     }
 }
 
@@ -1242,6 +1573,14 @@ struct UnifiedDiffHunk<'content> {
 }
 
 impl<'content> UnifiedDiffHunk<'content> {
+    fn has_non_blank_changes(&self) -> bool {
+        self.lines
+            .iter()
+            .filter(|(line_type, _)| *line_type != DiffLineType::Context)
+            .flat_map(|(_, tokens)| tokens)
+            .any(|(_, content)| !content.iter().all(|b| b.is_ascii_whitespace()))
+    }
+
     fn extend_context_lines(&mut self, lines: impl IntoIterator<Item = &'content [u8]>) {
         let old_len = self.lines.len();
         self.lines.extend(lines.into_iter().map(|line| {
@@ -1278,7 +1617,13 @@ fn unified_diff_hunks<'content>(
         right_line_range: 1..1,
         lines: vec![],
     };
-    let diff = diff_by_line([left_content, right_content], &options.line_diff);
+    // Blank-line changes are filtered out per hunk below so that the ones
+    // close to other changes are still shown.
+    let line_diff_options = LineDiffOptions {
+        ignore_blank_lines: false,
+        ..options.line_diff.clone()
+    };
+    let diff = diff_by_line([left_content, right_content], &line_diff_options);
     let mut diff_hunks = diff.hunks().peekable();
     while let Some(hunk) = diff_hunks.next() {
         match hunk.kind {
@@ -1324,6 +1669,9 @@ fn unified_diff_hunks<'content>(
     if !current_hunk.lines.is_empty() {
         hunks.push(current_hunk);
     }
+    if options.line_diff.ignore_blank_lines {
+        hunks.retain(|hunk| hunk.has_non_blank_changes());
+    }
     hunks
 }
 
@@ -1448,8 +1796,8 @@ pub fn show_git_diff(
             let right_path_string = right_path.as_internal_file_string();
             let (left_value, right_value) = values?;
 
-            let left_part = git_diff_part(left_path, left_value)?;
-            let right_part = git_diff_part(right_path, right_value)?;
+            let left_part = git_diff_part(left_path, left_value, options.conflict_marker_style)?;
+            let right_part = git_diff_part(right_path, right_value, options.conflict_marker_style)?;
 
             formatter.with_label("file_header", |formatter| {
                 writeln!(
@@ -1635,8 +1983,8 @@ pub fn show_diff_stat(
             let (left, right) = values?;
             let left_path = path.source();
             let right_path = path.target();
-            let left_content = diff_content(left_path, left)?;
-            let right_content = diff_content(right_path, right)?;
+            let left_content = diff_content(left_path, left, ConflictMarkerStyle::default())?;
+            let right_content = diff_content(right_path, right, ConflictMarkerStyle::default())?;
 
             let left_ui_path = path_converter.format_file_path(left_path);
             let path = if left_path == right_path {
@@ -1645,8 +1993,16 @@ pub fn show_diff_stat(
                 unresolved_renames.insert(left_ui_path);
                 path_converter.format_copied_path(left_path, right_path)
             };
-            max_path_width = max(max_path_width, path.width());
             let stat = get_diff_stat(path, &left_content, &right_content, options);
+            // Files whose changes were all blank lines aren't listed
+            if options.line_diff.ignore_blank_lines
+                && left_path == right_path
+                && stat.added + stat.removed == 0
+                && left_content.contents != right_content.contents
+            {
+                continue;
+            }
+            max_path_width = max(max_path_width, stat.path.width());
             max_diffs = max(max_diffs, stat.added + stat.removed);
             stats.push(stat);
         }
@@ -15,6 +15,8 @@
 //! Git utilities shared by various commands.
 
 use std::error;
+use std::fs;
+use std::io;
 use std::io::Read;
 use std::io::Write;
 use std::iter;
@@ -30,14 +32,19 @@ use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitImportStats;
 use jj_lib::git::RefName;
 use jj_lib::git_backend::GitBackend;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::settings::ConfigResultExt as _;
+use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
+use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 use unicode_width::UnicodeWidthStr;
 
+use crate::command_error::config_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::formatter::Formatter;
@@ -55,18 +62,104 @@ pub fn is_colocated_git_workspace(workspace: &Workspace, repo: &ReadonlyRepo) ->
     let Some(git_backend) = repo.store().backend_impl().downcast_ref::<GitBackend>() else {
         return false;
     };
-    let Some(git_workdir) = git_backend.git_workdir() else {
-        return false; // Bare repository
-    };
-    if git_workdir == workspace.workspace_root() {
-        return true;
+    let workspace_root = workspace.workspace_root();
+    let dot_git_path = workspace_root.join(".git");
+    if let Some(git_workdir) = git_backend.git_workdir() {
+        if git_workdir == workspace_root {
+            return true;
+        }
+        // Colocated workspace should have ".git" directory, file, or symlink. Compare
+        // its parent as the git_workdir might be resolved from the real ".git" path.
+        let Ok(dot_git_path) = dot_git_path.canonicalize() else {
+            return false;
+        };
+        if git_workdir.canonicalize().ok().as_deref() == dot_git_path.parent() {
+            return true;
+        }
     }
-    // Colocated workspace should have ".git" directory, file, or symlink. Compare
-    // its parent as the git_workdir might be resolved from the real ".git" path.
-    let Ok(dot_git_path) = workspace.workspace_root().join(".git").canonicalize() else {
+    // A linked Git worktree of the backing repo (e.g. created by `jj workspace
+    // add --colocate`) has its own HEAD, but shares the other refs. The backing
+    // repo may be bare.
+    dot_git_path.is_file() && is_linked_git_worktree(workspace_root, git_backend)
+}
+
+/// Returns true if `worktree_root` is a linked worktree of the Git repo of the
+/// `git_backend`, and not its main working directory.
+pub fn is_linked_git_worktree(worktree_root: &Path, git_backend: &GitBackend) -> bool {
+    let Ok(worktree_repo) = gix::open(worktree_root) else {
+        return false;
+    };
+    let Ok(common_dir) = worktree_repo.common_dir().canonicalize() else {
         return false;
     };
-    git_workdir.canonicalize().ok().as_deref() == dot_git_path.parent()
+    let backend_repo = git_backend.git_repo();
+    worktree_repo.git_dir() != backend_repo.git_dir()
+        && backend_repo.common_dir().canonicalize().ok() == Some(common_dir)
+}
+
+/// Warns if the workspace has a `.git` file which doesn't link to a worktree of
+/// the backing Git repo. Such a workspace isn't colocated with Git.
+pub fn print_git_worktree_link_warning(
+    ui: &Ui,
+    workspace: &Workspace,
+    repo: &ReadonlyRepo,
+) -> io::Result<()> {
+    if repo
+        .store()
+        .backend_impl()
+        .downcast_ref::<GitBackend>()
+        .is_none()
+    {
+        return Ok(());
+    }
+    let workspace_root = workspace.workspace_root();
+    let dot_git_path = workspace_root.join(".git");
+    if !dot_git_path.is_file() {
+        return Ok(());
+    }
+    let gitdir = fs::read_to_string(&dot_git_path)
+        .ok()
+        .and_then(|content| Some(content.strip_prefix("gitdir:")?.trim().to_owned()));
+    let problem = match &gitdir {
+        None => "The .git file doesn't specify a gitdir".to_owned(),
+        Some(gitdir) if !workspace_root.join(gitdir).is_dir() => {
+            format!("The .git file points to {gitdir}, which doesn't exist")
+        }
+        Some(_) => match gix::open(workspace_root) {
+            Ok(worktree_repo) => {
+                let common_dir = dunce::canonicalize(worktree_repo.common_dir())
+                    .unwrap_or_else(|_| worktree_repo.common_dir().to_owned());
+                writeln!(
+                    ui.warning_default(),
+                    "The .git file points to a worktree of {}, which isn't managed by JJ",
+                    common_dir.display()
+                )?;
+                return Ok(());
+            }
+            Err(err) => err.to_string(),
+        },
+    };
+    writeln!(
+        ui.warning_default(),
+        "Broken colocated git worktree: {problem}"
+    )?;
+    writeln!(
+        ui.hint_default(),
+        "Run `git worktree repair` in the main Git working tree to fix it."
+    )?;
+    Ok(())
+}
+
+/// Opens the Git repo colocated with a workspace. If `git_worktree_root` is
+/// specified, the linked worktree is opened instead of the backing repo.
+pub fn open_colocated_git_repo(
+    git_backend: &GitBackend,
+    git_worktree_root: Option<&Path>,
+) -> Result<git2::Repository, git2::Error> {
+    match git_worktree_root {
+        Some(worktree_root) => git2::Repository::open(worktree_root),
+        None => git_backend.open_git_repo(),
+    }
 }
 
 fn terminal_get_username(ui: &Ui, url: &str) -> Option<String> {
@@ -260,11 +353,42 @@ pub fn with_remote_git_callbacks<T>(
     f(callbacks)
 }
 
+/// Parses the `git.import-globs` setting, which restricts the Git refs
+/// imported into the repo.
+pub fn get_git_import_globs(settings: &UserSettings) -> Result<Vec<StringPattern>, CommandError> {
+    let globs: Vec<String> = settings
+        .config()
+        .get("git.import-globs")
+        .optional()?
+        .unwrap_or_default();
+    globs
+        .iter()
+        .map(|glob| {
+            StringPattern::glob(glob)
+                .map_err(|err| config_error_with_message("Invalid `git.import-globs` pattern", err))
+        })
+        .collect()
+}
+
+/// Returns true if the full Git ref name of `ref_name` matches any of the
+/// `globs`. All refs match if no globs are given.
+pub fn is_git_ref_in_import_scope(globs: &[StringPattern], ref_name: &RefName) -> bool {
+    globs.is_empty()
+        || git::to_git_ref_name(ref_name)
+            .is_some_and(|name| globs.iter().any(|glob| glob.matches(&name)))
+}
+
+/// Prints the import stats to the status output.
+///
+/// If `show_forced_updates` is set, refs that were moved to a commit that isn't
+/// a descendant of the old target are reported as `[forced]` rather than
+/// `[updated]`.
 pub fn print_git_import_stats(
     ui: &Ui,
     repo: &dyn Repo,
     stats: &GitImportStats,
     show_ref_stats: bool,
+    show_forced_updates: bool,
 ) -> Result<(), CommandError> {
     let Some(mut formatter) = ui.status_formatter() else {
         return Ok(());
@@ -274,7 +398,7 @@ pub fn print_git_import_stats(
             .changed_remote_refs
             .iter()
             .map(|(ref_name, (remote_ref, ref_target))| {
-                RefStatus::new(ref_name, remote_ref, ref_target, repo)
+                RefStatus::new(ref_name, remote_ref, ref_target, repo, show_forced_updates)
             })
             .collect_vec();
 
@@ -293,6 +417,16 @@ pub fn print_git_import_stats(
         }
     }
 
+    for ref_name in &stats.fast_forwarded_bookmarks {
+        if let RefName::RemoteBranch { branch, remote } = ref_name {
+            writeln!(
+                formatter,
+                "Fast-forwarded bookmark {branch} to {branch}@{remote} instead of creating a \
+                 conflict"
+            )?;
+        }
+    }
+
     if !stats.abandoned_commits.is_empty() {
         writeln!(
             formatter,
@@ -300,7 +434,35 @@ pub fn print_git_import_stats(
             stats.abandoned_commits.len()
         )?;
     }
+    if !stats.abandoned_local_commits.is_empty() {
+        writeln!(
+            formatter,
+            "Abandoned {} commits left behind by updated local bookmarks or tags.",
+            stats.abandoned_local_commits.len()
+        )?;
+    }
+
+    Ok(())
+}
 
+/// Prints the ref updates made by the import in a stable format meant to be
+/// parsed by scripts.
+///
+/// Each line consists of tab-separated fields: the ref kind (`bookmark` or
+/// `tag`), the ref name, the old and new commit ids (`-` if absent), the import
+/// status (`new`, `deleted`, or `updated`), and the tracking status (`tracked`,
+/// `untracked`, or `-` for tags).
+pub fn print_git_import_stats_porcelain(
+    ui: &Ui,
+    repo: &dyn Repo,
+    stats: &GitImportStats,
+    show_forced_updates: bool,
+) -> Result<(), CommandError> {
+    let mut formatter = ui.stdout_formatter();
+    for (ref_name, (remote_ref, ref_target)) in &stats.changed_remote_refs {
+        let status = RefStatus::new(ref_name, remote_ref, ref_target, repo, show_forced_updates);
+        status.output_porcelain(&remote_ref.target, ref_target, formatter.as_mut())?;
+    }
     Ok(())
 }
 
@@ -317,6 +479,7 @@ impl RefStatus {
         remote_ref: &RemoteRef,
         ref_target: &RefTarget,
         repo: &dyn Repo,
+        show_forced_updates: bool,
     ) -> Self {
         let (ref_name, ref_kind, tracking_status) = match ref_name {
             RefName::RemoteBranch { branch, remote } => (
@@ -338,9 +501,14 @@ impl RefStatus {
             }
         };
 
+        let is_forced = || match (remote_ref.target.as_normal(), ref_target.as_normal()) {
+            (Some(old_id), Some(new_id)) => !repo.index().is_ancestor(old_id, new_id),
+            _ => false,
+        };
         let import_status = match (remote_ref.target.is_absent(), ref_target.is_absent()) {
             (true, false) => ImportStatus::New,
             (false, true) => ImportStatus::Deleted,
+            _ if show_forced_updates && is_forced() => ImportStatus::Forced,
             _ => ImportStatus::Updated,
         };
 
@@ -358,17 +526,8 @@ impl RefStatus {
         has_both_ref_kinds: bool,
         out: &mut dyn Formatter,
     ) -> std::io::Result<()> {
-        let tracking_status = match self.tracking_status {
-            TrackingStatus::Tracked => "tracked",
-            TrackingStatus::Untracked => "untracked",
-            TrackingStatus::NotApplicable => "",
-        };
-
-        let import_status = match self.import_status {
-            ImportStatus::New => "new",
-            ImportStatus::Deleted => "deleted",
-            ImportStatus::Updated => "updated",
-        };
+        let tracking_status = self.tracking_status.as_str().unwrap_or("");
+        let import_status = self.import_status.as_str();
 
         let ref_name_display_width = self.ref_name.width();
         let pad_width = max_ref_name_width.saturating_sub(ref_name_display_width);
@@ -384,6 +543,34 @@ impl RefStatus {
         write!(out.labeled("bookmark"), "{padded_ref_name}")?;
         writeln!(out, " [{import_status}] {tracking_status}")
     }
+
+    fn output_porcelain(
+        &self,
+        old_target: &RefTarget,
+        new_target: &RefTarget,
+        out: &mut dyn Formatter,
+    ) -> std::io::Result<()> {
+        let ref_kind = match self.ref_kind {
+            RefKind::Branch => "bookmark",
+            RefKind::Tag => "tag",
+        };
+        let format_target = |target: &RefTarget| {
+            if target.is_absent() {
+                "-".to_owned()
+            } else {
+                target.added_ids().map(|id| id.hex()).join(",")
+            }
+        };
+        writeln!(
+            out,
+            "{ref_kind}\t{}\t{}\t{}\t{}\t{}",
+            self.ref_name,
+            format_target(old_target),
+            format_target(new_target),
+            self.import_status.as_str(),
+            self.tracking_status.as_str().unwrap_or("-"),
+        )
+    }
 }
 
 enum RefKind {
@@ -397,10 +584,32 @@ enum TrackingStatus {
     NotApplicable, // for tags
 }
 
+impl TrackingStatus {
+    fn as_str(&self) -> Option<&'static str> {
+        match self {
+            TrackingStatus::Tracked => Some("tracked"),
+            TrackingStatus::Untracked => Some("untracked"),
+            TrackingStatus::NotApplicable => None,
+        }
+    }
+}
+
 enum ImportStatus {
     New,
     Deleted,
     Updated,
+    Forced,
+}
+
+impl ImportStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ImportStatus::New => "new",
+            ImportStatus::Deleted => "deleted",
+            ImportStatus::Updated => "updated",
+            ImportStatus::Forced => "forced",
+        }
+    }
 }
 
 pub fn print_failed_git_export(
@@ -419,10 +628,12 @@ pub fn print_failed_git_export(
             writeln!(formatter)?;
         }
         drop(formatter);
-        if failed_refs
-            .iter()
-            .any(|failed| matches!(failed.reason, FailedRefExportReason::FailedToSet(_)))
-        {
+        if failed_refs.iter().any(|failed| {
+            matches!(
+                failed.reason,
+                FailedRefExportReason::FailedToSet(_) | FailedRefExportReason::ConflictingName
+            )
+        }) {
             writeln!(
                 ui.hint_default(),
                 r#"Git doesn't allow a branch name that looks like a parent directory of
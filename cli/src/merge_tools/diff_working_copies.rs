@@ -14,8 +14,10 @@ use jj_lib::local_working_copy::TreeState;
 use jj_lib::local_working_copy::TreeStateError;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::Matcher;
+use jj_lib::matchers::Visit;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::TreeDiffEntry;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::store::Store;
 use jj_lib::working_copy::CheckoutError;
@@ -33,6 +35,12 @@ pub enum DiffCheckoutError {
     Checkout(#[from] CheckoutError),
     #[error("Error setting up temporary directory")]
     SetUpDir(#[source] std::io::Error),
+    #[error("Failed to create directory {}", path.display())]
+    CreateDir {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
     #[error(transparent)]
     TreeState(#[from] TreeStateError),
 }
@@ -185,6 +193,56 @@ pub(crate) fn check_out_trees(
     })
 }
 
+/// Writes the two trees to the `left` and `right` subdirectories of
+/// `output_dir`, which must not exist yet. Unlike [`check_out_trees()`], all
+/// files matching `matcher` are written, not only the changed ones, and the
+/// directories are left in place. Returns the paths of the two directories.
+pub(crate) fn dump_trees(
+    store: &Arc<Store>,
+    left_tree: &MergedTree,
+    right_tree: &MergedTree,
+    matcher: &dyn Matcher,
+    output_dir: &Path,
+) -> Result<(PathBuf, PathBuf), DiffCheckoutError> {
+    let create_dir = |path: &Path| {
+        std::fs::create_dir(path).map_err(|source| DiffCheckoutError::CreateDir {
+            path: path.to_owned(),
+            source,
+        })
+    };
+    std::fs::create_dir_all(output_dir).map_err(|source| DiffCheckoutError::CreateDir {
+        path: output_dir.to_owned(),
+        source,
+    })?;
+    let left_wc_dir = output_dir.join("left");
+    let right_wc_dir = output_dir.join("right");
+    create_dir(&left_wc_dir)?;
+    create_dir(&right_wc_dir)?;
+    // The tree states are only needed for the checkout, so they don't end up in
+    // the output directory.
+    let state_dir = new_utf8_temp_dir("jj-diff-").map_err(DiffCheckoutError::SetUpDir)?;
+    let sparse_patterns_for = |tree: &MergedTree| {
+        if matcher.visit(RepoPath::root()) == Visit::AllRecursively {
+            vec![RepoPathBuf::root()]
+        } else {
+            tree.entries_matching(matcher)
+                .map(|(path, _)| path)
+                .collect()
+        }
+    };
+    for (tree, wc_dir, state_name) in [
+        (left_tree, &left_wc_dir, "left_state"),
+        (right_tree, &right_wc_dir, "right_state"),
+    ] {
+        let state_dir = state_dir.path().join(state_name);
+        std::fs::create_dir(&state_dir).map_err(DiffCheckoutError::SetUpDir)?;
+        let mut tree_state = TreeState::init(store.clone(), wc_dir.clone(), state_dir)?;
+        tree_state.set_sparse_patterns(sparse_patterns_for(tree))?;
+        tree_state.check_out(tree)?;
+    }
+    Ok((left_wc_dir, right_wc_dir))
+}
+
 pub(crate) struct DiffEditWorkingCopies {
     pub working_copies: DiffWorkingCopies,
     instructions_path_to_cleanup: Option<PathBuf>,
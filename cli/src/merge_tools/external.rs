@@ -115,6 +115,22 @@ impl ExternalMergeTool {
         Self::with_args_inner(command_args, |tool| &mut tool.merge_args)
     }
 
+    /// Creates a merge tool from Git's `mergetool.<name>.cmd` setting.
+    ///
+    /// The command is run by `sh` with `$BASE`, `$LOCAL`, `$REMOTE`, and
+    /// `$MERGED` set to the paths of the corresponding files, as `git
+    /// mergetool` would.
+    pub fn with_git_mergetool_cmd(cmd: &str) -> Self {
+        let script = format!(r#"BASE="$1" LOCAL="$2" REMOTE="$3" MERGED="$4"; {cmd}"#);
+        Self {
+            program: "sh".to_owned(),
+            merge_args: ["-c", &script, "sh", "$base", "$left", "$right", "$output"]
+                .map(ToOwned::to_owned)
+                .to_vec(),
+            ..Default::default()
+        }
+    }
+
     fn with_args_inner(
         command_args: &CommandNameAndArgs,
         get_mut_args: impl FnOnce(&mut Self) -> &mut Vec<String>,
@@ -403,4 +419,28 @@ mod tests {
             ["left", "right", "1", "2", "output", "right"],
         );
     }
+
+    #[test]
+    fn test_git_mergetool_cmd_args() {
+        let tool = ExternalMergeTool::with_git_mergetool_cmd(r#"meld "$LOCAL" "$MERGED""#);
+        let patterns = maplit::hashmap! {
+            "base" => "BASE",
+            "left" => "LEFT",
+            "right" => "RIGHT",
+            "output" => "OUTPUT",
+        };
+        assert_eq!(tool.program, "sh");
+        assert_eq!(
+            interpolate_variables(&tool.merge_args, &patterns),
+            [
+                "-c",
+                r#"BASE="$1" LOCAL="$2" REMOTE="$3" MERGED="$4"; meld "$LOCAL" "$MERGED""#,
+                "sh",
+                "BASE",
+                "LEFT",
+                "RIGHT",
+                "OUTPUT",
+            ],
+        );
+    }
 }
@@ -16,8 +16,10 @@ mod builtin;
 mod diff_working_copies;
 mod external;
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use bstr::ByteSlice as _;
 use config::ConfigError;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::conflicts::extract_as_single_hunk;
@@ -35,8 +37,9 @@ use thiserror::Error;
 use self::builtin::edit_diff_builtin;
 use self::builtin::edit_merge_builtin;
 use self::builtin::BuiltinToolError;
+pub(crate) use self::diff_working_copies::dump_trees;
 pub(crate) use self::diff_working_copies::new_utf8_temp_dir;
-use self::diff_working_copies::DiffCheckoutError;
+pub use self::diff_working_copies::DiffCheckoutError;
 use self::external::edit_diff_external;
 pub use self::external::generate_diff;
 pub use self::external::invoke_external_diff;
@@ -172,6 +175,25 @@ pub fn get_external_tool_config(
     }
 }
 
+/// Loads merge tools defined by `mergetool.<name>.cmd` in the Git config.
+///
+/// These are used as defaults for tools that aren't configured in
+/// `[merge-tools.<name>]`.
+pub fn get_git_mergetools(git_config: &gix::config::File) -> BTreeMap<String, ExternalMergeTool> {
+    let Some(sections) = git_config.sections_by_name("mergetool") else {
+        return BTreeMap::new();
+    };
+    // Later sections override earlier ones, as in Git.
+    sections
+        .filter_map(|section| {
+            let name = section.header().subsection_name()?.to_str().ok()?;
+            let cmd = section.value("cmd")?;
+            let tool = ExternalMergeTool::with_git_mergetool_cmd(cmd.to_str().ok()?);
+            Some((name.to_owned(), tool))
+        })
+        .collect()
+}
+
 /// Configured diff editor.
 #[derive(Clone, Debug)]
 pub struct DiffEditor {
@@ -263,6 +285,15 @@ impl MergeEditor {
         Self::new_inner(name, tool)
     }
 
+    /// Creates 3-way merge editor from the given external tool, which isn't
+    /// loaded from the settings.
+    pub fn with_external_tool(
+        name: &str,
+        tool: ExternalMergeTool,
+    ) -> Result<Self, MergeToolConfigError> {
+        Self::new_inner(name, MergeTool::external(tool))
+    }
+
     /// Loads the default 3-way merge editor from the settings.
     pub fn from_settings(ui: &Ui, settings: &UserSettings) -> Result<Self, MergeToolConfigError> {
         let args = editor_args_from_settings(ui, settings, "ui.merge-editor")?;
@@ -320,6 +320,9 @@ fn prepare_formatter_factory(
         ColorChoice::Auto => (terminal, false),
     };
     if color {
+        // Whether to use color has already been decided from `ui.color`, which
+        // takes $NO_COLOR into account. Don't let crossterm second-guess it.
+        crossterm::style::force_color_output(true);
         FormatterFactory::color(config, debug)
     } else if terminal {
         // Sanitize ANSI escape codes if we're printing to a terminal. Doesn't
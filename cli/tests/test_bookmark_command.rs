@@ -196,7 +196,10 @@ fn test_bookmark_move() {
 
     // Delete bookmark locally, but is still tracking remote
     test_env.jj_cmd_ok(&repo_path, &["describe", "@-", "-mcommit"]);
-    test_env.jj_cmd_ok(&repo_path, &["git", "push", "-r@-"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "push", "-r@-", "--include-untracked-bookmarks"],
+    );
     test_env.jj_cmd_ok(&repo_path, &["bookmark", "delete", "foo"]);
     insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
     foo (deleted)
@@ -226,6 +229,8 @@ fn test_bookmark_move() {
     let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "foo"]);
     insta::assert_snapshot!(stderr, @r###"
     Created 1 bookmarks pointing to mzvwutvl 66d48752 foo | (empty) (no description set)
+    Warning: Remote bookmark foo@origin exists but is not tracked.
+    Hint: Run `jj bookmark track foo@origin` to track it.
     "###);
     insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
     foo: mzvwutvl 66d48752 (empty) (no description set)
@@ -663,6 +668,77 @@ fn test_bookmark_delete_export() {
     "###);
 }
 
+#[test]
+fn test_bookmark_delete_remote() {
+    // Set up a git repo with a bookmark and a jj repo that has it as a remote.
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let tree_oid = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    git_repo
+        .commit(
+            Some("refs/heads/feature"),
+            &signature,
+            &signature,
+            "message",
+            &tree,
+            &[],
+        )
+        .unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature: zmwzkxot d62cea7e (empty) message
+      @origin: zmwzkxot d62cea7e (empty) message
+    "###);
+
+    // Only the remote bookmark is deleted; the local bookmark and its commit
+    // survive.
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "delete", "feature", "--remote", "origin"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Deleted 1 remote bookmarks.
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature: zmwzkxot d62cea7e (empty) message
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r=feature", "--no-graph"]);
+    insta::assert_snapshot!(stdout, @r###"
+    zmwzkxot some.one@example.com 1970-01-01 11:00:00 feature d62cea7e
+    (empty) message
+    "###);
+
+    // Unknown remote bookmarks are reported
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["bookmark", "delete", "feature", "--remote", "origin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such remote bookmark: feature@origin
+    "###);
+
+    // Git-tracking bookmarks can't be deleted this way
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["bookmark", "delete", "feature", "--remote", "git"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Git-tracking bookmarks cannot be deleted with --remote git
+    "###);
+}
+
 #[test]
 fn test_bookmark_forget_export() {
     let test_env = TestEnvironment::default();
@@ -825,6 +901,71 @@ fn test_bookmark_forget_fetched_bookmark() {
     "###);
 }
 
+#[test]
+fn test_bookmark_forget_include_remotes() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let mut tree_builder = git_repo.treebuilder(None).unwrap();
+    let file_oid = git_repo.blob(b"content").unwrap();
+    tree_builder
+        .insert("file", file_oid, git2::FileMode::Blob.into())
+        .unwrap();
+    let tree_oid = tree_builder.write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    git_repo
+        .commit(
+            Some("refs/heads/feature1"),
+            &signature,
+            &signature,
+            "message",
+            &tree,
+            &[],
+        )
+        .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature1: mzyxwzks 9f01a0e0 message
+      @origin: mzyxwzks 9f01a0e0 message
+    "###);
+
+    // By default, the remote bookmark is forgotten without being reported
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bookmark", "forget", "feature1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 bookmarks.
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @"");
+
+    // Fetching recreates the bookmark
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature1: mzyxwzks 9f01a0e0 message
+      @origin: mzyxwzks 9f01a0e0 message
+    "###);
+
+    // --include-remotes reports each forgotten remote bookmark
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "forget", "--include-remotes", "feature1"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 bookmarks.
+    Forgot remote bookmark feature1@origin
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @"");
+}
+
 #[test]
 fn test_bookmark_forget_deleted_or_nonexistent_bookmark() {
     // Much of this test is borrowed from `test_git_fetch_remote_only_bookmark` in
@@ -1007,9 +1148,9 @@ fn test_bookmark_track_untrack() {
     );
     let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(stderr, @r###"
-    bookmark: feature1@origin [updated] untracked
-    bookmark: feature2@origin [updated] untracked
-    bookmark: main@origin     [updated] tracked
+    bookmark: feature1@origin [forced] untracked
+    bookmark: feature2@origin [forced] untracked
+    bookmark: main@origin     [forced] tracked
     "###);
     insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
     feature1: sptzoqmo 7b33f629 commit 1
@@ -1042,10 +1183,10 @@ fn test_bookmark_track_untrack() {
     test_env.add_config("git.auto-local-branch = true");
     let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(stderr, @r###"
-    bookmark: feature1@origin [updated] untracked
-    bookmark: feature2@origin [updated] untracked
+    bookmark: feature1@origin [forced] untracked
+    bookmark: feature2@origin [forced] untracked
     bookmark: feature3@origin [new] tracked
-    bookmark: main@origin     [updated] tracked
+    bookmark: main@origin     [forced] tracked
     Abandoned 1 commits that are no longer reachable.
     "###);
     insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
@@ -1097,6 +1238,153 @@ fn test_bookmark_track_conflict() {
     "###);
 }
 
+#[test]
+fn test_bookmark_create_tracking() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Set up remote
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let empty_tree_oid = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let empty_tree = git_repo.find_tree(empty_tree_oid).unwrap();
+    git_repo
+        .commit(
+            Some("refs/heads/feature1"),
+            &signature,
+            &signature,
+            "remote commit",
+            &empty_tree,
+            &[],
+        )
+        .unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=local commit"]);
+
+    // The existing remote bookmark is tracked, but the local bookmark stays
+    // where it was created
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bookmark",
+            "create",
+            "feature1",
+            "feature2",
+            "--tracking",
+            "origin",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created 2 bookmarks pointing to qpvuntsm 7f8c2bfa feature1 feature2 | (empty) local commit
+    Hint: Use -r to specify the target revision.
+    Hint: Remote bookmark feature2@origin doesn't exist yet. Run `jj git push --remote origin --bookmark feature2` to create and track it.
+    Started tracking 1 remote bookmarks.
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature1: qpvuntsm 7f8c2bfa (empty) local commit
+      @origin (ahead by 1 commits, behind by 1 commits): luzzuoyk ab29b767 (empty) remote commit
+    feature2: qpvuntsm 7f8c2bfa (empty) local commit
+    "###);
+
+    // The other bookmark gets tracked once pushed
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "push", "-b=feature2"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Changes to push to origin:
+      Add bookmark feature2 to 7f8c2bfa6e40
+    Started tracking bookmark feature2@origin
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature1: qpvuntsm 7f8c2bfa (empty) local commit
+      @origin (ahead by 1 commits, behind by 1 commits): luzzuoyk ab29b767 (empty) remote commit
+    feature2: qpvuntsm 7f8c2bfa (empty) local commit
+      @origin: qpvuntsm 7f8c2bfa (empty) local commit
+    "###);
+
+    // Bookmarks of the git remote can't be tracked
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["bookmark", "create", "feature3", "--tracking", "git"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Bookmarks of the git remote can't be tracked
+    "###);
+
+    // The remote must exist
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["bookmark", "create", "feature3", "--tracking", "upstream"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No git remote named 'upstream'
+    "###);
+}
+
+#[test]
+fn test_bookmark_create_with_untracked_remote_bookmark() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Set up remote
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let empty_tree_oid = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let empty_tree = git_repo.find_tree(empty_tree_oid).unwrap();
+    git_repo
+        .commit(
+            Some("refs/heads/feature1"),
+            &signature,
+            &signature,
+            "remote commit",
+            &empty_tree,
+            &[],
+        )
+        .unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+
+    // Creating a bookmark of the same name as an untracked remote bookmark
+    // suggests tracking it
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "feature1", "feature2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created 2 bookmarks pointing to qpvuntsm 230dd059 feature1 feature2 | (empty) (no description set)
+    Hint: Use -r to specify the target revision.
+    Warning: Remote bookmark feature1@origin exists but is not tracked.
+    Hint: Run `jj bookmark track feature1@origin` to track it.
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature1: qpvuntsm 230dd059 (empty) (no description set)
+    feature1@origin: luzzuoyk ab29b767 (empty) remote commit
+    feature2: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // No suggestion if the remote bookmark is tracked explicitly
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "delete", "feature1"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "create", "feature1", "--tracking", "origin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 bookmarks pointing to qpvuntsm 230dd059 feature1 feature2 | (empty) (no description set)
+    Started tracking 1 remote bookmarks.
+    "###);
+}
+
 #[test]
 fn test_bookmark_track_untrack_patterns() {
     let test_env = TestEnvironment::default();
@@ -1578,6 +1866,45 @@ fn test_bookmark_list_filtered() {
       @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
     "###);
     insta::assert_snapshot!(stderr, @"");
+
+    // --points-at matches local and remote targets.
+    let (stdout, stderr) = query(&["--points-at=description(rewrite)"]);
+    insta::assert_snapshot!(stdout, @r###"
+    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, stderr) = query(&["--points-at=remote_bookmarks()"]);
+    insta::assert_snapshot!(stdout, @r###"
+    remote-delete (deleted)
+      @origin: yxusvupt dad5f298 (empty) remote-delete
+    remote-keep: nlwprzpn 911e9120 (empty) remote-keep
+    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Hint: Bookmarks marked as deleted will be *deleted permanently* on the remote on the next `jj git push`. Use `jj bookmark forget` to prevent this.
+    "###);
+
+    // --points-at is AND-ed with the other options.
+    let (stdout, stderr) = query(&["--points-at=remote_bookmarks()", "glob:*-keep"]);
+    insta::assert_snapshot!(stdout, @r###"
+    remote-keep: nlwprzpn 911e9120 (empty) remote-keep
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, stderr) = query(&["--points-at=remote_bookmarks()", "--all-remotes"]);
+    insta::assert_snapshot!(stdout, @r###"
+    remote-delete (deleted)
+      @origin: yxusvupt dad5f298 (empty) remote-delete
+    remote-keep: nlwprzpn 911e9120 (empty) remote-keep
+      @git: nlwprzpn 911e9120 (empty) remote-keep
+      @origin: nlwprzpn 911e9120 (empty) remote-keep
+    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Hint: Bookmarks marked as deleted will be *deleted permanently* on the remote on the next `jj git push`. Use `jj bookmark forget` to prevent this.
+    "###);
 }
 
 #[test]
@@ -32,6 +32,38 @@ fn test_commit_with_description_from_cli() {
     "###);
 }
 
+#[test]
+fn test_commit_refuse_empty() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.add_config("ui.refuse-empty-commit = true");
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(&edit_script, "write\nfrom editor").unwrap();
+
+    // An empty change isn't committed without a message
+    let stderr = test_env.jj_cmd_failure(&workspace_path, &["commit"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to commit a change without modifications
+    Hint: Use --allow-empty to commit it anyway, or pass a message with -m.
+    "###);
+
+    // A message or --allow-empty overrides the check
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m=with message"]);
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "--allow-empty"]);
+
+    // A change with modifications can be committed
+    std::fs::write(workspace_path.join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["commit"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_path), @r###"
+    @  c21cfe69497f
+    ○  9abfb7d66704 from editor
+    ○  6c9e18f4eced from editor
+    ○  d0e8e5453e26 with message
+    ◆  000000000000
+    "###);
+}
+
 #[test]
 fn test_commit_with_editor() {
     let mut test_env = TestEnvironment::default();
@@ -232,6 +232,62 @@ fn test_config_list_layer() {
     "###);
 }
 
+#[test]
+fn test_config_list_show_origin() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+    test-table.user-key = "user"
+    test-table.layered-key = "user"
+    "#,
+    );
+    test_env.add_config(
+        r#"
+    test-table.other-user-key = "user"
+    "#,
+    );
+    std::fs::write(
+        repo_path.join(".jj/repo/config.toml"),
+        r#"test-table.layered-key = "repo""#,
+    )
+    .unwrap();
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "list",
+            "test-table",
+            "--show-origin",
+            "--include-overridden",
+            "--config-toml=test-table.arg-key = 'arg'",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    file:$TEST_ENV/config/config0002.toml	# test-table.layered-key = "user"
+    file:$TEST_ENV/config/config0003.toml	test-table.other-user-key = "user"
+    file:$TEST_ENV/config/config0002.toml	test-table.user-key = "user"
+    file:$TEST_ENV/repo/.jj/repo/config.toml	test-table.layered-key = "repo"
+    command-line	test-table.arg-key = "arg"
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "list",
+            "merge-tools.vimdiff.program",
+            "--show-origin",
+            "--include-defaults",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    default	merge-tools.vimdiff.program = "vim"
+    "###);
+}
+
 #[test]
 fn test_config_layer_override_default() {
     let test_env = TestEnvironment::default();
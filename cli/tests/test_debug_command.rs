@@ -130,6 +130,7 @@ fn test_debug_reindex() {
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "reindex"]);
     assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
+    Reindexing commits at operation f76dc65ee301...
     Finished indexing 4 commits.
     "###);
     let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "index"]);
@@ -145,6 +146,37 @@ fn test_debug_reindex() {
         Name: [hash]
     "###
     );
+
+    // Make the index of the head operation point to the stale index of its
+    // parent operation
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "-T",
+            r#"id ++ "\n""#,
+            "--ignore-working-copy",
+        ],
+    );
+    let [head_op_id, parent_op_id, ..] = stdout.lines().collect::<Vec<_>>()[..] else {
+        panic!("unexpected op log output: {stdout}");
+    };
+    let op_links_dir = workspace_path.join(".jj/repo/index/operations");
+    let parent_op_link = std::fs::read(op_links_dir.join(parent_op_id)).unwrap();
+    std::fs::write(op_links_dir.join(head_op_id), parent_op_link).unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_path,
+        &["debug", "reindex", "--ignore-working-copy"],
+    );
+    assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Reindexing commits at operation ea98ecbed139...
+    Finished indexing 5 commits.
+    Fixed inconsistencies in the index: it had 4 commits, 4 changes, and 1 heads; it now has 5 commits, 5 changes, and 1 heads.
+    "###);
 }
 
 #[test]
@@ -241,6 +273,91 @@ fn test_debug_operation_id() {
     );
 }
 
+#[test]
+fn test_debug_op_view() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "--colocate", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m=first"]);
+    test_env.jj_cmd_ok(&workspace_path, &["bookmark", "create", "main", "-r@-"]);
+
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "op-view"]);
+    assert_snapshot!(stdout, @r###"
+    Operation: b84b16e808be30c3834e69a100bb4e992b2041e27f0bf3b93fa6a47a429f97b91f2fb8da0a9e20a4c064eae4e8c1b57f383ac9463c606807471837aeca818630
+    Heads:
+      e8ea92a8b6b35dd30842d619e124ca9cc4a3c0cc
+    Working copies:
+      default: e8ea92a8b6b35dd30842d619e124ca9cc4a3c0cc
+    Bookmarks:
+      main: fa15625b4a986997697639dfc2844138900c79f2
+    Remote bookmarks:
+      main@git: fa15625b4a986997697639dfc2844138900c79f2 (tracking)
+    Tags:
+    Git refs:
+      refs/heads/main: fa15625b4a986997697639dfc2844138900c79f2
+    Git HEADs:
+      default: fa15625b4a986997697639dfc2844138900c79f2
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["debug", "op-view", "@-", "--format=json"],
+    );
+    assert_snapshot!(stdout, @r###"
+    {
+      "bookmarks": {},
+      "git_heads": {
+        "default": "fa15625b4a986997697639dfc2844138900c79f2"
+      },
+      "git_refs": {},
+      "heads": [
+        "e8ea92a8b6b35dd30842d619e124ca9cc4a3c0cc"
+      ],
+      "operation": "1e4814d38d0faa7f182a71492a5fe3f1bdfa8abdcc2cc350850ae28f2827d7753ed35475e8ace0378a235db36d599259ea3d4685815fa10a1a61a2e93f26cb1a",
+      "remote_bookmarks": {},
+      "tags": {},
+      "working_copies": {
+        "default": "e8ea92a8b6b35dd30842d619e124ca9cc4a3c0cc"
+      }
+    }
+    "###);
+}
+
+#[test]
+fn test_debug_working_copy() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    std::fs::write(workspace_path.join("file1"), "").unwrap();
+    std::fs::create_dir(workspace_path.join("dir")).unwrap();
+    std::fs::write(workspace_path.join("dir").join("file2"), "").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    std::fs::write(workspace_path.join("file3"), "").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "working-copy"]);
+    assert_snapshot!(stdout, @r###"
+    Type: "local"
+    Current operation: OperationId("f57534ac70e85dbd7d819b7f41ac31d3c8586c2addddcda807d49e6ac4d90e61bff3acb957f04b4072b851ff16e36a2da120345e2bc7dad125bf644defb3c580")
+    Current tree: Merge(Resolved(TreeId("db93a289076defef70fb51941c75c05875b90033")))
+    Fsmonitor: none
+    Tracked files: 2
+    "###);
+
+    // The snapshot isn't recorded, so file3 is scanned but not tracked
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "working-copy", "--snapshot"]);
+    let regex = Regex::new(r"Snapshot duration: .*").unwrap();
+    assert_snapshot!(regex.replace(&stdout, "Snapshot duration: [duration]"), @r###"
+    Type: "local"
+    Current operation: OperationId("f57534ac70e85dbd7d819b7f41ac31d3c8586c2addddcda807d49e6ac4d90e61bff3acb957f04b4072b851ff16e36a2da120345e2bc7dad125bf644defb3c580")
+    Current tree: Merge(Resolved(TreeId("db93a289076defef70fb51941c75c05875b90033")))
+    Fsmonitor: none
+    Tracked files: 2
+    Snapshot duration: [duration]
+    Files scanned: 3
+    "###);
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "working-copy"]);
+    assert!(stdout.contains("Tracked files: 2"));
+}
+
 fn filter_index_stats(text: &str) -> String {
     let regex = Regex::new(r"    Name: [0-9a-z]+").unwrap();
     regex.replace_all(text, "    Name: [hash]").to_string()
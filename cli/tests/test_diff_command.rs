@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
+use std::path::PathBuf;
+
 use indoc::indoc;
 use itertools::Itertools;
 
@@ -222,6 +225,232 @@ fn test_diff_basic() {
     insta::assert_snapshot!(stderr, @"");
 }
 
+#[test]
+fn test_diff_between_ops() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=parent"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=child"]);
+    std::fs::write(repo_path.join("file1"), "a\nb\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "c\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let before_op_id = test_env.current_operation_id(&repo_path);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "squash",
+            "--from=description(child)",
+            "--into=description(parent)",
+            "file1",
+        ],
+    );
+
+    // The parent got the changes to file1 from the child, but the contents of
+    // the child didn't change
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "--between-ops",
+            &before_op_id,
+            "@",
+            "-r=description(parent)",
+            "--git",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 7898192261..422c2b7ab3 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,2 @@
+     a
+    +b
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "--between-ops",
+            &before_op_id,
+            "@",
+            "-r=description(child)",
+            "--git",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+
+    // The revision must exist at both operations
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "diff",
+            "--between-ops",
+            "@",
+            "@-----",
+            "-r=description(child)",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset "description(child)" didn't resolve to any revisions at operation e4a82b172928
+    "###);
+}
+
+#[test]
+fn test_diff_root() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "a\nb\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "c\n").unwrap();
+
+    // The first commit's diff is the same as the regular diff
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--root", "-r=@-", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..7898192261
+    --- /dev/null
+    +++ b/file1
+    @@ -1,0 +1,1 @@
+    +a
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r=@-", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..7898192261
+    --- /dev/null
+    +++ b/file1
+    @@ -1,0 +1,1 @@
+    +a
+    "###);
+
+    // All files in a later commit are shown as added
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--root", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..422c2b7ab3
+    --- /dev/null
+    +++ b/file1
+    @@ -1,0 +1,2 @@
+    +a
+    +b
+    diff --git a/file2 b/file2
+    new file mode 100644
+    index 0000000000..f2ad6c76f0
+    --- /dev/null
+    +++ b/file2
+    @@ -1,0 +1,1 @@
+    +c
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--root", "--summary", "file2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A file2
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "--root", "--from=@-"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--root' cannot be used with '--from <FROM>'
+
+    Usage: jj diff --root [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_diff_copy_detection() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let lines = |changed: usize| {
+        (0..10)
+            .map(|i| {
+                if i < changed {
+                    format!("changed {i}\n")
+                } else {
+                    format!("line {i}\n")
+                }
+            })
+            .collect::<String>()
+    };
+    std::fs::write(repo_path.join("rename-source"), lines(0)).unwrap();
+    std::fs::write(repo_path.join("copy-source"), "a\nb\nc\nd\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    // The renamed file is slightly modified (7 of 10 lines are unchanged)
+    std::fs::remove_file(repo_path.join("rename-source")).unwrap();
+    std::fs::write(repo_path.join("rename-target"), lines(3)).unwrap();
+    std::fs::write(repo_path.join("copy-source"), "a\nb\nc\nd\ne\n").unwrap();
+    std::fs::write(repo_path.join("copy-target"), "a\nb\nc\nd\n").unwrap();
+
+    // Copies and renames are detected by default
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M copy-source
+    C {copy-source => copy-target}
+    R {rename-source => rename-target}
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "rename-target"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/rename-source b/rename-target
+    rename from rename-source
+    rename to rename-target
+    index 27435e1ff9..c21e95a185 100644
+    --- a/rename-source
+    +++ b/rename-target
+    @@ -1,6 +1,6 @@
+    -line 0
+    -line 1
+    -line 2
+    +changed 0
+    +changed 1
+    +changed 2
+     line 3
+     line 4
+     line 5
+    "###);
+
+    // Only renames
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "-M"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M copy-source
+    A copy-target
+    R {rename-source => rename-target}
+    "###);
+
+    // The renamed file isn't similar enough
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "-C=80"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M copy-source
+    C {copy-source => copy-target}
+    D rename-source
+    A rename-target
+    "###);
+    // ...but is similar enough with a lower threshold
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "--find-renames=60"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M copy-source
+    A copy-target
+    R {rename-source => rename-target}
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "--find-copies=101"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value '101' for '--find-copies[=<PERCENT>]': 101 is not in 0..=100
+
+    For more information, try '--help'.
+    "###);
+}
+
 #[test]
 fn test_diff_empty() {
     let test_env = TestEnvironment::default();
@@ -487,6 +716,47 @@ fn test_diff_name_only() {
     "###);
 }
 
+#[test]
+fn test_diff_pathspec_glob_and_exclude() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir_all(repo_path.join("src/sub")).unwrap();
+    std::fs::write(repo_path.join("src/lib.rs"), "lib").unwrap();
+    std::fs::write(repo_path.join("src/sub/mod.rs"), "mod").unwrap();
+    std::fs::write(repo_path.join("src/sub/data.txt"), "data").unwrap();
+    std::fs::write(repo_path.join("README"), "readme").unwrap();
+
+    // Bare path with glob characters
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--name-only", "src/**/*.rs"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r###"
+    src/lib.rs
+    src/sub/mod.rs
+    "###);
+
+    // Exclusion only
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--name-only", ":!src/sub"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r###"
+    README
+    src/lib.rs
+    "###);
+
+    // Inclusion and exclusion
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["diff", "--name-only", "src", ":^src/**/*.rs"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r###"
+    src/sub/data.txt
+    "###);
+
+    // Literal path with glob characters can still be specified
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["diff", "--name-only", "cwd:src/*"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: No matching entries for paths: src/*
+    "###);
+}
+
 #[test]
 fn test_diff_bad_args() {
     let test_env = TestEnvironment::default();
@@ -1625,6 +1895,104 @@ fn test_diff_ignore_whitespace() {
     "#);
 }
 
+#[test]
+fn test_diff_ignore_blank_lines() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let lines = (1..=20).map(|i| format!("{i}\n")).collect_vec();
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\n").unwrap();
+    std::fs::write(repo_path.join("file2"), lines.concat()).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mblank lines"]);
+    // file1 only has blank-line changes
+    std::fs::write(repo_path.join("file1"), "a\n\nb\n  \n\nc\n").unwrap();
+    // file2 has blank lines added both far from and close to a real change
+    let mut new_lines = lines.clone();
+    new_lines[14] = "fifteen\n".to_owned();
+    new_lines.insert(13, "\n".to_owned());
+    new_lines.insert(2, "\n".to_owned());
+    std::fs::write(repo_path.join("file2"), new_lines.concat()).unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index de980441c3..2b179a59d7 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,3 +1,6 @@
+     a
+    +
+     b
+    +  
+    +
+     c
+    diff --git a/file2 b/file2
+    index 0ff3bbb9c8..d9ba35b784 100644
+    --- a/file2
+    +++ b/file2
+    @@ -1,5 +1,6 @@
+     1
+     2
+    +
+     3
+     4
+     5
+    @@ -11,8 +12,9 @@
+     11
+     12
+     13
+    +
+     14
+    -15
+    +fifteen
+     16
+     17
+     18
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--ignore-blank-lines"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index de980441c3..2b179a59d7 100644
+    --- a/file1
+    +++ b/file1
+    diff --git a/file2 b/file2
+    index 0ff3bbb9c8..d9ba35b784 100644
+    --- a/file2
+    +++ b/file2
+    @@ -11,8 +12,9 @@
+     11
+     12
+     13
+    +
+     14
+    -15
+    +fifteen
+     16
+     17
+     18
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat", "--ignore-blank-lines"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file2 | 2 +-
+    1 file changed, 1 insertion(+), 1 deletion(-)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--ignore-blank-lines"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Modified regular file file1:
+    Modified regular file file2:
+        ...
+      13   14: 13
+           15: 
+      14   16: 14
+      15   17: 15fifteen
+      16   18: 16
+      17   19: 17
+      18   20: 18
+        ...
+    "###);
+}
+
 #[test]
 fn test_diff_skipped_context() {
     let test_env = TestEnvironment::default();
@@ -2209,6 +2577,97 @@ fn test_diff_external_tool_symlink() {
     );
 }
 
+#[test]
+fn test_diff_dump_trees() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    std::fs::write(repo_path.join("dir").join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::remove_file(repo_path.join("file1")).unwrap();
+    std::fs::write(repo_path.join("dir").join("file2"), "foo\nbar\n").unwrap();
+    std::fs::write(repo_path.join("file3"), "foo\n").unwrap();
+
+    let list_files = |dir: &Path| {
+        walkdir(dir)
+            .into_iter()
+            .map(|path| path.strip_prefix(dir).unwrap().to_str().unwrap().to_owned())
+            .sorted()
+            .join("\n")
+    };
+
+    // Both trees are written in full, not just the changed files
+    let output_dir = test_env.env_root().join("dump");
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["diff", "--dump-trees", output_dir.to_str().unwrap()],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    $TEST_ENV/dump/left
+    $TEST_ENV/dump/right
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(list_files(&output_dir.join("left")), @r###"
+    dir/file2
+    file1
+    "###);
+    insta::assert_snapshot!(list_files(&output_dir.join("right")), @r###"
+    dir/file2
+    file3
+    "###);
+    insta::assert_snapshot!(
+        std::fs::read_to_string(output_dir.join("right").join("dir").join("file2")).unwrap(),
+        @r###"
+    foo
+    bar
+    "###);
+
+    // The directories must not exist yet
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["diff", "--dump-trees", output_dir.to_str().unwrap()],
+    );
+    insta::with_settings!({filters => vec![(r"2: .*", "2: ...")]}, {
+        insta::assert_snapshot!(stderr, @r###"
+        Error: Failed to write trees
+        Caused by:
+        1: Failed to create directory $TEST_ENV/dump/left
+        2: ...
+        "###);
+    });
+
+    // Paths restrict the written files, and relative paths are resolved from the
+    // current directory
+    let (stdout, _stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["diff", "--dump-trees", "dump2", "dir"]);
+    insta::assert_snapshot!(stdout, @r###"
+    $TEST_ENV/repo/dump2/left
+    $TEST_ENV/repo/dump2/right
+    "###);
+    insta::assert_snapshot!(list_files(&repo_path.join("dump2").join("left")), @r###"
+    dir/file2
+    "###);
+    insta::assert_snapshot!(list_files(&repo_path.join("dump2").join("right")), @r###"
+    dir/file2
+    "###);
+}
+
+fn walkdir(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![];
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            paths.extend(walkdir(&path));
+        } else {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
 #[test]
 fn test_diff_stat() {
     let test_env = TestEnvironment::default();
@@ -2407,3 +2866,212 @@ fn test_diff_binary() {
     4 files changed, 6 insertions(+), 6 deletions(-)
     "###);
 }
+
+#[test]
+fn test_diff_lfs_pointer() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let lfs_pointer = |oid_char: char, size: u64| {
+        format!(
+            "version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize {size}\n",
+            oid_char.to_string().repeat(64)
+        )
+    };
+
+    std::fs::write(
+        repo_path.join(".gitattributes"),
+        "*.bin filter=lfs diff=lfs merge=lfs -text\nsub/*.bin -filter\n",
+    )
+    .unwrap();
+    std::fs::write(repo_path.join("file1.bin"), lfs_pointer('a', 100)).unwrap();
+    std::fs::write(repo_path.join("file2.bin"), lfs_pointer('b', 2000)).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::remove_file(repo_path.join("file1.bin")).unwrap();
+    std::fs::write(repo_path.join("file2.bin"), lfs_pointer('c', 3000)).unwrap();
+    std::fs::write(repo_path.join("file3.bin"), lfs_pointer('d', 4000)).unwrap();
+    // Not a valid pointer file
+    std::fs::write(repo_path.join("file4.bin"), "version 1\nsize 5\n").unwrap();
+    // The oid must be 64 hex digits
+    let oid = format!("{}é{}", "a".repeat(11), "a".repeat(51));
+    std::fs::write(
+        repo_path.join("file5.bin"),
+        lfs_pointer('a', 100).replace(&"a".repeat(64), &oid),
+    )
+    .unwrap();
+    // Not tracked by Git LFS according to .gitattributes
+    std::fs::write(repo_path.join("file6.txt"), lfs_pointer('e', 600)).unwrap();
+    std::fs::create_dir(repo_path.join("sub")).unwrap();
+    std::fs::write(
+        repo_path.join("sub").join("file7.bin"),
+        lfs_pointer('f', 700),
+    )
+    .unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Removed regular file file1.bin:
+        (Git LFS object: 100 bytes, sha256:aaaaaaaaaaaa)
+    Modified regular file file2.bin:
+        (Git LFS object: 2000 bytes, sha256:bbbbbbbbbbbb => 3000 bytes, sha256:cccccccccccc)
+    Added regular file file3.bin:
+        (Git LFS object: 4000 bytes, sha256:dddddddddddd)
+    Added regular file file4.bin:
+            1: version 1
+            2: size 5
+    Added regular file file5.bin:
+            1: version https://git-lfs.github.com/spec/v1
+            2: oid sha256:aaaaaaaaaaaéaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+            3: size 100
+    Added regular file file6.txt:
+            1: version https://git-lfs.github.com/spec/v1
+            2: oid sha256:eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee
+            3: size 600
+    Added regular file sub/file7.bin:
+            1: version https://git-lfs.github.com/spec/v1
+            2: oid sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+            3: size 700
+    "###);
+
+    // The Git format shows the pointer files as they are stored
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "file2.bin"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file2.bin b/file2.bin
+    index f5eb4e7b4f..f8663ddd4d 100644
+    --- a/file2.bin
+    +++ b/file2.bin
+    @@ -1,3 +1,3 @@
+     version https://git-lfs.github.com/spec/v1
+    -oid sha256:bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb
+    -size 2000
+    +oid sha256:cccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc
+    +size 3000
+    "###);
+}
+
+#[test]
+fn test_diff_conflict_style() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\nbase\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file"), "a\nleft\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file"), "a\nright\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    index 2231ea9b16..0000000000 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,8 @@
+     a
+    -base
+    +<<<<<<< Conflict 1 of 1
+    +%%%%%%% Changes from base to side #1
+    +-left
+    ++base
+    ++++++++ Contents of side #2
+    +right
+    +>>>>>>> Conflict 1 of 1 ends
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--conflict-style=git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    index 2231ea9b16..0000000000 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,6 @@
+     a
+    +<<<<<<< Side #1 (Conflict 1 of 1)
+     base
+    +=======
+    +right
+    +>>>>>>> Side #2 (Conflict 1 of 1 ends)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--conflict-style=diff3"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    index 2231ea9b16..0000000000 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,8 @@
+     a
+    +<<<<<<< Side #1 (Conflict 1 of 1)
+     base
+    +||||||| Base
+    +left
+    +=======
+    +right
+    +>>>>>>> Side #2 (Conflict 1 of 1 ends)
+    "###);
+
+    // The style can be configured
+    test_env.add_config(r#"ui.conflict-style = "diff3""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Created conflict in file:
+       1    1: a
+            2: <<<<<<< Side #1 (Conflict 1 of 1)
+       2    3: base
+            4: ||||||| Base
+            5: left
+            6: =======
+            7: right
+            8: >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Commit ID: 3c6a1142fa81e476ac139c955c5874b1b97e309f
+    Change ID: kkmpptxzrspxrzommnulwmwkkqwworpl
+    Author: Test User <test.user@example.com> (2001-02-03 08:05:10)
+    Committer: Test User <test.user@example.com> (2001-02-03 08:05:10)
+
+        (no description set)
+
+    diff --git a/file b/file
+    index 2231ea9b16..0000000000 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,8 @@
+     a
+    +<<<<<<< Side #1 (Conflict 1 of 1)
+     base
+    +||||||| Base
+    +left
+    +=======
+    +right
+    +>>>>>>> Side #2 (Conflict 1 of 1 ends)
+    "###);
+
+    // The argument overrides the config
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--conflict-style=jj"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    index 2231ea9b16..0000000000 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,8 @@
+     a
+    -base
+    +<<<<<<< Conflict 1 of 1
+    +%%%%%%% Changes from base to side #1
+    +-left
+    ++base
+    ++++++++ Contents of side #2
+    +right
+    +>>>>>>> Conflict 1 of 1 ends
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "--conflict-style=foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'foo' for '--conflict-style <STYLE>'
+      [possible values: jj, git, diff3]
+
+    For more information, try '--help'.
+    "###);
+}
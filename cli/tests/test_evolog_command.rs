@@ -342,6 +342,7 @@ fn test_evolog_with_no_template() {
     - builtin_op_log_compact
     - builtin_op_log_node
     - builtin_op_log_node_ascii
+    - builtin_signature_status
     - commit_summary_separator
     - description_placeholder
     - email_placeholder
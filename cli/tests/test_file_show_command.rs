@@ -92,6 +92,30 @@ fn test_show() {
     c
     >>>>>>> Conflict 1 of 1 ends
     "###);
+
+    // Can print a conflict with Git-style markers
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "show", "--conflict-style=diff3", "file1"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    a
+    ||||||| Base
+    b
+    =======
+    c
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    "###);
+    test_env.add_config(r#"ui.conflict-style = "git""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    a
+    =======
+    c
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    "###);
 }
 
 #[cfg(unix)]
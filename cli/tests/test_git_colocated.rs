@@ -264,6 +264,31 @@ fn test_git_colocated_export_bookmarks_on_snapshot() {
         .to_string(), @"4d2c49a8f8e2f1ba61f48ba79e5f4a5faa6512cf");
 }
 
+#[test]
+fn test_git_colocated_export_reflog_messages() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+
+    std::fs::write(workspace_root.join("file"), "initial").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new"]);
+    std::fs::write(workspace_root.join("file"), "modified").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "foo"]);
+
+    // The reflog entries describe the bookmark updates, newest first
+    let reflog = git_repo.reflog("refs/heads/foo").unwrap();
+    let messages = reflog
+        .iter()
+        .map(|entry| entry.message().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        ["jj: move bookmark foo", "jj: create bookmark foo"]
+    );
+}
+
 #[test]
 fn test_git_colocated_rebase_on_import() {
     let test_env = TestEnvironment::default();
@@ -308,6 +333,43 @@ fn test_git_colocated_rebase_on_import() {
     "###);
 }
 
+#[test]
+fn test_git_colocated_import_quiet() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+    test_env.jj_cmd_ok(&workspace_root, &["commit", "-m", "A"]);
+    let create_git_branch = |name: &str| {
+        let commit = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch(name, &commit, false).unwrap();
+    };
+
+    // The import summary line is omitted with --quiet
+    create_git_branch("foo");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["bookmark", "list", "--quiet"]);
+    insta::assert_snapshot!(stdout, @r###"
+    foo: qpvuntsm a7e4cec4 (empty) A
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+
+    // Same with ui.quiet, including for an explicit import
+    create_git_branch("bar");
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "import", "--config-toml=ui.quiet=true"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+
+    // Without --quiet, the summary line is printed
+    create_git_branch("baz");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["bookmark", "list"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Done importing changes from the underlying Git repo.
+    "###);
+}
+
 #[test]
 fn test_git_colocated_bookmarks() {
     let test_env = TestEnvironment::default();
@@ -394,6 +456,31 @@ fn test_git_colocated_bookmark_forget() {
     insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @"");
 }
 
+#[test]
+fn test_git_colocated_bookmark_rename() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+    test_env.jj_cmd_ok(&workspace_root, &["new"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "old"]);
+    assert!(git_repo.find_reference("refs/heads/old").is_ok());
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["bookmark", "rename", "old", "new"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    // The old Git ref is deleted by the same operation
+    assert!(git_repo.find_reference("refs/heads/old").is_err());
+    insta::assert_snapshot!(
+        git_repo.find_reference("refs/heads/new").unwrap().target().unwrap().to_string(),
+        @"65b6b74e08973b88d38404430f119c8c79465250");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r###"
+    new: rlvkpnrz 65b6b74e (empty) (no description set)
+      @git: rlvkpnrz 65b6b74e (empty) (no description set)
+    "###);
+}
+
 #[test]
 fn test_git_colocated_bookmark_at_root() {
     let test_env = TestEnvironment::default();
@@ -439,16 +526,14 @@ fn test_git_colocated_conflicting_git_refs() {
     test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "main"]);
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "main/sub"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::with_settings!({filters => vec![("Failed to set: .*", "Failed to set: ...")]}, {
-        insta::assert_snapshot!(stderr, @r###"
-        Created 1 bookmarks pointing to qpvuntsm 230dd059 main main/sub | (empty) (no description set)
-        Warning: Failed to export some bookmarks:
-          main/sub: Failed to set: ...
-        Hint: Git doesn't allow a branch name that looks like a parent directory of
-        another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks that failed to
-        export or their "parent" bookmarks.
-        "###);
-    });
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 bookmarks pointing to qpvuntsm 230dd059 main main/sub | (empty) (no description set)
+    Warning: Failed to export some bookmarks:
+      main/sub: Name conflicts with another ref in Git
+    Hint: Git doesn't allow a branch name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks that failed to
+    export or their "parent" bookmarks.
+    "###);
 }
 
 #[test]
@@ -513,6 +598,100 @@ fn test_git_colocated_checkout_non_empty_working_copy() {
     "#);
 }
 
+#[test]
+fn test_git_colocated_commit_interactive() {
+    let mut test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+
+    std::fs::write(workspace_root.join("file1"), "foo\n").unwrap();
+    std::fs::write(workspace_root.join("file2"), "bar\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "feature"]);
+    let diff_editor = test_env.set_up_fake_diff_editor();
+    std::fs::write(diff_editor, "rm file2").unwrap();
+
+    // Select only file1 for the first commit
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["commit", "-i", "-m", "add file1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: kkmpptxz 87a58d67 (no description set)
+    Parent commit      : qpvuntsm d278c0c5 feature | add file1
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_root), @r###"
+    @  87a58d67f9375b4ab25d95dcae6bff6e15573832
+    ○  d278c0c56846ae8a625475ca5fa9b696bda75561 feature git_head() add file1
+    ◆  0000000000000000000000000000000000000000
+    "###);
+
+    // Git HEAD and the exported bookmark both point to the first commit, which
+    // contains only the selected changes
+    let head_commit = git_repo.head().unwrap().peel_to_commit().unwrap();
+    let bookmark_commit = git_repo
+        .find_reference("refs/heads/feature")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(head_commit.id(), bookmark_commit.id());
+    let head_tree = head_commit.tree().unwrap();
+    assert!(head_tree.get_name("file1").is_some());
+    assert!(head_tree.get_name("file2").is_none());
+
+    // The rest stays in the working copy
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A file2
+    "###);
+}
+
+#[test]
+fn test_git_colocated_mine_revset() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+
+    // Create Git commits by different authors, one of them the configured user
+    // with differently-cased email
+    let tree_oid = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    let mut parents = vec![];
+    for (name, email, message) in [
+        ("Someone", "someone@example.com", "by someone"),
+        ("Test User", "Test.User@Example.com", "by me"),
+        (
+            "Someone Else",
+            "someone.else@example.com",
+            "by someone else",
+        ),
+    ] {
+        let signature = git2::Signature::new(name, email, &git2::Time::new(0, 0)).unwrap();
+        let parent_refs = parents.iter().collect::<Vec<_>>();
+        let oid = git_repo
+            .commit(
+                Some("refs/heads/master"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parent_refs,
+            )
+            .unwrap();
+        parents = vec![git_repo.find_commit(oid).unwrap()];
+    }
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+
+    // The working-copy commit is mine, too
+    let template = r#"separate(" ", description.first_line(), author.email()) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &workspace_root,
+        &["log", "-r", "mine()", "--no-graph", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    test.user@example.com
+    by me Test.User@Example.com
+    "###);
+}
+
 #[test]
 fn test_git_colocated_fetch_deleted_or_moved_bookmark() {
     let test_env = TestEnvironment::default();
@@ -548,7 +727,7 @@ fn test_git_colocated_fetch_deleted_or_moved_bookmark() {
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
     bookmark: B_to_delete@origin [deleted] untracked
-    bookmark: C_to_move@origin   [updated] tracked
+    bookmark: C_to_move@origin   [forced] tracked
     Abandoned 2 commits that are no longer reachable.
     "###);
     // "original C" and "B_to_delete" are abandoned, as the corresponding bookmarks
@@ -599,6 +778,39 @@ fn test_git_colocated_rebase_dirty_working_copy() {
       feature: Modified ref had been deleted in Git
     Done importing changes from the underlying Git repo.
     "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status", "--format=json"]);
+    insta::assert_snapshot!(stdout, @r###"
+    {
+      "conflicted_bookmarks": [
+        {
+          "name": "feature",
+          "targets": [
+            "6bad94b10401f5fafc8a91064661224650d10d1b"
+          ]
+        }
+      ],
+      "conflicted_remote_bookmarks": [],
+      "working_copy": {
+        "change_id": "rlvkpnrzqnoowoytxnquwvuryrwnrmlp",
+        "changes": [
+          {
+            "path": "file",
+            "status": "modified"
+          }
+        ],
+        "commit_id": "6bad94b10401f5fafc8a91064661224650d10d1b",
+        "conflicts": [],
+        "description": "",
+        "parents": [
+          {
+            "change_id": "qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu",
+            "commit_id": "3230d52258f6de7e9afbd10da8d64503cc7cdca5",
+            "description": ""
+          }
+        ]
+      }
+    }
+    "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r#"
     @  6bad94b10401f5fafc8a91064661224650d10d1b feature??
     ○  3230d52258f6de7e9afbd10da8d64503cc7cdca5 git_head()
@@ -714,6 +926,46 @@ fn test_git_colocated_squash_undo() {
     "#);
 }
 
+#[test]
+fn test_git_colocated_squash_keep_emptied() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=A"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=B"]);
+    std::fs::write(repo_path.join("file"), "b").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["squash", "-r", "b", "--keep-emptied"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 descendant commits
+    Working copy now at: mzvwutvl 1b2c7579 (empty) (no description set)
+    Parent commit      : kkmpptxz 650bfab1 b | (empty) B
+    "###);
+    // The emptied source commit is kept, and so is its bookmark
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  1b2c75790d142f774620e019d90eb98ca265893d
+    ○  650bfab1f4063aab6dd258c1ef072c868aa976d0 b git_head() B
+    ○  d9a16cd5da087705f208a72d0b20cd8655a079b2 A
+    ◆  0000000000000000000000000000000000000000
+    "###);
+
+    // The bookmark is exported to the emptied commit, which has the same tree
+    // as its parent
+    let commit = git_repo
+        .find_reference("refs/heads/b")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    insta::assert_snapshot!(commit.id().to_string(), @r###"
+    650bfab1f4063aab6dd258c1ef072c868aa976d0
+    "###);
+    assert_eq!(commit.tree_id(), commit.parent(0).unwrap().tree_id());
+}
+
 #[test]
 fn test_git_colocated_undo_head_move() {
     let test_env = TestEnvironment::default();
@@ -771,6 +1023,524 @@ fn test_git_colocated_undo_head_move() {
     "#);
 }
 
+#[test]
+fn test_git_colocated_op_restore_replay_git() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let op_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-T=id.short()", "--limit=1"],
+    );
+
+    // Create a bookmark with an external Git command, which is imported by the
+    // next jj command, and then create a bookmark with jj.
+    let head_commit = git_repo.head().unwrap().peel_to_commit().unwrap();
+    git_repo.branch("external", &head_commit, false).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "-r@-", "jj-side"]);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    external: qpvuntsm fa15625b (empty) first
+      @git: qpvuntsm fa15625b (empty) first
+    jj-side: qpvuntsm fa15625b (empty) first
+      @git: qpvuntsm fa15625b (empty) first
+    "###);
+
+    // Without --replay-git, the externally created bookmark is lost
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["op", "restore", &op_id]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Restored to operation: adb209ee3792 (2001-02-03 08:05:09) new empty commit
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @"");
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // With --replay-git, only the jj-side change is discarded
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["op", "restore", "--replay-git", &op_id]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Restored to operation: adb209ee3792 (2001-02-03 08:05:09) new empty commit
+    Replayed changes imported from Git:
+      bookmark external
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    external: qpvuntsm fa15625b (empty) first
+      @git: qpvuntsm fa15625b (empty) first
+    "###);
+    assert!(git_repo
+        .find_branch("external", git2::BranchType::Local)
+        .is_ok());
+    assert!(git_repo
+        .find_branch("jj-side", git2::BranchType::Local)
+        .is_err());
+}
+
+#[test]
+fn test_git_colocated_export_update_head() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=second"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let first_oid = git_repo.revparse_single("HEAD~").unwrap().id();
+    let second_oid = git_repo.head().unwrap().target().unwrap();
+
+    // No-op when already in sync
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export", "--update-head"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // Move HEAD behind jj's back. Ignoring the working copy prevents jj from
+    // importing the moved HEAD.
+    git_repo.set_head_detached(first_oid).unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "export", "--update-head", "--ignore-working-copy"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Moved Git HEAD to 9ed53a4a1bec
+    "###);
+    assert_eq!(git_repo.head().unwrap().target(), Some(second_oid));
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  c97da310c66008034013412d321397242e1e43ef
+    ○  9ed53a4a1becd028f9a2fe0d5275973acea7e8da git_head() second
+    ○  fa15625b4a986997697639dfc2844138900c79f2 first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+
+    // Only supported in a colocated workspace
+    test_env.jj_cmd_ok(&repo_path, &["workspace", "add", "../secondary"]);
+    let stderr = test_env.jj_cmd_failure(
+        &test_env.env_root().join("secondary"),
+        &["git", "export", "--update-head"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: --update-head requires a workspace colocated with Git
+    "###);
+
+    // In a workspace colocated with a linked worktree, the worktree's HEAD is
+    // updated, not the main repo's
+    let worktree_path = test_env.env_root().join("worktree");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["workspace", "add", "--colocate", "../worktree"],
+    );
+    let worktree_repo = git2::Repository::open(&worktree_path).unwrap();
+    git_repo.set_head_detached(first_oid).unwrap();
+    worktree_repo.set_head_detached(first_oid).unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &worktree_path,
+        &["git", "export", "--update-head", "--ignore-working-copy"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Moved Git HEAD to 9ed53a4a1bec
+    "###);
+    assert_eq!(worktree_repo.head().unwrap().target(), Some(second_oid));
+    assert_eq!(git_repo.head().unwrap().target(), Some(first_oid));
+}
+
+#[test]
+fn test_git_colocated_git_head_per_workspace() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let secondary_path = test_env.env_root().join("secondary");
+    git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=first"]);
+    test_env.jj_cmd_ok(&repo_path, &["workspace", "add", "../secondary"]);
+    test_env.jj_cmd_ok(&secondary_path, &["new", "-m=second"]);
+
+    // Git HEAD of the colocated workspace isn't shown in the other workspace
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  15cf5f8b721b662dbf29b4ca5203e66e58dade36 second
+    ○  6ce1087054f9eb9d25074d9ad7e9407e998df71e
+    │ @  e8ea92a8b6b35dd30842d619e124ca9cc4a3c0cc
+    ├─╯
+    ○  fa15625b4a986997697639dfc2844138900c79f2 git_head() first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &secondary_path), @r###"
+    @  15cf5f8b721b662dbf29b4ca5203e66e58dade36 second
+    ○  6ce1087054f9eb9d25074d9ad7e9407e998df71e
+    │ ○  e8ea92a8b6b35dd30842d619e124ca9cc4a3c0cc
+    ├─╯
+    ○  fa15625b4a986997697639dfc2844138900c79f2 first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+    let template = r#"commit_id.short() ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r=git_head()", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @"fa15625b4a98");
+    let stdout = test_env.jj_cmd_success(
+        &secondary_path,
+        &["log", "--no-graph", "-r=git_head()", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @"");
+
+    // Moving the working copy of the other workspace doesn't move Git HEAD
+    test_env.jj_cmd_ok(&secondary_path, &["new"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r=git_head()", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @"fa15625b4a98");
+}
+
+#[test]
+fn test_git_colocated_workspace_independent_heads() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let secondary_path = test_env.env_root().join("secondary");
+    git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    std::fs::write(repo_path.join("file"), "first\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=first"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["workspace", "add", "--colocate", "../secondary"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created workspace in "../secondary"
+    Working copy now at: pmmvwywv 09c58747 (empty) (no description set)
+    Parent commit      : qpvuntsm e8249df7 first
+    Added 1 files, modified 0 files, removed 0 files
+    "###);
+    assert!(secondary_path.join(".git").is_file());
+    assert_eq!(
+        std::fs::read_to_string(secondary_path.join("file")).unwrap(),
+        "first\n"
+    );
+
+    // The new workspace is a Git worktree with its own HEAD and index
+    let worktree_repo = git2::Repository::open(&secondary_path).unwrap();
+    assert!(worktree_repo.is_worktree());
+    #[allow(clippy::format_collect)]
+    let get_git_statuses = || -> String {
+        worktree_repo
+            .statuses(None)
+            .unwrap()
+            .iter()
+            .map(|entry| format!("{:?} {}\n", entry.status(), entry.path().unwrap()))
+            .collect()
+    };
+    insta::assert_snapshot!(get_git_statuses(), @r###"
+    Status(IGNORED) .jj/.gitignore
+    Status(IGNORED) .jj/repo
+    Status(IGNORED) .jj/working_copy/
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &secondary_path), @r###"
+    @  09c58747ad00b47deed5dbada81f3173185ed8a0
+    │ ○  784dd5d28152449dc6b124eb5daeb576dcc5a1c6
+    ├─╯
+    ○  e8249df78a0909054a55c9a4cc3ffeb80bacfd37 git_head() first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+
+    std::fs::write(secondary_path.join("file"), "second\n").unwrap();
+    test_env.jj_cmd_ok(&secondary_path, &["commit", "-m=second"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &secondary_path), @r###"
+    @  a72ab1eb3415f422d0b62c3dbee0bdecbf6283b1
+    ○  a2ba99ca5f2b749d1a7aeade5b649d5d80e87fa7 git_head() second
+    │ ○  784dd5d28152449dc6b124eb5daeb576dcc5a1c6
+    ├─╯
+    ○  e8249df78a0909054a55c9a4cc3ffeb80bacfd37 first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  a72ab1eb3415f422d0b62c3dbee0bdecbf6283b1
+    ○  a2ba99ca5f2b749d1a7aeade5b649d5d80e87fa7 second
+    │ @  784dd5d28152449dc6b124eb5daeb576dcc5a1c6
+    ├─╯
+    ○  e8249df78a0909054a55c9a4cc3ffeb80bacfd37 git_head() first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+    insta::assert_snapshot!(get_git_statuses(), @r###"
+    Status(IGNORED) .jj/.gitignore
+    Status(IGNORED) .jj/repo
+    Status(IGNORED) .jj/working_copy/
+    "###);
+    let main_repo = git2::Repository::open(&repo_path).unwrap();
+    insta::assert_snapshot!(main_repo.head().unwrap().target().unwrap().to_string(), @"e8249df78a0909054a55c9a4cc3ffeb80bacfd37");
+    insta::assert_snapshot!(worktree_repo.head().unwrap().target().unwrap().to_string(), @"a2ba99ca5f2b749d1a7aeade5b649d5d80e87fa7");
+
+    // HEAD moved by git in the worktree is imported to the worktree's
+    // workspace only
+    let first_oid = main_repo.head().unwrap().target().unwrap();
+    worktree_repo.set_head_detached(first_oid).unwrap();
+    let (stdout, stderr) = get_log_output_with_stderr(&test_env, &secondary_path);
+    insta::assert_snapshot!(stdout, @r###"
+    @  e798f0319a8ea9168e8aeeb67fb6aca5e7596ce9
+    │ ○  a2ba99ca5f2b749d1a7aeade5b649d5d80e87fa7 second
+    ├─╯
+    │ ○  784dd5d28152449dc6b124eb5daeb576dcc5a1c6
+    ├─╯
+    ○  e8249df78a0909054a55c9a4cc3ffeb80bacfd37 git_head() first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+    insta::assert_snapshot!(stderr, @"Reset the working copy parent to the new Git HEAD.");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  e798f0319a8ea9168e8aeeb67fb6aca5e7596ce9
+    │ ○  a2ba99ca5f2b749d1a7aeade5b649d5d80e87fa7 second
+    ├─╯
+    │ @  784dd5d28152449dc6b124eb5daeb576dcc5a1c6
+    ├─╯
+    ○  e8249df78a0909054a55c9a4cc3ffeb80bacfd37 git_head() first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+}
+
+#[test]
+fn test_git_colocated_workspace_of_internal_git_repo() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let secondary_path = test_env.env_root().join("secondary");
+    std::fs::write(repo_path.join("file"), "first\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=first"]);
+
+    // The backing Git repo is bare, but the new workspace is still colocated
+    // with a worktree of it
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["workspace", "add", "--colocate", "../secondary"],
+    );
+    let worktree_repo = git2::Repository::open(&secondary_path).unwrap();
+    assert!(worktree_repo.is_worktree());
+    insta::assert_snapshot!(get_log_output(&test_env, &secondary_path), @r###"
+    @  09c58747ad00b47deed5dbada81f3173185ed8a0
+    │ ○  784dd5d28152449dc6b124eb5daeb576dcc5a1c6
+    ├─╯
+    ○  e8249df78a0909054a55c9a4cc3ffeb80bacfd37 git_head() first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  09c58747ad00b47deed5dbada81f3173185ed8a0
+    │ @  784dd5d28152449dc6b124eb5daeb576dcc5a1c6
+    ├─╯
+    ○  e8249df78a0909054a55c9a4cc3ffeb80bacfd37 first
+    ◆  0000000000000000000000000000000000000000
+    "###);
+}
+
+#[test]
+fn test_git_colocated_workspace_invalid_gitdir() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let secondary_path = test_env.env_root().join("secondary");
+    git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["workspace", "add", "--colocate", "../secondary"],
+    );
+
+    // The worktree's administrative files were removed, e.g. by `git worktree
+    // prune`
+    std::fs::remove_dir_all(repo_path.join(".git").join("worktrees").join("secondary")).unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&secondary_path, &["log", "-r=@", "-T=description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @
+    │
+    ~
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Broken colocated git worktree: The .git file points to $TEST_ENV/repo/.git/worktrees/secondary, which doesn't exist
+    Hint: Run `git worktree repair` in the main Git working tree to fix it.
+    "###);
+
+    // Not only `jj log`, but any command should warn
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&secondary_path, &["new"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Broken colocated git worktree: The .git file points to $TEST_ENV/repo/.git/worktrees/secondary, which doesn't exist
+    Hint: Run `git worktree repair` in the main Git working tree to fix it.
+    Working copy now at: zsuskuln 9634edd1 (empty) (no description set)
+    Parent commit      : uuqppmxq 57d63245 (empty) (no description set)
+    "###);
+
+    // The warning is printed once even if the workspace is loaded again
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&secondary_path, &["workspace", "update-stale"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Broken colocated git worktree: The .git file points to $TEST_ENV/repo/.git/worktrees/secondary, which doesn't exist
+    Hint: Run `git worktree repair` in the main Git working tree to fix it.
+    Nothing to do (the working copy is not stale).
+    "###);
+
+    // The .git file doesn't point to anything
+    std::fs::write(secondary_path.join(".git"), "").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&secondary_path, &["status"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Broken colocated git worktree: The .git file doesn't specify a gitdir
+    Hint: Run `git worktree repair` in the main Git working tree to fix it.
+    "###);
+}
+
+#[test]
+fn test_git_colocated_workspace_wrong_gitdir() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let secondary_path = test_env.env_root().join("secondary");
+    git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    test_env.jj_cmd_ok(&repo_path, &["workspace", "add", "../secondary"]);
+
+    // The .git file points to a worktree of a Git repo unrelated to the jj repo
+    let other_path = test_env.env_root().join("other");
+    let other_repo = git2::Repository::init(&other_path).unwrap();
+    let signature = git2::Signature::now("Some One", "some.one@example.com").unwrap();
+    let empty_tree_oid = other_repo.treebuilder(None).unwrap().write().unwrap();
+    let empty_tree = other_repo.find_tree(empty_tree_oid).unwrap();
+    other_repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "other",
+            &empty_tree,
+            &[],
+        )
+        .unwrap();
+    other_repo
+        .worktree("other-wt", &test_env.env_root().join("other-wt"), None)
+        .unwrap();
+    std::fs::copy(
+        test_env.env_root().join("other-wt").join(".git"),
+        secondary_path.join(".git"),
+    )
+    .unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&secondary_path, &["status"]);
+    insta::assert_snapshot!(stderr, @"Warning: The .git file points to a worktree of $TEST_ENV/other/.git, which isn't managed by JJ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_git_colocated_hooks() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    // Hooks are looked up in core.hooksPath, relative to the working tree
+    git2::Repository::open(&repo_path)
+        .unwrap()
+        .config()
+        .unwrap()
+        .set_str("core.hooksPath", "my-hooks")
+        .unwrap();
+    let hooks_dir = test_env.env_root().join("hooks");
+    std::fs::create_dir(&hooks_dir).unwrap();
+    let write_hook = |name: &str, content: &str| {
+        let hook_path = hooks_dir.join(name);
+        std::fs::write(&hook_path, content).unwrap();
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    write_hook(
+        "pre-commit",
+        indoc::indoc! {r#"
+            #!/bin/sh
+            echo "pre-commit GIT_DIR=$GIT_DIR"
+            # The snapshotted changes are staged
+            git diff --cached --name-status
+            if [ -e unformatted ]; then
+                echo "formatted" > unformatted
+            fi
+            if [ -e reject ]; then
+                echo "reject is not allowed" >&2
+                exit 1
+            fi
+        "#},
+    );
+    write_hook(
+        "post-checkout",
+        indoc::indoc! {r#"
+            #!/bin/sh
+            echo "post-checkout $1 $2 $3"
+        "#},
+    );
+    std::os::unix::fs::symlink(&hooks_dir, repo_path.join("my-hooks")).unwrap();
+    std::fs::write(repo_path.join(".gitignore"), "my-hooks\n").unwrap();
+
+    // Hooks aren't run by default
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["commit", "-m=first"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: rlvkpnrz 08b681c7 (empty) (no description set)
+    Parent commit      : qpvuntsm fb9f5eee first
+    "###);
+
+    // The pre-commit hook is run once, and the post-checkout hook is run after
+    // the working copy is updated
+    test_env.add_config("git.run-hooks = true");
+    std::fs::write(repo_path.join("file"), "contents\n").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["commit", "-m=second"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    pre-commit GIT_DIR=$TEST_ENV/repo/.git/
+    A	file
+    Working copy now at: kkmpptxz a46f5262 (empty) (no description set)
+    Parent commit      : rlvkpnrz 06a5ae03 second
+    post-checkout fb9f5eee6904676eb074f9547625bf94b34cb21a 06a5ae03cadac4aaf6c992857eb277a3221eaef0 1
+    "###);
+
+    // Files modified by the pre-commit hook are snapshotted
+    std::fs::write(repo_path.join("unformatted"), "unformatted\n").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Working copy changes:
+    A unformatted
+    Working copy : kkmpptxz f8cb2df1 (no description set)
+    Parent commit: rlvkpnrz 06a5ae03 second
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    pre-commit GIT_DIR=$TEST_ENV/repo/.git/
+    A	unformatted
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-r@", "unformatted"]);
+    insta::assert_snapshot!(stdout, @"formatted");
+
+    // The pre-commit hook is run when the working-copy commit is rewritten,
+    // and a failing hook aborts the snapshot
+    std::fs::write(repo_path.join("reject"), "").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["status"]);
+    insta::assert_snapshot!(stderr, @r###"
+    pre-commit GIT_DIR=$TEST_ENV/repo/.git/
+    A	reject
+    A	unformatted
+    reject is not allowed
+    Error: The pre-commit hook failed (exit status: 1)
+    Hint: The working copy was not snapshotted. Use --ignore-working-copy to run the command anyway.
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status", "--ignore-working-copy"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Working copy changes:
+    A unformatted
+    Working copy : kkmpptxz f8cb2df1 (no description set)
+    Parent commit: rlvkpnrz 06a5ae03 second
+    "###);
+
+    // Rewriting the working-copy commit doesn't run the post-checkout hook
+    std::fs::remove_file(repo_path.join("reject")).unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["describe", "-m=third"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: kkmpptxz a18fd853 third
+    Parent commit      : rlvkpnrz 06a5ae03 second
+    "###);
+}
+
 fn get_log_output_divergence(test_env: &TestEnvironment, repo_path: &Path) -> String {
     let template = r#"
     separate(" ",
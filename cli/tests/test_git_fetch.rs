@@ -13,8 +13,392 @@
 // limitations under the License.
 use std::path::Path;
 
+use jj_lib::git;
+
 use crate::common::TestEnvironment;
 
+#[test]
+fn test_git_fetch_porcelain() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    let initial_commit = git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    git_repo
+        .tag_lightweight("v1", initial_commit.as_object(), false)
+        .unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--porcelain"]);
+    insta::assert_snapshot!(stdout, @r###"
+    bookmark	origin@origin	-	ffecd2d6782755e28b71ef963e583a29c36c6a5d	new	tracked
+    tag	v1	-	ffecd2d6782755e28b71ef963e583a29c36c6a5d	new	-
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+
+    // Update and delete remote refs
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    git_repo
+        .commit(
+            Some("refs/heads/other"),
+            &signature,
+            &signature,
+            "other",
+            &initial_commit.tree().unwrap(),
+            &[&initial_commit],
+        )
+        .unwrap();
+    git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .delete()
+        .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--porcelain"]);
+    insta::assert_snapshot!(stdout, @r###"
+    bookmark	origin@origin	ffecd2d6782755e28b71ef963e583a29c36c6a5d	-	deleted	untracked
+    bookmark	other@origin	-	bf146d660403ed4220f253b507223be921a8f9d4	new	tracked
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+}
+
+#[test]
+fn test_git_fetch_forced_updates() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+
+    let initial_commit = git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let commit_on_remote = |message: &str, parents: &[&git2::Commit]| {
+        let oid = git_repo
+            .commit(
+                None,
+                &signature,
+                &signature,
+                message,
+                &initial_commit.tree().unwrap(),
+                parents,
+            )
+            .unwrap();
+        git_repo
+            .reference("refs/heads/origin", oid, true, "")
+            .unwrap();
+        git_repo.find_commit(oid).unwrap()
+    };
+
+    // A fast-forward is reported as an update
+    let child_commit = commit_on_remote("child", &[&initial_commit]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [updated] tracked
+    "###);
+
+    // Rewritten history is reported as a forced update
+    commit_on_remote("rewritten child", &[&initial_commit]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [forced] tracked
+    Abandoned 1 commits that are no longer reachable.
+    "###);
+
+    // ... unless disabled
+    commit_on_remote("child again", &[&child_commit]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "fetch", "--no-show-forced-updates", "--porcelain"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    bookmark	origin@origin	2263a18becd32b78efe0a87baf919bcc0c456253	e5ee7876cdef883671bd94fc40630e032cacf169	updated	tracked
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Abandoned 1 commits that are no longer reachable.
+    "###);
+}
+
+#[test]
+fn test_git_fetch_negotiation_tip() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    // `git fetch` doesn't run in the workspace, so the remote path must be
+    // absolute.
+    init_git_remote(&test_env, "origin");
+    let origin_path = test_env.env_root().join("origin");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            origin_path.to_str().unwrap(),
+        ],
+    );
+
+    // Running git must be enabled explicitly
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["git", "fetch", "--negotiation-tip=root()"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Fetching with these options requires running the `git` executable
+    Hint: --negotiation-tip, --filter and --unshallow need the `git` executable. Set `git.subprocess = true` to allow running it.
+    "###);
+
+    test_env.add_config("git.subprocess = true");
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--negotiation-tip=root()"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [new] tracked
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    origin: oputwtnw ffecd2d6 message
+      @origin: oputwtnw ffecd2d6 message
+    "###);
+
+    // Update and delete remote refs
+    let git_repo = git2::Repository::open(&origin_path).unwrap();
+    let initial_commit = git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    git_repo
+        .commit(
+            Some("refs/heads/other"),
+            &signature,
+            &signature,
+            "other",
+            &initial_commit.tree().unwrap(),
+            &[&initial_commit],
+        )
+        .unwrap();
+    git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .delete()
+        .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "fetch", "--negotiation-tip=origin@origin", "--debug"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    assert!(stderr.contains("fetch negotiation finished"), "{stderr}");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    other: xoqkyurv bf146d66 (empty) other
+      @origin: xoqkyurv bf146d66 (empty) other
+    "###);
+
+    // Negotiation tips can't be used when fetching from a URL
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["git", "fetch", "--remote=../origin", "--negotiation-tip=@"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot use --negotiation-tip with ../origin because it isn't a configured remote
+    "###);
+}
+
+#[test]
+fn test_git_fetch_filter() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.add_config("git.subprocess = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    init_git_remote(&test_env, "origin");
+    let origin_path = test_env.env_root().join("origin");
+    git2::Repository::open(&origin_path)
+        .unwrap()
+        .config()
+        .unwrap()
+        .set_bool("uploadpack.allowFilter", true)
+        .unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            origin_path.to_str().unwrap(),
+        ],
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--filter=blob:none"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [new] tracked
+    "###);
+
+    // The blob wasn't fetched
+    let git_dir = repo_path.join(".jj/repo/store/git");
+    let list_missing = || {
+        let output = std::process::Command::new("git")
+            .current_dir(&git_dir)
+            .args(["rev-list", "--objects", "--missing=print", "--all"])
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .filter(|line| line.starts_with('?'))
+            .count()
+    };
+    assert_eq!(list_missing(), 1);
+
+    // Reading the file fails clearly if the remote isn't reachable
+    let moved_path = test_env.env_root().join("moved");
+    std::fs::rename(&origin_path, &moved_path).unwrap();
+    let stderr = test_env
+        .jj_cmd_internal_error(&repo_path, &["file", "show", "-r", "origin@origin", "file"]);
+    assert!(
+        stderr.contains("Failed to fetch missing object from promisor remote"),
+        "{stderr}"
+    );
+    std::fs::rename(&moved_path, &origin_path).unwrap();
+
+    // Missing objects aren't fetched unless running git is enabled
+    let stderr = test_env.jj_cmd_internal_error(
+        &repo_path,
+        &[
+            "file",
+            "show",
+            "-r=origin@origin",
+            "file",
+            "--config-toml=git.subprocess = false",
+        ],
+    );
+    assert!(
+        stderr.contains("Set `git.subprocess = true` to fetch it"),
+        "{stderr}"
+    );
+
+    // The blob is fetched on demand when it's diffed
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r=origin@origin", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    new file mode 100644
+    index 0000000000..6fa78783bf
+    --- /dev/null
+    +++ b/file
+    @@ -1,0 +1,1 @@
+    +origin
+    \ No newline at end of file
+    "###);
+    assert_eq!(list_missing(), 0);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["file", "show", "-r", "origin@origin", "file"]);
+    insta::assert_snapshot!(stdout, @r###"
+    origin
+    "###);
+
+    // Filters can't be used when fetching from a URL
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["git", "fetch", "--remote=../origin", "--filter=blob:none"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot use --filter with ../origin because it isn't a configured remote
+    "###);
+}
+
+#[test]
+fn test_git_fetch_unshallow() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.subprocess = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let origin_path = test_env.env_root().join("origin");
+    let origin_repo = git2::Repository::init(&origin_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let empty_tree_oid = origin_repo.treebuilder(None).unwrap().write().unwrap();
+    let empty_tree = origin_repo.find_tree(empty_tree_oid).unwrap();
+    let mut parents = vec![];
+    for message in ["first", "second", "third"] {
+        let oid = origin_repo
+            .commit(
+                Some("refs/heads/main"),
+                &signature,
+                &signature,
+                message,
+                &empty_tree,
+                &parents.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+        parents = vec![origin_repo.find_commit(oid).unwrap()];
+    }
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            origin_path.to_str().unwrap(),
+        ],
+    );
+
+    // Make a shallow fetch with git, since jj can't deepen the history by
+    // itself
+    let output = std::process::Command::new("git")
+        .current_dir(repo_path.join(".jj/repo/store/git"))
+        .args([
+            "fetch",
+            "--depth=1",
+            "origin",
+            "+refs/heads/*:refs/remotes/origin/*",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ◆  654445466d05 third main@origin
+    │ @  230dd059e1b0
+    ├─╯
+    ◆  000000000000
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--unshallow"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Fetched the full history of 1 shallow commits
+    Reindexed 5 commits
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  230dd059e1b0
+    │ ◆  654445466d05 third main@origin
+    │ ◆  d48dba0488c9 second
+    │ ◆  eafa497979dd first
+    ├─╯
+    ◆  000000000000
+    "###);
+
+    // Nothing to do if the repo isn't shallow
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--unshallow"]);
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+}
+
 /// Creates a remote Git repo containing a bookmark with the same name
 fn init_git_remote(test_env: &TestEnvironment, remote: &str) {
     let git_repo_path = test_env.env_root().join(remote);
@@ -85,6 +469,118 @@ fn test_git_fetch_with_default_config() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_write_commit_graph() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let commit_graph_path = repo_path.join(".jj/repo/store/git/objects/info/commit-graph");
+
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    assert!(!commit_graph_path.exists());
+
+    // Requires running the git executable
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["git", "fetch", "--write-commit-graph"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    Error: Writing the commit-graph file requires running the `git` executable
+    Hint: --write-commit-graph needs the `git` executable. Set `git.subprocess = true` to allow running it.
+    "###);
+    assert!(!commit_graph_path.exists());
+
+    test_env.add_config("git.subprocess = true");
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--write-commit-graph"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    Wrote the commit-graph file
+    "###);
+    assert!(commit_graph_path.exists());
+}
+
+#[test]
+fn test_git_fetch_auto_gc() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=local"]);
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+
+    // Off by default
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [new] untracked
+    "###);
+
+    // After a number of fetches
+    test_env.add_config("git.auto-gc.fetches = 2");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    Ran automatic garbage collection after 2 fetches
+    "###);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // When there are too many loose objects
+    std::fs::write(repo_path.join("file"), "new contents").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--config-toml=git.auto-gc = { fetches = 100, loose-objects = 1 }",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    Ran automatic garbage collection since there were 3 loose objects
+    "###);
+
+    // Objects reachable from jj's commits are kept
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-r=@", "file"]);
+    insta::assert_snapshot!(stdout, @r###"
+    new contents
+    "###);
+
+    // 0 disables the check
+    std::fs::write(repo_path.join("file"), "more contents").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--config-toml=git.auto-gc = { fetches = 0, loose-objects = 0 }",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // Invalid values are reported
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--config-toml=git.auto-gc.fetches = 'often'",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: invalid type: string "often", expected an integer for key `git.auto-gc.fetches`
+    For help, see https://martinvonz.github.io/jj/latest/config/.
+    "###);
+}
+
 #[test]
 fn test_git_fetch_default_remote() {
     let test_env = TestEnvironment::default();
@@ -168,6 +664,58 @@ fn test_git_fetch_single_remote_from_config() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_narrowed_remote() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let upstream_repo = git2::Repository::init(test_env.env_root().join("upstream")).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let tree_id = upstream_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = upstream_repo.find_tree(tree_id).unwrap();
+    for branch in ["main", "feature"] {
+        upstream_repo
+            .commit(
+                Some(&format!("refs/heads/{branch}")),
+                &signature,
+                &signature,
+                branch,
+                &tree,
+                &[],
+            )
+            .unwrap();
+    }
+
+    // A remote configured outside of jj to fetch a single branch, as done by
+    // `git clone --single-branch`
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+    git_repo
+        .remote_with_fetch(
+            "upstream",
+            "../upstream",
+            "+refs/heads/main:refs/remotes/upstream/main",
+        )
+        .unwrap();
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Hint: Fetching from the only existing remote: upstream
+    bookmark: main@upstream [new] untracked
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    main@upstream: oslyvumk 1630028e (empty) main
+    "###);
+
+    // Other branches can still be fetched explicitly
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--branch=feature"]);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature@upstream: ktvwlxur 422129c6 (empty) feature
+    main@upstream: oslyvumk 1630028e (empty) main
+    "###);
+}
+
 #[test]
 fn test_git_fetch_multiple_remotes() {
     let test_env = TestEnvironment::default();
@@ -262,6 +810,187 @@ fn test_git_fetch_nonexistent_remote_from_config() {
     insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @"");
 }
 
+#[test]
+fn test_git_fetch_mirror() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    let commit = git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    git_repo.branch("feature", &commit, false).unwrap();
+    git_repo
+        .tag_lightweight("v1", commit.as_object(), false)
+        .unwrap();
+
+    // Remote branches and tags are imported as local refs
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--mirror"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: feature [new] tracked
+    bookmark: origin  [new] tracked
+    tag:    v1      [new] 
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    feature: oputwtnw ffecd2d6 message
+      @git: oputwtnw ffecd2d6 message
+    origin: oputwtnw ffecd2d6 message
+      @git: oputwtnw ffecd2d6 message
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["tag", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    v1: oputwtnw ffecd2d6 message
+    "###);
+
+    // Branches deleted on the remote are deleted locally
+    git_repo
+        .find_branch("feature", git2::BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--mirror"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: feature [deleted] tracked
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    origin: oputwtnw ffecd2d6 message
+      @git: oputwtnw ffecd2d6 message
+    "###);
+
+    // Can't mirror a URL
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["git", "fetch", "--mirror", "--remote", "../origin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot mirror ../origin because it isn't a configured remote
+    "###);
+}
+
+#[test]
+fn test_git_fetch_update_head() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let commit_on_remote = |message: &str, parents: &[&git2::Commit]| {
+        let tree = parents[0].tree().unwrap();
+        let oid = git_repo
+            .commit(None, &signature, &signature, message, &tree, parents)
+            .unwrap();
+        let commit = git_repo.find_commit(oid).unwrap();
+        git_repo.branch("origin", &commit, true).unwrap();
+        commit
+    };
+
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "origin"]);
+    std::fs::write(repo_path.join("local-file"), "local").unwrap();
+
+    // Nothing to do if the bookmark didn't move
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--update-head"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // The working copy is moved onto the fast-forwarded bookmark
+    let initial_commit = git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    commit_on_remote("child", &[&initial_commit]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--update-head"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [updated] tracked
+    Moved working copy onto bookmark origin: vtzvmktt 9ad47451 origin | (empty) child
+    Working copy now at: zsuskuln 0a0bd90a (no description set)
+    Parent commit      : vtzvmktt 9ad47451 origin | (empty) child
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  0a0bd90af875
+    ○  9ad474513140 child origin
+    ○  ffecd2d67827 message
+    ◆  000000000000
+    "###);
+
+    // The working copy isn't moved if the bookmark moved sideways
+    commit_on_remote("sideways", &[&initial_commit]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--update-head"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [forced] tracked
+    Abandoned 1 commits that are no longer reachable.
+    Warning: Not moving the working copy because bookmark origin wasn't fast-forwarded
+    Rebased 1 descendant commits
+    Working copy now at: zsuskuln 2a0df350 (no description set)
+    Parent commit      : oputwtnw ffecd2d6 message
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  2a0df3506399
+    │ ○  3e9b3e863b04 sideways origin
+    ├─╯
+    ○  ffecd2d67827 message
+    ◆  000000000000
+    "###);
+}
+
+#[test]
+fn test_git_fetch_from_url() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    init_git_remote(&test_env, "rem1");
+    init_git_remote(&test_env, "rem2");
+
+    // Bookmarks are imported under the temporary remote, and aren't tracked
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote", "../rem1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Fetching ../rem1 into temporary remote anonymous
+    bookmark: rem1@anonymous [new] untracked
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    rem1@anonymous: qxosxrvv 6a211027 message
+    "###);
+
+    // Fetching from another URL replaces the bookmarks of the previous one
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote", "../rem2"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Fetching ../rem2 into temporary remote anonymous
+    bookmark: rem1@anonymous [deleted] untracked
+    bookmark: rem2@anonymous [new] untracked
+    Abandoned 1 commits that are no longer reachable.
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    rem2@anonymous: yszkquru 2497a8a0 message
+    "###);
+
+    // A configured remote named like the temporary one is rejected
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "anonymous", "../rem1"],
+    );
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["git", "fetch", "--remote", "../rem2"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Fetching ../rem2 into temporary remote anonymous
+    Error: Git remote named 'anonymous' is reserved for fetching from a URL
+    "###);
+}
+
 #[test]
 fn test_git_fetch_from_remote_named_git() {
     let test_env = TestEnvironment::default();
@@ -398,6 +1127,82 @@ fn test_git_fetch_conflicting_bookmarks_colocated() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_fast_forward_conflicted_bookmark() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let commit_on_remote = |message: &str, parent: &git2::Commit| {
+        let tree = parent.tree().unwrap();
+        let oid = git_repo
+            .commit(None, &signature, &signature, message, &tree, &[parent])
+            .unwrap();
+        let commit = git_repo.find_commit(oid).unwrap();
+        git_repo.branch("origin", &commit, true).unwrap();
+        commit
+    };
+    let initial_commit = git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let first_commit = commit_on_remote("first", &initial_commit);
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+
+    // Move the local bookmark backwards, then advance the remote bookmark. The
+    // local target is an ancestor of the new remote target, so the bookmark is
+    // fast-forwarded instead of becoming conflicted.
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bookmark",
+            "set",
+            "origin",
+            "--allow-backwards",
+            "-r",
+            "origin-",
+        ],
+    );
+    commit_on_remote("second", &first_commit);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [updated] tracked
+    Fast-forwarded bookmark origin to origin@origin instead of creating a conflict
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    origin: tqvrwtoq c19e1d73 (empty) second
+      @origin: tqvrwtoq c19e1d73 (empty) second
+    "###);
+
+    // The bookmark still conflicts if the local target isn't an ancestor of the
+    // remote target.
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "local"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "set", "origin", "--allow-backwards", "-r", "@"],
+    );
+    commit_on_remote("third", &initial_commit);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [forced] tracked
+    Abandoned 2 commits that are no longer reachable.
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    origin (conflicted):
+      - tqvrwtoq hidden c19e1d73 (empty) second
+      + yqosqzyt 9dd68117 (empty) local
+      + lumkwwvw dabdbd1c (empty) third
+      @origin (behind by 1 commits): lumkwwvw dabdbd1c (empty) third
+    "###);
+}
+
 // Helper functions to test obtaining multiple bookmarks at once and changed
 // bookmarks
 fn create_colocated_repo_and_bookmarks_from_trunk1(
@@ -539,9 +1344,9 @@ fn test_git_fetch_all() {
     let (stdout, stderr) = test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    bookmark: a1@origin     [updated] tracked
-    bookmark: a2@origin     [updated] tracked
-    bookmark: b@origin      [updated] tracked
+    bookmark: a1@origin     [forced] tracked
+    bookmark: a2@origin     [forced] tracked
+    bookmark: b@origin      [forced] tracked
     bookmark: trunk2@origin [new] tracked
     Abandoned 2 commits that are no longer reachable.
     "###);
@@ -723,8 +1528,8 @@ fn test_git_fetch_some_of_many_bookmarks() {
     );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    bookmark: a1@origin [updated] tracked
-    bookmark: b@origin  [updated] tracked
+    bookmark: a1@origin [forced] tracked
+    bookmark: b@origin  [forced] tracked
     Abandoned 1 commits that are no longer reachable.
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &target_jj_repo_path), @r###"
@@ -762,7 +1567,7 @@ fn test_git_fetch_some_of_many_bookmarks() {
     );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    bookmark: a2@origin [updated] tracked
+    bookmark: a2@origin [forced] tracked
     Abandoned 1 commits that are no longer reachable.
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &target_jj_repo_path), @r###"
@@ -1380,3 +2185,148 @@ fn test_git_fetch_remote_only_bookmark() {
     feature2@origin: mzyxwzks 9f01a0e0 message
     "###);
 }
+
+#[test]
+fn test_git_fetch_set_head() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let origin_git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    origin_git_repo.set_head("refs/heads/origin").unwrap();
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+
+    // The default branch isn't recorded by default
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    assert_eq!(git::get_remote_head(&git_repo, "origin"), None);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--set-head"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Set the default branch of remote origin to origin
+    Nothing changed.
+    Setting the revset alias "trunk()" to "origin@origin"
+    "###);
+    assert_eq!(
+        git::get_remote_head(&git_repo, "origin").as_deref(),
+        Some("origin")
+    );
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r=trunk()", "-T=bookmarks"]),
+        @"origin@origin");
+
+    // Nothing is reported if the default branch didn't change
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--set-head"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // The default branch moved on the remote
+    let initial_commit = origin_git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    origin_git_repo
+        .branch("main", &initial_commit, false)
+        .unwrap();
+    origin_git_repo.set_head("refs/heads/main").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--set-head"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: main@origin [new] untracked
+    Set the default branch of remote origin to main
+    Setting the revset alias "trunk()" to "main@origin"
+    "###);
+    assert_eq!(
+        git::get_remote_head(&git_repo, "origin").as_deref(),
+        Some("main")
+    );
+
+    // A customized trunk() alias isn't overridden
+    origin_git_repo.set_head("refs/heads/origin").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--set-head",
+            r#"--config-toml=revset-aliases."trunk()" = "root()""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Set the default branch of remote origin to origin
+    Nothing changed.
+    "###);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "config",
+            "set",
+            "--repo",
+            r#"revset-aliases."trunk()""#,
+            "root()",
+        ],
+    );
+    origin_git_repo.set_head("refs/heads/main").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--set-head"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Set the default branch of remote origin to main
+    Nothing changed.
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r=trunk()", "-T=commit_id"]),
+        @"0000000000000000000000000000000000000000");
+
+    // The default branch of each remote is recorded
+    add_git_remote(&test_env, &repo_path, "upstream");
+    let upstream_git_repo = git2::Repository::open(test_env.env_root().join("upstream")).unwrap();
+    upstream_git_repo.set_head("refs/heads/upstream").unwrap();
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--set-head", "--all-remotes"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: upstream@upstream [new] untracked
+    Set the default branch of remote upstream to upstream
+    "###);
+    assert_eq!(
+        git::get_remote_head(&git_repo, "upstream").as_deref(),
+        Some("upstream")
+    );
+
+    // An alias pointing to one of several fetched remotes follows its default
+    // branch
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "config",
+            "set",
+            "--repo",
+            r#"revset-aliases."trunk()""#,
+            "main@origin",
+        ],
+    );
+    origin_git_repo.set_head("refs/heads/origin").unwrap();
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--set-head", "--all-remotes"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Set the default branch of remote origin to origin
+    Nothing changed.
+    Setting the revset alias "trunk()" to "origin@origin"
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r=trunk()", "-T=bookmarks"]),
+        @"main@origin origin@origin");
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["git", "fetch", "--set-head", "--remote=../origin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot use --set-head with ../origin because it isn't a configured remote
+    "###);
+}
@@ -65,15 +65,164 @@ fn test_git_export_conflicting_git_refs() {
     test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main/sub"]);
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::with_settings!({filters => vec![("Failed to set: .*", "Failed to set: ...")]}, {
-        insta::assert_snapshot!(stderr, @r###"
-        Warning: Failed to export some bookmarks:
-          main/sub: Failed to set: ...
-        Hint: Git doesn't allow a branch name that looks like a parent directory of
-        another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks that failed to
-        export or their "parent" bookmarks.
-        "###);
-    });
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Failed to export some bookmarks:
+      main/sub: Name conflicts with another ref in Git
+    Hint: Git doesn't allow a branch name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks that failed to
+    export or their "parent" bookmarks.
+    "###);
+}
+
+#[test]
+fn test_git_export_dry_run() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=a"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "create", "moved", "deleted", "modified-in-git"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=b"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "set", "moved", "modified-in-git"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "delete", "deleted"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "new", "moved/sub"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "root", "-r=root()"]);
+    // Move the bookmark to another commit in Git behind jj's back
+    let parent = git_repo
+        .find_branch("moved", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    let signature =
+        git2::Signature::new("Someone", "someone@example.com", &git2::Time::new(0, 0)).unwrap();
+    git_repo
+        .commit(
+            Some("refs/heads/modified-in-git"),
+            &signature,
+            &signature,
+            "git commit",
+            &parent.tree().unwrap(),
+            &[&parent],
+        )
+        .unwrap();
+    let refs_before = get_git_repo_refs(&git_repo);
+
+    let op_log_before = test_env.jj_cmd_success(&repo_path, &["op", "log"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export", "--dry-run"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Changes to export to Git:
+      Delete refs/heads/deleted from d8d5f980a897
+      Move refs/heads/moved from d8d5f980a897 to 0a60b6e5aa35
+      Create refs/heads/new at 0a60b6e5aa35
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Failed to export some bookmarks:
+      modified-in-git: Modified ref had been modified in Git
+      moved/sub: Name conflicts with another ref in Git
+      root: Ref cannot point to the root commit in Git
+    Hint: Git doesn't allow a branch name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks that failed to
+    export or their "parent" bookmarks.
+    Dry-run requested, not exporting.
+    "###);
+
+    let dry_run_stderr = stderr;
+
+    // Nothing was exported
+    assert_eq!(get_git_repo_refs(&git_repo), refs_before);
+    let op_log_after = test_env.jj_cmd_success(&repo_path, &["op", "log"]);
+    assert_eq!(op_log_before, op_log_after);
+
+    // The real export fails to export the same refs for the same reasons
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    insta::assert_snapshot!(stdout, @"");
+    assert_eq!(
+        dry_run_stderr,
+        format!("{stderr}Dry-run requested, not exporting.\n")
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Failed to export some bookmarks:
+      modified-in-git: Modified ref had been modified in Git
+      moved/sub: Name conflicts with another ref in Git
+      root: Ref cannot point to the root commit in Git
+    Hint: Git doesn't allow a branch name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks that failed to
+    export or their "parent" bookmarks.
+    "###);
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r###"
+    [
+        (
+            "refs/heads/modified-in-git",
+            CommitId(
+                "9ece2e4019a231e035c877b3ec558a6b01e38d0f",
+            ),
+        ),
+        (
+            "refs/heads/moved",
+            CommitId(
+                "0a60b6e5aa351abc3446d7ac41a322b9e1429ed2",
+            ),
+        ),
+        (
+            "refs/heads/new",
+            CommitId(
+                "0a60b6e5aa351abc3446d7ac41a322b9e1429ed2",
+            ),
+        ),
+    ]
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export", "--dry-run"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    Warning: Failed to export some bookmarks:
+      modified-in-git: Modified ref had been modified in Git
+      moved/sub: Name conflicts with another ref in Git
+      root: Ref cannot point to the root commit in Git
+    Hint: Git doesn't allow a branch name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks that failed to
+    export or their "parent" bookmarks.
+    Dry-run requested, not exporting.
+    "###);
+}
+
+#[test]
+fn test_git_export_dry_run_colocated() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo", "."]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a"]);
+    let refs_before = get_git_repo_refs(&git_repo);
+
+    // Modify the working copy, which would move bookmark "a" and export it if
+    // a snapshot were taken
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    let op_log_before = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--ignore-working-copy", "--no-graph"],
+    );
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export", "--dry-run"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    Dry-run requested, not exporting.
+    "###);
+
+    // Nothing was exported
+    assert_eq!(get_git_repo_refs(&git_repo), refs_before);
+    let op_log_after = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--ignore-working-copy", "--no-graph"],
+    );
+    assert_eq!(op_log_before, op_log_after);
 }
 
 #[test]
@@ -129,6 +278,61 @@ fn test_git_export_undo() {
     "###);
 }
 
+#[test]
+fn test_git_export_prune() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+
+    // Move "a" in Git, and add a branch jj doesn't know about
+    let b_oid = git_repo.refname_to_id("refs/heads/b").unwrap();
+    git_repo.reference("refs/heads/a", b_oid, true, "").unwrap();
+    git_repo
+        .reference("refs/heads/unmanaged", b_oid, false, "")
+        .unwrap();
+
+    // Plain export can't delete the modified branch
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "delete", "a", "b"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Failed to export some bookmarks:
+      a: Deleted ref had been modified in Git
+    "###);
+
+    // --prune doesn't delete it either, since it carries work from Git, and
+    // leaves the unmanaged branch alone
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export", "--prune"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    Warning: Failed to export some bookmarks:
+      a: Deleted ref had been modified in Git
+    "###);
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r###"
+    [
+        (
+            "refs/heads/a",
+            CommitId(
+                "4db490c88528133d579540b6900b8098f0c17701",
+            ),
+        ),
+        (
+            "refs/heads/unmanaged",
+            CommitId(
+                "4db490c88528133d579540b6900b8098f0c17701",
+            ),
+        ),
+    ]
+    "###);
+}
+
 #[test]
 fn test_git_import_undo() {
     let test_env = TestEnvironment::default();
@@ -177,6 +381,299 @@ fn test_git_import_undo() {
     "###);
 }
 
+#[test]
+fn test_git_import_dry_run() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=a"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=b"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+
+    // Delete bookmark "a" and create bookmark "b" in git repo
+    let commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "-Tcommit_id", "--no-graph", "-r@"]);
+    let commit = git_repo
+        .find_commit(git2::Oid::from_str(&commit_id).unwrap())
+        .unwrap();
+    git_repo.branch("b", &commit, true).unwrap();
+    git_repo
+        .find_branch("a", git2::BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    let op_log_before = test_env.jj_cmd_success(&repo_path, &["op", "log"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "import", "--dry-run"]);
+    insta::assert_snapshot!(stdout, @r###"
+
+    Changed commits:
+    ○  Change rlvkpnrzqnoo
+       - rlvkpnrz hidden d8d5f980 (empty) a
+
+    Changed local bookmarks:
+    a:
+    + (absent)
+    - rlvkpnrz hidden d8d5f980 (empty) a
+    b:
+    + zsuskuln 1868260a b | (empty) b
+    - (absent)
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Dry-run requested, not importing.
+    "###);
+
+    // Nothing was imported
+    let op_log_after = test_env.jj_cmd_success(&repo_path, &["op", "log"]);
+    assert_eq!(op_log_before, op_log_after);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    a: rlvkpnrz d8d5f980 (empty) a
+      @git: rlvkpnrz d8d5f980 (empty) a
+    "###);
+}
+
+#[test]
+fn test_git_import_dry_run_colocated() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo", "."]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    // Create bookmark "a" in git repo behind jj's back
+    let commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "-Tcommit_id", "--no-graph", "-r@-"]);
+    let commit = git_repo
+        .find_commit(git2::Oid::from_str(&commit_id).unwrap())
+        .unwrap();
+    git_repo.branch("a", &commit, true).unwrap();
+
+    let op_log_before = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--ignore-working-copy", "--no-graph"],
+    );
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "import", "--dry-run"]);
+    insta::assert_snapshot!(stdout, @r###"
+
+    Changed local bookmarks:
+    a:
+    + qpvuntsm d8d5f980 a | (empty) a
+    - (absent)
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Dry-run requested, not importing.
+    "###);
+
+    // The dry run didn't import the bookmark as part of a snapshot
+    let op_log_after = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--ignore-working-copy", "--no-graph"],
+    );
+    assert_eq!(op_log_before, op_log_after);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["bookmark", "list", "--all-remotes", "--ignore-working-copy"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_git_import_glob() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+
+    // Create bookmarks in git repo, only some of which are in scope
+    let commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "-Tcommit_id", "--no-graph", "-r@"]);
+    let commit = git_repo
+        .find_commit(git2::Oid::from_str(&commit_id).unwrap())
+        .unwrap();
+    git_repo.branch("team-foo/a", &commit, true).unwrap();
+    git_repo.branch("team-foo/b", &commit, true).unwrap();
+    git_repo.branch("other", &commit, true).unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "import", "--glob", "refs/heads/team-foo/*"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: team-foo/a [new] tracked
+    bookmark: team-foo/b [new] tracked
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    team-foo/a: qpvuntsm 230dd059 (empty) (no description set)
+      @git: qpvuntsm 230dd059 (empty) (no description set)
+    team-foo/b: qpvuntsm 230dd059 (empty) (no description set)
+      @git: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // Deletions within the scope are imported, refs outside it are left alone
+    git_repo
+        .find_branch("team-foo/a", git2::BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "import", "--glob", "refs/heads/team-foo/*"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: team-foo/a [deleted] tracked
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    team-foo/b: qpvuntsm 230dd059 (empty) (no description set)
+      @git: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // Multiple patterns can be specified
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "import",
+            "--glob",
+            "refs/heads/oth*",
+            "--glob",
+            "refs/tags/*",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: other [new] tracked
+    "###);
+
+    // Invalid pattern
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["git", "import", "--glob", "[a"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value '[a' for '--glob <GLOB>': Pattern syntax error near position 0: invalid range pattern
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_git_import_globs_config_colocated() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo", "."]);
+    test_env.add_config(r#"git.import-globs = ["refs/heads/team-foo/*"]"#);
+
+    // The automatic import only picks up the refs in scope
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "-Tcommit_id", "--no-graph", "-r@-"]);
+    let commit = git_repo
+        .find_commit(git2::Oid::from_str(&commit_id).unwrap())
+        .unwrap();
+    git_repo.branch("team-foo/a", &commit, true).unwrap();
+    git_repo.branch("other", &commit, true).unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bookmark", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @r###"
+    team-foo/a: qpvuntsm d8d5f980 (empty) a
+      @git: qpvuntsm d8d5f980 (empty) a
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Done importing changes from the underlying Git repo.
+    "###);
+
+    // `--glob` overrides the config
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["git", "import", "--glob", "refs/heads/*"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: other [new] tracked
+    "###);
+
+    // Invalid pattern in config
+    test_env.add_config(r#"git.import-globs = ["[a"]"#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bookmark", "list"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid `git.import-globs` pattern
+    Caused by: Pattern syntax error near position 0: invalid range pattern
+    For help, see https://martinvonz.github.io/jj/latest/config/.
+    "###);
+}
+
+#[test]
+fn test_git_import_abandon_policy() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+
+    // Create bookmark "a" in git repo, and an unreferenced commit in jj
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=a"]);
+    let commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "-Tcommit_id", "--no-graph", "-r@"]);
+    let commit = git_repo
+        .find_commit(git2::Oid::from_str(&commit_id).unwrap())
+        .unwrap();
+    git_repo.branch("a", &commit, true).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=unreferenced"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
+    let base_operation_id = test_env.current_operation_id(&repo_path);
+
+    // Delete bookmark "a" in git repo
+    git_repo
+        .find_branch("a", git2::BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "import", "--abandon=never"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: a [deleted] tracked
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @
+    │ ○  unreferenced
+    ├─╯
+    │ ○  a
+    ├─╯
+    ◆
+    "###);
+
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &base_operation_id]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: a [deleted] tracked
+    Abandoned 1 commits that are no longer reachable.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @
+    │ ○  unreferenced
+    ├─╯
+    ◆
+    "###);
+
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &base_operation_id]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "import", "--abandon=all"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: a [deleted] tracked
+    Abandoned 1 commits that are no longer reachable.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @
+    │ ○  unreferenced
+    ├─╯
+    ◆
+    "###);
+}
+
 #[test]
 fn test_git_import_move_export_with_default_undo() {
     let test_env = TestEnvironment::default();
@@ -257,6 +754,10 @@ fn test_git_import_move_export_with_default_undo() {
     "###);
 }
 
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    test_env.jj_cmd_success(repo_path, &["log", "-T", "description.first_line()"])
+}
+
 fn get_bookmark_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     test_env.jj_cmd_success(repo_path, &["bookmark", "list", "--all-remotes"])
 }
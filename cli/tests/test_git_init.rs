@@ -241,6 +241,196 @@ fn test_git_init_external_import_trunk(bare: bool) {
     }
 }
 
+#[test]
+fn test_git_init_external_at_ref() {
+    let test_env = TestEnvironment::default();
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = init_git_repo(&git_repo_path, false);
+
+    // Add a "dev" branch on top of "my-bookmark", leaving HEAD where it is
+    let parent = git_repo
+        .find_reference("refs/heads/my-bookmark")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let git_signature = parent.author();
+    git_repo
+        .commit(
+            Some("refs/heads/dev"),
+            &git_signature,
+            &git_signature,
+            "Dev commit",
+            &parent.tree().unwrap(),
+            &[&parent],
+        )
+        .unwrap();
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "init",
+            "repo",
+            "--git-repo",
+            git_repo_path.to_str().unwrap(),
+            "--at-ref",
+            "refs/heads/dev",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Done importing changes from the underlying Git repo.
+    Working copy now at: sqpuoqvx 03e9d2d2 (empty) (no description set)
+    Parent commit      : wmxyrsqr 5738d188 dev | (empty) Dev commit
+    Added 1 files, modified 0 files, removed 0 files
+    Initialized repo in "repo"
+    "#);
+
+    let workspace_root = test_env.env_root().join("repo");
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_root), @r"
+    @  03e9d2d2cffd
+    ○  5738d188d42d dev Dev commit
+    ○  8d698d4a8ee1 my-bookmark My commit message
+    ◆  000000000000
+    ");
+
+    // A short ref name is resolved too
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "init",
+            "repo2",
+            "--git-repo",
+            git_repo_path.to_str().unwrap(),
+            "--at-ref",
+            "my-bookmark",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Done importing changes from the underlying Git repo.
+    Working copy now at: pmmvwywv 8e1824e2 (empty) (no description set)
+    Parent commit      : mwrttmos 8d698d4a my-bookmark | My commit message
+    Added 1 files, modified 0 files, removed 0 files
+    Initialized repo in "repo2"
+    "#);
+
+    // Missing ref is an error
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &[
+            "git",
+            "init",
+            "repo3",
+            "--git-repo",
+            git_repo_path.to_str().unwrap(),
+            "--at-ref",
+            "refs/heads/missing",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Git ref refs/heads/missing not found
+    Caused by: no reference found for shorthand 'refs/heads/missing'; class=Reference (4); code=NotFound (-3)
+    ");
+
+    // --at-ref requires --git-repo
+    let stderr = test_env.jj_cmd_cli_error(
+        test_env.env_root(),
+        &["git", "init", "repo4", "--at-ref", "dev"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      --git-repo <GIT_REPO>
+
+    Usage: jj git init --git-repo <GIT_REPO> --at-ref <AT_REF> <DESTINATION>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn test_git_init_external_import_refs() {
+    let test_env = TestEnvironment::default();
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = init_git_repo(&git_repo_path, false);
+
+    // Add "dev" and "feature" branches on top of "my-bookmark"
+    let parent = git_repo
+        .find_reference("refs/heads/my-bookmark")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let git_signature = parent.author();
+    for name in ["dev", "feature"] {
+        git_repo
+            .commit(
+                Some(&format!("refs/heads/{name}")),
+                &git_signature,
+                &git_signature,
+                &format!("{name} commit"),
+                &parent.tree().unwrap(),
+                &[&parent],
+            )
+            .unwrap();
+    }
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "init",
+            "repo",
+            "--git-repo",
+            git_repo_path.to_str().unwrap(),
+            "--import-refs",
+            "refs/heads/dev",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Importing 1 Git refs, skipped 2 refs not matching --import-refs
+    Done importing changes from the underlying Git repo.
+    Working copy now at: sqpuoqvx f6950fc1 (empty) (no description set)
+    Parent commit      : mwrttmos 8d698d4a My commit message
+    Added 1 files, modified 0 files, removed 0 files
+    Initialized repo in "repo"
+    "###);
+    let workspace_root = test_env.env_root().join("repo");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r###"
+    dev: wvqmqpwu a34819be (empty) dev commit
+      @git: wvqmqpwu a34819be (empty) dev commit
+    "###);
+
+    // Globs and multiple patterns are supported
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "init",
+            "repo2",
+            "--git-repo",
+            git_repo_path.to_str().unwrap(),
+            "--import-refs",
+            "refs/heads/d*",
+            "--import-refs",
+            "refs/heads/feature",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Importing 2 Git refs, skipped 1 refs not matching --import-refs
+    Done importing changes from the underlying Git repo.
+    Working copy now at: pmmvwywv 8e1824e2 (empty) (no description set)
+    Parent commit      : mwrttmos 8d698d4a My commit message
+    Added 1 files, modified 0 files, removed 0 files
+    Initialized repo in "repo2"
+    "###);
+    let workspace_root = test_env.env_root().join("repo2");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r###"
+    dev: wvqmqpwu a34819be (empty) dev commit
+      @git: wvqmqpwu a34819be (empty) dev commit
+    feature: mkwvwsso 521ae329 (empty) feature commit
+      @git: mkwvwsso 521ae329 (empty) feature commit
+    "###);
+}
+
 #[test]
 fn test_git_init_external_ignore_working_copy() {
     let test_env = TestEnvironment::default();
@@ -400,6 +590,68 @@ fn test_git_init_colocated_via_git_repo_path_gitlink() {
     "#);
 }
 
+#[test]
+fn test_git_init_colocated_via_git_repo_path_worktree() {
+    let test_env = TestEnvironment::default();
+    // <workspace_root>/.git -> <git_repo_path>/.git/worktrees/<name>
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = init_git_repo(&git_repo_path, false);
+    let head_commit = git_repo.head().unwrap().peel_to_commit().unwrap();
+    let git_signature = head_commit.author();
+    let feature_oid = git_repo
+        .commit(
+            None,
+            &git_signature,
+            &git_signature,
+            "Feature commit",
+            &head_commit.tree().unwrap(),
+            &[&head_commit],
+        )
+        .unwrap();
+    let feature_commit = git_repo.find_commit(feature_oid).unwrap();
+    let feature_branch = git_repo.branch("feature", &feature_commit, false).unwrap();
+    git_repo
+        .worktree(
+            "repo",
+            &workspace_root,
+            Some(git2::WorktreeAddOptions::new().reference(Some(feature_branch.get()))),
+        )
+        .unwrap();
+    assert!(workspace_root.join(".git").is_file());
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Done importing changes from the underlying Git repo.
+    Initialized repo in "."
+    "###);
+    insta::assert_snapshot!(read_git_target(&workspace_root), @"../../../.git");
+
+    // Check that the worktree's HEAD, not the main checkout's, got checked out
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_root), @r#"
+    @  81e476d0a7e8
+    ○  18dac96fb564 feature git_head() Feature commit
+    ○  8d698d4a8ee1 my-bookmark My commit message
+    ◆  000000000000
+    "#);
+
+    // Check that the worktree's HEAD moves, and the main checkout's HEAD stays
+    test_env.jj_cmd_ok(&workspace_root, &["new"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_root), @r#"
+    @  aa66d8a30c79
+    ○  81e476d0a7e8 git_head()
+    ○  18dac96fb564 feature Feature commit
+    ○  8d698d4a8ee1 my-bookmark My commit message
+    ◆  000000000000
+    "#);
+    let git_repo = git2::Repository::open(&git_repo_path).unwrap();
+    assert_eq!(
+        git_repo.head().unwrap().name(),
+        Some("refs/heads/my-bookmark")
+    );
+}
+
 #[cfg(unix)]
 #[test]
 fn test_git_init_colocated_via_git_repo_path_symlink_directory() {
@@ -646,6 +898,54 @@ fn test_git_init_colocated_dirty_working_copy() {
     "###);
 }
 
+#[test]
+fn test_git_init_colocated_via_flag_dirty_working_copy() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = init_git_repo(&workspace_root, false);
+
+    std::fs::write(workspace_root.join("some-file"), "staged content").unwrap();
+    std::fs::write(workspace_root.join("new-staged-file"), "new content").unwrap();
+    let mut index = git_repo.index().unwrap();
+    index.add_path(Path::new("some-file")).unwrap();
+    index.add_path(Path::new("new-staged-file")).unwrap();
+    index.write().unwrap();
+    std::fs::write(workspace_root.join("some-file"), "unstaged content").unwrap();
+    std::fs::write(workspace_root.join("unstaged-file"), "new content").unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--colocate"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Done importing changes from the underlying Git repo.
+    Initialized repo in "."
+    "###);
+
+    // The Git HEAD should be the parent, and both the staged and the unstaged
+    // changes should be in the working-copy commit.
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["log", "-s", "--ignore-working-copy"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  sqpuoqvx test.user@example.com 2001-02-03 08:05:07 ab80beff
+    │  (no description set)
+    │  A new-staged-file
+    │  M some-file
+    │  A unstaged-file
+    ○  mwrttmos git.user@example.com 1970-01-01 11:02:03 my-bookmark git_head() 8d698d4a
+    │  My commit message
+    │  A some-file
+    ◆  zzzzzzzz root() 00000000
+    "###);
+
+    // Files on disk shouldn't have been touched.
+    assert_eq!(
+        std::fs::read_to_string(workspace_root.join("some-file")).unwrap(),
+        "unstaged content"
+    );
+    assert_eq!(
+        std::fs::read_to_string(workspace_root.join("unstaged-file")).unwrap(),
+        "new content"
+    );
+}
+
 #[test]
 fn test_git_init_colocated_ignore_working_copy() {
     let test_env = TestEnvironment::default();
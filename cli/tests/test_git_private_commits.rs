@@ -198,6 +198,7 @@ fn test_git_private_commits_already_on_the_remote_do_not_block_push() {
     Changes to push to origin:
       Move forward bookmark main from 7eb97bf230ad to fbb352762352
       Add bookmark bookmark1 to 7eb97bf230ad
+    Started tracking bookmark bookmark1@origin
     Warning: The working-copy commit in workspace 'default' became immutable, so a new commit has been created on top of it.
     Working copy now at: kpqxywon a7b08364 (empty) (no description set)
     Parent commit      : yostqsxw fbb35276 main | (empty) public 3
@@ -227,6 +228,7 @@ fn test_git_private_commits_already_on_the_remote_do_not_block_push() {
     insta::assert_snapshot!(stderr, @r#"
     Changes to push to origin:
       Add bookmark bookmark2 to ee5b808b0b95
+    Started tracking bookmark bookmark2@origin
     "#);
 }
 
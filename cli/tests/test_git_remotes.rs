@@ -90,6 +90,90 @@ fn test_git_remote_add() {
     "###);
 }
 
+#[test]
+fn test_git_remote_add_track() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let upstream_repo = git2::Repository::init(test_env.env_root().join("upstream")).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let tree_id = upstream_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = upstream_repo.find_tree(tree_id).unwrap();
+    for branch in ["main", "release/1", "release/2"] {
+        upstream_repo
+            .commit(
+                Some(&format!("refs/heads/{branch}")),
+                &signature,
+                &signature,
+                branch,
+                &tree,
+                &[],
+            )
+            .unwrap();
+    }
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "upstream",
+            "../upstream",
+            "--track=release/*",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let git_config = fs::read_to_string(repo_path.join(".jj/repo/store/git/config")).unwrap();
+    assert!(
+        git_config.contains("fetch = +refs/heads/release/*:refs/remotes/upstream/release/*"),
+        "{git_config}"
+    );
+
+    // Only the matching branches are fetched
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=upstream"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: release/1@upstream [new] untracked
+    bookmark: release/2@upstream [new] untracked
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(stdout, @r###"
+    release/1@upstream: lyrmnoqr f82e8db1 (empty) release/1
+    release/2@upstream: oqzttttz b1965ca6 (empty) release/2
+    "###);
+
+    // Branches can still be fetched explicitly
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "fetch", "--remote=upstream", "--branch=main"],
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(stdout, @r###"
+    main@upstream: oslyvumk 1630028e (empty) main
+    release/1@upstream: lyrmnoqr f82e8db1 (empty) release/1
+    release/2@upstream: oqzttttz b1965ca6 (empty) release/2
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "bad",
+            "../upstream",
+            "--track=foo:bar",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid branch pattern provided. Patterns may not contain the characters `:`, `^`, `?`, `[`, `]`
+    "###);
+}
+
 #[test]
 fn test_git_remote_set_url() {
     let test_env = TestEnvironment::default();
@@ -457,6 +457,45 @@ fn test_color_config() {
     "###);
 }
 
+#[test]
+fn test_color_never_and_no_color() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "foo\n").unwrap();
+
+    // NO_COLOR disables color unless overridden by arguments
+    test_env.add_env_var("NO_COLOR", "1");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "get", "ui.color"]);
+    insta::assert_snapshot!(stdout, @r###"
+    never
+    "###);
+    for args in [&["log"][..], &["diff"], &["show"]] {
+        let stdout = test_env.jj_cmd_success(&repo_path, args);
+        assert!(!stdout.contains('\x1b'), "{args:?}: {stdout:?}");
+    }
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--color=always", "log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    [1m[38;5;2m@[0m  [38;5;4mb54fb07f66de3dd9b1d012924dabd0362639707d[39m
+    [1m[38;5;14m◆[0m  [38;5;4m0000000000000000000000000000000000000000[39m
+    "###);
+
+    // An empty NO_COLOR is ignored
+    test_env.add_env_var("NO_COLOR", "");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["config", "get", "ui.color"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: configuration property "ui.color" not found
+    For help, see https://martinvonz.github.io/jj/latest/config/.
+    "###);
+
+    // --color=never overrides the config in every command that renders output
+    test_env.add_config(r#"ui.color="always""#);
+    for args in [&["log"][..], &["diff"], &["show"]] {
+        let stdout = test_env.jj_cmd_success(&repo_path, &[&["--color=never"], args].concat());
+        assert!(!stdout.contains('\x1b'), "{args:?}: {stdout:?}");
+    }
+}
+
 #[test]
 fn test_color_ui_messages() {
     let test_env = TestEnvironment::default();
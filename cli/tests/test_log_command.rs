@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use indoc::indoc;
+use itertools::Itertools as _;
+
 use crate::common::get_stdout_string;
 use crate::common::TestEnvironment;
 
@@ -51,6 +54,7 @@ fn test_log_with_no_template() {
     - builtin_op_log_compact
     - builtin_op_log_node
     - builtin_op_log_node_ascii
+    - builtin_signature_status
     - commit_summary_separator
     - description_placeholder
     - email_placeholder
@@ -58,6 +62,79 @@ fn test_log_with_no_template() {
     "#);
 }
 
+#[test]
+fn test_log_template_file() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a new commit"]);
+
+    let template_path = test_env.env_root().join("template.txt");
+    std::fs::write(
+        &template_path,
+        indoc! {r#"
+            separate(" ",
+              change_id.short(),
+              description.first_line(),
+            ) ++ "\n"
+        "#},
+    )
+    .unwrap();
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--template-file", "../template.txt", "--no-graph"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    qpvuntsmwlqt a new commit
+    zzzzzzzzzzzz
+    "###);
+
+    // Parse errors point to the location in the file
+    std::fs::write(
+        &template_path,
+        indoc! {r#"
+            separate(" ",
+              change_id.short(),
+              description.bad_method(),
+            )
+        "#},
+    )
+    .unwrap();
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["log", "--template-file", "../template.txt"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse template: Method "bad_method" doesn't exist for type "String"
+    Caused by:  --> 3:15
+      |
+    3 |   description.bad_method(),
+      |               ^--------^
+      |
+      = Method "bad_method" doesn't exist for type "String"
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--template-file", "missing.txt"]);
+    // The error message from the OS differs across platforms
+    insta::assert_snapshot!(stderr.lines().next().unwrap().replace('\\', "/"), @"Error: Failed to read template file $TEST_ENV/repo/missing.txt");
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &[
+            "log",
+            "--template-file",
+            "../template.txt",
+            "-T",
+            "description",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--template-file <PATH>' cannot be used with '--template <TEMPLATE>'
+
+    Usage: jj log --template-file <PATH> [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
 #[test]
 fn test_log_with_or_without_diff() {
     let test_env = TestEnvironment::default();
@@ -857,6 +934,23 @@ fn test_log_filtered_by_path() {
     A file2
     "###);
 
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-s",
+            "file*",
+            ":!file1",
+            "--no-graph",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    second
+    A file2
+    "###);
+
     // Fileset/pattern syntax can be disabled.
     let stderr = test_env.jj_cmd_failure(
         test_env.env_root(),
@@ -1005,6 +1099,166 @@ fn test_log_limit() {
     "###);
 }
 
+#[test]
+fn test_log_no_graph_large_revset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "--colocate", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Create a long chain of commits in the underlying Git repo
+    let git_repo = git2::Repository::open(&repo_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let tree_id = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = git_repo.find_tree(tree_id).unwrap();
+    let mut parents = vec![];
+    for i in 0..1000 {
+        let parent_refs = parents.iter().collect_vec();
+        let oid = git_repo
+            .commit(
+                None,
+                &signature,
+                &signature,
+                &i.to_string(),
+                &tree,
+                &parent_refs,
+            )
+            .unwrap();
+        parents = vec![git_repo.find_commit(oid).unwrap()];
+    }
+    git_repo.branch("main", &parents[0], false).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+
+    // The template doesn't end with a newline
+    let template = r#"description.first_line() ++ " ""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "::main ~ root()", "-T", template],
+    );
+    let descriptions = stdout.split_whitespace().collect_vec();
+    assert_eq!(descriptions.len(), 1000);
+    assert_eq!(descriptions.first(), Some(&"999"));
+    assert_eq!(descriptions.last(), Some(&"0"));
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "::main",
+            "-T",
+            template,
+            "--limit=3",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"999 998 997 ");
+
+    // Reversing the output requires evaluating the whole revset first
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "::main ~ root()",
+            "-T",
+            template,
+            "--reversed",
+            "--limit=3",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"0 1 2 ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_log_no_graph_flushes_each_revision() {
+    use std::io::Read as _;
+    use std::io::Write as _;
+    use std::os::unix::fs::PermissionsExt as _;
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=second"]);
+
+    // The diff tool returns immediately for the first revision, but blocks
+    // for the next one until the test writes to the FIFO.
+    let fifo_path = test_env.env_root().join("fifo");
+    let marker_path = test_env.env_root().join("marker");
+    let status = std::process::Command::new("mkfifo")
+        .arg(&fifo_path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let tool_path = test_env.env_root().join("blocking-diff-tool");
+    std::fs::write(
+        &tool_path,
+        format!(
+            "#!/bin/sh\nif [ -e '{marker}' ]; then read _ < '{fifo}'; fi\n: > '{marker}'\n",
+            marker = marker_path.display(),
+            fifo = fifo_path.display(),
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&tool_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    // The template doesn't end with a newline
+    let mut child = test_env
+        .jj_std_cmd(
+            &repo_path,
+            &[
+                "log",
+                "--no-graph",
+                "-r=~root()",
+                "-T",
+                r#"description.first_line() ++ " ""#,
+                "--tool",
+                tool_path.to_str().unwrap(),
+            ],
+        )
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdout = child.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    let reader = std::thread::spawn(move || {
+        let mut buf = [0; 1024];
+        loop {
+            let n = stdout.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            sender.send(buf[..n].to_vec()).unwrap();
+        }
+    });
+
+    // The first revision is printed while the second is being rendered
+    let mut output = vec![];
+    while output != b"second " {
+        let Ok(chunk) = receiver.recv_timeout(Duration::from_secs(10)) else {
+            break;
+        };
+        output.extend(chunk);
+    }
+    std::fs::File::create(&fifo_path)
+        .unwrap()
+        .write_all(b"\n")
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output), "second ");
+
+    assert!(child.wait().unwrap().success());
+    reader.join().unwrap();
+    output.extend(receiver.iter().flatten());
+    assert_eq!(String::from_utf8_lossy(&output), "second first ");
+}
+
 #[test]
 fn test_log_warn_path_might_be_revset() {
     let test_env = TestEnvironment::default();
@@ -1652,3 +1906,257 @@ fn test_log_with_custom_symbols() {
     ^
     "###);
 }
+
+#[test]
+fn test_log_elide() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=1"]);
+    for i in 2..=6 {
+        test_env.jj_cmd_ok(&repo_path, &["new", &format!("-m={i}")]);
+    }
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "create", "-r=description(4)", "b4"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=side", "description(1)"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=7", "description(6)"]);
+    for i in 8..=10 {
+        test_env.jj_cmd_ok(&repo_path, &["new", &format!("-m={i}")]);
+    }
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "-m=merge", "description(10)", "description(side)"],
+    );
+
+    let template = r#"description.first_line() ++ " " ++ bookmarks"#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r=::", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    @    merge
+    ├─╮
+    │ ○  side
+    ○ │  10
+    ○ │  9
+    ○ │  8
+    ○ │  7
+    ○ │  6
+    ○ │  5
+    ○ │  4 b4
+    ○ │  3
+    ○ │  2
+    ├─╯
+    ○  1
+    ◆
+    "###);
+
+    // Runs shorter than the threshold aren't collapsed. The forked commit and
+    // the bookmarked commit aren't part of runs.
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r=::", "-T", template, "--elide"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @    merge
+    ├─╮
+    │ ○  side
+    ~ │  (elided 6 revisions)
+    ○ │  4 b4
+    ○ │  3
+    ○ │  2
+    ├─╯
+    ○  1
+    ◆
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r=::",
+            "-T",
+            template,
+            "--elide",
+            "--config-toml=ui.log-elide-threshold=2",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @    merge
+    ├─╮
+    │ ○  side
+    ~ │  (elided 6 revisions)
+    ○ │  4 b4
+    ~ │  (elided 2 revisions)
+    ├─╯
+    ○  1
+    ◆
+    "###);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_log_show_signature() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let test_env = TestEnvironment::default();
+    // The fake GPG program signs everything, and reports the signature as bad
+    // or from an unknown key depending on the signed description.
+    let gpg_path = test_env.env_root().join("fake-gpg");
+    std::fs::write(
+        &gpg_path,
+        indoc! {r#"
+            #!/bin/sh
+            data=$(cat)
+            case "$*" in
+            *--verify*)
+                case "$data" in
+                *bad*) echo "[GNUPG:] BADSIG 123ABC Test User <test.user@example.com>" ;;
+                *unknown*) echo "[GNUPG:] NO_PUBKEY 456DEF" ;;
+                *) echo "[GNUPG:] GOODSIG 123ABC Test User <test.user@example.com>" ;;
+                esac
+                ;;
+            *)
+                printf -- '-----BEGIN PGP SIGNATURE-----\nfake\n-----END PGP SIGNATURE-----\n'
+                ;;
+            esac
+        "#},
+    )
+    .unwrap();
+    std::fs::set_permissions(&gpg_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=unsigned"]);
+    let signing_config = format!(
+        r#"
+        signing.backend = "gpg"
+        signing.sign-all = true
+        signing.backends.gpg.program = "{}"
+        "#,
+        gpg_path.to_str().unwrap()
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "-m=good", "--config-toml", &signing_config],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "-m=bad", "--config-toml", &signing_config],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "-m=unknown", "--config-toml", &signing_config],
+    );
+    test_env.add_config(&signing_config);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r=::@ ~ root()",
+            "-T=description",
+            "--show-signature",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @  unknown
+    │  Signature: unknown
+    ○  bad
+    │  Signature: bad by Test User <test.user@example.com> (key 123ABC)
+    ○  good
+    │  Signature: good by Test User <test.user@example.com> (key 123ABC)
+    ○  unsigned
+    │
+    ~
+    "###);
+
+    // Signatures aren't shown by default
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r=@", "-T=description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  unknown
+    │
+    ~
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["show", "@-", "--show-signature", "--no-patch"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Commit ID: ddb2657a1ffdb45b8fb70224599f6e7aceb3ca29
+    Change ID: zsuskulnrvyrovkzqrwmxqlsskqntxvp
+    Author: Test User <test.user@example.com> (2001-02-03 08:05:10)
+    Committer: Test User <test.user@example.com> (2001-02-03 08:05:10)
+
+        bad
+
+    Signature: bad by Test User <test.user@example.com> (key 123ABC)
+    "###);
+
+    // The signature can be accessed in templates
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r=::@ ~ root()",
+            "-T",
+            r#"description.first_line() ++ ": " ++ if(signature, separate(" ", signature.status(), signature.key()), "unsigned") ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    unknown: unknown
+    bad: bad 123ABC
+    good: good 123ABC
+    unsigned: unsigned
+    "###);
+}
+
+#[test]
+fn test_log_author_committer() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m=first", "--author=Alice <alice@example.com>"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "-m=second",
+            "--author=Alice <alice@example.com>",
+            "--config-toml=user.name='Bob'\nuser.email='bob@example.com'",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=third"]);
+
+    let template = r#"description.first_line() ++ ": " ++ author.email() ++ " / " ++ committer.email() ++ "\n""#;
+    let render = |args: &[&str]| {
+        let mut args = [&["log", "--no-graph", "-T", template], args].concat();
+        args.push("-r=~root()");
+        test_env.jj_cmd_success(&repo_path, &args)
+    };
+    insta::assert_snapshot!(render(&["--author=alice"]), @r###"
+    second: alice@example.com / bob@example.com
+    first: alice@example.com / test.user@example.com
+    "###);
+    insta::assert_snapshot!(render(&["--committer=bob"]), @r###"
+    second: alice@example.com / bob@example.com
+    "###);
+    insta::assert_snapshot!(render(&["--author=Alice", "--committer=test.user"]), @r###"
+    first: alice@example.com / test.user@example.com
+    "###);
+    insta::assert_snapshot!(render(&["--author=exact:Alice", "--author=bob"]), @r###"
+    second: alice@example.com / bob@example.com
+    first: alice@example.com / test.user@example.com
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["log", "--author=foo:bar"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'foo:bar' for '--author <PATTERN>': Invalid string pattern kind "foo:"
+
+    For more information, try '--help'.
+    Hint: Try prefixing with one of `exact:`, `glob:`, `regex:`, or `substring:`
+    "###);
+}
@@ -63,6 +63,57 @@ fn test_new() {
     "###);
 }
 
+#[test]
+fn test_new_revisions_at_op() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "lost"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "other"]);
+    let op_id = test_env.current_operation_id(&repo_path);
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "description(lost)"]);
+    insta::assert_snapshot!(get_short_log_output(&test_env, &repo_path), @r###"
+    @  other
+    ◆  root
+    "###);
+
+    // The abandoned commit can be resolved at the earlier operation, and
+    // becomes visible again
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "description(lost)",
+            "--revisions-at-op",
+            &op_id,
+            "-m",
+            "recovered",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: yqosqzyt 090cd1c8 (empty) recovered
+    Parent commit      : qpvuntsm bb351bdf (empty) lost
+    "###);
+    insta::assert_snapshot!(get_short_log_output(&test_env, &repo_path), @r###"
+    @  recovered
+    ○  lost
+    │ ○  other
+    ├─╯
+    ◆  root
+    "###);
+
+    // The revision must exist at the operation
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["new", "description(recovered)", "--revisions-at-op", &op_id],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset "description(recovered)" didn't resolve to any revisions at operation 304055639c56
+    "###);
+}
+
 #[test]
 fn test_new_merge() {
     let test_env = TestEnvironment::default();
@@ -222,6 +273,33 @@ fn test_new_insert_after() {
     ◆  root
     "###);
 
+    // --insert takes the revisions from the arguments
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["new", "-m", "I", "--insert", "-r", "E"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 1 descendant commits
+    Working copy now at: xtnwkqum e3d2aeed (empty) I
+    Parent commit      : znkkpsqq 41a89ffc E | (empty) E
+    "###);
+    insta::assert_snapshot!(get_short_log_output(&test_env, &repo_path), @r###"
+    ○    F
+    ├─╮
+    │ @  I
+    │ ○  E
+    │ │ ○  C
+    ├───╯
+    ○ │    G
+    ├───╮
+    │ │ ○  H
+    │ │ ○  D
+    │ ├─╯
+    ○ │  B
+    ○ │  A
+    ├─╯
+    ◆  root
+    "###);
+
     // --after cannot be used with revisions
     let stderr = test_env.jj_cmd_cli_error(&repo_path, &["new", "--after", "B", "D"]);
     insta::assert_snapshot!(stderr, @r###"
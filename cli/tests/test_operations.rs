@@ -160,6 +160,7 @@ fn test_op_log_with_no_template() {
     - builtin_op_log_compact
     - builtin_op_log_node
     - builtin_op_log_node_ascii
+    - builtin_signature_status
     - commit_summary_separator
     - description_placeholder
     - email_placeholder
@@ -179,6 +180,67 @@ fn test_op_log_limit() {
     "###);
 }
 
+#[test]
+fn test_op_log_since_until() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    // Each command, including the `op log` commands below, runs one second
+    // after the previous one. The operations above end at 04:05:08-04:05:10.
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "second"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "third"]);
+
+    let template = r#"description ++ " " ++ time.end().format("%H:%M:%S") ++ "\n""#;
+    // Runs at 04:05:11
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "-T", template, "--since=3 seconds ago"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @  describe commit 53aecb7ce88042051caf0cdcad69078542757d79 04:05:10
+    ○  describe commit fa15625b4a986997697639dfc2844138900c79f2 04:05:09
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22 04:05:08
+    │
+    ~
+    "###);
+
+    // Runs at 04:05:12
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "-T", template, "--until=3 seconds ago"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22 04:05:08
+    ○  add workspace 'default' 04:05:07
+    ○   00:00:00
+    "###);
+
+    // Runs at 04:05:13
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "-T",
+            template,
+            "--no-graph",
+            "--since=4 seconds ago",
+            "--until=2 seconds ago",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    describe commit 53aecb7ce88042051caf0cdcad69078542757d79 04:05:10
+    describe commit fa15625b4a986997697639dfc2844138900c79f2 04:05:09
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "--since=whenever"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid date "whenever"
+    Caused by: expected week day or month name
+    "###);
+}
+
 #[test]
 fn test_op_log_no_graph() {
     let test_env = TestEnvironment::default();
@@ -205,6 +267,98 @@ fn test_op_log_no_graph() {
     "#);
 }
 
+#[test]
+fn test_op_log_reversed() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "second"]);
+
+    let template = r#"description.first_line() ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "--reversed", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+
+    add workspace 'default'
+    describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    describe commit fa15625b4a986997697639dfc2844138900c79f2
+    "###);
+
+    // The limit is applied after reordering
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "--reverse",
+            "-n2",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+
+    add workspace 'default'
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["op", "log", "--reversed"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the following required arguments were not provided:
+      --no-graph
+
+    Usage: jj operation log --no-graph --reversed
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_op_log_concurrent_workspaces() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "--colocate", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let secondary_path = test_env.env_root().join("secondary");
+    test_env.jj_cmd_ok(&repo_path, &["workspace", "add", "../secondary"]);
+    let base_op_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-n1", "-Tid.short()"],
+    );
+
+    // Run an operation in each workspace, both based on the same operation.
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "--at-op", &base_op_id, "-m", "default"],
+    );
+    test_env.jj_cmd_ok(
+        &secondary_path,
+        &["describe", "--at-op", &base_op_id, "-m", "secondary"],
+    );
+
+    let template = r#"id.short() ++ " " ++ description.first_line() ++ "\n""#;
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["op", "log", "--ignore-working-copy", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @    afdd91685ae9 reconcile divergent operations
+    ├─╮
+    ○ │  2ba8e776a8ee describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    │ ○  d3ac6e55db1a describe commit 57d63245a308c0e039bacfc11b7abf55642794fa
+    ├─╯
+    ○  6bd0572f5d64 create initial working-copy commit in workspace secondary
+    ○  080cfcdbbea0 add workspace 'secondary'
+    ○  eac759b9ab75 add workspace 'default'
+    ○  000000000000
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Concurrent modification detected, resolving automatically.
+    "###);
+}
+
 #[test]
 fn test_op_log_no_graph_null_terminated() {
     let test_env = TestEnvironment::default();
@@ -726,6 +880,7 @@ fn test_op_recover_from_bad_gc() {
     let stderr =
         test_env.jj_cmd_internal_error(&repo_path, &["--at-op", head_op_id, "debug", "reindex"]);
     insta::assert_snapshot!(strip_last_line(&stderr), @r#"
+    Reindexing commits at operation f999e12a5d8b...
     Internal error: Failed to index commits at operation e7377e6a642bae88039615ee159117d49688719e9d5ece9de8b0b42d7be7076904d2fa8381391f8289a0c3527405de81e8dd6504655311c69175c3681786dd3c
     Caused by:
     1: Object ddf84fc5e0dd314092b3dfb13e09e37fa7d04ef9 of type commit not found
@@ -781,6 +936,124 @@ fn test_op_recover_from_bad_gc() {
     "###);
 }
 
+#[test]
+fn test_op_restore_dry_run() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=first"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "foo"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-T=id.short()", "-n=1"],
+    );
+    let base_op_id = stdout.trim().to_owned();
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=second"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "set", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "bar"]);
+
+    let op_log_before = test_env.jj_cmd_success(&repo_path, &["op", "log"]);
+    let op_store_path = repo_path.join(".jj").join("repo").join("op_store");
+    let count_op_store_files = || {
+        ["operations", "views"]
+            .iter()
+            .map(|dir| std::fs::read_dir(op_store_path.join(dir)).unwrap().count())
+            .sum::<usize>()
+    };
+    let op_store_files_before = count_op_store_files();
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["op", "restore", "--dry-run", &base_op_id]);
+    insta::assert_snapshot!(stdout, @r###"
+
+    Changed commits:
+    ○  Change mzvwutvlkqwt
+       - mzvwutvl hidden 4ce02568 (empty) second
+
+    Changed local bookmarks:
+    bar:
+    + (absent)
+    - mzvwutvl hidden 4ce02568 (empty) second
+    foo:
+    + qpvuntsm fa15625b foo | (empty) first
+    - mzvwutvl hidden 4ce02568 (empty) second
+
+    Working copy would move to: qpvuntsm fa15625b foo | (empty) first
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Would restore to operation: 683b00fd4a4c (2001-02-03 08:05:09) create bookmark foo pointing to commit fa15625b4a986997697639dfc2844138900c79f2
+    Dry-run requested, not restoring.
+    "###);
+
+    // Nothing was written to the op store, and the repo is unchanged
+    assert_eq!(count_op_store_files(), op_store_files_before);
+    let op_log_after = test_env.jj_cmd_success(&repo_path, &["op", "log"]);
+    assert_eq!(op_log_before, op_log_after);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T=description ++ bookmarks"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  second
+    │  bar foo
+    ○  first
+    ◆
+    "###);
+}
+
+#[test]
+fn test_op_restore_preserve_working_copy() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "first\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=first"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-T=id.short()", "-n=1"],
+    );
+    let base_op_id = stdout.trim().to_owned();
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=second"]);
+    std::fs::write(repo_path.join("file1"), "modified\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "uncommitted\n").unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["op", "restore", "--preserve-working-copy", &base_op_id],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Preserved the working-copy files in a new commit on top of the restored working copy
+    Restored to operation: 8731866bb8b7 (2001-02-03 08:05:08) describe commit c177063527de08760c680d6d9b01e8e8cd10ea3e
+    Working copy now at: mzvwutvl 1a710a93 (no description set)
+    Parent commit      : qpvuntsm 767f8b32 first
+    "###);
+    // The files on disk are unchanged
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("file1")).unwrap(),
+        "modified\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("file2")).unwrap(),
+        "uncommitted\n"
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T=description", "-s"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @
+    │  M file1
+    │  A file2
+    ○  first
+    │  A file1
+    ◆
+    "###);
+
+    // Without the flag, the files are reset to the restored state
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &base_op_id]);
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("file1")).unwrap(),
+        "first\n"
+    );
+    assert!(!repo_path.join("file2").exists());
+}
+
 #[test]
 fn test_op_summary_diff_template() {
     let test_env = TestEnvironment::default();
@@ -1116,6 +1389,8 @@ fn test_op_diff() {
     "###);
     insta::assert_snapshot!(&stderr, @r###"
     Created 1 bookmarks pointing to qzxslznx d487febd bookmark-2 bookmark-2@origin | Commit 5
+    Warning: Remote bookmark bookmark-2@origin exists but is not tracked.
+    Hint: Run `jj bookmark track bookmark-2@origin` to track it.
     "###);
     let stdout = test_env.jj_cmd_success(&repo_path, &["op", "diff"]);
     insta::assert_snapshot!(&stdout, @r#"
@@ -1803,6 +2078,8 @@ fn test_op_show() {
     "###);
     insta::assert_snapshot!(&stderr, @r###"
     Created 1 bookmarks pointing to qzxslznx d487febd bookmark-2 bookmark-2@origin | Commit 5
+    Warning: Remote bookmark bookmark-2@origin exists but is not tracked.
+    Hint: Run `jj bookmark track bookmark-2@origin` to track it.
     "###);
     let stdout = test_env.jj_cmd_success(&repo_path, &["op", "show"]);
     insta::assert_snapshot!(&stdout, @r#"
@@ -2492,6 +2492,219 @@ fn test_rebase_skip_emptied() {
     "###);
 }
 
+#[test]
+fn test_rebase_skip_divergent() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "main", &[]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "x"]);
+    std::fs::write(repo_path.join("x"), "x\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "main"]);
+    // Rewrite "x" onto main, and concurrently rewrite it in place without
+    // changing its contents
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-r", "description(x)", "-d", "main"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "set", "main", "-r", "description(x)"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "--at-op=@--",
+            "-r",
+            "description(x)",
+            "-m",
+            "x v2",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["describe", "description(v2)", "-m", "x"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(x) ~ ::main", "-m", "y"]);
+    std::fs::write(repo_path.join("y"), "y\n").unwrap();
+
+    // Test the setup
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]), @r###"
+    @  y
+    ○  x
+    │ ○  x
+    │ ○  main
+    ├─╯
+    ◆
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "-s",
+            "description(x) ~ ::main",
+            "-d",
+            "main",
+            "--skip-divergent",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 1 commits
+    Abandoned 1 commits that already exist in the destination:
+      zsuskuln?? c0447c4c x
+    Working copy now at: znkkpsqq c5f69460 y
+    Parent commit      : zsuskuln 8e02bc1c main | x
+    Added 1 files, modified 0 files, removed 0 files
+    "###);
+
+    // The copy of "x" was abandoned, and "y" is on top of the one in main
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]), @r###"
+    @  y
+    ○  x
+    ○  main
+    ◆
+    "###);
+}
+
+#[test]
+fn test_rebase_skip_divergent_with_new_description() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "main", &[]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "x"]);
+    std::fs::write(repo_path.join("x"), "x\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "main"]);
+    // Rewrite "x" onto main, and concurrently describe it in place
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-r", "description(x)", "-d", "main"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "set", "main", "-r", "description(x)"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "--at-op=@--",
+            "-r",
+            "description(x)",
+            "-m",
+            "x v2",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(v2)", "-m", "y"]);
+    std::fs::write(repo_path.join("y"), "y\n").unwrap();
+
+    // The local copy has a new description, so it's kept even though its
+    // contents are the same
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "-s",
+            "description(v2)",
+            "-d",
+            "main",
+            "--skip-divergent",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 commits
+    Warning: Kept 1 commits that differ from their change in the destination:
+      zsuskuln?? 88faa50a x v2
+    Working copy now at: yostqsxw b149418a y
+    Parent commit      : zsuskuln?? eb030251 (empty) x v2
+    Added 1 files, modified 0 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]), @r###"
+    @  y
+    ○  x v2
+    ○  x
+    ○  main
+    ◆
+    "###);
+}
+
+#[test]
+fn test_rebase_skip_divergent_with_local_edits() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "main", &[]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "x"]);
+    std::fs::write(repo_path.join("x"), "x\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "main"]);
+    let setup_opid = test_env.current_operation_id(&repo_path);
+    // Edit "x" in place, and concurrently rewrite it onto main
+    test_env.jj_cmd_ok(&repo_path, &["edit", "description(x)"]);
+    std::fs::write(repo_path.join("z"), "z\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "x v2"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "y"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "--at-op",
+            &setup_opid,
+            "-r",
+            "description(x)",
+            "-d",
+            "main",
+        ],
+    );
+
+    // Test the setup
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ○  x
+    ○  main
+    │ @  y
+    │ ○  x v2
+    ├─╯
+    ◆
+    "###);
+
+    // The local copy has edits that aren't in main, so it's kept
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "-s",
+            "description(v2)",
+            "-d",
+            "main+",
+            "--skip-divergent",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 commits
+    Warning: Kept 1 commits that differ from their change in the destination:
+      zsuskuln?? b88e5d70 x v2
+    Working copy now at: yostqsxw 4bb38267 (empty) y
+    Parent commit      : zsuskuln?? 83031e54 x v2
+    Added 1 files, modified 0 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]), @r###"
+    @  y
+    ○  x v2
+    ○  x
+    ○  main
+    ◆
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r", "@-", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A z
+    "###);
+}
+
 #[test]
 fn test_rebase_skip_if_on_destination() {
     let test_env = TestEnvironment::default();
@@ -981,3 +981,219 @@ fn test_multiple_conflicts() {
     Error: No conflicts found at this revision
     "###);
 }
+
+#[test]
+fn test_resolve_ours_theirs() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[
+            ("file1", "base1\n"),
+            ("file2", "base2\n"),
+            ("file3", "base3\n"),
+        ],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "a1\n"), ("file2", "a2\n"), ("file3", "a3\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "b1\n"), ("file2", "b2\n")],
+    );
+    std::fs::remove_file(repo_path.join("file3")).unwrap();
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]),
+    @r###"
+    file1    2-sided conflict
+    file2    2-sided conflict
+    file3    2-sided conflict including 1 deletion
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--ours", "file1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file1
+    Working copy now at: vruxwmqv c0c10827 conflict | (conflict) conflict
+    Parent commit      : zsuskuln 5c141e9b a | a
+    Parent commit      : royxmykx b7090996 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file2    2-sided conflict
+    file3    2-sided conflict including 1 deletion
+    New conflicts appeared in these commits:
+      vruxwmqv c0c10827 conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqv
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file1")).unwrap(), @r###"
+    a1
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--theirs", "file2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file2
+    Working copy now at: vruxwmqv 07bb2f9e conflict | (conflict) conflict
+    Parent commit      : zsuskuln 5c141e9b a | a
+    Parent commit      : royxmykx b7090996 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file3    2-sided conflict including 1 deletion
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file2")).unwrap(), @r###"
+    b2
+    "###);
+
+    // The path is no longer conflicted
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--theirs", "file2"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No conflicts found at the given path(s)
+    "###);
+
+    // The file was deleted on the second side
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve", "--theirs", "file3"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot resolve file3 with --theirs because the file doesn't exist on that side
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--ours", "file3"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file3
+    Working copy now at: vruxwmqv 89653e95 conflict | conflict
+    Parent commit      : zsuskuln 5c141e9b a | a
+    Parent commit      : royxmykx b7090996 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    Existing conflicts were resolved or abandoned from these commits:
+      vruxwmqv hidden 07bb2f9e (conflict) conflict
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file3")).unwrap(), @r###"
+    a3
+    "###);
+}
+
+#[test]
+fn test_resolve_ours_too_many_sides() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "c", &["base"], &[("file", "c\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b", "c"], &[]);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve", "--ours"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot resolve file with --ours because the conflict has 3 sides
+    "###);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolve_git_mergetool() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "--colocate", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let mut git_config = git2::Repository::open(&repo_path)
+        .unwrap()
+        .config()
+        .unwrap();
+    git_config
+        .set_str(
+            "mergetool.concat-sides.cmd",
+            r#"{ cat "$BASE"; cat "$LOCAL"; cat "$REMOTE"; } > "$MERGED""#,
+        )
+        .unwrap();
+    git_config
+        .set_str("mergetool.shadowed.cmd", "false")
+        .unwrap();
+    test_env.add_config(
+        r#"
+        [merge-tools.shadowed]
+        program = "my-merge"
+        merge-args = ["--base", "$base", "$left", "$right", "-o", "$output"]
+        "#,
+    );
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    // Tools configured in jj take precedence over tools from the Git config
+    let stdout = test_env.jj_cmd_success(&repo_path, &["resolve", "--tool-list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    :builtin: (built-in merge editor)
+    concat-sides: sh -c 'BASE="$1" LOCAL="$2" REMOTE="$3" MERGED="$4"; { cat "$BASE"; cat "$LOCAL"; cat "$REMOTE"; } > "$MERGED"' sh $base $left $right $output
+    kdiff3: kdiff3 $base $left $right -o $output --auto
+    meld: meld $left $base $right -o $output --auto-merge
+    shadowed: my-merge --base $base $left $right -o $output
+    vimdiff: vim -f -d $output -M $left $base $right -c 'wincmd J' -c 'set modifiable' -c 'set write'
+    vscode: code --wait --merge $left $right $base $output
+    vscodium: codium --wait --merge $left $right $base $output
+    "###);
+
+    // The Git merge tool is run with Git's variables
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool=concat-sides"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Working copy now at: vruxwmqv 3ffd2595 conflict | conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r###"
+    base
+    a
+    b
+    "###);
+
+    // Git's merge.tool is used if no merge editor is configured in jj
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    git_config.set_str("merge.tool", "concat-sides").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Working copy now at: vruxwmqv c8dad6aa conflict | conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r###"
+    base
+    a
+    b
+    "###);
+
+    // Git merge tools aren't used outside of colocated workspaces
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "not-colocated"]);
+    let other_repo_path = test_env.env_root().join("not-colocated");
+    git2::Repository::open(other_repo_path.join(".jj/repo/store/git"))
+        .unwrap()
+        .config()
+        .unwrap()
+        .set_str("mergetool.concat-sides.cmd", "false")
+        .unwrap();
+    let stdout = test_env.jj_cmd_success(&other_repo_path, &["resolve", "--tool-list"]);
+    assert!(!stdout.contains("concat-sides"));
+}
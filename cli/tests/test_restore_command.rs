@@ -261,6 +261,62 @@ fn test_restore_conflicted_merge() {
     "###);
 }
 
+#[test]
+fn test_restore_from_parent() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+
+    // Restores only the given file from the parent
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["restore", "--from-parent", "file1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created rlvkpnrz 5b2ba145 (no description set)
+    Working copy now at: rlvkpnrz 5b2ba145 (no description set)
+    Parent commit      : qpvuntsm fc687cb8 (no description set)
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file2
+    "###);
+
+    // Cannot be combined with --from
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["restore", "--from-parent", "--from=@-"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--from-parent' cannot be used with '--from <FROM>'
+
+    Usage: jj restore --from-parent [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_restore_pathspec_exclude() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1.txt"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2.txt"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file3.rs"), "c\n").unwrap();
+
+    // Restore all text files except file2.txt
+    test_env.jj_cmd_ok(&repo_path, &["restore", "*.txt", ":!file2.txt"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A file2.txt
+    A file3.rs
+    "###);
+}
+
 #[test]
 fn test_restore_restore_descendants() {
     let test_env = TestEnvironment::default();
@@ -342,6 +398,117 @@ fn test_restore_restore_descendants() {
     "#);
 }
 
+#[test]
+fn test_restore_apply() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file1", "a\n"), ("file2", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "fix",
+        &["base"],
+        &[("file1", "a\nb\n"), ("file2", "b\n"), ("file3", "c\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "other",
+        &["base"],
+        &[("file2", "c\n")],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "other"]);
+
+    // The changes in "fix" are applied to the working copy, and conflicts are
+    // left in files the changes don't apply cleanly to
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["restore", "--apply=fix"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created vruxwmqv eaf8e4e6 (conflict) (no description set)
+    Applied changes to 3 files (1 with conflicts)
+    Working copy now at: vruxwmqv eaf8e4e6 (conflict) (no description set)
+    Parent commit      : royxmykx d2710392 other | other
+    Added 1 files, modified 2 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file2    2-sided conflict
+    New conflicts appeared in these commits:
+      vruxwmqv eaf8e4e6 (conflict) (no description set)
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqv
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 7898192261..422c2b7ab3 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,2 @@
+     a
+    +b
+    diff --git a/file2 b/file2
+    index f2ad6c76f0..0000000000 100644
+    --- a/file2
+    +++ b/file2
+    @@ -1,1 +1,7 @@
+    -c
+    +<<<<<<< Conflict 1 of 1
+    +%%%%%%% Changes from base to side #1
+    +-a
+    ++c
+    ++++++++ Contents of side #2
+    +b
+    +>>>>>>> Conflict 1 of 1 ends
+    diff --git a/file3 b/file3
+    new file mode 100644
+    index 0000000000..f2ad6c76f0
+    --- /dev/null
+    +++ b/file3
+    @@ -1,0 +1,1 @@
+    +c
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @
+    ○  other
+    │ ○  fix
+    ├─╯
+    ○  base
+    ◆
+    "###);
+
+    // Only the given paths are changed
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["restore", "--apply=fix", "file1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created vruxwmqv 1019deb2 (no description set)
+    Applied changes to 1 files
+    Working copy now at: vruxwmqv 1019deb2 (no description set)
+    Parent commit      : royxmykx d2710392 other | other
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+
+    // Can't restore from another revision at the same time
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["restore", "--apply=fix", "--from=base"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--apply <REVISION>' cannot be used with '--from <FROM>'
+
+    Usage: jj restore --apply <REVISION> [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
 fn create_commit(
     test_env: &TestEnvironment,
     repo_path: &Path,
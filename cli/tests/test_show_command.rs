@@ -121,6 +121,19 @@ fn test_show_basic() {
     FF {file1 => file3}
     "###);
 
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--name-only"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Commit ID: e34f04317a81edc6ba41fef239c0d0180f10656f
+    Change ID: rlvkpnrzqnoowoytxnquwvuryrwnrmlp
+    Author: Test User <test.user@example.com> (2001-02-03 08:05:09)
+    Committer: Test User <test.user@example.com> (2001-02-03 08:05:09)
+
+        (no description set)
+
+    file2
+    file3
+    "###);
+
     let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--git"]);
     insta::assert_snapshot!(stdout, @r###"
     Commit ID: e34f04317a81edc6ba41fef239c0d0180f10656f
@@ -229,6 +242,37 @@ fn test_show_basic() {
     "###);
 }
 
+#[test]
+fn test_show_no_patch() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "add file1"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--no-patch"]);
+    insta::assert_snapshot!(stdout, @r"
+    Commit ID: ee807f7deadc1688384de66d352e49a8e875039a
+    Change ID: qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
+    Author: Test User <test.user@example.com> (2001-02-03 08:05:08)
+    Committer: Test User <test.user@example.com> (2001-02-03 08:05:08)
+
+        add file1
+    ");
+
+    // Diff format flags are ignored
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--no-patch", "--git"]);
+    insta::assert_snapshot!(stdout, @r"
+    Commit ID: ee807f7deadc1688384de66d352e49a8e875039a
+    Change ID: qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
+    Author: Test User <test.user@example.com> (2001-02-03 08:05:08)
+    Committer: Test User <test.user@example.com> (2001-02-03 08:05:08)
+
+        add file1
+    ");
+}
+
 #[test]
 fn test_show_with_template() {
     let test_env = TestEnvironment::default();
@@ -265,6 +309,7 @@ fn test_show_with_no_template() {
     - builtin_op_log_compact
     - builtin_op_log_node
     - builtin_op_log_node_ascii
+    - builtin_signature_status
     - commit_summary_separator
     - description_placeholder
     - email_placeholder
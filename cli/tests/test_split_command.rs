@@ -317,6 +317,71 @@ fn test_split_with_merge_child() {
     "###);
 }
 
+#[test]
+fn test_split_revision_with_descendants_colocated() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "--colocate", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["describe", "-m=a"]);
+    std::fs::write(workspace_path.join("file1"), "foo\n").unwrap();
+    std::fs::write(workspace_path.join("file2"), "bar\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["bookmark", "create", "a"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new", "-m=b"]);
+    std::fs::write(workspace_path.join("file3"), "baz\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_path), @r###"
+    @  mzvwutvlkqwt true
+    ○  zsuskulnrvyr false b
+    ○  qpvuntsmwlqt false a
+    │   a
+    ◆  zzzzzzzzzzzz true
+    "###);
+
+    // Split a commit other than the working-copy commit. The descendants are
+    // rebased onto the second part, which the bookmark moves to.
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(
+        edit_script,
+        ["write\nAdd file1", "next invocation\n", "write\nAdd file2"].join("\0"),
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["split", "-r=a", "file1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 descendant commits
+    First part: qpvuntsm 883bfed1 Add file1
+    Second part: yqosqzyt c0aae3e5 a* | Add file2
+    Working copy now at: mzvwutvl e637d47f (empty) (no description set)
+    Parent commit      : zsuskuln d1133f68 b
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_path), @r###"
+    @  mzvwutvlkqwt true
+    ○  zsuskulnrvyr false b
+    ○  yqosqzytrlsw false Add file2
+    │   a
+    ○  qpvuntsmwlqt false Add file1
+    ◆  zzzzzzzzzzzz true
+    "###);
+
+    // The bookmark and HEAD are exported to Git as a linear chain
+    let git_repo = git2::Repository::open(&workspace_path).unwrap();
+    let summary = |rev: &str| {
+        let commit = git_repo
+            .revparse_single(rev)
+            .unwrap()
+            .peel_to_commit()
+            .unwrap();
+        format!("{} {}", commit.summary().unwrap(), commit.parent_count())
+    };
+    insta::assert_snapshot!(
+        ["HEAD", "HEAD~", "a", "a~"].map(summary).join("\n"), @r###"
+    b 1
+    Add file2 1
+    Add file2 1
+    Add file1 0
+    "###);
+}
+
 #[test]
 // Split a commit with no descendants into siblings. Also tests that the default
 // description is set correctly on the first commit.
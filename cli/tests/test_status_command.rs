@@ -368,3 +368,142 @@ fn test_status_simplify_conflict_sides() {
     Then run `jj squash` to move the resolution into the conflicted commit.
     "#);
 }
+
+#[test]
+fn test_status_json() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir/modified"), "base").unwrap();
+    std::fs::write(repo_path.join("removed"), "base").unwrap();
+    std::fs::write(repo_path.join("rename-source"), "rename").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=base"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("dir/modified"), "new").unwrap();
+    std::fs::write(repo_path.join("added"), "added").unwrap();
+    std::fs::remove_file(repo_path.join("removed")).unwrap();
+    std::fs::remove_file(repo_path.join("rename-source")).unwrap();
+    std::fs::write(repo_path.join("rename-target"), "rename").unwrap();
+
+    // Paths are relative to the workspace root regardless of the cwd
+    let stdout = test_env.jj_cmd_success(&repo_path.join("dir"), &["status", "--format=json"]);
+    insta::assert_snapshot!(stdout, @r###"
+    {
+      "conflicted_bookmarks": [],
+      "conflicted_remote_bookmarks": [],
+      "working_copy": {
+        "change_id": "kkmpptxzrspxrzommnulwmwkkqwworpl",
+        "changes": [
+          {
+            "path": "added",
+            "status": "added"
+          },
+          {
+            "path": "dir/modified",
+            "status": "modified"
+          },
+          {
+            "path": "removed",
+            "status": "removed"
+          },
+          {
+            "path": "rename-target",
+            "source": "rename-source",
+            "status": "renamed"
+          }
+        ],
+        "commit_id": "8479e71b180c570dfab5e9a624b9cd452b96eddf",
+        "conflicts": [],
+        "description": "",
+        "parents": [
+          {
+            "change_id": "qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu",
+            "commit_id": "fa2661343e86408bc40d5cdcb73cfce3b05d0cc9",
+            "description": "base\n"
+          }
+        ]
+      }
+    }
+    "###);
+
+    // Paths filter the change list
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status", "--format=json", "added"]);
+    insta::assert_snapshot!(stdout, @r###"
+    {
+      "conflicted_bookmarks": [],
+      "conflicted_remote_bookmarks": [],
+      "working_copy": {
+        "change_id": "kkmpptxzrspxrzommnulwmwkkqwworpl",
+        "changes": [
+          {
+            "path": "added",
+            "status": "added"
+          }
+        ],
+        "commit_id": "8479e71b180c570dfab5e9a624b9cd452b96eddf",
+        "conflicts": [],
+        "description": "",
+        "parents": [
+          {
+            "change_id": "qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu",
+            "commit_id": "fa2661343e86408bc40d5cdcb73cfce3b05d0cc9",
+            "description": "base\n"
+          }
+        ]
+      }
+    }
+    "###);
+}
+
+#[test]
+fn test_status_json_filtered_conflicts() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "base").unwrap();
+    std::fs::write(repo_path.join("file2"), "base").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=base"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=left"]);
+    std::fs::write(repo_path.join("file1"), "left").unwrap();
+    std::fs::write(repo_path.join("file2"), "left").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m=right"]);
+    std::fs::write(repo_path.join("file1"), "right").unwrap();
+    std::fs::write(repo_path.join("file2"), "right").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(left)", "description(right)"],
+    );
+
+    // Paths filter the conflict list
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status", "--format=json", "file2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    {
+      "conflicted_bookmarks": [],
+      "conflicted_remote_bookmarks": [],
+      "working_copy": {
+        "change_id": "mzvwutvlkqwtuzoztpszkqxkqmqyqyxo",
+        "changes": [],
+        "commit_id": "0cd0ada94f7f8f40ab888d47525600457f1a01ee",
+        "conflicts": [
+          "file2"
+        ],
+        "description": "",
+        "parents": [
+          {
+            "change_id": "kkmpptxzrspxrzommnulwmwkkqwworpl",
+            "commit_id": "aebba6ca9cf9012915f250ac7ef6b9e8eac84af1",
+            "description": "left\n"
+          },
+          {
+            "change_id": "zsuskulnrvyrovkzqrwmxqlsskqntxvp",
+            "commit_id": "37260f93dc534d39dbd1664c88bc59cfd188d23d",
+            "description": "right\n"
+          }
+        ]
+      }
+    }
+    "###);
+}
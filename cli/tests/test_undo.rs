@@ -46,6 +46,53 @@ fn test_undo_rewrite_with_child() {
     "###);
 }
 
+#[test]
+fn test_undo_repeat() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "third"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "-r@-", "b"]);
+    assert!(git_repo.head().is_ok());
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["undo", "-n", "3"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Undid 3 operations:
+      3fbcc8400a22 (2001-02-03 08:05:11) create bookmark b pointing to commit 9ed53a4a1becd028f9a2fe0d5275973acea7e8da
+      db4204b72c30 (2001-02-03 08:05:10) new empty commit
+      1598cae4e4d8 (2001-02-03 08:05:09) new empty commit
+    Working copy now at: qpvuntsm fa15625b (empty) first
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  first
+    ◆
+    "###);
+    // The Git HEAD is restored too
+    assert!(git_repo.head().is_err());
+
+    // Undoing the undo brings the operations back
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  third
+    ○  second
+    ○  first
+    ◆
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["undo", "--repeat=100"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot undo repo initialization
+    "###);
+}
+
 #[test]
 fn test_git_push_undo() {
     let test_env = TestEnvironment::default();
@@ -58,7 +105,10 @@ fn test_git_push_undo() {
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "AA"]);
-    test_env.jj_cmd_ok(&repo_path, &["git", "push"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "push", "--include-untracked-bookmarks"],
+    );
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "BB"]);
     //   Refs at this point look as follows (-- means no ref)
@@ -131,7 +181,10 @@ fn test_git_push_undo_with_import() {
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "AA"]);
-    test_env.jj_cmd_ok(&repo_path, &["git", "push"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "push", "--include-untracked-bookmarks"],
+    );
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "BB"]);
     //   Refs at this point look as follows (-- means no ref)
@@ -211,7 +264,10 @@ fn test_git_push_undo_colocated() {
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "AA"]);
-    test_env.jj_cmd_ok(&repo_path, &["git", "push"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "push", "--include-untracked-bookmarks"],
+    );
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "BB"]);
     //   Refs at this point look as follows (-- means no ref)
@@ -291,7 +347,10 @@ fn test_git_push_undo_repo_only() {
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "AA"]);
-    test_env.jj_cmd_ok(&repo_path, &["git", "push"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "push", "--include-untracked-bookmarks"],
+    );
     insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
     main: qpvuntsm 2080bdb8 (empty) AA
       @origin: qpvuntsm 2080bdb8 (empty) AA
@@ -335,7 +394,10 @@ fn test_bookmark_track_untrack_undo() {
 
     test_env.jj_cmd_ok(&repo_path, &["describe", "-mcommit"]);
     test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "feature1", "feature2"]);
-    test_env.jj_cmd_ok(&repo_path, &["git", "push"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "push", "--include-untracked-bookmarks"],
+    );
     test_env.jj_cmd_ok(&repo_path, &["bookmark", "delete", "feature2"]);
     insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
     feature1: qpvuntsm 8da1cfc8 (empty) commit
@@ -49,7 +49,9 @@ fn test_gc_args() {
     let repo_path = test_env.env_root().join("repo");
 
     let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["util", "gc"]);
-    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Reclaimed 0.0B
+    "###);
 
     let stderr = test_env.jj_cmd_failure(&repo_path, &["util", "gc", "--at-op=@-"]);
     insta::assert_snapshot!(stderr, @r###"
@@ -58,7 +60,17 @@ fn test_gc_args() {
 
     let stderr = test_env.jj_cmd_failure(&repo_path, &["util", "gc", "--expire=foobar"]);
     insta::assert_snapshot!(stderr, @r###"
-    Error: --expire only accepts 'now'
+    Error: Invalid --expire time "foobar"
+    Caused by: expected week day or month name
+    "###);
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["util", "gc", "--expire=2.weeks.ago"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Reclaimed 0.0B
+    "###);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["util", "gc", "--expire=3 days ago"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Reclaimed 0.0B
     "###);
 }
 
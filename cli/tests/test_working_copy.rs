@@ -57,3 +57,32 @@ fn test_snapshot_large_file() {
     let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list"]);
     insta::assert_snapshot!(stdout, @"");
 }
+
+#[test]
+fn test_snapshot_large_file_colocated() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "--colocate", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"snapshot.max-new-file-size = 10"#);
+
+    // The file isn't ingested, so no Git blob is written for it
+    std::fs::write(repo_path.join("large"), "a lot of text").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["status"]);
+    insta::assert_snapshot!(stderr.lines().take(2).collect::<Vec<_>>().join("\n"), @r###"
+    Error: Failed to snapshot the working copy
+    The file '$TEST_ENV/repo/large' is too large to be snapshotted: it is 3 bytes too large; the maximum size allowed is 10 bytes (10.0B).
+    "###);
+    let git_repo = git2::Repository::open(&repo_path).unwrap();
+    let blob_id = git2::Oid::hash_object(git2::ObjectType::Blob, b"a lot of text").unwrap();
+    assert!(git_repo.find_blob(blob_id).is_err());
+
+    // Existing files may grow beyond the limit
+    std::fs::write(repo_path.join("large"), "small").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    std::fs::write(repo_path.join("large"), "a lot of text").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
+    insta::assert_snapshot!(stdout, @r###"
+    large | 1 +
+    1 file changed, 1 insertion(+), 0 deletions(-)
+    "###);
+}
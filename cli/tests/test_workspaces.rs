@@ -519,6 +519,7 @@ fn test_workspaces_conflicting_edits() {
     insta::assert_snapshot!(stderr, @r###"
     Concurrent modification detected, resolving automatically.
     Rebased 1 descendant commits onto commits rewritten by other operation
+    Updated working copy from operation 7ca721d5b94e to aa8598812f42
     Working copy now at: pmmvwywv?? e82cd4ee (empty) (no description set)
     Added 0 files, modified 1 files, removed 0 files
     "###);
@@ -596,6 +597,7 @@ fn test_workspaces_updated_by_other() {
     // divergent commit should be created.
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
+    Updated working copy from operation c81af45155a2 to 01eaf5c18277
     Working copy now at: pmmvwywv e82cd4ee (empty) (no description set)
     Added 0 files, modified 1 files, removed 0 files
     "###);
@@ -1039,6 +1041,33 @@ fn test_workspaces_root() {
     "###);
 }
 
+/// Test getting the workspace id from primary and secondary workspaces
+#[test]
+fn test_workspaces_id() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    let secondary_path = test_env.env_root().join("secondary");
+
+    let main_subdir_path = main_path.join("subdir");
+    std::fs::create_dir(&main_subdir_path).unwrap();
+    let stdout = test_env.jj_cmd_success(&main_subdir_path, &["workspace", "id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    default
+    "###);
+
+    test_env.jj_cmd_ok(
+        &main_path,
+        &["workspace", "add", "--name", "secondary", "../secondary"],
+    );
+    let secondary_subdir_path = secondary_path.join("subdir");
+    std::fs::create_dir(&secondary_subdir_path).unwrap();
+    let stdout = test_env.jj_cmd_success(&secondary_subdir_path, &["workspace", "id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    secondary
+    "###);
+}
+
 #[test]
 fn test_debug_snapshot() {
     let test_env = TestEnvironment::default();
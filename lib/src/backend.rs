@@ -193,6 +193,25 @@ pub struct CopyRecord {
     pub source_commit: CommitId,
 }
 
+/// Options for detecting copies and renames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CopyDetectionOptions {
+    /// Whether to detect copies in addition to renames.
+    pub find_copies: bool,
+    /// Minimum similarity of the file contents, between 0.0 and 1.0, for a
+    /// file to be considered copied or renamed.
+    pub similarity_threshold: f32,
+}
+
+impl Default for CopyDetectionOptions {
+    fn default() -> Self {
+        CopyDetectionOptions {
+            find_copies: true,
+            similarity_threshold: 0.5,
+        }
+    }
+}
+
 /// Error that may occur during backend initialization.
 #[derive(Debug, Error)]
 #[error(transparent)]
@@ -481,6 +500,7 @@ pub trait Backend: Send + Sync + Debug {
         paths: Option<&[RepoPathBuf]>,
         root: &CommitId,
         head: &CommitId,
+        options: &CopyDetectionOptions,
     ) -> BackendResult<BoxStream<BackendResult<CopyRecord>>>;
 
     /// Perform garbage collection.
@@ -54,6 +54,8 @@ const CONFLICT_END_LINE: &[u8] = b">>>>>>>";
 const CONFLICT_DIFF_LINE: &[u8] = b"%%%%%%%";
 const CONFLICT_MINUS_LINE: &[u8] = b"-------";
 const CONFLICT_PLUS_LINE: &[u8] = b"+++++++";
+const GIT_CONFLICT_BASE_LINE: &[u8] = b"|||||||";
+const GIT_CONFLICT_SEPARATOR_LINE: &[u8] = b"=======";
 const CONFLICT_START_LINE_CHAR: u8 = CONFLICT_START_LINE[0];
 const CONFLICT_END_LINE_CHAR: u8 = CONFLICT_END_LINE[0];
 const CONFLICT_DIFF_LINE_CHAR: u8 = CONFLICT_DIFF_LINE[0];
@@ -231,9 +233,34 @@ async fn materialize_tree_value_no_access_denied(
     }
 }
 
+/// How conflicts are rendered when they are materialized as text.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictMarkerStyle {
+    /// Show the changes from the base to each side as diffs.
+    #[default]
+    Jj,
+    /// Show a snapshot of each side, like Git's "merge" style.
+    Git,
+    /// Show a snapshot of each side and of the base, like Git's "diff3" style.
+    Diff3,
+}
+
 pub fn materialize_merge_result(
     single_hunk: &Merge<BString>,
     output: &mut dyn Write,
+) -> std::io::Result<()> {
+    materialize_merge_result_with_style(single_hunk, ConflictMarkerStyle::Jj, output)
+}
+
+/// Materializes the conflict with markers in the given style.
+///
+/// The Git styles can only represent conflicts with two sides. Conflicts with
+/// more sides are rendered in the `Jj` style.
+pub fn materialize_merge_result_with_style(
+    single_hunk: &Merge<BString>,
+    style: ConflictMarkerStyle,
+    output: &mut dyn Write,
 ) -> std::io::Result<()> {
     let merge_result = files::merge(single_hunk);
     match merge_result {
@@ -251,80 +278,21 @@ pub fn materialize_merge_result(
                     output.write_all(content)?;
                 } else {
                     conflict_index += 1;
-                    output.write_all(CONFLICT_START_LINE)?;
-                    output.write_all(
-                        format!(" Conflict {conflict_index} of {num_conflicts}\n").as_bytes(),
-                    )?;
-                    let mut add_index = 0;
-                    for (base_index, left) in hunk.removes().enumerate() {
-                        // The vast majority of conflicts one actually tries to
-                        // resolve manually have 1 base.
-                        let base_str = if hunk.removes().len() == 1 {
-                            "base".to_string()
-                        } else {
-                            format!("base #{}", base_index + 1)
-                        };
-
-                        let right1 = if let Some(right1) = hunk.get_add(add_index) {
-                            right1
-                        } else {
-                            // If we have no more positive terms, emit the remaining negative
-                            // terms as snapshots.
-                            output.write_all(CONFLICT_MINUS_LINE)?;
-                            output.write_all(format!(" Contents of {base_str}\n").as_bytes())?;
-                            output.write_all(left)?;
-                            continue;
-                        };
-                        let diff1 = Diff::by_line([&left, &right1]).hunks().collect_vec();
-                        // Check if the diff against the next positive term is better. Since
-                        // we want to preserve the order of the terms, we don't match against
-                        // any later positive terms.
-                        if let Some(right2) = hunk.get_add(add_index + 1) {
-                            let diff2 = Diff::by_line([&left, &right2]).hunks().collect_vec();
-                            if diff_size(&diff2) < diff_size(&diff1) {
-                                // If the next positive term is a better match, emit
-                                // the current positive term as a snapshot and the next
-                                // positive term as a diff.
-                                output.write_all(CONFLICT_PLUS_LINE)?;
-                                output.write_all(
-                                    format!(" Contents of side #{}\n", add_index + 1).as_bytes(),
-                                )?;
-                                output.write_all(right1)?;
-                                output.write_all(CONFLICT_DIFF_LINE)?;
-                                output.write_all(
-                                    format!(
-                                        " Changes from {base_str} to side #{}\n",
-                                        add_index + 2
-                                    )
-                                    .as_bytes(),
-                                )?;
-                                write_diff_hunks(&diff2, output)?;
-                                add_index += 2;
-                                continue;
-                            }
+                    let conflict_info = format!("Conflict {conflict_index} of {num_conflicts}");
+                    match style {
+                        ConflictMarkerStyle::Git | ConflictMarkerStyle::Diff3
+                            if hunk.num_sides() == 2 =>
+                        {
+                            let include_base = style == ConflictMarkerStyle::Diff3;
+                            materialize_git_style_hunk(
+                                &hunk,
+                                &conflict_info,
+                                include_base,
+                                output,
+                            )?;
                         }
-
-                        output.write_all(CONFLICT_DIFF_LINE)?;
-                        output.write_all(
-                            format!(" Changes from {base_str} to side #{}\n", add_index + 1)
-                                .as_bytes(),
-                        )?;
-                        write_diff_hunks(&diff1, output)?;
-                        add_index += 1;
+                        _ => materialize_jj_style_hunk(&hunk, &conflict_info, output)?,
                     }
-
-                    //  Emit the remaining positive terms as snapshots.
-                    for (add_index, slice) in hunk.adds().enumerate().skip(add_index) {
-                        output.write_all(CONFLICT_PLUS_LINE)?;
-                        output.write_all(
-                            format!(" Contents of side #{}\n", add_index + 1).as_bytes(),
-                        )?;
-                        output.write_all(slice)?;
-                    }
-                    output.write_all(CONFLICT_END_LINE)?;
-                    output.write_all(
-                        format!(" Conflict {conflict_index} of {num_conflicts} ends\n").as_bytes(),
-                    )?;
                 }
             }
         }
@@ -332,6 +300,98 @@ pub fn materialize_merge_result(
     Ok(())
 }
 
+fn materialize_jj_style_hunk(
+    hunk: &Merge<BString>,
+    conflict_info: &str,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    output.write_all(CONFLICT_START_LINE)?;
+    output.write_all(format!(" {conflict_info}\n").as_bytes())?;
+    let mut add_index = 0;
+    for (base_index, left) in hunk.removes().enumerate() {
+        // The vast majority of conflicts one actually tries to
+        // resolve manually have 1 base.
+        let base_str = if hunk.removes().len() == 1 {
+            "base".to_string()
+        } else {
+            format!("base #{}", base_index + 1)
+        };
+
+        let right1 = if let Some(right1) = hunk.get_add(add_index) {
+            right1
+        } else {
+            // If we have no more positive terms, emit the remaining negative
+            // terms as snapshots.
+            output.write_all(CONFLICT_MINUS_LINE)?;
+            output.write_all(format!(" Contents of {base_str}\n").as_bytes())?;
+            output.write_all(left)?;
+            continue;
+        };
+        let diff1 = Diff::by_line([&left, &right1]).hunks().collect_vec();
+        // Check if the diff against the next positive term is better. Since
+        // we want to preserve the order of the terms, we don't match against
+        // any later positive terms.
+        if let Some(right2) = hunk.get_add(add_index + 1) {
+            let diff2 = Diff::by_line([&left, &right2]).hunks().collect_vec();
+            if diff_size(&diff2) < diff_size(&diff1) {
+                // If the next positive term is a better match, emit
+                // the current positive term as a snapshot and the next
+                // positive term as a diff.
+                output.write_all(CONFLICT_PLUS_LINE)?;
+                output.write_all(format!(" Contents of side #{}\n", add_index + 1).as_bytes())?;
+                output.write_all(right1)?;
+                output.write_all(CONFLICT_DIFF_LINE)?;
+                output.write_all(
+                    format!(" Changes from {base_str} to side #{}\n", add_index + 2).as_bytes(),
+                )?;
+                write_diff_hunks(&diff2, output)?;
+                add_index += 2;
+                continue;
+            }
+        }
+
+        output.write_all(CONFLICT_DIFF_LINE)?;
+        output.write_all(
+            format!(" Changes from {base_str} to side #{}\n", add_index + 1).as_bytes(),
+        )?;
+        write_diff_hunks(&diff1, output)?;
+        add_index += 1;
+    }
+
+    //  Emit the remaining positive terms as snapshots.
+    for (add_index, slice) in hunk.adds().enumerate().skip(add_index) {
+        output.write_all(CONFLICT_PLUS_LINE)?;
+        output.write_all(format!(" Contents of side #{}\n", add_index + 1).as_bytes())?;
+        output.write_all(slice)?;
+    }
+    output.write_all(CONFLICT_END_LINE)?;
+    output.write_all(format!(" {conflict_info} ends\n").as_bytes())?;
+    Ok(())
+}
+
+/// Writes a two-sided conflict hunk with Git-style markers.
+fn materialize_git_style_hunk(
+    hunk: &Merge<BString>,
+    conflict_info: &str,
+    include_base: bool,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    output.write_all(CONFLICT_START_LINE)?;
+    output.write_all(format!(" Side #1 ({conflict_info})\n").as_bytes())?;
+    output.write_all(hunk.get_add(0).unwrap())?;
+    if include_base {
+        output.write_all(GIT_CONFLICT_BASE_LINE)?;
+        output.write_all(b" Base\n")?;
+        output.write_all(hunk.get_remove(0).unwrap())?;
+    }
+    output.write_all(GIT_CONFLICT_SEPARATOR_LINE)?;
+    output.write_all(b"\n")?;
+    output.write_all(hunk.get_add(1).unwrap())?;
+    output.write_all(CONFLICT_END_LINE)?;
+    output.write_all(format!(" Side #2 ({conflict_info} ends)\n").as_bytes())?;
+    Ok(())
+}
+
 fn diff_size(hunks: &[DiffHunk]) -> usize {
     hunks
         .iter()
@@ -508,6 +508,10 @@ fn intersect_unchanged_words(
     .collect()
 }
 
+fn is_blank(text: &[u8]) -> bool {
+    text.iter().all(|b| b.is_ascii_whitespace())
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 struct UnchangedRange {
     // Inline up to two sides (base + one other)
@@ -767,6 +771,32 @@ impl<'input> Diff<'input> {
         self.compact_unchanged_regions();
     }
 
+    /// Marks the changed regions that consist only of blank lines as
+    /// unchanged.
+    ///
+    /// This is intended for line-level diffs. A region is considered blank if
+    /// all of its lines contain only whitespace on every side.
+    pub fn ignore_blank_line_changes(&mut self) {
+        let mut new_unchanged_ranges = vec![self.unchanged_regions[0].clone()];
+        for window in self.unchanged_regions.windows(2) {
+            let [previous, current]: &[_; 2] = window.try_into().unwrap();
+            if self
+                .hunk_between(previous, current)
+                .all(|content| is_blank(content))
+            {
+                new_unchanged_ranges.push(UnchangedRange {
+                    base: previous.base.end..current.base.start,
+                    others: iter::zip(&previous.others, &current.others)
+                        .map(|(prev, cur)| prev.end..cur.start)
+                        .collect(),
+                });
+            }
+            new_unchanged_ranges.push(current.clone());
+        }
+        self.unchanged_regions = new_unchanged_ranges;
+        self.compact_unchanged_regions();
+    }
+
     fn compact_unchanged_regions(&mut self) {
         let mut compacted = vec![];
         let mut maybe_previous: Option<UnchangedRange> = None;
@@ -1417,6 +1447,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_diff_ignore_blank_line_changes() {
+        fn diff(inputs: [&str; 2]) -> Vec<DiffHunk<'_>> {
+            let mut diff = Diff::for_tokenizer(inputs, find_line_ranges, CompareBytesExactly);
+            diff.ignore_blank_line_changes();
+            diff.hunks().collect()
+        }
+
+        assert_eq!(diff(["", "\n"]), vec![DiffHunk::matching(["", "\n"])]);
+        assert_eq!(
+            diff(["a\nb\n", "a\n\n \nb\n"]),
+            vec![DiffHunk::matching(["a\nb\n", "a\n\n \nb\n"])]
+        );
+        // Changes that aren't only blank lines are kept
+        assert_eq!(
+            diff(["a\n\nb\n", "a\nc\nb\n"]),
+            vec![
+                DiffHunk::matching(["a\n", "a\n"]),
+                DiffHunk::different(["\n", "c\n"]),
+                DiffHunk::matching(["b\n", "b\n"]),
+            ]
+        );
+        assert_eq!(
+            diff(["a\nb\n", "\na\nc\n"]),
+            vec![
+                DiffHunk::matching(["a\n", "\na\n"]),
+                DiffHunk::different(["b\n", "c\n"]),
+            ]
+        );
+    }
+
     #[test]
     fn test_diff_real_case_write_fmt() {
         // This is from src/ui.rs in commit f44d246e3f88 in this repo. It highlights the
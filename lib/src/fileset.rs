@@ -135,6 +135,22 @@ impl FilePattern {
         Ok(FilePattern::PrefixPath(path))
     }
 
+    /// Pattern that matches cwd-relative path prefix, or file path glob if
+    /// `input` contains glob characters.
+    ///
+    /// This is the default interpretation of unquoted file paths, which is
+    /// similar to Git pathspecs. Quoted paths always match literally.
+    pub fn cwd_prefix_path_or_glob(
+        path_converter: &RepoPathUiConverter,
+        input: impl AsRef<str>,
+    ) -> Result<Self, FilePatternParseError> {
+        if input.as_ref().contains(GLOB_CHARS) {
+            Self::cwd_file_glob(path_converter, input)
+        } else {
+            Self::cwd_prefix_path(path_converter, input)
+        }
+    }
+
     /// Pattern that matches cwd-relative file path glob.
     pub fn cwd_file_glob(
         path_converter: &RepoPathUiConverter,
@@ -186,9 +202,10 @@ impl FilePattern {
     }
 }
 
+const GLOB_CHARS: &[char] = &['?', '*', '[', ']']; // see glob::Pattern::escape()
+
 /// Splits `input` path into literal directory path and glob pattern.
 fn split_glob_path(input: &str) -> (&str, &str) {
-    const GLOB_CHARS: &[char] = &['?', '*', '[', ']']; // see glob::Pattern::escape()
     let prefix_len = input
         .split_inclusive(path::is_separator)
         .take_while(|component| !component.contains(GLOB_CHARS))
@@ -427,8 +444,13 @@ fn resolve_expression(
         |err| FilesetParseError::expression("Invalid file pattern", node.span).with_source(err);
     match &node.kind {
         ExpressionKind::Identifier(name) => {
-            let pattern =
-                FilePattern::cwd_prefix_path(path_converter, name).map_err(wrap_pattern_error)?;
+            let pattern = FilePattern::cwd_prefix_path_or_glob(path_converter, name)
+                .map_err(wrap_pattern_error)?;
+            Ok(FilesetExpression::pattern(pattern))
+        }
+        ExpressionKind::BareString(name) => {
+            let pattern = FilePattern::cwd_prefix_path_or_glob(path_converter, name)
+                .map_err(wrap_pattern_error)?;
             Ok(FilesetExpression::pattern(pattern))
         }
         ExpressionKind::String(name) => {
@@ -659,6 +681,41 @@ mod tests {
             },
         )
         "#);
+        // bare path with meta characters is parsed as glob
+        insta::assert_debug_snapshot!(
+            parse("foo/**/*.rs").unwrap(), @r#"
+        Pattern(
+            FileGlob {
+                dir: "cur*/foo",
+                pattern: Pattern {
+                    original: "**/*.rs",
+                    tokens: _,
+                    is_recursive: true,
+                },
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse(r#"cwd:"foo*""#).unwrap(),
+            @r#"Pattern(PrefixPath("cur*/foo*"))"#);
+        // quoted path with meta characters is parsed literally
+        insta::assert_debug_snapshot!(
+            parse(r#""foo*""#).unwrap(),
+            @r#"Pattern(PrefixPath("cur*/foo*"))"#);
+        // bare string with meta characters is parsed as glob
+        insta::assert_debug_snapshot!(
+            parse("foo bar/*.rs").unwrap(), @r#"
+        Pattern(
+            FileGlob {
+                dir: "cur*/foo bar",
+                pattern: Pattern {
+                    original: "*.rs",
+                    tokens: _,
+                    is_recursive: false,
+                },
+            },
+        )
+        "#);
         assert!(parse(r#"glob:"../../*""#).is_err());
         assert!(parse(r#"glob:"/*""#).is_err());
         // no support for relative path component after glob meta character
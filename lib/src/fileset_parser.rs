@@ -172,7 +172,10 @@ fn rename_rules_in_pest_error(err: pest::error::Error<Rule>) -> pest::error::Err
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ExpressionKind<'i> {
     Identifier(&'i str),
+    /// Quoted string literal.
     String(String),
+    /// Unquoted string that couldn't be parsed as an expression.
+    BareString(String),
     StringPattern {
         kind: &'i str,
         value: String,
@@ -340,7 +343,7 @@ pub fn parse_program_or_bare_string(text: &str) -> FilesetParseResult<Expression
             let value = rhs.as_str().to_owned();
             ExpressionKind::StringPattern { kind, value }
         }
-        Rule::bare_string => ExpressionKind::String(first.as_str().to_owned()),
+        Rule::bare_string => ExpressionKind::BareString(first.as_str().to_owned()),
         r => panic!("unexpected program or bare string rule: {r:?}"),
     };
     Ok(ExpressionNode::new(expr, span))
@@ -404,6 +407,7 @@ mod tests {
         let normalized_kind = match node.kind {
             ExpressionKind::Identifier(_)
             | ExpressionKind::String(_)
+            | ExpressionKind::BareString(_)
             | ExpressionKind::StringPattern { .. } => node.kind,
             ExpressionKind::Unary(op, arg) => {
                 let arg = Box::new(normalize_tree(*arg));
@@ -662,21 +666,21 @@ mod tests {
         // Bare string
         assert_eq!(
             parse_maybe_bare_into_kind("Foo Bar.txt"),
-            Ok(ExpressionKind::String("Foo Bar.txt".to_owned()))
+            Ok(ExpressionKind::BareString("Foo Bar.txt".to_owned()))
         );
         assert_eq!(
             parse_maybe_bare_into_kind(r#"Windows\Path with space"#),
-            Ok(ExpressionKind::String(
+            Ok(ExpressionKind::BareString(
                 r#"Windows\Path with space"#.to_owned()
             ))
         );
         assert_eq!(
             parse_maybe_bare_into_kind("柔 術 . j j"),
-            Ok(ExpressionKind::String("柔 術 . j j".to_owned()))
+            Ok(ExpressionKind::BareString("柔 術 . j j".to_owned()))
         );
         assert_eq!(
             parse_maybe_bare_into_kind("Unicode emoji 💩"),
-            Ok(ExpressionKind::String("Unicode emoji 💩".to_owned()))
+            Ok(ExpressionKind::BareString("Unicode emoji 💩".to_owned()))
         );
         assert_eq!(
             parse_maybe_bare_into_kind("looks like & expression"),
@@ -719,7 +723,7 @@ mod tests {
         // space is valid bare_string character.
         assert_eq!(
             parse_maybe_bare_into_kind(" No trim "),
-            Ok(ExpressionKind::String(" No trim ".to_owned()))
+            Ok(ExpressionKind::BareString(" No trim ".to_owned()))
         );
     }
 
@@ -16,13 +16,22 @@
 
 use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::default::Default;
 use std::fmt;
+use std::fs;
+use std::io;
+use std::io::BufRead as _;
 use std::io::Read;
+use std::io::Write as _;
 use std::num::NonZeroU32;
+use std::path::Path;
 use std::path::PathBuf;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
 use std::str;
 
 use git2::Oid;
@@ -32,26 +41,37 @@ use thiserror::Error;
 
 use crate::backend::BackendError;
 use crate::backend::CommitId;
+use crate::backend::Signature;
 use crate::commit::Commit;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
 use crate::git_backend::GitBackend;
 use crate::index::Index;
+use crate::merged_tree::MergedTree;
 use crate::object_id::ObjectId;
 use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt;
 use crate::op_store::RemoteRef;
 use crate::op_store::RemoteRefState;
+use crate::op_store::WorkspaceId;
 use crate::refs;
 use crate::refs::BookmarkPushUpdate;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
 use crate::revset::RevsetExpression;
+use crate::settings::GitAbandonPolicy;
 use crate::settings::GitSettings;
+use crate::signing::SignError;
+use crate::signing::SignResult;
 use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::view::View;
 
 /// Reserved remote name for the backing Git repo.
 pub const REMOTE_NAME_FOR_LOCAL_GIT_REPO: &str = "git";
+/// Remote name under which branches fetched from a URL (rather than from a
+/// configured remote) are stored.
+pub const REMOTE_NAME_FOR_ANONYMOUS_FETCH: &str = "anonymous";
 /// Ref name used as a placeholder to unset HEAD without a commit.
 const UNBORN_ROOT_REF_NAME: &str = "refs/jj/root";
 
@@ -92,7 +112,8 @@ pub fn parse_git_ref(ref_name: &str) -> Option<RefName> {
     }
 }
 
-fn to_git_ref_name(parsed_ref: &RefName) -> Option<String> {
+/// Returns the full Git ref name (e.g. `refs/heads/main`) of the given ref.
+pub fn to_git_ref_name(parsed_ref: &RefName) -> Option<String> {
     match parsed_ref {
         RefName::LocalBranch(branch) => {
             (!branch.is_empty() && branch != "HEAD").then(|| format!("refs/heads/{branch}"))
@@ -216,9 +237,16 @@ impl GitImportError {
 pub struct GitImportStats {
     /// Commits superseded by newly imported commits.
     pub abandoned_commits: Vec<CommitId>,
+    /// Commits that were reachable only from the previous local targets of the
+    /// bookmarks and tags updated by the import. These are abandoned only with
+    /// [`GitAbandonPolicy::All`].
+    pub abandoned_local_commits: Vec<CommitId>,
     /// Remote `(ref_name, (old_remote_ref, new_target))`s to be merged in to
     /// the local refs.
     pub changed_remote_refs: BTreeMap<RefName, (RemoteRef, RefTarget)>,
+    /// Local bookmarks that would have conflicted with their remote bookmarks,
+    /// but were fast-forwarded to the new remote targets instead.
+    pub fast_forwarded_bookmarks: Vec<RefName>,
 }
 
 #[derive(Debug)]
@@ -300,6 +328,8 @@ pub fn import_some_refs(
     for (full_name, new_target) in changed_git_refs {
         mut_repo.set_git_ref_target(&full_name, new_target);
     }
+    let mut fast_forwarded_bookmarks = vec![];
+    let mut old_local_targets = vec![];
     for (ref_name, (old_remote_ref, new_target)) in &changed_remote_refs {
         let base_target = old_remote_ref.tracking_target();
         let new_remote_ref = RemoteRef {
@@ -313,6 +343,7 @@ pub fn import_some_refs(
         match ref_name {
             RefName::LocalBranch(branch) => {
                 if new_remote_ref.is_tracking() {
+                    old_local_targets.push(mut_repo.get_local_bookmark(branch));
                     mut_repo.merge_local_bookmark(branch, base_target, &new_remote_ref.target);
                 }
                 // Update Git-tracking branch like the other remote branches.
@@ -324,7 +355,17 @@ pub fn import_some_refs(
             }
             RefName::RemoteBranch { branch, remote } => {
                 if new_remote_ref.is_tracking() {
+                    let old_local_target = mut_repo.get_local_bookmark(branch);
                     mut_repo.merge_local_bookmark(branch, base_target, &new_remote_ref.target);
+                    if fast_forward_conflicted_bookmark(
+                        mut_repo,
+                        branch,
+                        &old_local_target,
+                        &new_remote_ref.target,
+                    ) {
+                        fast_forwarded_bookmarks.push(ref_name.clone());
+                    }
+                    old_local_targets.push(old_local_target);
                 }
                 // Remote-tracking branch is the last known state of the branch in the remote.
                 // It shouldn't diverge even if we had inconsistent view.
@@ -332,6 +373,7 @@ pub fn import_some_refs(
             }
             RefName::Tag(name) => {
                 if new_remote_ref.is_tracking() {
+                    old_local_targets.push(mut_repo.get_tag(name));
                     mut_repo.merge_tag(name, base_target, &new_remote_ref.target);
                 }
                 // TODO: If we add Git-tracking tag, it will be updated here.
@@ -339,29 +381,74 @@ pub fn import_some_refs(
         }
     }
 
-    let abandoned_commits = if git_settings.abandon_unreachable_commits {
-        abandon_unreachable_commits(mut_repo, &changed_remote_refs)
-    } else {
-        vec![]
+    let abandoned_commits = match git_settings.abandon_policy {
+        GitAbandonPolicy::Never => vec![],
+        GitAbandonPolicy::Unreachable | GitAbandonPolicy::All => {
+            let hidable_git_heads = changed_remote_refs
+                .values()
+                .flat_map(|(old_remote_ref, _)| old_remote_ref.target.added_ids())
+                .cloned()
+                .collect_vec();
+            abandon_unreachable_commits(mut_repo, hidable_git_heads)
+        }
+    };
+    let abandoned_local_commits = match git_settings.abandon_policy {
+        GitAbandonPolicy::Never | GitAbandonPolicy::Unreachable => vec![],
+        GitAbandonPolicy::All => {
+            let hidable_local_heads = old_local_targets
+                .iter()
+                .flat_map(|target| target.added_ids())
+                .cloned()
+                .collect_vec();
+            // Commits abandoned above are still visible in the view
+            let already_abandoned: HashSet<_> = abandoned_commits.iter().collect();
+            abandon_unreachable_commits(mut_repo, hidable_local_heads)
+                .into_iter()
+                .filter(|id| !already_abandoned.contains(id))
+                .collect()
+        }
     };
     let stats = GitImportStats {
         abandoned_commits,
+        abandoned_local_commits,
         changed_remote_refs,
+        fast_forwarded_bookmarks,
     };
     Ok(stats)
 }
 
-/// Finds commits that used to be reachable in git that no longer are reachable.
-/// Those commits will be recorded as abandoned in the `MutableRepo`.
+/// Resolves the conflict in the local bookmark `name` if its target before
+/// merging the remote changes is an ancestor of the new remote target.
+///
+/// Returns true if the bookmark was fast-forwarded to the remote target.
+fn fast_forward_conflicted_bookmark(
+    mut_repo: &mut MutableRepo,
+    name: &str,
+    old_local_target: &RefTarget,
+    new_remote_target: &RefTarget,
+) -> bool {
+    if !mut_repo.get_local_bookmark(name).has_conflict() {
+        return false;
+    }
+    let (Some(old_local_id), Some(new_remote_id)) =
+        (old_local_target.as_normal(), new_remote_target.as_normal())
+    else {
+        return false;
+    };
+    if !mut_repo.index().is_ancestor(old_local_id, new_remote_id) {
+        return false;
+    }
+    mut_repo.set_local_bookmark_target(name, new_remote_target.clone());
+    true
+}
+
+/// Finds commits that used to be reachable from `hidable_git_heads` that no
+/// longer are reachable. Those commits will be recorded as abandoned in the
+/// `MutableRepo`.
 fn abandon_unreachable_commits(
     mut_repo: &mut MutableRepo,
-    changed_remote_refs: &BTreeMap<RefName, (RemoteRef, RefTarget)>,
+    hidable_git_heads: Vec<CommitId>,
 ) -> Vec<CommitId> {
-    let hidable_git_heads = changed_remote_refs
-        .values()
-        .flat_map(|(old_remote_ref, _)| old_remote_ref.target.added_ids())
-        .cloned()
-        .collect_vec();
     if hidable_git_heads.is_empty() {
         return vec![];
     }
@@ -449,7 +536,7 @@ fn diff_refs_to_import(
             continue;
         };
         let Some(ref_name) = parse_git_ref(full_name) else {
-            // Skip other refs (such as notes) and symbolic refs.
+            // Skip other refs (such as notes) and symbolic HEAD refs.
             continue;
         };
         if !git_ref_filter(&ref_name) {
@@ -459,8 +546,10 @@ fn diff_refs_to_import(
             return Err(GitImportError::RemoteReservedForLocalGitRepo);
         }
         let old_git_target = known_git_refs.get(full_name).copied().flatten();
+        // Symbolic refs (e.g. refs/heads/alias -> refs/heads/main) are followed,
+        // and imported as if they were pointing to the resolved commit.
         let Some(id) = resolve_git_ref_to_commit_id(&git_ref, old_git_target) else {
-            // Skip (or remove existing) invalid refs.
+            // Skip (or remove existing) invalid refs, including dangling symbolic refs.
             continue;
         };
         let new_target = RefTarget::normal(id);
@@ -549,11 +638,43 @@ fn remotely_pinned_commit_ids(view: &View) -> Vec<CommitId> {
 /// Unlike `reset_head()`, this function doesn't move the working-copy commit to
 /// the child of the new HEAD revision.
 pub fn import_head(mut_repo: &mut MutableRepo) -> Result<(), GitImportError> {
+    import_workspace_head(mut_repo, &WorkspaceId::default())
+}
+
+/// Imports HEAD from the underlying Git repo as the Git HEAD of the given
+/// workspace.
+pub fn import_workspace_head(
+    mut_repo: &mut MutableRepo,
+    workspace_id: &WorkspaceId,
+) -> Result<(), GitImportError> {
+    let git_backend = get_git_backend(mut_repo.store()).ok_or(GitImportError::UnexpectedBackend)?;
+    let git_repo = git_backend.git_repo();
+    import_head_from(mut_repo, &git_repo, workspace_id)
+}
+
+/// Imports HEAD of the Git worktree at `worktree_root` as the Git HEAD of the
+/// given workspace.
+///
+/// The worktree should share the object database with the underlying Git repo,
+/// e.g. it is a linked worktree created by `add_worktree()`.
+pub fn import_worktree_head(
+    mut_repo: &mut MutableRepo,
+    worktree_root: &Path,
+    workspace_id: &WorkspaceId,
+) -> Result<(), GitImportError> {
+    let git_repo = gix::open(worktree_root).map_err(GitImportError::from_git)?;
+    import_head_from(mut_repo, &git_repo, workspace_id)
+}
+
+fn import_head_from(
+    mut_repo: &mut MutableRepo,
+    git_repo: &gix::Repository,
+    workspace_id: &WorkspaceId,
+) -> Result<(), GitImportError> {
     let store = mut_repo.store();
     let git_backend = get_git_backend(store).ok_or(GitImportError::UnexpectedBackend)?;
-    let git_repo = git_backend.git_repo();
 
-    let old_git_head = mut_repo.view().git_head();
+    let old_git_head = mut_repo.view().workspace_git_head(workspace_id);
     let new_git_head_id = if let Ok(oid) = git_repo.head_id() {
         Some(CommitId::from_bytes(oid.as_bytes()))
     } else {
@@ -582,7 +703,7 @@ pub fn import_head(mut_repo: &mut MutableRepo) -> Result<(), GitImportError> {
             .map_err(GitImportError::InternalBackend)?;
     }
 
-    mut_repo.set_git_head_target(RefTarget::resolved(new_git_head_id));
+    mut_repo.set_workspace_git_head_target(workspace_id, RefTarget::resolved(new_git_head_id));
     Ok(())
 }
 
@@ -629,6 +750,13 @@ pub enum FailedRefExportReason {
     /// We wanted to modify it, but Git had deleted it
     #[error("Modified ref had been deleted in Git")]
     ModifiedInJjDeletedInGit,
+    /// We wanted to modify it, but Git had moved it to a different target
+    #[error("Modified ref had been modified in Git")]
+    ModifiedInJjModifiedInGit,
+    /// Another ref in Git looks like a parent or child directory of it (e.g.
+    /// `foo` and `foo/bar`)
+    #[error("Name conflicts with another ref in Git")]
+    ConflictingName,
     /// Failed to delete the ref from the Git repo
     #[error("Failed to delete")]
     FailedToDelete(#[source] Box<gix::reference::edit::Error>),
@@ -668,7 +796,7 @@ pub fn export_some_refs(
     let RefsToExport {
         branches_to_update,
         branches_to_delete,
-        mut failed_branches,
+        failed_branches,
     } = diff_refs_to_export(
         mut_repo.view(),
         mut_repo.store().root_commit_id(),
@@ -707,11 +835,24 @@ pub fn export_some_refs(
             }
         }
     }
-    for (parsed_ref_name, old_oid) in branches_to_delete {
-        let Some(git_ref_name) = to_git_ref_name(&parsed_ref_name) else {
-            failed_branches.insert(parsed_ref_name, FailedRefExportReason::InvalidGitName);
-            continue;
-        };
+    let RefsExportPlan {
+        to_delete,
+        to_update,
+        unchanged,
+        failed: mut failed_branches,
+    } = plan_export_refs(
+        &git_repo,
+        RefsToExport {
+            branches_to_update,
+            branches_to_delete,
+            failed_branches,
+        },
+    )?;
+    for (git_ref_name, oid) in unchanged {
+        let target = RefTarget::resolved(oid.map(|oid| CommitId::from_bytes(oid.as_bytes())));
+        mut_repo.set_git_ref_target(&git_ref_name, target);
+    }
+    for (parsed_ref_name, git_ref_name, old_oid) in to_delete {
         if let Err(reason) = delete_git_ref(&git_repo, &git_ref_name, &old_oid) {
             failed_branches.insert(parsed_ref_name, reason);
         } else {
@@ -719,12 +860,15 @@ pub fn export_some_refs(
             mut_repo.set_git_ref_target(&git_ref_name, new_target);
         }
     }
-    for (parsed_ref_name, (old_oid, new_oid)) in branches_to_update {
-        let Some(git_ref_name) = to_git_ref_name(&parsed_ref_name) else {
-            failed_branches.insert(parsed_ref_name, FailedRefExportReason::InvalidGitName);
-            continue;
-        };
-        if let Err(reason) = update_git_ref(&git_repo, &git_ref_name, old_oid, new_oid) {
+    for (parsed_ref_name, git_ref_name, old_oid, new_oid) in to_update {
+        let log_message = format!(
+            "jj: {} {}",
+            if old_oid.is_some() { "move" } else { "create" },
+            describe_ref_for_reflog(&parsed_ref_name)
+        );
+        if let Err(reason) =
+            update_git_ref(&git_repo, &git_ref_name, old_oid, new_oid, &log_message)
+        {
             failed_branches.insert(parsed_ref_name, reason);
         } else {
             let new_target = RefTarget::normal(CommitId::from_bytes(new_oid.as_bytes()));
@@ -746,6 +890,242 @@ pub fn export_some_refs(
     Ok(failed_branches)
 }
 
+/// A change to a ref in the Git repo that [`export_refs()`] would make.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RefExportUpdate {
+    pub name: RefName,
+    pub git_ref_name: String,
+    /// The current target in Git, or `None` if the ref would be created.
+    pub old_target: Option<CommitId>,
+    /// The target after the export, or `None` if the ref would be deleted.
+    pub new_target: Option<CommitId>,
+}
+
+/// Changes [`export_refs()`] would make to the Git repo.
+#[derive(Debug, Default)]
+pub struct ExportRefsPreview {
+    /// Refs that would be created, moved, or deleted, sorted by name.
+    pub updated_refs: Vec<RefExportUpdate>,
+    /// Refs that would fail to export, sorted by name.
+    pub failed_refs: Vec<FailedRefExport>,
+}
+
+/// Computes the changes [`export_refs()`] would make to the Git repo without
+/// writing anything.
+///
+/// Refs that would fail to export because they were changed in Git, or
+/// because their names conflict with other refs, are predicted from the
+/// current state of the Git repo.
+pub fn preview_export_refs(repo: &dyn Repo) -> Result<ExportRefsPreview, GitExportError> {
+    let git_repo = get_git_repo(repo.store()).ok_or(GitExportError::UnexpectedBackend)?;
+    let refs_to_export = diff_refs_to_export(repo.view(), repo.store().root_commit_id(), |_| true);
+    let RefsExportPlan {
+        to_delete,
+        to_update,
+        unchanged: _,
+        failed,
+    } = plan_export_refs(&git_repo, refs_to_export)?;
+
+    let to_commit_id = |oid: gix::ObjectId| CommitId::from_bytes(oid.as_bytes());
+    let deletions = to_delete
+        .into_iter()
+        .map(|(name, git_ref_name, old_oid)| RefExportUpdate {
+            name,
+            git_ref_name,
+            old_target: Some(to_commit_id(old_oid)),
+            new_target: None,
+        });
+    let updates = to_update
+        .into_iter()
+        .map(|(name, git_ref_name, old_oid, new_oid)| RefExportUpdate {
+            name,
+            git_ref_name,
+            old_target: old_oid.map(to_commit_id),
+            new_target: Some(to_commit_id(new_oid)),
+        });
+    let updated_refs = itertools::chain(deletions, updates)
+        .sorted_unstable_by(|a, b| a.git_ref_name.cmp(&b.git_ref_name))
+        .collect();
+    let failed_refs = failed
+        .into_iter()
+        .map(|(name, reason)| FailedRefExport { name, reason })
+        .sorted_unstable_by(|a, b| a.name.cmp(&b.name))
+        .collect();
+    Ok(ExportRefsPreview {
+        updated_refs,
+        failed_refs,
+    })
+}
+
+/// Changes to the Git repo that [`export_refs()`] will try to make, checked
+/// against the current state of the Git repo.
+struct RefsExportPlan {
+    /// Refs to delete from Git, with their current target.
+    to_delete: Vec<(RefName, String, gix::ObjectId)>,
+    /// Refs to create or move in Git, with their current and new targets.
+    to_update: Vec<(RefName, String, Option<gix::ObjectId>, gix::ObjectId)>,
+    /// Refs that already have the exported target in Git.
+    unchanged: Vec<(String, Option<gix::ObjectId>)>,
+    failed: HashMap<RefName, FailedRefExportReason>,
+}
+
+/// Decides how to export each ref, so that the actual export and the preview
+/// report the same changes and failures.
+fn plan_export_refs(
+    git_repo: &gix::Repository,
+    refs_to_export: RefsToExport,
+) -> Result<RefsExportPlan, GitExportError> {
+    let RefsToExport {
+        branches_to_update,
+        branches_to_delete,
+        failed_branches,
+    } = refs_to_export;
+    let current_oid = |git_ref_name: &str| {
+        let git_ref = git_repo.find_reference(git_ref_name).ok()?;
+        git_ref.inner.target.try_id().map(ToOwned::to_owned)
+    };
+    // Names of the refs that will exist in Git, to detect names that Git
+    // can't store next to each other.
+    let mut existing_names: BTreeSet<String> = git_repo
+        .references()
+        .map_err(GitExportError::from_git)?
+        .all()
+        .map_err(GitExportError::from_git)?
+        .filter_map(Result::ok)
+        .map(|git_ref| git_ref.name().as_bstr().to_string())
+        .collect();
+    let mut plan = RefsExportPlan {
+        to_delete: vec![],
+        to_update: vec![],
+        unchanged: vec![],
+        failed: failed_branches,
+    };
+    for (parsed_ref_name, old_oid) in branches_to_delete {
+        let Some(git_ref_name) = to_git_ref_name(&parsed_ref_name) else {
+            plan.failed
+                .insert(parsed_ref_name, FailedRefExportReason::InvalidGitName);
+            continue;
+        };
+        match current_oid(&git_ref_name) {
+            Some(oid) if oid == old_oid => {
+                existing_names.remove(&git_ref_name);
+                plan.to_delete.push((parsed_ref_name, git_ref_name, oid));
+            }
+            Some(_) => {
+                let reason = FailedRefExportReason::DeletedInJjModifiedInGit;
+                plan.failed.insert(parsed_ref_name, reason);
+            }
+            None => plan.unchanged.push((git_ref_name, None)), // Already deleted in Git
+        }
+    }
+    for (parsed_ref_name, (old_oid, new_oid)) in branches_to_update {
+        let Some(git_ref_name) = to_git_ref_name(&parsed_ref_name) else {
+            plan.failed
+                .insert(parsed_ref_name, FailedRefExportReason::InvalidGitName);
+            continue;
+        };
+        let current_oid = current_oid(&git_ref_name);
+        if current_oid == Some(new_oid) {
+            // Already up to date in Git
+            plan.unchanged.push((git_ref_name, current_oid));
+            continue;
+        }
+        let reason = match (old_oid, current_oid) {
+            (None, Some(_)) => Some(FailedRefExportReason::AddedInJjAddedInGit),
+            (Some(_), None) => Some(FailedRefExportReason::ModifiedInJjDeletedInGit),
+            (Some(old_oid), Some(current_oid)) if old_oid != current_oid => {
+                Some(FailedRefExportReason::ModifiedInJjModifiedInGit)
+            }
+            (None, None) if has_conflicting_ref_name(&existing_names, &git_ref_name) => {
+                Some(FailedRefExportReason::ConflictingName)
+            }
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            plan.failed.insert(parsed_ref_name, reason);
+            continue;
+        }
+        existing_names.insert(git_ref_name.clone());
+        plan.to_update
+            .push((parsed_ref_name, git_ref_name, current_oid, new_oid));
+    }
+    Ok(plan)
+}
+
+/// Returns true if `names` contains a parent or child directory of the ref
+/// `name`, which Git can't store next to it.
+fn has_conflicting_ref_name(names: &BTreeSet<String>, name: &str) -> bool {
+    let child_prefix = format!("{name}/");
+    let has_child = names
+        .range(child_prefix.clone()..)
+        .next()
+        .is_some_and(|other| other.starts_with(&child_prefix));
+    let has_parent = name
+        .match_indices('/')
+        .any(|(index, _)| names.contains(&name[..index]));
+    has_child || has_parent
+}
+
+/// Branches deleted from Git by [`prune_git_branches()`].
+#[derive(Debug, Default)]
+pub struct PrunedGitBranches {
+    /// Branches that were deleted.
+    pub pruned: Vec<RefName>,
+    /// Branches that weren't deleted, e.g. because they had been modified in
+    /// Git since jj last imported or exported them.
+    pub failed: Vec<FailedRefExport>,
+}
+
+/// Deletes Git branches that jj exported or imported before but that no
+/// longer have a corresponding local bookmark.
+///
+/// A branch is only deleted if it still points to the commit jj last saw, so
+/// work added in Git isn't lost. Branches that had been modified in Git are
+/// reported as failed instead, like [`export_refs()`] does. Branches jj has
+/// never seen are left alone.
+pub fn prune_git_branches(mut_repo: &mut MutableRepo) -> Result<PrunedGitBranches, GitExportError> {
+    let git_repo = get_git_repo(mut_repo.store()).ok_or(GitExportError::UnexpectedBackend)?;
+    let orphaned_branches = mut_repo
+        .view()
+        .git_refs()
+        .iter()
+        .filter_map(|(full_name, target)| match parse_git_ref(full_name)? {
+            RefName::LocalBranch(branch) => Some((full_name.clone(), branch, target.clone())),
+            _ => None,
+        })
+        .filter(|(_, branch, _)| mut_repo.view().get_local_bookmark(branch).is_absent())
+        .collect_vec();
+    let mut result = PrunedGitBranches::default();
+    for (git_ref_name, branch, old_target) in orphaned_branches {
+        let name = RefName::LocalBranch(branch.clone());
+        if let Ok(git_ref) = git_repo.find_reference(&git_ref_name) {
+            let Some(old_id) = old_target.as_normal() else {
+                result.failed.push(FailedRefExport {
+                    name,
+                    reason: FailedRefExportReason::ConflictedOldState,
+                });
+                continue;
+            };
+            let current_id = git_ref
+                .target()
+                .try_id()
+                .map(|oid| CommitId::from_bytes(oid.as_bytes()));
+            if current_id.as_ref() != Some(old_id) {
+                result.failed.push(FailedRefExport {
+                    name,
+                    reason: FailedRefExportReason::DeletedInJjModifiedInGit,
+                });
+                continue;
+            }
+            git_ref.delete().map_err(GitExportError::from_git)?;
+        }
+        mut_repo.set_git_ref_target(&git_ref_name, RefTarget::absent());
+        mut_repo.set_remote_bookmark(&branch, REMOTE_NAME_FOR_LOCAL_GIT_REPO, RemoteRef::absent());
+        result.pruned.push(name);
+    }
+    Ok(result)
+}
+
 fn copy_exportable_local_branches_to_remote_view(
     mut_repo: &mut MutableRepo,
     remote_name: &str,
@@ -885,11 +1265,22 @@ fn delete_git_ref(
     Ok(())
 }
 
+/// Returns the description of the ref to be included in reflog messages, e.g.
+/// "bookmark main".
+fn describe_ref_for_reflog(ref_name: &RefName) -> String {
+    match ref_name {
+        RefName::LocalBranch(branch) => format!("bookmark {branch}"),
+        RefName::RemoteBranch { branch, remote } => format!("remote bookmark {branch}@{remote}"),
+        RefName::Tag(tag) => format!("tag {tag}"),
+    }
+}
+
 fn update_git_ref(
     git_repo: &gix::Repository,
     git_ref_name: &str,
     old_oid: Option<gix::ObjectId>,
     new_oid: gix::ObjectId,
+    log_message: &str,
 ) -> Result<(), FailedRefExportReason> {
     match old_oid {
         None => {
@@ -906,7 +1297,7 @@ fn update_git_ref(
                         git_ref_name,
                         new_oid,
                         gix::refs::transaction::PreviousValue::MustNotExist,
-                        "export from jj",
+                        log_message,
                     )
                     .map_err(|err| FailedRefExportReason::FailedToSet(err.into()))?;
             }
@@ -917,13 +1308,17 @@ fn update_git_ref(
                 git_ref_name,
                 new_oid,
                 gix::refs::transaction::PreviousValue::MustExistAndMatch(old_oid.into()),
-                "export from jj",
+                log_message,
             ) {
                 // The reference was probably updated in git
                 if let Ok(git_repo_ref) = git_repo.find_reference(git_ref_name) {
                     // We still consider this a success if it was updated to our desired target
-                    if git_repo_ref.inner.target.try_id() != Some(&new_oid) {
-                        return Err(FailedRefExportReason::FailedToSet(err.into()));
+                    match git_repo_ref.inner.target.try_id() {
+                        Some(oid) if oid == new_oid => {}
+                        Some(oid) if oid != old_oid => {
+                            return Err(FailedRefExportReason::ModifiedInJjModifiedInGit);
+                        }
+                        _ => return Err(FailedRefExportReason::FailedToSet(err.into())),
                     }
                 } else {
                     // The reference was deleted in git and moved in jj
@@ -987,6 +1382,17 @@ pub fn reset_head(
     mut_repo: &mut MutableRepo,
     git_repo: &git2::Repository,
     wc_commit: &Commit,
+) -> Result<(), git2::Error> {
+    reset_workspace_head(mut_repo, git_repo, &WorkspaceId::default(), wc_commit)
+}
+
+/// Like `reset_head()`, but records the new HEAD as the Git HEAD of the given
+/// workspace.
+pub fn reset_workspace_head(
+    mut_repo: &mut MutableRepo,
+    git_repo: &git2::Repository,
+    workspace_id: &WorkspaceId,
+    wc_commit: &Commit,
 ) -> Result<(), git2::Error> {
     let first_parent_id = &wc_commit.parent_ids()[0];
     let first_parent = if first_parent_id != mut_repo.store().root_commit_id() {
@@ -995,7 +1401,7 @@ pub fn reset_head(
         RefTarget::absent()
     };
     if first_parent.is_present() {
-        let git_head = mut_repo.view().git_head();
+        let git_head = mut_repo.view().workspace_git_head(workspace_id);
         let new_git_commit_id = Oid::from_bytes(first_parent_id.as_bytes()).unwrap();
         let new_git_commit = git_repo.find_commit(new_git_commit_id)?;
         if git_head != &first_parent {
@@ -1035,7 +1441,7 @@ pub fn reset_head(
         // We can't set_head() an arbitrary unborn ref, so use reference_symbolic()
         // instead. Git CLI appears to deal with that. It would be nice if Git CLI
         // couldn't create a commit without setting a valid branch name.
-        if mut_repo.git_head().is_present() {
+        if mut_repo.workspace_git_head(workspace_id).is_present() {
             match git_repo.find_reference(UNBORN_ROOT_REF_NAME) {
                 Ok(mut git_repo_ref) => git_repo_ref.delete()?,
                 Err(err) if err.code() == git2::ErrorCode::NotFound => {}
@@ -1049,7 +1455,7 @@ pub fn reset_head(
         index.write()?;
         git_repo.cleanup_state()?;
     }
-    mut_repo.set_git_head_target(first_parent);
+    mut_repo.set_workspace_git_head_target(workspace_id, first_parent);
     Ok(())
 }
 
@@ -1064,6 +1470,11 @@ pub enum GitRemoteManagementError {
         name = REMOTE_NAME_FOR_LOCAL_GIT_REPO
     )]
     RemoteReservedForLocalGitRepo,
+    #[error(
+        "Invalid branch pattern provided. Patterns may not contain the characters `{chars}`",
+        chars = INVALID_REFSPEC_CHARS.iter().join("`, `")
+    )]
+    InvalidBranchPattern,
     #[error(transparent)]
     InternalGitError(git2::Error),
 }
@@ -1085,21 +1496,44 @@ fn is_remote_exists_err(err: &git2::Error) -> bool {
     )
 }
 
+/// Adds a remote with the given URL.
+///
+/// If `fetch_branches` isn't empty, the remote is configured to fetch only the
+/// branches matching these patterns instead of all branches. `fetch()` honors
+/// this when asked to fetch all branches.
 pub fn add_remote(
     git_repo: &git2::Repository,
     remote_name: &str,
     url: &str,
+    fetch_branches: &[StringPattern],
 ) -> Result<(), GitRemoteManagementError> {
     if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
         return Err(GitRemoteManagementError::RemoteReservedForLocalGitRepo);
     }
-    git_repo.remote(remote_name, url).map_err(|err| {
+    let refspecs = branch_fetch_refspecs(remote_name, fetch_branches)
+        .map_err(|_| GitRemoteManagementError::InvalidBranchPattern)?;
+    let map_add_err = |err: git2::Error| {
         if is_remote_exists_err(&err) {
             GitRemoteManagementError::RemoteAlreadyExists(remote_name.to_owned())
         } else {
             GitRemoteManagementError::InternalGitError(err)
         }
-    })?;
+    };
+    match refspecs.split_first() {
+        None => {
+            git_repo.remote(remote_name, url).map_err(map_add_err)?;
+        }
+        Some((first, rest)) => {
+            git_repo
+                .remote_with_fetch(remote_name, url, first)
+                .map_err(map_add_err)?;
+            for refspec in rest {
+                git_repo
+                    .remote_add_fetch(remote_name, refspec)
+                    .map_err(GitRemoteManagementError::InternalGitError)?;
+            }
+        }
+    }
     Ok(())
 }
 
@@ -1162,6 +1596,34 @@ pub fn rename_remote(
     Ok(())
 }
 
+/// Records `branch` as the default branch of the remote, like `git remote
+/// set-head`, by pointing the `refs/remotes/<remote>/HEAD` symref to the
+/// remote-tracking branch.
+pub fn set_remote_head(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    branch: &str,
+) -> Result<(), git2::Error> {
+    git_repo.reference_symbolic(
+        &format!("refs/remotes/{remote_name}/HEAD"),
+        &format!("refs/remotes/{remote_name}/{branch}"),
+        true,
+        "jj: set remote HEAD",
+    )?;
+    Ok(())
+}
+
+/// Returns the default branch of the remote recorded by [`set_remote_head()`]
+/// or `git remote set-head`.
+pub fn get_remote_head(git_repo: &git2::Repository, remote_name: &str) -> Option<String> {
+    let git_ref = git_repo
+        .find_reference(&format!("refs/remotes/{remote_name}/HEAD"))
+        .ok()?;
+    let target = git_ref.symbolic_target()?;
+    let branch = target.strip_prefix(&format!("refs/remotes/{remote_name}/"))?;
+    Some(branch.to_owned())
+}
+
 pub fn set_remote_url(
     git_repo: &git2::Repository,
     remote_name: &str,
@@ -1217,6 +1679,11 @@ const INVALID_REFSPEC_CHARS: [char; 5] = [':', '^', '?', '[', ']'];
 pub enum GitFetchError {
     #[error("No git remote named '{0}'")]
     NoSuchRemote(String),
+    #[error(
+        "Git remote named '{name}' is reserved for fetching from a URL",
+        name = REMOTE_NAME_FOR_ANONYMOUS_FETCH
+    )]
+    RemoteReservedForAnonymousFetch,
     #[error(
         "Invalid branch pattern provided. Patterns may not contain the characters `{chars}`",
         chars = INVALID_REFSPEC_CHARS.iter().join("`, `")
@@ -1224,6 +1691,10 @@ pub enum GitFetchError {
     InvalidBranchPattern,
     #[error("Failed to import Git refs")]
     GitImportError(#[from] GitImportError),
+    #[error("Failed to run git fetch: {0}")]
+    GitCommandFailed(String),
+    #[error("Fetching with these options requires running the `git` executable")]
+    GitCommandDisabled,
     // TODO: I'm sure there are other errors possible, such as transport-level errors.
     #[error("Unexpected git error when fetching")]
     InternalGitError(#[from] git2::Error),
@@ -1236,6 +1707,10 @@ pub struct GitFetchStats {
     pub default_branch: Option<String>,
     /// Changes made by the import.
     pub import_stats: GitImportStats,
+    /// Commits at the shallow boundary before the fetch, whose parents have
+    /// been fetched. Since the index recorded them as root commits, it should
+    /// be rebuilt.
+    pub unshallowed_commits: Vec<CommitId>,
 }
 
 #[tracing::instrument(skip(mut_repo, git_repo, callbacks))]
@@ -1248,110 +1723,576 @@ pub fn fetch(
     git_settings: &GitSettings,
     depth: Option<NonZeroU32>,
 ) -> Result<GitFetchStats, GitFetchError> {
-    // Perform a `git fetch` on the local git repo, updating the remote-tracking
-    // branches in the git repo.
-    let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
+    let remote = git_repo.find_remote(remote_name).map_err(|err| {
         if is_remote_not_found_err(&err) {
             GitFetchError::NoSuchRemote(remote_name.to_string())
         } else {
             GitFetchError::InternalGitError(err)
         }
     })?;
-    let mut fetch_options = git2::FetchOptions::new();
-    let mut proxy_options = git2::ProxyOptions::new();
-    proxy_options.auto();
-    fetch_options.proxy_options(proxy_options);
-    let callbacks = callbacks.into_git();
-    fetch_options.remote_callbacks(callbacks);
-    if let Some(depth) = depth {
-        fetch_options.depth(depth.get().try_into().unwrap_or(i32::MAX));
+    let branch_names = fetched_branch_patterns(&remote, remote_name, branch_names)?;
+    let refspecs = branch_fetch_refspecs(remote_name, &branch_names)?;
+    fetch_from_remote(
+        mut_repo,
+        remote,
+        &refspecs,
+        |ref_name| is_fetched_branch_or_tag(ref_name, remote_name, &branch_names),
+        callbacks,
+        git_settings,
+        depth,
+    )
+}
+
+/// Options for [`fetch_with_git_command()`] that libgit2 doesn't support.
+#[derive(Clone, Debug, Default)]
+pub struct GitCommandFetchOptions<'a> {
+    /// Commits passed as `--negotiation-tip` arguments, so only they and their
+    /// ancestors are reported to the remote as already present. This can make
+    /// the negotiation converge faster in large repos.
+    pub negotiation_tips: &'a [CommitId],
+    /// Partial clone filter spec such as `blob:none`, passed as `--filter`.
+    /// Git marks the remote as a promisor remote, and the objects left out
+    /// are fetched on demand when they are read.
+    pub filter: Option<&'a str>,
+    /// Fetch the full history of a shallow repo, passed as `--unshallow` if
+    /// the repo is shallow.
+    pub unshallow: bool,
+}
+
+/// Like `fetch()`, but runs the `git` executable to support the given
+/// `options`.
+///
+/// This requires `git.subprocess` to be enabled, and `git` to be installed.
+/// Since `git` talks to the remote by itself, it uses Git's credential helpers
+/// instead of the `callbacks`. The callbacks are used to look up the remote's
+/// default branch and to report the progress of the fetch.
+#[tracing::instrument(skip(mut_repo, git_repo, callbacks))]
+pub fn fetch_with_git_command(
+    mut_repo: &mut MutableRepo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    branch_names: &[StringPattern],
+    options: &GitCommandFetchOptions,
+    mut callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
+) -> Result<GitFetchStats, GitFetchError> {
+    if !git_settings.subprocess {
+        return Err(GitFetchError::GitCommandDisabled);
     }
-    // At this point, we are only updating Git's remote tracking branches, not the
-    // local branches.
-    let refspecs: Vec<_> = branch_names
-        .iter()
-        .map(|pattern| {
-            pattern
-                .to_glob()
-                .filter(|glob| !glob.contains(INVALID_REFSPEC_CHARS))
-                .map(|glob| format!("+refs/heads/{glob}:refs/remotes/{remote_name}/{glob}"))
-        })
-        .collect::<Option<_>>()
-        .ok_or(GitFetchError::InvalidBranchPattern)?;
+    let remote = git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitFetchError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitFetchError::InternalGitError(err)
+        }
+    })?;
+    let branch_names = fetched_branch_patterns(&remote, remote_name, branch_names)?;
+    let refspecs = branch_fetch_refspecs(remote_name, &branch_names)?;
     if refspecs.is_empty() {
-        // Don't fall back to the base refspecs.
-        let stats = GitFetchStats::default();
-        return Ok(stats);
+        return Ok(GitFetchStats::default());
     }
-    tracing::debug!("remote.download");
-    remote.download(&refspecs, Some(&mut fetch_options))?;
-    tracing::debug!("remote.prune");
-    remote.prune(None)?;
-    tracing::debug!("remote.update_tips");
-    remote.update_tips(
-        None,
-        git2::RemoteUpdateFlags::empty(),
-        git2::AutotagOption::Unspecified,
-        None,
-    )?;
-    // TODO: We could make it optional to get the default branch since we only care
-    // about it on clone.
-    let mut default_branch = None;
-    if let Ok(default_ref_buf) = remote.default_branch() {
-        if let Some(default_ref) = default_ref_buf.as_str() {
-            // LocalBranch here is the local branch on the remote, so it's really the remote
-            // branch
-            if let Some(RefName::LocalBranch(branch_name)) = parse_git_ref(default_ref) {
-                tracing::debug!(default_branch = branch_name);
-                default_branch = Some(branch_name);
+    let progress_cb = callbacks.progress.take();
+    let default_branch = remote_default_branch_with_git_command(git_repo, remote_name);
+
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["fetch", "--prune"]);
+    if progress_cb.is_some() {
+        git.arg("--progress");
+    }
+    let root_commit_id = mut_repo.store().root_commit_id();
+    for commit_id in options.negotiation_tips {
+        // The root commit doesn't exist in the Git repo.
+        if commit_id != root_commit_id {
+            git.arg(format!("--negotiation-tip={}", commit_id.hex()));
+        }
+    }
+    if let Some(filter) = options.filter {
+        git.arg(format!("--filter={filter}"));
+    }
+    let old_shallow_commits = if options.unshallow {
+        read_shallow_commits(git_repo).map_err(GitImportError::from_git)?
+    } else {
+        vec![]
+    };
+    if !old_shallow_commits.is_empty() {
+        git.arg("--unshallow");
+    }
+    git.arg(remote_name);
+    git.args(&refspecs);
+    let trace_negotiation = tracing::enabled!(tracing::Level::DEBUG);
+    if trace_negotiation {
+        // Trace2 events are written to stderr as JSON lines.
+        git.env("GIT_TRACE2_EVENT", "2");
+    }
+    git.current_dir(git_repo.path());
+    tracing::debug!(?git, "running git fetch");
+    let mut child = git
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| GitFetchError::GitCommandFailed(err.to_string()))?;
+    let mut progress_cb = progress_cb;
+    let mut error_lines = vec![];
+    let mut handle_stderr_line = |line: &str| {
+        if line.starts_with('{') {
+            if !trace_negotiation {
+                return;
+            }
+            let Ok(event) = serde_json::from_str::<serde_json::Value>(line) else {
+                return;
+            };
+            if event["key"] == "total_rounds" {
+                tracing::debug!(rounds = %event["value"], "fetch negotiation finished");
             }
+        } else if let Some(overall) = parse_git_fetch_progress(line) {
+            if let Some(cb) = &mut progress_cb {
+                cb(&Progress {
+                    bytes_downloaded: None,
+                    overall,
+                });
+            }
+        } else if !line.is_empty() {
+            error_lines.push(line.to_owned());
+        }
+    };
+    // Progress updates are terminated by "\r" rather than "\n", so read the
+    // output byte by byte to report them as they come.
+    let mut line = vec![];
+    for byte in io::BufReader::new(child.stderr.take().unwrap())
+        .bytes()
+        .chain([Ok(b'\n')])
+    {
+        let byte = byte.map_err(|err| GitFetchError::GitCommandFailed(err.to_string()))?;
+        if byte == b'\r' || byte == b'\n' {
+            handle_stderr_line(&String::from_utf8_lossy(&line));
+            line.clear();
+        } else {
+            line.push(byte);
         }
     }
-    tracing::debug!("remote.disconnect");
-    remote.disconnect()?;
+    let status = child
+        .wait()
+        .map_err(|err| GitFetchError::GitCommandFailed(err.to_string()))?;
+    if !status.success() {
+        return Err(GitFetchError::GitCommandFailed(error_lines.join("\n")));
+    }
+
+    let unshallowed_commits = if old_shallow_commits.is_empty() {
+        vec![]
+    } else {
+        let new_shallow_commits =
+            read_shallow_commits(git_repo).map_err(GitImportError::from_git)?;
+        old_shallow_commits
+            .into_iter()
+            .filter(|id| !new_shallow_commits.contains(id))
+            .collect_vec()
+    };
+    if !unshallowed_commits.is_empty() {
+        // The ancestors of the previously shallow commits weren't imported
+        // along with them.
+        let git_backend =
+            get_git_backend(mut_repo.store()).ok_or(GitImportError::UnexpectedBackend)?;
+        let mut parent_ids = vec![];
+        for id in &unshallowed_commits {
+            let git_commit = git_repo.find_commit(Oid::from_bytes(id.as_bytes()).unwrap())?;
+            parent_ids.extend(
+                git_commit
+                    .parent_ids()
+                    .map(|oid| CommitId::from_bytes(oid.as_bytes())),
+            );
+        }
+        git_backend
+            .import_head_commits(&parent_ids)
+            .map_err(GitImportError::InternalBackend)?;
+    }
 
-    // Import the remote-tracking branches into the jj repo and update jj's
-    // local branches. We also import local tags since remote tags should have
-    // been merged by Git.
     tracing::debug!("import_refs");
     let import_stats = import_some_refs(mut_repo, git_settings, |ref_name| {
-        to_remote_branch(ref_name, remote_name)
-            .map(|branch| branch_names.iter().any(|pattern| pattern.matches(branch)))
-            .unwrap_or_else(|| matches!(ref_name, RefName::Tag(_)))
+        is_fetched_branch_or_tag(ref_name, remote_name, &branch_names)
     })?;
-    let stats = GitFetchStats {
+    Ok(GitFetchStats {
         default_branch,
         import_stats,
-    };
-    Ok(stats)
+        unshallowed_commits,
+    })
 }
 
-#[derive(Error, Debug, PartialEq)]
-pub enum GitPushError {
-    #[error("No git remote named '{0}'")]
-    NoSuchRemote(String),
-    #[error(
-        "Git remote named '{name}' is reserved for local Git repository",
-        name = REMOTE_NAME_FOR_LOCAL_GIT_REPO
-    )]
-    RemoteReservedForLocalGitRepo,
-    #[error("Refs in unexpected location: {0:?}")]
-    RefInUnexpectedLocation(Vec<String>),
-    #[error("Remote rejected the update of some refs (do you have permission to push to {0:?}?)")]
-    RefUpdateRejected(Vec<String>),
-    // TODO: I'm sure there are other errors possible, such as transport-level errors,
-    // and errors caused by the remote rejecting the push.
-    #[error("Unexpected git error when pushing")]
-    InternalGitError(#[from] git2::Error),
+/// Parses a progress line of `git fetch --progress` such as `Receiving
+/// objects:  45% (9/20)` into the overall progress.
+fn parse_git_fetch_progress(line: &str) -> Option<f32> {
+    let rest = line.strip_prefix("Receiving objects:")?;
+    let (percent, _) = rest.trim_start().split_once('%')?;
+    let percent: u8 = percent.parse().ok()?;
+    Some(f32::from(percent) / 100.0)
 }
 
-#[derive(Clone, Debug)]
-pub struct GitBranchPushTargets {
-    pub branch_updates: Vec<(String, BookmarkPushUpdate)>,
+/// Returns the commits at the shallow boundary of the Git repo, or an empty
+/// list if the repo isn't shallow.
+fn read_shallow_commits(git_repo: &git2::Repository) -> io::Result<Vec<CommitId>> {
+    let content = match fs::read_to_string(common_git_dir(git_repo).join("shallow")) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| CommitId::try_from_hex(line.trim()).ok())
+        .collect())
 }
 
-pub struct GitRefUpdate {
-    pub qualified_name: String,
+/// Returns the patterns of the branches to fetch from the remote.
+///
+/// Fetching all branches means fetching the branches the remote is configured
+/// to fetch.
+fn fetched_branch_patterns(
+    remote: &git2::Remote<'_>,
+    remote_name: &str,
+    branch_names: &[StringPattern],
+) -> Result<Vec<StringPattern>, GitFetchError> {
+    match configured_fetch_globs(remote, remote_name) {
+        Some(globs)
+            if branch_names
+                .iter()
+                .any(|pattern| pattern.to_glob().as_deref() == Some("*")) =>
+        {
+            globs
+                .iter()
+                .map(|glob| StringPattern::glob(glob))
+                .try_collect()
+                .map_err(|_| GitFetchError::InvalidBranchPattern)
+        }
+        _ => Ok(branch_names.to_vec()),
+    }
+}
+
+/// Returns the branch globs that the remote is configured to fetch, or `None`
+/// if it fetches all branches or has refspecs other than
+/// `+refs/heads/<glob>:refs/remotes/<remote>/<glob>`.
+fn configured_fetch_globs(remote: &git2::Remote<'_>, remote_name: &str) -> Option<Vec<String>> {
+    let dst_prefix = format!("refs/remotes/{remote_name}/");
+    let globs: Vec<String> = remote
+        .fetch_refspecs()
+        .ok()?
+        .iter()
+        .map(|refspec| {
+            let (src, dst) = refspec?.trim_start_matches('+').split_once(':')?;
+            let glob = src.strip_prefix("refs/heads/")?;
+            (dst.strip_prefix(&dst_prefix)? == glob).then(|| glob.to_owned())
+        })
+        .collect::<Option<_>>()?;
+    (!globs.is_empty() && !globs.iter().any(|glob| glob == "*")).then_some(globs)
+}
+
+/// Fetches all branches, tags, and notes from the named remote into the
+/// matching local ref namespaces, and imports them.
+///
+/// Unlike `fetch()`, remote branches are fetched as local Git branches
+/// (`refs/heads/*`), so they replace the local bookmarks as if they had been
+/// updated in the underlying Git repo. Local Git refs that don't exist on the
+/// remote are deleted.
+#[tracing::instrument(skip(mut_repo, git_repo, callbacks))]
+pub fn fetch_mirror(
+    mut_repo: &mut MutableRepo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
+) -> Result<GitFetchStats, GitFetchError> {
+    let remote = git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitFetchError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitFetchError::InternalGitError(err)
+        }
+    })?;
+    let refspecs = MIRRORED_REF_NAMESPACES
+        .iter()
+        .map(|namespace| format!("+{namespace}*:{namespace}*"))
+        .collect_vec();
+    fetch_from_remote(
+        mut_repo,
+        remote,
+        &refspecs,
+        |ref_name| matches!(ref_name, RefName::LocalBranch(_) | RefName::Tag(_)),
+        callbacks,
+        git_settings,
+        None,
+    )
+}
+
+/// Ref namespaces copied verbatim by `fetch_mirror()` and `push_mirror()`.
+const MIRRORED_REF_NAMESPACES: [&str; 3] = ["refs/heads/", "refs/tags/", "refs/notes/"];
+
+fn branch_fetch_refspecs(
+    remote_name: &str,
+    branch_names: &[StringPattern],
+) -> Result<Vec<String>, GitFetchError> {
+    branch_names
+        .iter()
+        .map(|pattern| {
+            pattern
+                .to_glob()
+                .filter(|glob| !glob.contains(INVALID_REFSPEC_CHARS))
+                .map(|glob| format!("+refs/heads/{glob}:refs/remotes/{remote_name}/{glob}"))
+        })
+        .collect::<Option<_>>()
+        .ok_or(GitFetchError::InvalidBranchPattern)
+}
+
+/// Whether the ref should be imported after fetching `branch_names` from the
+/// remote. We also import local tags since remote tags should have been
+/// merged by Git.
+fn is_fetched_branch_or_tag(
+    ref_name: &RefName,
+    remote_name: &str,
+    branch_names: &[StringPattern],
+) -> bool {
+    to_remote_branch(ref_name, remote_name)
+        .map(|branch| branch_names.iter().any(|pattern| pattern.matches(branch)))
+        .unwrap_or_else(|| matches!(ref_name, RefName::Tag(_)))
+}
+
+/// Fetches from the given URL without configuring a remote for it.
+///
+/// The fetched branches are stored as remote branches of the
+/// [`REMOTE_NAME_FOR_ANONYMOUS_FETCH`] remote, replacing the ones from any
+/// previous fetch from a URL. They are never tracked automatically.
+#[tracing::instrument(skip(mut_repo, git_repo, callbacks))]
+pub fn fetch_url(
+    mut_repo: &mut MutableRepo,
+    git_repo: &git2::Repository,
+    url: &str,
+    branch_names: &[StringPattern],
+    callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
+    depth: Option<NonZeroU32>,
+) -> Result<GitFetchStats, GitFetchError> {
+    let remote_name = REMOTE_NAME_FOR_ANONYMOUS_FETCH;
+    match git_repo.find_remote(remote_name) {
+        Ok(_) => return Err(GitFetchError::RemoteReservedForAnonymousFetch),
+        Err(err) if is_remote_not_found_err(&err) => {}
+        Err(err) => return Err(err.into()),
+    }
+    let remote = git_repo.remote_anonymous(url)?;
+    // Anonymous remotes can't be pruned, so start from an empty namespace.
+    for git_ref in git_repo.references_glob(&format!("refs/remotes/{remote_name}/*"))? {
+        git_ref?.delete()?;
+    }
+    let git_settings = GitSettings {
+        auto_local_bookmark: false,
+        ..git_settings.clone()
+    };
+    let refspecs = branch_fetch_refspecs(remote_name, branch_names)?;
+    fetch_from_remote(
+        mut_repo,
+        remote,
+        &refspecs,
+        |ref_name| is_fetched_branch_or_tag(ref_name, remote_name, branch_names),
+        callbacks,
+        &git_settings,
+        depth,
+    )
+}
+
+fn fetch_from_remote(
+    mut_repo: &mut MutableRepo,
+    mut remote: git2::Remote<'_>,
+    refspecs: &[String],
+    git_ref_filter: impl Fn(&RefName) -> bool,
+    callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
+    depth: Option<NonZeroU32>,
+) -> Result<GitFetchStats, GitFetchError> {
+    // Perform a `git fetch` on the local git repo, updating the remote-tracking
+    // branches in the git repo.
+    let mut fetch_options = git2::FetchOptions::new();
+    let mut proxy_options = git2::ProxyOptions::new();
+    proxy_options.auto();
+    fetch_options.proxy_options(proxy_options);
+    let callbacks = callbacks.into_git();
+    fetch_options.remote_callbacks(callbacks);
+    if let Some(depth) = depth {
+        fetch_options.depth(depth.get().try_into().unwrap_or(i32::MAX));
+    }
+    if refspecs.is_empty() {
+        // Don't fall back to the base refspecs.
+        let stats = GitFetchStats::default();
+        return Ok(stats);
+    }
+    tracing::debug!("remote.download");
+    remote.download(refspecs, Some(&mut fetch_options))?;
+    if remote.name().is_some() {
+        tracing::debug!("remote.prune");
+        remote.prune(None)?;
+    }
+    tracing::debug!("remote.update_tips");
+    remote.update_tips(
+        None,
+        git2::RemoteUpdateFlags::empty(),
+        git2::AutotagOption::Unspecified,
+        None,
+    )?;
+    // TODO: We could make it optional to get the default branch since we only care
+    // about it on clone.
+    let default_branch = remote_default_branch(&remote);
+    tracing::debug!("remote.disconnect");
+    remote.disconnect()?;
+
+    // Import the fetched refs into the jj repo and update jj's local branches.
+    tracing::debug!("import_refs");
+    let import_stats = import_some_refs(mut_repo, git_settings, git_ref_filter)?;
+    let stats = GitFetchStats {
+        default_branch,
+        import_stats,
+        unshallowed_commits: vec![],
+    };
+    Ok(stats)
+}
+
+/// Returns the branch that the HEAD of the connected remote points to.
+fn remote_default_branch(remote: &git2::Remote<'_>) -> Option<String> {
+    let default_ref_buf = remote.default_branch().ok()?;
+    // LocalBranch here is the local branch on the remote, so it's really the remote
+    // branch
+    match parse_git_ref(default_ref_buf.as_str()?) {
+        Some(RefName::LocalBranch(branch_name)) => {
+            tracing::debug!(default_branch = branch_name);
+            Some(branch_name)
+        }
+        _ => None,
+    }
+}
+
+/// Looks up the default branch of the remote by running `git ls-remote`, so
+/// that the remote is accessed the same way as by `git fetch`.
+fn remote_default_branch_with_git_command(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+) -> Option<String> {
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["ls-remote", "--symref", remote_name, "HEAD"]);
+    git.current_dir(git_repo.path());
+    tracing::debug!(?git, "running git ls-remote");
+    // If the remote can't be reached, git fetch will report the error.
+    let output = git
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_ls_remote_default_branch(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parses the `ref: refs/heads/<branch>\tHEAD` line printed by
+/// `git ls-remote --symref`.
+fn parse_ls_remote_default_branch(output: &str) -> Option<String> {
+    let default_ref = output.lines().find_map(|line| {
+        let (target, name) = line.strip_prefix("ref: ")?.split_once('\t')?;
+        (name == "HEAD").then_some(target)
+    })?;
+    match parse_git_ref(default_ref) {
+        Some(RefName::LocalBranch(branch_name)) => {
+            tracing::debug!(default_branch = branch_name);
+            Some(branch_name)
+        }
+        _ => None,
+    }
+}
+
+/// A ref update that the remote refused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RejectedRefUpdate {
+    /// Full name of the ref on the remote.
+    pub qualified_name: String,
+    /// Why the update was rejected, as reported by the remote. For example,
+    /// "remote rejected: hook declined".
+    pub reason: Option<String>,
+}
+
+impl fmt::Display for RejectedRefUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            Some(reason) => write!(f, "{} ({reason})", self.qualified_name),
+            None => write!(
+                f,
+                "{} (do you have permission to push to it?)",
+                self.qualified_name
+            ),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum GitPushError {
+    #[error("No git remote named '{0}'")]
+    NoSuchRemote(String),
+    #[error(
+        "Git remote named '{name}' is reserved for local Git repository",
+        name = REMOTE_NAME_FOR_LOCAL_GIT_REPO
+    )]
+    RemoteReservedForLocalGitRepo,
+    #[error("Refs in unexpected location: {0:?}")]
+    RefInUnexpectedLocation(Vec<String>),
+    #[error(
+        "Remote rejected the update of some refs: {}",
+        .0.iter().map(|update| update.to_string()).join(", ")
+    )]
+    RefUpdateRejected(Vec<RejectedRefUpdate>),
+    #[error("Failed to run git push: {0}")]
+    GitCommandFailed(String),
+    #[error("The pre-push hook {0}")]
+    HookFailed(String),
+    #[error("Git remote named '{0}' doesn't support push options")]
+    PushOptionsNotSupported(String),
+    #[error("Git remote named '{0}' doesn't support atomic pushes")]
+    AtomicPushNotSupported(String),
+    #[error("Pushing with these options requires running the `git` executable")]
+    GitCommandDisabled,
+    // TODO: I'm sure there are other errors possible, such as transport-level errors,
+    // and errors caused by the remote rejecting the push.
+    #[error("Unexpected git error when pushing")]
+    InternalGitError(#[from] git2::Error),
+}
+
+impl GitPushError {
+    /// Returns true if the error was likely caused by a transient transport
+    /// failure, so pushing again may succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            GitPushError::InternalGitError(err) => {
+                matches!(
+                    err.class(),
+                    git2::ErrorClass::Net | git2::ErrorClass::Http | git2::ErrorClass::Ssh
+                ) && !matches!(
+                    err.code(),
+                    git2::ErrorCode::Auth | git2::ErrorCode::Certificate
+                )
+            }
+            GitPushError::NoSuchRemote(_)
+            | GitPushError::RemoteReservedForLocalGitRepo
+            | GitPushError::RefInUnexpectedLocation(_)
+            | GitPushError::RefUpdateRejected(_)
+            | GitPushError::GitCommandFailed(_)
+            | GitPushError::HookFailed(_)
+            | GitPushError::PushOptionsNotSupported(_)
+            | GitPushError::AtomicPushNotSupported(_)
+            | GitPushError::GitCommandDisabled => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GitBranchPushTargets {
+    pub branch_updates: Vec<(String, BookmarkPushUpdate)>,
+}
+
+pub struct GitRefUpdate {
+    pub qualified_name: String,
     /// Expected position on the remote or None if we expect the ref to not
     /// exist on the remote
     ///
@@ -1368,7 +2309,108 @@ pub fn push_branches(
     targets: &GitBranchPushTargets,
     callbacks: RemoteCallbacks<'_>,
 ) -> Result<(), GitPushError> {
-    let ref_updates = targets
+    let ref_updates = branch_ref_updates(targets);
+    push_updates(mut_repo, git_repo, remote_name, &ref_updates, callbacks)?;
+
+    // TODO: add support for partially pushed refs? we could update the view
+    // excluding rejected refs, but the transaction would be aborted anyway
+    // if we returned an Err.
+    set_pushed_remote_branches(mut_repo, remote_name, targets);
+    Ok(())
+}
+
+/// Pushes the specified branches so that the remote applies either all or
+/// none of the updates, and updates the repo view accordingly.
+///
+/// libgit2 doesn't support atomic pushes, so this runs the `git` executable,
+/// which requires `git.subprocess` to be enabled. Nothing is pushed if the
+/// remote doesn't support atomic pushes.
+pub fn push_branches_atomic(
+    mut_repo: &mut MutableRepo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    targets: &GitBranchPushTargets,
+    push_options: &[String],
+    callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
+) -> Result<(), GitPushError> {
+    let ref_updates = branch_ref_updates(targets);
+    let options = GitCommandPushOptions {
+        atomic: true,
+        push_options,
+    };
+    push_updates_with_git(
+        mut_repo,
+        git_repo,
+        remote_name,
+        &ref_updates,
+        &options,
+        callbacks,
+        git_settings,
+    )?;
+    set_pushed_remote_branches(mut_repo, remote_name, targets);
+    Ok(())
+}
+
+/// Pushes the specified branches, sending `push_options` to the server, and
+/// updates the repo view accordingly.
+///
+/// The push fails if the remote doesn't support push options.
+pub fn push_branches_with_options(
+    mut_repo: &mut MutableRepo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    targets: &GitBranchPushTargets,
+    push_options: &[String],
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitPushError> {
+    let ref_updates = branch_ref_updates(targets);
+    push_ref_updates(
+        mut_repo,
+        git_repo,
+        remote_name,
+        &ref_updates,
+        push_options,
+        callbacks,
+    )?;
+    set_pushed_remote_branches(mut_repo, remote_name, targets);
+    Ok(())
+}
+
+/// Pushes the specified branches with the `git` executable, sending
+/// `push_options` to the server, and updates the repo view accordingly.
+///
+/// This requires `git.subprocess` to be enabled. Unlike libgit2, `git`
+/// reports why the remote rejected each ref.
+pub fn push_branches_with_git(
+    mut_repo: &mut MutableRepo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    targets: &GitBranchPushTargets,
+    push_options: &[String],
+    callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
+) -> Result<(), GitPushError> {
+    let ref_updates = branch_ref_updates(targets);
+    let options = GitCommandPushOptions {
+        atomic: false,
+        push_options,
+    };
+    push_updates_with_git(
+        mut_repo,
+        git_repo,
+        remote_name,
+        &ref_updates,
+        &options,
+        callbacks,
+        git_settings,
+    )?;
+    set_pushed_remote_branches(mut_repo, remote_name, targets);
+    Ok(())
+}
+
+fn branch_ref_updates(targets: &GitBranchPushTargets) -> Vec<GitRefUpdate> {
+    targets
         .branch_updates
         .iter()
         .map(|(branch_name, update)| GitRefUpdate {
@@ -1376,12 +2418,14 @@ pub fn push_branches(
             expected_current_target: update.old_target.clone(),
             new_target: update.new_target.clone(),
         })
-        .collect_vec();
-    push_updates(mut_repo, git_repo, remote_name, &ref_updates, callbacks)?;
+        .collect()
+}
 
-    // TODO: add support for partially pushed refs? we could update the view
-    // excluding rejected refs, but the transaction would be aborted anyway
-    // if we returned an Err.
+fn set_pushed_remote_branches(
+    mut_repo: &mut MutableRepo,
+    remote_name: &str,
+    targets: &GitBranchPushTargets,
+) {
     for (branch_name, update) in &targets.branch_updates {
         let git_ref_name = format!("refs/remotes/{remote_name}/{branch_name}");
         let new_remote_ref = RemoteRef {
@@ -1391,8 +2435,24 @@ pub fn push_branches(
         mut_repo.set_git_ref_target(&git_ref_name, new_remote_ref.target.clone());
         mut_repo.set_remote_bookmark(branch_name, remote_name, new_remote_ref);
     }
+}
 
-    Ok(())
+/// Records that the remote bookmark is now expected to point to `target`.
+///
+/// The next push of the bookmark will use `target` as the expected location of
+/// the remote bookmark. The Git remote-tracking ref is updated by the next
+/// [`export_refs()`], so the change isn't reverted by a later import.
+pub fn set_expected_remote_bookmark(
+    mut_repo: &mut MutableRepo,
+    remote_name: &str,
+    branch_name: &str,
+    target: &CommitId,
+) {
+    let new_remote_ref = RemoteRef {
+        target: RefTarget::normal(target.clone()),
+        state: RemoteRefState::Tracking,
+    };
+    mut_repo.set_remote_bookmark(branch_name, remote_name, new_remote_ref);
 }
 
 /// Pushes the specified Git refs without updating the repo view.
@@ -1402,6 +2462,17 @@ pub fn push_updates(
     remote_name: &str,
     updates: &[GitRefUpdate],
     callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitPushError> {
+    push_ref_updates(repo, git_repo, remote_name, updates, &[], callbacks)
+}
+
+fn push_ref_updates(
+    repo: &dyn Repo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    updates: &[GitRefUpdate],
+    remote_push_options: &[String],
+    callbacks: RemoteCallbacks<'_>,
 ) -> Result<(), GitPushError> {
     let mut qualified_remote_refs_expected_locations = HashMap::new();
     let mut refspecs = vec![];
@@ -1430,6 +2501,7 @@ pub fn push_updates(
         remote_name,
         &qualified_remote_refs_expected_locations,
         &refspecs,
+        remote_push_options,
         callbacks,
     )
 }
@@ -1440,6 +2512,7 @@ fn push_refs(
     remote_name: &str,
     qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
     refspecs: &[String],
+    remote_push_options: &[String],
     callbacks: RemoteCallbacks<'_>,
 ) -> Result<(), GitPushError> {
     if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
@@ -1456,12 +2529,15 @@ fn push_refs(
         .keys()
         .copied()
         .collect();
+    let mut rejection_reasons = HashMap::new();
     let mut failed_push_negotiations = vec![];
     let push_result = {
         let mut push_options = git2::PushOptions::new();
         let mut proxy_options = git2::ProxyOptions::new();
         proxy_options.auto();
         push_options.proxy_options(proxy_options);
+        push_options
+            .remote_push_options(&remote_push_options.iter().map(String::as_str).collect_vec());
         let mut callbacks = callbacks.into_git();
         callbacks.push_negotiation(|updates| {
             for update in updates {
@@ -1525,7 +2601,9 @@ fn push_refs(
         });
         callbacks.push_update_reference(|refname, status| {
             // The status is Some if the ref update was rejected
-            if status.is_none() {
+            if let Some(status) = status {
+                rejection_reasons.insert(refname.to_owned(), format!("remote rejected: {status}"));
+            } else {
                 remaining_remote_refs.remove(refname);
             }
             Ok(())
@@ -1546,21 +2624,815 @@ fn push_refs(
             failed_push_negotiations,
         ))
     } else {
-        push_result?;
-        if remaining_remote_refs.is_empty() {
+        match push_result {
+            // libgit2 checks the push-options capability before pushing.
+            Err(err)
+                if !remote_push_options.is_empty() && err.class() == git2::ErrorClass::Invalid =>
+            {
+                return Err(GitPushError::PushOptionsNotSupported(
+                    remote_name.to_owned(),
+                ));
+            }
+            result => result?,
+        }
+        if remaining_remote_refs.is_empty() {
             Ok(())
         } else {
             Err(GitPushError::RefUpdateRejected(
                 remaining_remote_refs
                     .iter()
                     .sorted()
-                    .map(|name| name.to_string())
+                    .map(|name| RejectedRefUpdate {
+                        qualified_name: name.to_string(),
+                        reason: rejection_reasons.remove(*name),
+                    })
                     .collect(),
             ))
         }
     }
 }
 
+/// Options of `git push` that libgit2 doesn't support.
+struct GitCommandPushOptions<'a> {
+    /// Apply either all or none of the updates on the remote.
+    atomic: bool,
+    /// Options sent to the server with the push-options capability.
+    push_options: &'a [String],
+}
+
+/// Pushes the specified Git refs with `git push`.
+///
+/// The remote refs are checked with the same rules as the push negotiation
+/// done by `push_refs()`, by connecting to the remote with the `callbacks`.
+/// Their actual locations are then passed as `--force-with-lease` arguments,
+/// so the push fails if they moved in the meantime. Since `git` talks to the
+/// remote by itself, it uses Git's credential helpers. The messages sent by
+/// the remote are reported to the sideband progress callback.
+fn push_updates_with_git(
+    repo: &dyn Repo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    updates: &[GitRefUpdate],
+    options: &GitCommandPushOptions,
+    mut callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
+) -> Result<(), GitPushError> {
+    if !git_settings.subprocess {
+        return Err(GitPushError::GitCommandDisabled);
+    }
+    if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        return Err(GitPushError::RemoteReservedForLocalGitRepo);
+    }
+    let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitPushError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitPushError::InternalGitError(err)
+        }
+    })?;
+    let mut sideband_progress_cb = callbacks.sideband_progress.take();
+    let actual_remote_locations: HashMap<String, CommitId> = {
+        let mut proxy_options = git2::ProxyOptions::new();
+        proxy_options.auto();
+        let connection = remote.connect_auth(
+            git2::Direction::Push,
+            Some(callbacks.into_git()),
+            Some(proxy_options),
+        )?;
+        connection
+            .list()?
+            .iter()
+            .map(|head| {
+                (
+                    head.name().to_owned(),
+                    CommitId::from_bytes(head.oid().as_bytes()),
+                )
+            })
+            .collect()
+    };
+    let mut failed_push_negotiations = vec![];
+    for update in updates {
+        let actual_remote_location = actual_remote_locations.get(&update.qualified_name);
+        if allow_push(
+            repo.index(),
+            actual_remote_location,
+            update.expected_current_target.as_ref(),
+            update.new_target.as_ref(),
+        )
+        .is_err()
+        {
+            failed_push_negotiations.push(update.qualified_name.clone());
+        }
+    }
+    if !failed_push_negotiations.is_empty() {
+        failed_push_negotiations.sort();
+        return Err(GitPushError::RefInUnexpectedLocation(
+            failed_push_negotiations,
+        ));
+    }
+
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["push", "--porcelain"]);
+    // The pre-push hook is run by run_pre_push_hook() instead.
+    git.arg("--no-verify");
+    if options.atomic {
+        git.arg("--atomic");
+    }
+    for option in options.push_options {
+        git.arg(format!("--push-option={option}"));
+    }
+    for update in updates {
+        // An empty expected value means the ref must not exist on the remote.
+        let actual = actual_remote_locations
+            .get(&update.qualified_name)
+            .map_or_else(String::new, |id| id.hex());
+        git.arg(format!(
+            "--force-with-lease={}:{actual}",
+            update.qualified_name
+        ));
+    }
+    git.arg(remote_name);
+    for update in updates {
+        // The lease allows non-fast-forward updates, so the refspecs don't
+        // need to be prefixed with `+`.
+        let src = update
+            .new_target
+            .as_ref()
+            .map_or_else(String::new, |id| id.hex());
+        git.arg(format!("{src}:{}", update.qualified_name));
+    }
+    // Don't specify it by GIT_DIR/--git-dir. On Windows, the "\\?\" path might
+    // not be supported by git.
+    git.current_dir(git_repo.path());
+    tracing::debug!(?git, "running git push");
+    let mut child = git
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| GitPushError::GitCommandFailed(err.to_string()))?;
+    let mut stdout = child.stdout.take().unwrap();
+    let stdout_reader = std::thread::spawn(move || {
+        let mut buf = vec![];
+        stdout.read_to_end(&mut buf).map(|_| buf)
+    });
+    let mut error_lines = vec![];
+    for line in io::BufReader::new(child.stderr.take().unwrap()).split(b'\n') {
+        let line = line.map_err(|err| GitPushError::GitCommandFailed(err.to_string()))?;
+        match (line.strip_prefix(b"remote: "), &mut sideband_progress_cb) {
+            (Some(message), Some(cb)) => {
+                // Drop the spaces git appends to clear the end of the line.
+                let len = message
+                    .iter()
+                    .rposition(|&b| b != b' ')
+                    .map_or(0, |i| i + 1);
+                cb(&[&message[..len], b"\n"].concat());
+            }
+            _ => error_lines.push(String::from_utf8_lossy(&line).into_owned()),
+        }
+    }
+    let stdout = stdout_reader
+        .join()
+        .unwrap()
+        .map_err(|err| GitPushError::GitCommandFailed(err.to_string()))?;
+    let status = child
+        .wait()
+        .map_err(|err| GitPushError::GitCommandFailed(err.to_string()))?;
+    if status.success() {
+        return Ok(());
+    }
+    let stderr = error_lines.join("\n");
+    if stderr.contains("does not support --atomic push") {
+        return Err(GitPushError::AtomicPushNotSupported(remote_name.to_owned()));
+    }
+    if stderr.contains("does not support push options") {
+        return Err(GitPushError::PushOptionsNotSupported(
+            remote_name.to_owned(),
+        ));
+    }
+    // Rejected refs are reported as "!\t<src>:<dst>\t<summary>". If any ref is
+    // rejected, the other refs are reported as "(atomic push failed)".
+    let mut stale_refs = vec![];
+    let mut rejected_refs = vec![];
+    for line in String::from_utf8_lossy(&stdout).lines() {
+        let Some(("!", refspec, summary)) = line.split('\t').collect_tuple() else {
+            continue;
+        };
+        let Some((_, dst_refname)) = refspec.split_once(':') else {
+            continue;
+        };
+        if summary.contains("(stale info)") {
+            stale_refs.push(dst_refname.to_owned());
+        } else if !summary.contains("(atomic push failed)") {
+            rejected_refs.push(RejectedRefUpdate {
+                qualified_name: dst_refname.to_owned(),
+                reason: parse_push_rejection_summary(summary),
+            });
+        }
+    }
+    if !stale_refs.is_empty() {
+        stale_refs.sort();
+        Err(GitPushError::RefInUnexpectedLocation(stale_refs))
+    } else if !rejected_refs.is_empty() {
+        rejected_refs.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
+        Err(GitPushError::RefUpdateRejected(rejected_refs))
+    } else {
+        Err(GitPushError::GitCommandFailed(stderr.trim().to_owned()))
+    }
+}
+
+/// Turns a `git push --porcelain` summary such as "[remote rejected] (hook
+/// declined)" into a reason such as "remote rejected: hook declined".
+///
+/// With the report-status-v2 protocol, the detail is the message sent by the
+/// remote for the ref.
+fn parse_push_rejection_summary(summary: &str) -> Option<String> {
+    let summary = summary.trim();
+    let (kind, detail) = summary.strip_prefix('[')?.split_once(']')?;
+    match detail
+        .trim()
+        .strip_prefix('(')
+        .and_then(|d| d.strip_suffix(')'))
+    {
+        Some(detail) => Some(format!("{kind}: {detail}")),
+        None => Some(kind.to_owned()),
+    }
+}
+
+/// Runs the `pre-push` hook of the Git repo, if there is an executable one.
+///
+/// Like `git push`, this passes the remote name and URL as arguments, and a
+/// line `<local ref> <local oid> <remote ref> <remote oid>` per ref update on
+/// stdin. The output of the hook is sent to stderr. An error is returned if
+/// the hook exits with a non-zero status.
+pub fn run_pre_push_hook(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    targets: &GitBranchPushTargets,
+) -> Result<(), GitPushError> {
+    let remote = git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitPushError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitPushError::InternalGitError(err)
+        }
+    })?;
+    let Some(hook_path) = find_hook(git_repo, "pre-push")? else {
+        return Ok(());
+    };
+    let zero_oid = Oid::zero().to_string();
+    let mut input = String::new();
+    for update in branch_ref_updates(targets) {
+        let (local_ref, local_oid) = match &update.new_target {
+            Some(id) => (update.qualified_name.as_str(), id.hex()),
+            None => ("(delete)", zero_oid.clone()),
+        };
+        let remote_oid = update
+            .expected_current_target
+            .as_ref()
+            .map_or_else(|| zero_oid.clone(), |id| id.hex());
+        input.push_str(&format!(
+            "{local_ref} {local_oid} {} {remote_oid}\n",
+            update.qualified_name
+        ));
+    }
+    let mut hook = Command::new(&hook_path);
+    hook.arg(remote_name)
+        .arg(remote.url().unwrap_or(remote_name))
+        .current_dir(git_repo.workdir().unwrap_or_else(|| git_repo.path()))
+        .env("GIT_DIR", git_repo.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(std::io::stderr()));
+    let status = run_hook(&mut hook, input.as_bytes())
+        .map_err(|err| GitPushError::HookFailed(format!("couldn't be run: {err}")))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(GitPushError::HookFailed(format!("failed ({status})")))
+    }
+}
+
+fn run_hook(hook: &mut Command, input: &[u8]) -> io::Result<ExitStatus> {
+    let mut child = hook.spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    // The hook doesn't have to read its input.
+    match stdin.write_all(input) {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => return Err(err),
+        _ => {}
+    }
+    drop(stdin);
+    child.wait()
+}
+
+#[derive(Debug, Error)]
+pub enum GitHookError {
+    #[error("The {hook} hook couldn't be run")]
+    Spawn {
+        hook: &'static str,
+        #[source]
+        source: io::Error,
+    },
+    #[error("The {hook} hook failed ({status})")]
+    Failed {
+        hook: &'static str,
+        status: ExitStatus,
+    },
+    #[error("Failed to write the Git index for the {hook} hook")]
+    WriteIndex {
+        hook: &'static str,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Unexpected git error when running hook")]
+    InternalGitError(#[from] git2::Error),
+}
+
+/// Runs the `pre-commit` hook of the Git repo, if there is an executable one.
+///
+/// The Git index is kept at the working-copy parent, so the hook is instead
+/// given a temporary index containing `tree` as the staged changes. Like
+/// `git commit`, this sets `GIT_DIR` and `GIT_INDEX_FILE`. The output of the
+/// hook is sent to stderr. An error is returned if the hook exits with a
+/// non-zero status.
+pub fn run_pre_commit_hook(
+    git_repo: &git2::Repository,
+    tree: &MergedTree,
+) -> Result<(), GitHookError> {
+    const HOOK: &str = "pre-commit";
+    if find_hook(git_repo, HOOK)?.is_none() {
+        return Ok(());
+    }
+    let index_dir = tempfile::Builder::new()
+        .prefix("jj-index-")
+        .tempdir_in(git_repo.path())
+        .map_err(|source| GitHookError::WriteIndex { hook: HOOK, source })?;
+    let index_path = index_dir.path().join("index");
+    // A conflicted tree is staged as its first side since the index can't
+    // represent jj's conflicts.
+    let tree_id = tree.id().to_merge().first().clone();
+    let git_tree = git_repo.find_tree(Oid::from_bytes(tree_id.as_bytes())?)?;
+    let mut index = git2::Index::open(&index_path)?;
+    index.read_tree(&git_tree)?;
+    index.write()?;
+    run_git_hook(git_repo, HOOK, &[], &[("GIT_INDEX_FILE", &index_path)])
+}
+
+/// Runs the `post-checkout` hook of the Git repo, if there is an executable
+/// one.
+///
+/// Like `git checkout`, this passes the old and new `HEAD` (or the null object
+/// ID if unset) and `1` as arguments.
+pub fn run_post_checkout_hook(
+    git_repo: &git2::Repository,
+    old_head: Option<&CommitId>,
+    new_head: Option<&CommitId>,
+) -> Result<(), GitHookError> {
+    let to_hex = |id: Option<&CommitId>| id.map_or_else(|| Oid::zero().to_string(), |id| id.hex());
+    let args = [to_hex(old_head), to_hex(new_head), "1".to_owned()];
+    run_git_hook(git_repo, "post-checkout", &args, &[])
+}
+
+fn run_git_hook(
+    git_repo: &git2::Repository,
+    name: &'static str,
+    args: &[String],
+    envs: &[(&str, &Path)],
+) -> Result<(), GitHookError> {
+    let Some(hook_path) = find_hook(git_repo, name)? else {
+        return Ok(());
+    };
+    let status = Command::new(&hook_path)
+        .args(args)
+        .current_dir(git_repo.workdir().unwrap_or_else(|| git_repo.path()))
+        .env("GIT_DIR", git_repo.path())
+        .envs(envs.iter().copied())
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(std::io::stderr()))
+        .status()
+        .map_err(|source| GitHookError::Spawn { hook: name, source })?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(GitHookError::Failed { hook: name, status })
+    }
+}
+
+/// Returns the Git directory shared by all worktrees of the repo.
+fn common_git_dir(git_repo: &git2::Repository) -> PathBuf {
+    match fs::read_to_string(git_repo.path().join("commondir")) {
+        Ok(common_dir) => git_repo.path().join(common_dir.trim_end()),
+        Err(_) => git_repo.path().to_owned(),
+    }
+}
+
+/// Returns the path to the given hook if it exists and is executable.
+fn find_hook(git_repo: &git2::Repository, name: &str) -> Result<Option<PathBuf>, git2::Error> {
+    let hooks_dir = match git_repo.config()?.get_path("core.hooksPath") {
+        // A relative path is relative to where hooks are run.
+        Ok(path) => git_repo
+            .workdir()
+            .unwrap_or_else(|| git_repo.path())
+            .join(path),
+        // Hooks are shared by linked worktrees.
+        Err(err) if err.code() == git2::ErrorCode::NotFound => {
+            common_git_dir(git_repo).join("hooks")
+        }
+        Err(err) => return Err(err),
+    };
+    let path = hooks_dir.join(name);
+    let Ok(metadata) = path.metadata() else {
+        return Ok(None);
+    };
+    #[cfg(unix)]
+    let is_executable = {
+        use std::os::unix::fs::PermissionsExt as _;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    };
+    #[cfg(not(unix))]
+    let is_executable = metadata.is_file();
+    Ok(is_executable.then_some(path))
+}
+
+/// Registers `workdir` as a linked worktree of the given Git repo, like `git
+/// worktree add --detach --no-checkout`.
+///
+/// The worktree is named after the basename of `workdir`, with a numeric suffix
+/// if the name is already taken. HEAD of the new worktree is left unset, and
+/// the worktree files aren't checked out. Returns the path to the per-worktree
+/// Git directory.
+pub fn add_worktree(git_repo: &gix::Repository, workdir: &Path) -> Result<PathBuf, PathError> {
+    let workdir = workdir.canonicalize().context(workdir)?;
+    let worktrees_dir = git_repo
+        .common_dir()
+        .canonicalize()
+        .context(git_repo.common_dir())?
+        .join("worktrees");
+    fs::create_dir_all(&worktrees_dir).context(&worktrees_dir)?;
+    let base_name = workdir
+        .file_name()
+        .map_or_else(|| "worktree".into(), |name| name.to_string_lossy());
+    let mut worktree_git_dir = worktrees_dir.join(base_name.as_ref());
+    for i in 1.. {
+        match fs::create_dir(&worktree_git_dir) {
+            Ok(()) => break,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                worktree_git_dir = worktrees_dir.join(format!("{base_name}{i}"));
+            }
+            Err(err) => return Err(err).context(&worktree_git_dir),
+        }
+    }
+    let write = |path: PathBuf, contents: String| fs::write(&path, contents).context(&path);
+    write(
+        worktree_git_dir.join("gitdir"),
+        format!("{}\n", workdir.join(".git").display()),
+    )?;
+    write(worktree_git_dir.join("commondir"), "../..\n".to_owned())?;
+    write(
+        worktree_git_dir.join("HEAD"),
+        format!("ref: {UNBORN_ROOT_REF_NAME}\n"),
+    )?;
+    write(
+        workdir.join(".git"),
+        format!("gitdir: {}\n", worktree_git_dir.display()),
+    )?;
+    Ok(worktree_git_dir)
+}
+
+#[derive(Error, Debug)]
+pub enum GitCommitGraphError {
+    #[error("Failed to write the commit-graph file: {0}")]
+    GitCommandFailed(String),
+    #[error("Writing the commit-graph file requires running the `git` executable")]
+    GitCommandDisabled,
+}
+
+/// Writes Git's commit-graph file for all commits reachable from refs.
+///
+/// The commit-graph speeds up history walks, both in git and in the Git
+/// backend. This runs `git commit-graph write --reachable`, so `git` must be
+/// in the `PATH`, and `git_settings.subprocess` must be set. If debug logging
+/// is enabled, the time it takes to walk the history is logged before and
+/// after the file is written.
+#[tracing::instrument(skip_all)]
+pub fn write_commit_graph(
+    git_repo: &git2::Repository,
+    git_settings: &GitSettings,
+) -> Result<(), GitCommitGraphError> {
+    if !git_settings.subprocess {
+        return Err(GitCommitGraphError::GitCommandDisabled);
+    }
+    let log_walk_duration = |when: &str| {
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            // Open the repo again so cached commits aren't reused.
+            match git2::Repository::open(git_repo.path()).and_then(|repo| walk_history(&repo)) {
+                Ok((count, duration)) => {
+                    tracing::debug!(?duration, count, "walked history {when} commit-graph write");
+                }
+                Err(err) => tracing::debug!(%err, "failed to walk history"),
+            }
+        }
+    };
+    log_walk_duration("before");
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["commit-graph", "write", "--reachable"]);
+    git.current_dir(git_repo.path());
+    let output = git
+        .output()
+        .map_err(|err| GitCommitGraphError::GitCommandFailed(err.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitCommitGraphError::GitCommandFailed(
+            stderr.trim().to_owned(),
+        ));
+    }
+    log_walk_duration("after");
+    Ok(())
+}
+
+/// Counts the loose objects in the Git repo, ignoring errors.
+pub fn count_loose_objects(git_repo: &git2::Repository) -> u64 {
+    let Ok(entries) = git_repo.path().join("objects").read_dir() else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit())
+        })
+        .filter_map(|entry| entry.path().read_dir().ok())
+        .map(|objects| objects.filter_map(|entry| entry.ok()).count() as u64)
+        .sum()
+}
+
+/// Walks all commits reachable from refs, returning the number of commits and
+/// the time it took.
+fn walk_history(git_repo: &git2::Repository) -> Result<(usize, std::time::Duration), git2::Error> {
+    let start = std::time::Instant::now();
+    let mut revwalk = git_repo.revwalk()?;
+    revwalk.push_glob("*")?;
+    let mut count = 0;
+    for oid in revwalk {
+        git_repo.find_commit(oid?)?;
+        count += 1;
+    }
+    Ok((count, start.elapsed()))
+}
+
+#[derive(Error, Debug)]
+pub enum GitSignTagsError {
+    #[error("No such tag: {0}")]
+    NoSuchTag(String),
+    #[error("Tag {0} is already an annotated tag")]
+    AlreadyAnnotated(String),
+    #[error("Failed to sign tag {name}")]
+    Sign {
+        name: String,
+        #[source]
+        source: SignError,
+    },
+    #[error("Unexpected git error when signing tags")]
+    InternalGitError(#[from] git2::Error),
+}
+
+/// A tag replaced by [`sign_tags()`].
+#[derive(Clone, Debug)]
+pub struct SignedTag {
+    pub name: String,
+    /// The object the lightweight tag pointed to.
+    pub old_oid: git2::Oid,
+    /// The signed tag object.
+    pub new_oid: git2::Oid,
+}
+
+/// Signs the given lightweight tags in the Git repo.
+///
+/// Each tag is replaced with an annotated tag pointing to the same object,
+/// tagged by `tagger`. The signature created by `sign_fn` is appended to the
+/// tag object as `git tag -s` would do, so `git verify-tag` can check it.
+/// Annotated tags are refused since they may have been created (and signed) by
+/// someone else. The refs are updated in a single Git ref transaction, so
+/// either all or none of the tags are replaced.
+pub fn sign_tags(
+    git_repo: &git2::Repository,
+    names: &[String],
+    tagger: &Signature,
+    mut sign_fn: impl FnMut(&[u8]) -> SignResult<Vec<u8>>,
+) -> Result<Vec<SignedTag>, GitSignTagsError> {
+    let tagger_line = format_git_tagger_line(
+        &tagger.name,
+        &tagger.email,
+        tagger.timestamp.timestamp.0.div_euclid(1000),
+        tagger.timestamp.tz_offset,
+    );
+    let mut signed_tags = vec![];
+    for name in names {
+        let full_name = format!("refs/tags/{name}");
+        let git_ref = match git_repo.find_reference(&full_name) {
+            Ok(git_ref) => git_ref,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => {
+                return Err(GitSignTagsError::NoSuchTag(name.clone()));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let oid = git_ref
+            .resolve()?
+            .target()
+            .expect("resolved ref should point to an object");
+        let target = git_repo.find_object(oid, None)?;
+        if target.kind() == Some(git2::ObjectType::Tag) {
+            return Err(GitSignTagsError::AlreadyAnnotated(name.clone()));
+        }
+        let mut content = format!(
+            "object {}\ntype {}\ntag {name}\ntagger {tagger_line}\n\n{name}\n",
+            target.id(),
+            target.kind().map_or("commit", |kind| kind.str()),
+        )
+        .into_bytes();
+        let signature = sign_fn(&content).map_err(|source| GitSignTagsError::Sign {
+            name: name.clone(),
+            source,
+        })?;
+        content.extend_from_slice(&signature);
+        let new_oid = git_repo.odb()?.write(git2::ObjectType::Tag, &content)?;
+        signed_tags.push(SignedTag {
+            name: name.clone(),
+            old_oid: target.id(),
+            new_oid,
+        });
+    }
+    update_tag_refs(git_repo, &signed_tags, |tag| tag.new_oid, "sign tag")?;
+    Ok(signed_tags)
+}
+
+/// Points the tags replaced by [`sign_tags()`] back to their original objects.
+pub fn unsign_tags(
+    git_repo: &git2::Repository,
+    signed_tags: &[SignedTag],
+) -> Result<(), git2::Error> {
+    update_tag_refs(git_repo, signed_tags, |tag| tag.old_oid, "unsign tag")
+}
+
+fn update_tag_refs(
+    git_repo: &git2::Repository,
+    signed_tags: &[SignedTag],
+    target: impl Fn(&SignedTag) -> git2::Oid,
+    message: &str,
+) -> Result<(), git2::Error> {
+    let mut transaction = git_repo.transaction()?;
+    for tag in signed_tags {
+        let full_name = format!("refs/tags/{}", tag.name);
+        transaction.lock_ref(&full_name)?;
+        transaction.set_target(&full_name, target(tag), None, message)?;
+    }
+    transaction.commit()
+}
+
+fn format_git_tagger_line(name: &str, email: &str, seconds: i64, tz_offset: i32) -> String {
+    let sign = if tz_offset < 0 { '-' } else { '+' };
+    let tz_offset = tz_offset.abs();
+    format!(
+        "{name} <{email}> {seconds} {sign}{:02}{:02}",
+        tz_offset / 60,
+        tz_offset % 60
+    )
+}
+
+/// Pushes all local Git branches, tags, and notes to the remote, overwriting
+/// the remote refs.
+///
+/// Remote branches that are known to have been deleted locally (i.e. the
+/// remote-tracking branch exists but the local branch doesn't) are deleted on
+/// the remote. Unlike `push_branches()`, the repo view isn't updated.
+///
+/// Like `git push --force-with-lease`, the remote branches are expected to be
+/// at their remote-tracking branches, and nothing is pushed if any of them
+/// moved since they were last fetched. Tags and notes are overwritten without
+/// checking.
+pub fn push_mirror(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitPushError> {
+    if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        return Err(GitPushError::RemoteReservedForLocalGitRepo);
+    }
+    let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitPushError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitPushError::InternalGitError(err)
+        }
+    })?;
+    let mut local_ref_names = vec![];
+    for namespace in MIRRORED_REF_NAMESPACES {
+        for git_ref in git_repo.references_glob(&format!("{namespace}*"))? {
+            if let Some(name) = git_ref?.name() {
+                local_ref_names.push(name.to_owned());
+            }
+        }
+    }
+    let remote_tracking_prefix = format!("refs/remotes/{remote_name}/");
+    let mut expected_branch_locations = HashMap::new();
+    let mut deleted_ref_names = vec![];
+    for git_ref in git_repo.references_glob(&format!("{remote_tracking_prefix}*"))? {
+        let git_ref = git_ref?;
+        let Some(branch) = git_ref
+            .name()
+            .and_then(|name| name.strip_prefix(&remote_tracking_prefix))
+        else {
+            continue;
+        };
+        if branch == "HEAD" {
+            continue;
+        }
+        let name = format!("refs/heads/{branch}");
+        if !local_ref_names.contains(&name) {
+            deleted_ref_names.push(name.clone());
+        }
+        expected_branch_locations.insert(name, git_ref.target());
+    }
+    let refspecs = itertools::chain(
+        local_ref_names.iter().map(|name| format!("+{name}:{name}")),
+        deleted_ref_names.iter().map(|name| format!(":{name}")),
+    )
+    .collect_vec();
+    if refspecs.is_empty() {
+        return Ok(());
+    }
+
+    let mut rejected_refs = vec![];
+    let mut failed_push_negotiations = vec![];
+    let push_result = {
+        let mut push_options = git2::PushOptions::new();
+        let mut proxy_options = git2::ProxyOptions::new();
+        proxy_options.auto();
+        push_options.proxy_options(proxy_options);
+        let mut callbacks = callbacks.into_git();
+        callbacks.push_negotiation(|updates| {
+            for update in updates {
+                let dst_refname = update
+                    .dst_refname()
+                    .expect("Expect reference name to be valid UTF-8");
+                if !dst_refname.starts_with("refs/heads/") {
+                    continue;
+                }
+                // A branch without a remote-tracking branch isn't expected to
+                // exist on the remote.
+                let expected_oid = expected_branch_locations
+                    .get(dst_refname)
+                    .copied()
+                    .flatten()
+                    .unwrap_or_else(Oid::zero);
+                let actual_oid = update.src();
+                // Pushing a branch to where it already is is fine.
+                if actual_oid != expected_oid && actual_oid != update.dst() {
+                    tracing::info!(
+                        "Cannot mirror {dst_refname}; it is unexpectedly at {actual_oid} on \
+                         the server as opposed to the expected {expected_oid}",
+                    );
+                    failed_push_negotiations.push(dst_refname.to_owned());
+                }
+            }
+            if failed_push_negotiations.is_empty() {
+                Ok(())
+            } else {
+                Err(git2::Error::from_str("failed push negotiation"))
+            }
+        });
+        callbacks.push_update_reference(|refname, status| {
+            // The status is Some if the ref update was rejected
+            if let Some(status) = status {
+                rejected_refs.push(RejectedRefUpdate {
+                    qualified_name: refname.to_owned(),
+                    reason: Some(format!("remote rejected: {status}")),
+                });
+            }
+            Ok(())
+        });
+        push_options.remote_callbacks(callbacks);
+        remote.push(&refspecs, Some(&mut push_options))
+    };
+    if !failed_push_negotiations.is_empty() {
+        // See push_refs() for why the error returned by the push isn't used.
+        assert!(push_result.is_err());
+        failed_push_negotiations.sort();
+        return Err(GitPushError::RefInUnexpectedLocation(
+            failed_push_negotiations,
+        ));
+    }
+    push_result?;
+    if rejected_refs.is_empty() {
+        Ok(())
+    } else {
+        rejected_refs.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
+        Err(GitPushError::RefUpdateRejected(rejected_refs))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum PushAllowReason {
     NormalMatch,
@@ -57,6 +57,7 @@ use crate::backend::CommitId;
 use crate::backend::Conflict;
 use crate::backend::ConflictId;
 use crate::backend::ConflictTerm;
+use crate::backend::CopyDetectionOptions;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
@@ -159,6 +160,9 @@ pub struct GitBackend {
     empty_tree_id: TreeId,
     extra_metadata_store: TableStore,
     cached_extra_metadata: Mutex<Option<Arc<ReadonlyTable>>>,
+    /// Whether objects missing from a partial clone can be fetched by running
+    /// the `git` executable.
+    fetch_missing_objects: bool,
 }
 
 impl GitBackend {
@@ -166,7 +170,11 @@ impl GitBackend {
         "git"
     }
 
-    fn new(base_repo: gix::ThreadSafeRepository, extra_metadata_store: TableStore) -> Self {
+    fn new(
+        settings: &UserSettings,
+        base_repo: gix::ThreadSafeRepository,
+        extra_metadata_store: TableStore,
+    ) -> Self {
         let repo = Mutex::new(base_repo.to_thread_local());
         let root_commit_id = CommitId::from_bytes(&[0; HASH_LENGTH]);
         let root_change_id = ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]);
@@ -179,6 +187,7 @@ impl GitBackend {
             empty_tree_id,
             extra_metadata_store,
             cached_extra_metadata: Mutex::new(None),
+            fetch_missing_objects: settings.git_settings().subprocess,
         }
     }
 
@@ -194,7 +203,7 @@ impl GitBackend {
             gix_open_opts_from_settings(settings),
         )
         .map_err(GitBackendInitError::InitRepository)?;
-        Self::init_with_repo(store_path, git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, git_repo_path, git_repo)
     }
 
     /// Initializes backend by creating a new Git repo at the specified
@@ -218,7 +227,7 @@ impl GitBackend {
         )
         .map_err(GitBackendInitError::InitRepository)?;
         let git_repo_path = workspace_root.join(".git");
-        Self::init_with_repo(store_path, &git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, &git_repo_path, git_repo)
     }
 
     /// Initializes backend with an existing Git repo at the specified path.
@@ -238,10 +247,11 @@ impl GitBackend {
             gix_open_opts_from_settings(settings),
         )
         .map_err(GitBackendInitError::OpenRepository)?;
-        Self::init_with_repo(store_path, git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, git_repo_path, git_repo)
     }
 
     fn init_with_repo(
+        settings: &UserSettings,
         store_path: &Path,
         git_repo_path: &Path,
         git_repo: gix::ThreadSafeRepository,
@@ -271,7 +281,7 @@ impl GitBackend {
                 .map_err(GitBackendInitError::Path)?;
         };
         let extra_metadata_store = TableStore::init(extra_path, HASH_LENGTH);
-        Ok(GitBackend::new(git_repo, extra_metadata_store))
+        Ok(GitBackend::new(settings, git_repo, extra_metadata_store))
     }
 
     pub fn load(
@@ -294,7 +304,7 @@ impl GitBackend {
         )
         .map_err(GitBackendLoadError::OpenRepository)?;
         let extra_metadata_store = TableStore::load(store_path.join("extra"), HASH_LENGTH);
-        Ok(GitBackend::new(repo, extra_metadata_store))
+        Ok(GitBackend::new(settings, repo, extra_metadata_store))
     }
 
     fn lock_git_repo(&self) -> MutexGuard<'_, gix::Repository> {
@@ -410,14 +420,35 @@ impl GitBackend {
     }
 
     fn read_file_sync(&self, id: &FileId) -> BackendResult<Box<dyn Read>> {
+        let data = self.read_blob_data(id)?;
+        Ok(Box::new(Cursor::new(data)))
+    }
+
+    /// Reads the blob, fetching it from the promisor remote if it's missing
+    /// from a partial clone and `git.subprocess` is enabled.
+    fn read_blob_data(&self, id: &impl ObjectId) -> BackendResult<Vec<u8>> {
         let git_blob_id = validate_git_object_id(id)?;
         let locked_repo = self.lock_git_repo();
-        let mut blob = locked_repo
-            .find_object(git_blob_id)
-            .map_err(|err| map_not_found_err(err, id))?
+        let object = match locked_repo.find_object(git_blob_id) {
+            Ok(object) => object,
+            Err(gix::object::find::existing::Error::NotFound { .. })
+                if has_promisor_remote(&locked_repo) =>
+            {
+                if !self.fetch_missing_objects {
+                    return Err(to_read_object_err(
+                        "Object is missing from the partial clone. Set `git.subprocess = true` \
+                         to fetch it from the promisor remote with `git`.",
+                        id,
+                    ));
+                }
+                return fetch_missing_blob(self.git_repo_path(), id);
+            }
+            Err(err) => return Err(map_not_found_err(err, id)),
+        };
+        let mut blob = object
             .try_into_blob()
             .map_err(|err| to_read_object_err(err, id))?;
-        Ok(Box::new(Cursor::new(blob.take_data())))
+        Ok(blob.take_data())
     }
 
     fn new_diff_platform(&self) -> BackendResult<gix::diff::blob::Platform> {
@@ -802,10 +833,16 @@ fn recreate_no_gc_refs(
     Ok(())
 }
 
-fn run_git_gc(git_dir: &Path) -> Result<(), GitGcError> {
+fn run_git_gc(git_dir: &Path, keep_newer: SystemTime) -> Result<(), GitGcError> {
+    // "@<seconds> <offset>" is the raw timestamp format understood by Git's
+    // date parser.
+    let keep_newer_secs = keep_newer
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
     let mut git = Command::new("git");
     git.arg("--git-dir=."); // turn off discovery
     git.arg("gc");
+    git.arg(format!("--prune=@{keep_newer_secs} +0000"));
     // Don't specify it by GIT_DIR/--git-dir. On Windows, the "\\?\" path might
     // not be supported by git.
     git.current_dir(git_dir);
@@ -817,6 +854,36 @@ fn run_git_gc(git_dir: &Path) -> Result<(), GitGcError> {
     Ok(())
 }
 
+/// Returns true if the repo is a partial clone, i.e. objects may be missing
+/// locally and can be fetched from a promisor remote.
+fn has_promisor_remote(repo: &gix::Repository) -> bool {
+    let config = repo.config_snapshot();
+    config.string("extensions.partialClone").is_some()
+        || repo.remote_names().iter().any(|name| {
+            config
+                .boolean(format!("remote.{name}.promisor").as_str())
+                .unwrap_or(false)
+        })
+}
+
+/// Runs `git cat-file`, which lets Git fetch the missing blob from the
+/// promisor remote.
+fn fetch_missing_blob(git_dir: &Path, id: &impl ObjectId) -> BackendResult<Vec<u8>> {
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["cat-file", "blob", &id.hex()]);
+    git.current_dir(git_dir);
+    let output = git.output().map_err(|err| to_read_object_err(err, id))?;
+    if !output.status.success() {
+        let message = format!(
+            "Failed to fetch missing object from promisor remote: {}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        );
+        return Err(to_read_object_err(message, id));
+    }
+    Ok(output.stdout)
+}
+
 fn validate_git_object_id(id: &impl ObjectId) -> BackendResult<gix::ObjectId> {
     if id.as_bytes().len() != HASH_LENGTH {
         return Err(BackendError::InvalidHashLength {
@@ -967,15 +1034,9 @@ impl Backend for GitBackend {
     }
 
     async fn read_symlink(&self, _path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
-        let git_blob_id = validate_git_object_id(id)?;
-        let locked_repo = self.lock_git_repo();
-        let mut blob = locked_repo
-            .find_object(git_blob_id)
-            .map_err(|err| map_not_found_err(err, id))?
-            .try_into_blob()
-            .map_err(|err| to_read_object_err(err, id))?;
-        let target = String::from_utf8(blob.take_data())
-            .map_err(|err| to_invalid_utf8_err(err.utf8_error(), id))?;
+        let data = self.read_blob_data(id)?;
+        let target =
+            String::from_utf8(data).map_err(|err| to_invalid_utf8_err(err.utf8_error(), id))?;
         Ok(target)
     }
 
@@ -1312,6 +1373,7 @@ impl Backend for GitBackend {
         paths: Option<&[RepoPathBuf]>,
         root_id: &CommitId,
         head_id: &CommitId,
+        options: &CopyDetectionOptions,
     ) -> BackendResult<BoxStream<BackendResult<CopyRecord>>> {
         let repo = self.git_repo();
         let root_tree = self.read_tree_for_commit(&repo, root_id)?;
@@ -1358,11 +1420,11 @@ impl Backend for GitBackend {
             .map_err(|err| BackendError::Other(err.into()))?;
         change_platform.track_path();
         change_platform.track_rewrites(Some(gix::diff::Rewrites {
-            copies: Some(gix::diff::rewrites::Copies {
+            copies: options.find_copies.then_some(gix::diff::rewrites::Copies {
                 source: gix::diff::rewrites::CopySource::FromSetOfModifiedFiles,
-                percentage: Some(0.5),
+                percentage: Some(options.similarity_threshold),
             }),
-            percentage: Some(0.5),
+            percentage: Some(options.similarity_threshold),
             limit: 1000,
         }));
         change_platform
@@ -1394,8 +1456,8 @@ impl Backend for GitBackend {
         // mtime <= keep_newer? (it won't be consistent with no-gc refs
         // preserved by the keep_newer timestamp though)
         // TODO: remove unreachable extras table segments
-        // TODO: pass in keep_newer to "git gc" command
-        run_git_gc(self.git_repo_path()).map_err(|err| BackendError::Other(err.into()))?;
+        run_git_gc(self.git_repo_path(), keep_newer)
+            .map_err(|err| BackendError::Other(err.into()))?;
         // Since "git gc" will move loose refs into packed refs, in-memory
         // packed-refs cache should be invalidated without relying on mtime.
         git_repo.refs.force_refresh_packed_buffer().ok();
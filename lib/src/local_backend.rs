@@ -43,6 +43,7 @@ use crate::backend::CommitId;
 use crate::backend::Conflict;
 use crate::backend::ConflictId;
 use crate::backend::ConflictTerm;
+use crate::backend::CopyDetectionOptions;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
@@ -338,6 +339,7 @@ impl Backend for LocalBackend {
         _paths: Option<&[RepoPathBuf]>,
         _root: &CommitId,
         _head: &CommitId,
+        _options: &CopyDetectionOptions,
     ) -> BackendResult<BoxStream<BackendResult<CopyRecord>>> {
         Ok(Box::pin(stream::empty()))
     }
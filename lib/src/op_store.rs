@@ -32,6 +32,7 @@ use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::Timestamp;
 use crate::content_hash::ContentHash;
+use crate::content_hash::DigestUpdate;
 use crate::merge::Merge;
 use crate::object_id::id_type;
 use crate::object_id::HexPrefix;
@@ -264,7 +265,7 @@ pub struct BookmarkTarget<'a> {
 
 /// Represents the way the repo looks at a given time, just like how a Tree
 /// object represents how the file system looks at a given time.
-#[derive(ContentHash, PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct View {
     /// All head commits
     pub head_ids: HashSet<CommitId>,
@@ -272,16 +273,47 @@ pub struct View {
     pub tags: BTreeMap<String, RefTarget>,
     pub remote_views: BTreeMap<String, RemoteView>,
     pub git_refs: BTreeMap<String, RefTarget>,
-    /// The commit the Git HEAD points to.
-    // TODO: Support multiple Git worktrees?
+    /// The commit the Git HEAD points to, per workspace. Only the workspaces
+    /// that share their working copy with Git have an entry.
     // TODO: Do we want to store the current bookmark name too?
-    pub git_head: RefTarget,
+    pub git_heads: BTreeMap<WorkspaceId, RefTarget>,
     // The commit that *should be* checked out in the workspace. Note that the working copy
     // (.jj/working_copy/) has the source of truth about which commit *is* checked out (to be
     // precise: the commit to which we most recently completed an update to).
     pub wc_commit_ids: HashMap<WorkspaceId, CommitId>,
 }
 
+// The Git HEAD of the default workspace is hashed in place of the old single
+// `git_head` field, so the view ID doesn't change unless other workspaces have
+// Git HEAD.
+impl ContentHash for View {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        let default_workspace_id = WorkspaceId::default();
+        self.head_ids.hash(state);
+        self.local_bookmarks.hash(state);
+        self.tags.hash(state);
+        self.remote_views.hash(state);
+        self.git_refs.hash(state);
+        self.git_heads
+            .get(&default_workspace_id)
+            .flatten()
+            .hash(state);
+        self.wc_commit_ids.hash(state);
+        let other_git_heads = self
+            .git_heads
+            .iter()
+            .filter(|&(workspace_id, _)| *workspace_id != default_workspace_id)
+            .collect_vec();
+        if !other_git_heads.is_empty() {
+            state.update(&(other_git_heads.len() as u64).to_le_bytes());
+            for (workspace_id, target) in other_git_heads {
+                workspace_id.hash(state);
+                target.hash(state);
+            }
+        }
+    }
+}
+
 impl View {
     /// Creates new truly empty view.
     ///
@@ -294,7 +326,7 @@ impl View {
             tags: BTreeMap::new(),
             remote_views: BTreeMap::new(),
             git_refs: BTreeMap::new(),
-            git_head: RefTarget::absent(),
+            git_heads: BTreeMap::new(),
             wc_commit_ids: HashMap::new(),
         }
     }
@@ -307,7 +339,7 @@ impl View {
             tags: BTreeMap::new(),
             remote_views: BTreeMap::new(),
             git_refs: BTreeMap::new(),
-            git_head: RefTarget::absent(),
+            git_heads: BTreeMap::new(),
             wc_commit_ids: HashMap::new(),
         }
     }
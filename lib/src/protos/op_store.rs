@@ -120,8 +120,12 @@ pub struct View {
     #[deprecated]
     #[prost(bytes = "vec", tag = "7")]
     pub git_head_legacy: ::prost::alloc::vec::Vec<u8>,
+    /// Git HEAD of the default workspace.
     #[prost(message, optional, tag = "9")]
     pub git_head: ::core::option::Option<RefTarget>,
+    /// Git HEADs of the other workspaces. Older versions ignore this field.
+    #[prost(map = "string, message", tag = "11")]
+    pub git_heads: ::std::collections::HashMap<::prost::alloc::string::String, RefTarget>,
     /// Whether "@git" bookmark have been migrated to remote_targets.
     #[prost(bool, tag = "10")]
     pub has_git_refs_migrated_to_remote: bool,
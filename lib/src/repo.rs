@@ -189,7 +189,7 @@ impl ReadonlyRepo {
         let backend = backend_initializer(user_settings, &store_path)?;
         let backend_path = store_path.join("type");
         fs::write(&backend_path, backend.name()).context(&backend_path)?;
-        let store = Store::new(backend, signer);
+        let store = Store::new(backend, signer, user_settings.commit_cache_capacity());
         let repo_settings = user_settings.with_repo(&repo_path).unwrap();
 
         let op_store_path = repo_path.join("op_store");
@@ -660,6 +660,7 @@ impl RepoLoader {
         let store = Store::new(
             store_factories.load_backend(user_settings, &repo_path.join("store"))?,
             Signer::from_settings(user_settings)?,
+            user_settings.commit_cache_capacity(),
         );
         let repo_settings = user_settings.with_repo(repo_path).unwrap();
         let root_op_data = RootOperationData {
@@ -991,6 +992,12 @@ impl MutableRepo {
         !self.parent_mapping.is_empty()
     }
 
+    /// Returns true if the commit has been rewritten or abandoned in this
+    /// transaction.
+    pub fn has_rewrite(&self, old_id: &CommitId) -> bool {
+        self.parent_mapping.contains_key(old_id)
+    }
+
     /// Calculates new parents for a commit that's currently based on the given
     /// parents. It does that by considering how previous commits have been
     /// rewritten and abandoned.
@@ -1667,10 +1674,33 @@ impl MutableRepo {
         self.view.with_ref(|v| v.git_head().clone())
     }
 
+    pub fn workspace_git_head(&self, workspace_id: &WorkspaceId) -> RefTarget {
+        self.view
+            .with_ref(|v| v.workspace_git_head(workspace_id).clone())
+    }
+
     pub fn set_git_head_target(&mut self, target: RefTarget) {
         self.view_mut().set_git_head_target(target);
     }
 
+    pub fn set_workspace_git_head_target(&mut self, workspace_id: &WorkspaceId, target: RefTarget) {
+        self.view_mut()
+            .set_workspace_git_head_target(workspace_id, target);
+    }
+
+    fn merge_git_head(
+        &mut self,
+        workspace_id: &WorkspaceId,
+        base_target: &RefTarget,
+        other_target: &RefTarget,
+    ) {
+        let view = self.view.get_mut();
+        let index = self.index.as_index();
+        let self_target = view.workspace_git_head(workspace_id);
+        let new_target = merge_ref_targets(index, self_target, base_target, other_target);
+        view.set_workspace_git_head_target(workspace_id, new_target);
+    }
+
     pub fn set_view(&mut self, data: op_store::View) {
         self.view_mut().set_view(data);
         self.view.mark_dirty();
@@ -1767,13 +1797,10 @@ impl MutableRepo {
             self.merge_remote_bookmark(name, remote_name, base_ref, other_ref);
         }
 
-        let new_git_head_target = merge_ref_targets(
-            self.index(),
-            self.view().git_head(),
-            base.git_head(),
-            other.git_head(),
-        );
-        self.set_git_head_target(new_git_head_target);
+        let changed_git_heads = diff_named_ref_targets(base.git_heads(), other.git_heads());
+        for (workspace_id, (base_target, other_target)) in changed_git_heads {
+            self.merge_git_head(workspace_id, base_target, other_target);
+        }
     }
 
     /// Finds and records commits that were rewritten or abandoned between
@@ -141,7 +141,7 @@ pub enum RevsetCommitRef {
     },
     Tags,
     GitRefs,
-    GitHead,
+    GitHead(WorkspaceId),
 }
 
 /// A custom revset filter expression, defined by an extension.
@@ -300,8 +300,13 @@ impl RevsetExpression {
         Rc::new(Self::CommitRef(RevsetCommitRef::GitRefs))
     }
 
+    /// Git HEAD of the default workspace.
     pub fn git_head() -> Rc<Self> {
-        Rc::new(Self::CommitRef(RevsetCommitRef::GitHead))
+        Self::workspace_git_head(WorkspaceId::default())
+    }
+
+    pub fn workspace_git_head(workspace_id: WorkspaceId) -> Rc<Self> {
+        Rc::new(Self::CommitRef(RevsetCommitRef::GitHead(workspace_id)))
     }
 
     pub fn latest(self: &Rc<Self>, count: usize) -> Rc<Self> {
@@ -727,9 +732,16 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         function.expect_no_arguments()?;
         Ok(RevsetExpression::git_refs())
     });
-    map.insert("git_head", |_diagnostics, function, _context| {
+    map.insert("git_head", |_diagnostics, function, context| {
         function.expect_no_arguments()?;
-        Ok(RevsetExpression::git_head())
+        // Without workspace context, resolve to the default workspace's HEAD
+        // as before Git HEAD was stored per workspace.
+        match &context.workspace {
+            Some(ctx) => Ok(RevsetExpression::workspace_git_head(
+                ctx.workspace_id.clone(),
+            )),
+            None => Ok(RevsetExpression::git_head()),
+        }
     });
     map.insert("latest", |diagnostics, function, context| {
         let ([candidates_arg], [count_opt_arg]) = function.expect_arguments()?;
@@ -949,7 +961,7 @@ fn parse_remote_bookmarks_arguments(
     remote_ref_state: Option<RemoteRefState>,
 ) -> Result<Rc<RevsetExpression>, RevsetParseError> {
     let ([], [bookmark_opt_arg, remote_opt_arg]) =
-        function.expect_named_arguments(&["", "remote"])?;
+        function.expect_named_arguments(&["bookmark", "remote"])?;
     let bookmark_pattern = if let Some(bookmark_arg) = bookmark_opt_arg {
         expect_string_pattern(diagnostics, bookmark_arg)?
     } else {
@@ -1934,7 +1946,12 @@ fn resolve_commit_ref(
             }
             Ok(commit_ids)
         }
-        RevsetCommitRef::GitHead => Ok(repo.view().git_head().added_ids().cloned().collect()),
+        RevsetCommitRef::GitHead(workspace_id) => Ok(repo
+            .view()
+            .workspace_git_head(workspace_id)
+            .added_ids()
+            .cloned()
+            .collect()),
     }
 }
 
@@ -2052,9 +2069,12 @@ impl VisibilityResolutionContext<'_> {
                 sources: self.resolve(sources).into(),
                 domain: self.resolve(domain).into(),
             },
-            RevsetExpression::Heads(candidates) => {
-                ResolvedExpression::Heads(self.resolve(candidates).into())
-            }
+            RevsetExpression::Heads(candidates) => match candidates.as_ref() {
+                // Since all() is the ancestors of the visible heads, there's no
+                // need to walk the whole history to find its heads.
+                RevsetExpression::All => self.resolve_visible_heads(),
+                _ => ResolvedExpression::Heads(self.resolve(candidates).into()),
+            },
             RevsetExpression::Roots(candidates) => {
                 ResolvedExpression::Roots(self.resolve(candidates).into())
             }
@@ -2986,6 +3006,16 @@ mod tests {
             },
         )
         "###);
+        insta::assert_debug_snapshot!(
+            parse("remote_bookmarks(remote=bar, bookmark=exact:foo)").unwrap(), @r###"
+        CommitRef(
+            RemoteBookmarks {
+                bookmark_pattern: Exact("foo"),
+                remote_pattern: Substring("bar"),
+                remote_ref_state: None,
+            },
+        )
+        "###);
         insta::assert_debug_snapshot!(
             parse("tracked_remote_bookmarks(foo, remote=bar)").unwrap(), @r###"
         CommitRef(
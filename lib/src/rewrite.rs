@@ -482,7 +482,8 @@ pub struct MoveCommitsStats {
 /// `new_children` can be rewritten, and there will be no cycles in
 /// the resulting graph. `target_commits` should be in reverse
 /// topological order. `target_roots`, if provided, should be a subset
-/// of `target_commits`.
+/// of `target_commits`. Commits that have already been rewritten or
+/// abandoned in `mut_repo` are not rewritten again.
 pub fn move_commits(
     settings: &UserSettings,
     mut_repo: &mut MutableRepo,
@@ -823,6 +824,10 @@ pub fn move_commits(
     // Rebase each commit onto its new parents in the reverse topological order
     // computed above.
     while let Some(old_commit_id) = to_visit.pop() {
+        // Descendants of such commits are rebased onto their replacements.
+        if mut_repo.has_rewrite(&old_commit_id) {
+            continue;
+        }
         let old_commit = to_visit_commits.get(&old_commit_id).unwrap();
         let parent_ids = to_visit_commits_new_parents.get(&old_commit_id).unwrap();
         let new_parent_ids = mut_repo.new_parents(parent_ids);
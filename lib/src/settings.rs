@@ -14,6 +14,7 @@
 
 #![allow(missing_docs)]
 
+use std::num::NonZeroUsize;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -29,6 +30,7 @@ use crate::backend::Timestamp;
 use crate::fmt_util::binary_prefix;
 use crate::fsmonitor::FsmonitorSettings;
 use crate::signing::SignBehavior;
+use crate::store::DEFAULT_COMMIT_CACHE_CAPACITY;
 
 #[derive(Debug, Clone)]
 pub struct UserSettings {
@@ -45,16 +47,28 @@ pub struct RepoSettings {
 #[derive(Debug, Clone)]
 pub struct GitSettings {
     pub auto_local_bookmark: bool,
-    pub abandon_unreachable_commits: bool,
+    pub abandon_policy: GitAbandonPolicy,
+    /// Whether to run the hooks of the Git repo.
+    pub run_hooks: bool,
+    /// Whether to run the `git` executable for operations that libgit2
+    /// doesn't support, such as partial or unshallowing fetches.
+    pub subprocess: bool,
 }
 
 impl GitSettings {
     pub fn from_config(config: &config::Config) -> Self {
+        let abandon_unreachable_commits = config
+            .get_bool("git.abandon-unreachable-commits")
+            .unwrap_or(true);
         GitSettings {
             auto_local_bookmark: config.get_bool("git.auto-local-branch").unwrap_or(false),
-            abandon_unreachable_commits: config
-                .get_bool("git.abandon-unreachable-commits")
-                .unwrap_or(true),
+            abandon_policy: if abandon_unreachable_commits {
+                GitAbandonPolicy::Unreachable
+            } else {
+                GitAbandonPolicy::Never
+            },
+            run_hooks: config.get_bool("git.run-hooks").unwrap_or(false),
+            subprocess: config.get_bool("git.subprocess").unwrap_or(false),
         }
     }
 }
@@ -63,11 +77,56 @@ impl Default for GitSettings {
     fn default() -> Self {
         GitSettings {
             auto_local_bookmark: false,
-            abandon_unreachable_commits: true,
+            abandon_policy: GitAbandonPolicy::Unreachable,
+            run_hooks: false,
+            subprocess: false,
         }
     }
 }
 
+/// When `jj git fetch` runs garbage collection automatically.
+#[derive(Debug, Clone, Default)]
+pub struct GitAutoGcSettings {
+    /// Run garbage collection after this many fetches.
+    pub fetches: Option<u64>,
+    /// Run garbage collection after fetching if there are more loose objects
+    /// than this.
+    pub loose_objects: Option<u64>,
+}
+
+impl GitAutoGcSettings {
+    pub fn from_config(config: &config::Config) -> Result<Self, config::ConfigError> {
+        // A threshold of 0 disables the check rather than running garbage
+        // collection after every fetch.
+        let get_threshold = |key: &str| -> Result<Option<u64>, config::ConfigError> {
+            Ok(config.get::<u64>(key).optional()?.filter(|&n| n > 0))
+        };
+        Ok(GitAutoGcSettings {
+            fetches: get_threshold("git.auto-gc.fetches")?,
+            loose_objects: get_threshold("git.auto-gc.loose-objects")?,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.fetches.is_some() || self.loose_objects.is_some()
+    }
+}
+
+/// Which commits to abandon when importing refs from Git.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GitAbandonPolicy {
+    /// Keep all commits.
+    Never,
+    /// Abandon commits that are no longer reachable from any remote or local
+    /// ref, but were reachable from the previous remote refs.
+    #[default]
+    Unreachable,
+    /// Like `Unreachable`, but also abandon commits that were reachable only
+    /// from the previous local targets of the bookmarks and tags updated by
+    /// the import. Commits not related to the updated refs are kept.
+    All,
+}
+
 /// Commit signing settings, describes how to and if to sign commits.
 #[derive(Debug, Clone, Default)]
 pub struct SignSettings {
@@ -233,6 +292,19 @@ impl UserSettings {
         GitSettings::from_config(&self.config)
     }
 
+    pub fn git_auto_gc_settings(&self) -> Result<GitAutoGcSettings, config::ConfigError> {
+        GitAutoGcSettings::from_config(&self.config)
+    }
+
+    /// Number of commits to keep in memory while a command runs.
+    pub fn commit_cache_capacity(&self) -> NonZeroUsize {
+        self.config
+            .get::<usize>("core.commit-cache-size")
+            .ok()
+            .and_then(NonZeroUsize::new)
+            .unwrap_or_else(|| DEFAULT_COMMIT_CACHE_CAPACITY.try_into().unwrap())
+    }
+
     pub fn max_new_file_size(&self) -> Result<u64, config::ConfigError> {
         let cfg = self
             .config
@@ -52,6 +52,7 @@ use crate::op_store::Operation;
 use crate::op_store::OperationId;
 use crate::op_store::OperationMetadata;
 use crate::op_store::RefTarget;
+use crate::op_store::RefTargetOptionExt as _;
 use crate::op_store::RemoteRef;
 use crate::op_store::RemoteRefState;
 use crate::op_store::RemoteView;
@@ -456,7 +457,19 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
         });
     }
 
-    proto.git_head = ref_target_to_proto(&view.git_head);
+    // The HEAD of the default workspace is stored in the legacy field so the
+    // encoded view doesn't change unless other workspaces have Git HEAD.
+    proto.git_head = ref_target_to_proto(view.git_heads.get(&WorkspaceId::default()).flatten());
+    for (workspace_id, target) in &view.git_heads {
+        if *workspace_id == WorkspaceId::default() {
+            continue;
+        }
+        if let Some(target_proto) = ref_target_to_proto(target) {
+            proto
+                .git_heads
+                .insert(workspace_id.as_str().to_owned(), target_proto);
+        }
+    }
 
     proto
 }
@@ -498,10 +511,23 @@ fn view_from_proto(proto: crate::protos::op_store::View) -> View {
     }
 
     #[allow(deprecated)]
-    if proto.git_head.is_some() {
-        view.git_head = ref_target_from_proto(proto.git_head);
+    let default_git_head = if proto.git_head.is_some() {
+        ref_target_from_proto(proto.git_head)
     } else if !proto.git_head_legacy.is_empty() {
-        view.git_head = RefTarget::normal(CommitId::new(proto.git_head_legacy));
+        RefTarget::normal(CommitId::new(proto.git_head_legacy))
+    } else {
+        RefTarget::absent()
+    };
+    if default_git_head.is_present() {
+        view.git_heads
+            .insert(WorkspaceId::default(), default_git_head);
+    }
+    for (workspace_id, target) in proto.git_heads {
+        let target = ref_target_from_proto(Some(target));
+        if target.is_present() {
+            view.git_heads
+                .insert(WorkspaceId::new(workspace_id), target);
+        }
     }
 
     if !proto.has_git_refs_migrated_to_remote {
@@ -766,7 +792,10 @@ mod tests {
                 "refs/heads/main".to_string() => git_refs_main_target,
                 "refs/heads/feature".to_string() => git_refs_feature_target,
             },
-            git_head: RefTarget::normal(CommitId::from_hex("fff111")),
+            git_heads: btreemap! {
+                WorkspaceId::default() => RefTarget::normal(CommitId::from_hex("fff111")),
+                WorkspaceId::new("test".to_string()) => RefTarget::normal(CommitId::from_hex("fff222")),
+            },
             wc_commit_ids: hashmap! {
                 WorkspaceId::default() => default_wc_commit_id,
                 WorkspaceId::new("test".to_string()) => test_wc_commit_id,
@@ -807,7 +836,7 @@ mod tests {
         // Test exact output so we detect regressions in compatibility
         assert_snapshot!(
             ViewId::new(blake2b_hash(&create_view()).to_vec()).hex(),
-            @"f426676b3a2f7c6b9ec8677cb05ed249d0d244ab7e86a7c51117e2d8a4829db65e55970c761231e2107d303bf3d33a1f2afdd4ed2181f223e99753674b20a35e"
+            @"3654cd5c5ad85095bed47a563f1d2114cccc284a49d5115b4a8e45335a05cd19ce680da620c14a6be6475dcc9032be40a5aed8331069c15d9a38b14f2d9a22e2"
         );
     }
 
@@ -846,6 +875,55 @@ mod tests {
         assert_eq!(read_operation, operation);
     }
 
+    #[test]
+    fn test_git_head_legacy_compat() {
+        let git_head_target = RefTarget::normal(CommitId::from_hex("fff111"));
+        let git_head_default = btreemap! {
+            WorkspaceId::default() => git_head_target.clone(),
+        };
+
+        // Git HEAD of the default workspace is written to the old field
+        let view = View {
+            git_heads: btreemap! {
+                WorkspaceId::default() => git_head_target.clone(),
+                WorkspaceId::new("test".to_string()) => RefTarget::normal(CommitId::from_hex("fff222")),
+            },
+            ..View::empty()
+        };
+        let proto = view_to_proto(&view);
+        assert_eq!(
+            ref_target_from_proto(proto.git_head.clone()),
+            git_head_target
+        );
+        assert_eq!(
+            proto.git_heads.keys().collect_vec(),
+            vec![&"test".to_string()]
+        );
+        assert_eq!(view_from_proto(proto).git_heads, view.git_heads);
+
+        // Absent Git HEAD is encoded as before, and isn't read back
+        let proto = view_to_proto(&View::empty());
+        assert!(proto.git_head.is_some());
+        assert!(proto.git_heads.is_empty());
+        assert!(view_from_proto(proto).git_heads.is_empty());
+
+        // The old field is read as Git HEAD of the default workspace
+        let proto = crate::protos::op_store::View {
+            git_head: ref_target_to_proto(&git_head_target),
+            has_git_refs_migrated_to_remote: true,
+            ..Default::default()
+        };
+        assert_eq!(view_from_proto(proto).git_heads, git_head_default);
+
+        #[allow(deprecated)]
+        let proto = crate::protos::op_store::View {
+            git_head_legacy: CommitId::from_hex("fff111").to_bytes(),
+            has_git_refs_migrated_to_remote: true,
+            ..Default::default()
+        };
+        assert_eq!(view_from_proto(proto).git_heads, git_head_default);
+    }
+
     #[test]
     fn test_bookmark_views_legacy_roundtrip() {
         let new_remote_ref = |target: &RefTarget| RemoteRef {
@@ -18,6 +18,7 @@ use std::any::Any;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::io::Read;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::SystemTime;
@@ -32,6 +33,7 @@ use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::backend::ConflictId;
+use crate::backend::CopyDetectionOptions;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
@@ -49,9 +51,13 @@ use crate::signing::Signer;
 use crate::tree::Tree;
 use crate::tree_builder::TreeBuilder;
 
-// There are more tree objects than commits, and trees are often shared across
-// commits.
-const COMMIT_CACHE_CAPACITY: usize = 100;
+/// Default number of commits kept in the commit cache.
+///
+/// Commands evaluating large revsets (e.g. `jj log -r 'all()'`) may look up the
+/// same commits several times, so the commit cache is as large as the tree
+/// cache by default. Commit objects are immutable, so rewritten commits never
+/// hit stale entries.
+pub const DEFAULT_COMMIT_CACHE_CAPACITY: usize = 1000;
 const TREE_CACHE_CAPACITY: usize = 1000;
 
 /// Wraps the low-level backend and makes it return more convenient types. Also
@@ -72,11 +78,15 @@ impl Debug for Store {
 }
 
 impl Store {
-    pub fn new(backend: Box<dyn Backend>, signer: Signer) -> Arc<Self> {
+    pub fn new(
+        backend: Box<dyn Backend>,
+        signer: Signer,
+        commit_cache_capacity: NonZeroUsize,
+    ) -> Arc<Self> {
         Arc::new(Store {
             backend,
             signer,
-            commit_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
+            commit_cache: Mutex::new(CLruCache::new(commit_cache_capacity)),
             tree_cache: Mutex::new(CLruCache::new(TREE_CACHE_CAPACITY.try_into().unwrap())),
         })
     }
@@ -94,8 +104,9 @@ impl Store {
         paths: Option<&[RepoPathBuf]>,
         root: &CommitId,
         head: &CommitId,
+        options: &CopyDetectionOptions,
     ) -> BackendResult<BoxStream<BackendResult<CopyRecord>>> {
-        self.backend.get_copy_records(paths, root, head)
+        self.backend.get_copy_records(paths, root, head, options)
     }
 
     pub fn commit_id_length(&self) -> usize {
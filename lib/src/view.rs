@@ -84,8 +84,20 @@ impl View {
         &self.data.git_refs
     }
 
+    /// Returns the Git HEAD of each workspace that has one.
+    pub fn git_heads(&self) -> &BTreeMap<WorkspaceId, RefTarget> {
+        &self.data.git_heads
+    }
+
+    /// Returns the Git HEAD of the default workspace.
     pub fn git_head(&self) -> &RefTarget {
-        &self.data.git_head
+        self.workspace_git_head(&WorkspaceId::default())
+    }
+
+    /// Returns the Git HEAD of the given workspace, which is absent if the
+    /// workspace doesn't share its working copy with Git.
+    pub fn workspace_git_head(&self, workspace_id: &WorkspaceId) -> &RefTarget {
+        self.data.git_heads.get(workspace_id).flatten()
     }
 
     pub fn set_wc_commit(&mut self, workspace_id: WorkspaceId, commit_id: CommitId) {
@@ -94,6 +106,7 @@ impl View {
 
     pub fn remove_wc_commit(&mut self, workspace_id: &WorkspaceId) {
         self.data.wc_commit_ids.remove(workspace_id);
+        self.data.git_heads.remove(workspace_id);
     }
 
     pub fn rename_workspace(
@@ -113,6 +126,11 @@ impl View {
             .ok_or_else(|| RenameWorkspaceError::WorkspaceDoesNotExist {
                 workspace_id: old_workspace_id.as_str().to_owned(),
             })?;
+        if let Some(git_head) = self.data.git_heads.remove(old_workspace_id) {
+            self.data
+                .git_heads
+                .insert(new_workspace_id.clone(), git_head);
+        }
         self.data
             .wc_commit_ids
             .insert(new_workspace_id, wc_commit_id);
@@ -333,10 +351,20 @@ impl View {
         }
     }
 
-    /// Sets Git HEAD to point to the given target. If the target is absent, the
-    /// reference will be cleared.
+    /// Sets Git HEAD of the default workspace to point to the given target. If
+    /// the target is absent, the reference will be cleared.
     pub fn set_git_head_target(&mut self, target: RefTarget) {
-        self.data.git_head = target;
+        self.set_workspace_git_head_target(&WorkspaceId::default(), target);
+    }
+
+    /// Sets Git HEAD of the given workspace to point to the given target. If
+    /// the target is absent, the reference will be cleared.
+    pub fn set_workspace_git_head_target(&mut self, workspace_id: &WorkspaceId, target: RefTarget) {
+        if target.is_present() {
+            self.data.git_heads.insert(workspace_id.clone(), target);
+        } else {
+            self.data.git_heads.remove(workspace_id);
+        }
     }
 
     /// Iterates all commit ids referenced by this view.
@@ -362,7 +390,7 @@ impl View {
             tags,
             remote_views,
             git_refs,
-            git_head,
+            git_heads,
             wc_commit_ids,
         } = &self.data;
         itertools::chain!(
@@ -376,7 +404,7 @@ impl View {
                     .flat_map(|remote_ref| ref_target_ids(&remote_ref.target))
             }),
             git_refs.values().flat_map(ref_target_ids),
-            ref_target_ids(git_head),
+            git_heads.values().flat_map(ref_target_ids),
             wc_commit_ids.values()
         )
     }
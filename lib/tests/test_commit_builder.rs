@@ -12,14 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use assert_matches::assert_matches;
 use futures::StreamExt as _;
 use itertools::Itertools;
+use jj_lib::backend::BackendError;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
@@ -199,6 +202,99 @@ fn test_rewrite(backend: TestRepoBackend) {
     );
 }
 
+#[test]
+fn test_commit_cache_eviction() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Local);
+    let repo = &test_repo.repo;
+    let store = repo.store().clone();
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut write_commit = |description: String| {
+        tx.repo_mut()
+            .new_commit(
+                &settings,
+                vec![store.root_commit_id().clone()],
+                store.empty_merged_tree_id(),
+            )
+            .set_description(description)
+            .write()
+            .unwrap()
+    };
+    let commit = write_commit("cached".to_owned());
+    // Remove the commit from the backend so that it can only be read from the
+    // cache
+    let commit_path = test_repo
+        .repo_path()
+        .join("store")
+        .join("commits")
+        .join(commit.id().hex());
+    std::fs::remove_file(commit_path).unwrap();
+    assert_eq!(store.get_commit(commit.id()).unwrap(), commit);
+
+    // Hundreds of other commits don't evict it
+    for i in 0..500 {
+        write_commit(format!("other {i}"));
+    }
+    assert_eq!(store.get_commit(commit.id()).unwrap(), commit);
+
+    // But it's eventually evicted
+    for i in 500..1500 {
+        write_commit(format!("other {i}"));
+    }
+    assert_matches!(
+        store.get_commit(commit.id()),
+        Err(BackendError::ObjectNotFound { .. })
+    );
+}
+
+#[test]
+fn test_commit_cache_capacity_setting() {
+    let config = testutils::base_config()
+        .set_override("core.commit-cache-size", 10)
+        .unwrap()
+        .build()
+        .unwrap();
+    let settings = UserSettings::from_config(config);
+    let test_repo = TestRepo::init_with_backend_and_settings(TestRepoBackend::Local, &settings);
+    let repo = &test_repo.repo;
+    let store = repo.store().clone();
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut write_commit = |description: String| {
+        tx.repo_mut()
+            .new_commit(
+                &settings,
+                vec![store.root_commit_id().clone()],
+                store.empty_merged_tree_id(),
+            )
+            .set_description(description)
+            .write()
+            .unwrap()
+    };
+    let commit = write_commit("cached".to_owned());
+    let commit_path = test_repo
+        .repo_path()
+        .join("store")
+        .join("commits")
+        .join(commit.id().hex());
+    std::fs::remove_file(commit_path).unwrap();
+
+    for i in 0..5 {
+        write_commit(format!("other {i}"));
+    }
+    assert_eq!(store.get_commit(commit.id()).unwrap(), commit);
+
+    // Evicted after more commits than the configured capacity
+    for i in 5..20 {
+        write_commit(format!("other {i}"));
+    }
+    assert_matches!(
+        store.get_commit(commit.id()),
+        Err(BackendError::ObjectNotFound { .. })
+    );
+}
+
 // An author field with an empty name/email should get filled in on rewrite
 #[test_case(TestRepoBackend::Local ; "local backend")]
 #[test_case(TestRepoBackend::Git ; "git backend")]
@@ -16,8 +16,10 @@ use indoc::indoc;
 use jj_lib::backend::FileId;
 use jj_lib::conflicts::extract_as_single_hunk;
 use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_merge_result_with_style;
 use jj_lib::conflicts::parse_conflict;
 use jj_lib::conflicts::update_from_content;
+use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::merge::Merge;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
@@ -117,6 +119,76 @@ fn test_materialize_conflict_basic() {
     );
 }
 
+#[test]
+fn test_materialize_conflict_git_style() {
+    let test_repo = TestRepo::init();
+    let store = test_repo.repo.store();
+
+    let path = RepoPath::from_internal_string("file");
+    let base_id = testutils::write_file(store, path, "line 1\nline 2\nline 3\n");
+    let left_id = testutils::write_file(store, path, "line 1\nleft 2\nline 3\n");
+    let right_id = testutils::write_file(store, path, "line 1\nright 2\nline 3\n");
+    let other_id = testutils::write_file(store, path, "line 1\nother 2\nline 3\n");
+
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_id.clone())],
+        vec![Some(left_id.clone()), Some(right_id.clone())],
+    );
+    insta::assert_snapshot!(
+        &materialize_conflict_string_with_style(store, path, &conflict, ConflictMarkerStyle::Git),
+        @r###"
+    line 1
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    left 2
+    =======
+    right 2
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 3
+    "###
+    );
+    insta::assert_snapshot!(
+        &materialize_conflict_string_with_style(store, path, &conflict, ConflictMarkerStyle::Diff3),
+        @r###"
+    line 1
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    left 2
+    ||||||| Base
+    line 2
+    =======
+    right 2
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 3
+    "###
+    );
+
+    // Conflicts with more than two sides fall back to the jj style
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_id.clone()), Some(base_id.clone())],
+        vec![
+            Some(left_id.clone()),
+            Some(right_id.clone()),
+            Some(other_id.clone()),
+        ],
+    );
+    insta::assert_snapshot!(
+        &materialize_conflict_string_with_style(store, path, &conflict, ConflictMarkerStyle::Diff3),
+        @r###"
+    line 1
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base #1 to side #1
+    -line 2
+    +left 2
+    %%%%%%% Changes from base #2 to side #2
+    -line 2
+    +right 2
+    +++++++ Contents of side #3
+    other 2
+    >>>>>>> Conflict 1 of 1 ends
+    line 3
+    "###
+    );
+}
+
 #[test]
 fn test_materialize_conflict_multi_rebase_conflicts() {
     let test_repo = TestRepo::init();
@@ -1070,3 +1142,17 @@ fn materialize_conflict_string(
     materialize_merge_result(&contents, &mut result).unwrap();
     String::from_utf8(result).unwrap()
 }
+
+fn materialize_conflict_string_with_style(
+    store: &Store,
+    path: &RepoPath,
+    conflict: &Merge<Option<FileId>>,
+    style: ConflictMarkerStyle,
+) -> String {
+    let mut result: Vec<u8> = vec![];
+    let contents = extract_as_single_hunk(conflict, store, path)
+        .block_on()
+        .unwrap();
+    materialize_merge_result_with_style(&contents, style, &mut result).unwrap();
+    String::from_utf8(result).unwrap()
+}
@@ -42,6 +42,8 @@ use jj_lib::git::GitFetchError;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitPushError;
 use jj_lib::git::GitRefUpdate;
+use jj_lib::git::GitSignTagsError;
+use jj_lib::git::RefExportUpdate;
 use jj_lib::git::RefName;
 use jj_lib::git::SubmoduleConfig;
 use jj_lib::git_backend::GitBackend;
@@ -55,6 +57,7 @@ use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
+use jj_lib::settings::GitAbandonPolicy;
 use jj_lib::settings::GitSettings;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::Signer;
@@ -1026,7 +1029,7 @@ fn test_import_refs_reimport_abandoning_disabled() {
     // Test that we don't abandoned unreachable commits if configured not to
     let settings = testutils::user_settings();
     let git_settings = GitSettings {
-        abandon_unreachable_commits: false,
+        abandon_policy: GitAbandonPolicy::Never,
         ..Default::default()
     };
     let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
@@ -1052,6 +1055,54 @@ fn test_import_refs_reimport_abandoning_disabled() {
     assert!(tx.repo_mut().view().heads().contains(&jj_id(&commit2)));
 }
 
+#[test]
+fn test_import_refs_abandon_policy_all_keeps_unrelated_commits() {
+    // Test that the "all" policy only abandons commits related to the refs
+    // updated by the import
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let git_repo = get_git_repo(repo);
+
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let commit2 = empty_git_commit(&git_repo, "refs/heads/feature", &[&commit1]);
+    let mut tx = repo.start_transaction(&settings);
+    git::import_refs(tx.repo_mut(), &GitSettings::default()).unwrap();
+    let main_commit = tx.repo().store().get_commit(&jj_id(&commit1)).unwrap();
+    let unreferenced_commit = write_random_commit(tx.repo_mut(), &settings);
+    let child_commit = create_random_commit(tx.repo_mut(), &settings)
+        .set_parents(vec![main_commit.id().clone()])
+        .write()
+        .unwrap();
+    tx.repo_mut()
+        .set_local_bookmark_target("topic", RefTarget::normal(child_commit.id().clone()));
+    // Move the bookmark locally, leaving the child commit unreferenced
+    tx.repo_mut()
+        .set_local_bookmark_target("topic", RefTarget::normal(main_commit.id().clone()));
+
+    // Delete the feature bookmark in git and re-import
+    git_repo
+        .find_reference("refs/heads/feature")
+        .unwrap()
+        .delete()
+        .unwrap();
+    let git_settings = GitSettings {
+        abandon_policy: GitAbandonPolicy::All,
+        ..Default::default()
+    };
+    let stats = git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    assert_eq!(stats.abandoned_commits, vec![jj_id(&commit2)]);
+    assert!(stats.abandoned_local_commits.is_empty());
+    tx.repo_mut().rebase_descendants(&settings).unwrap();
+    assert_eq!(
+        *tx.repo().view().heads(),
+        hashset! {
+            unreferenced_commit.id().clone(),
+            child_commit.id().clone(),
+        }
+    );
+}
+
 #[test]
 fn test_import_refs_reimport_conflicted_remote_bookmark() {
     let settings = testutils::user_settings();
@@ -1446,6 +1497,93 @@ fn test_import_refs_missing_git_commit() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_import_refs_symbolic_ref() {
+    let settings = testutils::user_settings();
+    let git_settings = GitSettings::default();
+    let test_workspace = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_workspace.repo;
+    let git_repo = get_git_repo(repo);
+
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    // A symbolic ref in the heads namespace is imported as a bookmark pointing
+    // to the resolved commit. A dangling symbolic ref is ignored.
+    git_repo
+        .reference_symbolic("refs/heads/alias", "refs/heads/main", false, "")
+        .unwrap();
+    git_repo
+        .reference_symbolic("refs/heads/dangling", "refs/heads/missing", false, "")
+        .unwrap();
+    git_repo
+        .reference_symbolic("refs/remotes/origin/HEAD", "refs/heads/main", false, "")
+        .unwrap();
+
+    let mut tx = repo.start_transaction(&settings);
+    git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    tx.repo_mut().rebase_descendants(&settings).unwrap();
+    let repo = tx.commit("test");
+    let view = repo.view();
+    assert_eq!(*view.heads(), hashset! { jj_id(&commit1) });
+    assert_eq!(
+        view.local_bookmarks().collect_vec(),
+        vec![
+            ("alias", &RefTarget::normal(jj_id(&commit1))),
+            ("main", &RefTarget::normal(jj_id(&commit1))),
+        ]
+    );
+    assert_eq!(view.all_remote_bookmarks().count(), 2);
+
+    // Moving the referent moves the bookmark of the symbolic ref on reimport.
+    let commit2 = empty_git_commit(&git_repo, "refs/heads/main", &[&commit1]);
+    let mut tx = repo.start_transaction(&settings);
+    git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    tx.repo_mut().rebase_descendants(&settings).unwrap();
+    let repo = tx.commit("test");
+    let view = repo.view();
+    assert_eq!(*view.heads(), hashset! { jj_id(&commit2) });
+    assert_eq!(
+        view.get_local_bookmark("alias"),
+        &RefTarget::normal(jj_id(&commit2))
+    );
+    assert_eq!(
+        view.get_local_bookmark("main"),
+        &RefTarget::normal(jj_id(&commit2))
+    );
+}
+
+#[test]
+fn test_add_worktree() {
+    let test_data = GitRepoData::create();
+    let commit1 = empty_git_commit(&test_data.git_repo, "refs/heads/main", &[]);
+    let git_repo = gix::open(test_data.git_repo.path()).unwrap();
+    let temp_dir = testutils::new_temp_dir();
+    let workdir = temp_dir.path().join("worktree");
+    std::fs::create_dir(&workdir).unwrap();
+
+    let worktree_git_dir = git::add_worktree(&git_repo, &workdir).unwrap();
+    assert_eq!(worktree_git_dir.file_name().unwrap(), "worktree");
+    let worktree_repo = git2::Repository::open(&workdir).unwrap();
+    assert!(worktree_repo.is_worktree());
+    assert_eq!(
+        worktree_repo.head().err().map(|err| err.code()),
+        Some(git2::ErrorCode::UnbornBranch)
+    );
+    // The worktree shares refs with the main repo
+    assert_eq!(
+        worktree_repo
+            .find_reference("refs/heads/main")
+            .unwrap()
+            .target(),
+        Some(commit1.id())
+    );
+
+    // The name is disambiguated if it's already taken
+    let workdir2 = temp_dir.path().join("other").join("worktree");
+    std::fs::create_dir_all(&workdir2).unwrap();
+    let worktree_git_dir2 = git::add_worktree(&git_repo, &workdir2).unwrap();
+    assert_eq!(worktree_git_dir2.file_name().unwrap(), "worktree1");
+}
+
 #[test]
 fn test_import_refs_detached_head() {
     let test_data = GitRepoData::create();
@@ -1626,6 +1764,49 @@ fn test_export_refs_unborn_git_bookmark(move_placeholder_ref: bool) {
     assert!(git_repo.find_reference("refs/jj/root").is_err());
 }
 
+#[test]
+fn test_export_refs_expected_remote_bookmark() {
+    // The expected location of a remote bookmark is written to the Git
+    // remote-tracking ref by the export, not when it's recorded
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/remotes/origin/main", &[]);
+    let commit2 = empty_git_commit(&git_repo, "refs/heads/feature", &[]);
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &git_settings).unwrap();
+
+    git::set_expected_remote_bookmark(mut_repo, "origin", "main", &jj_id(&commit2));
+    assert_eq!(
+        mut_repo.get_remote_bookmark("main", "origin"),
+        RemoteRef {
+            target: RefTarget::normal(jj_id(&commit2)),
+            state: RemoteRefState::Tracking,
+        }
+    );
+    assert_eq!(
+        git_repo
+            .find_reference("refs/remotes/origin/main")
+            .unwrap()
+            .target(),
+        Some(commit1.id())
+    );
+
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert_eq!(
+        mut_repo.get_git_ref("refs/remotes/origin/main"),
+        RefTarget::normal(jj_id(&commit2))
+    );
+    assert_eq!(
+        git_repo
+            .find_reference("refs/remotes/origin/main")
+            .unwrap()
+            .target(),
+        Some(commit2.id())
+    );
+}
+
 #[test]
 fn test_export_import_sequence() {
     // Import a bookmark pointing to A, modify it in jj to point to B, export it,
@@ -1865,7 +2046,7 @@ fn test_export_partial_failure() {
     assert_eq!(failed[1].name, RefName::LocalBranch("HEAD".to_string()));
     assert_matches!(failed[1].reason, FailedRefExportReason::InvalidGitName);
     assert_eq!(failed[2].name, RefName::LocalBranch("main/sub".to_string()));
-    assert_matches!(failed[2].reason, FailedRefExportReason::FailedToSet(_));
+    assert_matches!(failed[2].reason, FailedRefExportReason::ConflictingName);
 
     // The `main` bookmark should have succeeded but the other should have failed
     assert!(git_repo.find_reference("refs/heads/").is_err());
@@ -1926,6 +2107,132 @@ fn test_export_partial_failure() {
     );
 }
 
+#[test]
+fn test_prune_git_branches() {
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    git_repo
+        .reference("refs/heads/feature", commit1.id(), false, "test")
+        .unwrap();
+    git_repo
+        .reference("refs/heads/unmanaged", commit1.id(), false, "test")
+        .unwrap();
+
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    mut_repo.set_git_ref_target("refs/heads/unmanaged", RefTarget::absent());
+    mut_repo.set_local_bookmark_target("unmanaged", RefTarget::absent());
+    mut_repo.set_remote_bookmark("unmanaged", "git", RemoteRef::absent());
+
+    // "main" is deleted in jj, and "feature" is deleted in jj and moved in Git
+    mut_repo.set_local_bookmark_target("main", RefTarget::absent());
+    mut_repo.set_local_bookmark_target("feature", RefTarget::absent());
+    let commit2 = empty_git_commit(&git_repo, "refs/heads/feature", &[&commit1]);
+
+    let pruned = git::prune_git_branches(mut_repo).unwrap();
+    assert_eq!(pruned.pruned, vec![RefName::LocalBranch("main".to_owned())]);
+    assert_eq!(pruned.failed.len(), 1);
+    assert_eq!(
+        pruned.failed[0].name,
+        RefName::LocalBranch("feature".to_owned())
+    );
+    assert_matches!(
+        pruned.failed[0].reason,
+        FailedRefExportReason::DeletedInJjModifiedInGit
+    );
+    assert!(git_repo.find_reference("refs/heads/main").is_err());
+    assert_eq!(mut_repo.get_git_ref("refs/heads/main"), RefTarget::absent());
+    // The branch moved in Git, and the one jj never saw, are left alone
+    assert_eq!(
+        git_repo
+            .find_reference("refs/heads/feature")
+            .unwrap()
+            .target(),
+        Some(commit2.id())
+    );
+    assert_eq!(
+        git_repo
+            .find_reference("refs/heads/unmanaged")
+            .unwrap()
+            .target(),
+        Some(commit1.id())
+    );
+}
+
+#[test]
+fn test_preview_export_refs() {
+    let test_data = GitRepoData::create();
+    let git_repo = test_data.git_repo;
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    let mut_repo = tx.repo_mut();
+    let commit_a = write_random_commit(mut_repo, &test_data.settings);
+    let commit_b = write_random_commit(mut_repo, &test_data.settings);
+    mut_repo.set_local_bookmark_target("moved", RefTarget::normal(commit_a.id().clone()));
+    mut_repo.set_local_bookmark_target("deleted", RefTarget::normal(commit_a.id().clone()));
+    git::export_refs(mut_repo).unwrap();
+
+    mut_repo.set_local_bookmark_target("moved", RefTarget::normal(commit_b.id().clone()));
+    mut_repo.set_local_bookmark_target("deleted", RefTarget::absent());
+    mut_repo.set_local_bookmark_target("main", RefTarget::normal(commit_a.id().clone()));
+    mut_repo.set_local_bookmark_target("main/sub", RefTarget::normal(commit_a.id().clone()));
+    let preview = git::preview_export_refs(mut_repo).unwrap();
+    assert_eq!(
+        preview.updated_refs,
+        vec![
+            RefExportUpdate {
+                name: RefName::LocalBranch("deleted".to_string()),
+                git_ref_name: "refs/heads/deleted".to_string(),
+                old_target: Some(commit_a.id().clone()),
+                new_target: None,
+            },
+            RefExportUpdate {
+                name: RefName::LocalBranch("main".to_string()),
+                git_ref_name: "refs/heads/main".to_string(),
+                old_target: None,
+                new_target: Some(commit_a.id().clone()),
+            },
+            RefExportUpdate {
+                name: RefName::LocalBranch("moved".to_string()),
+                git_ref_name: "refs/heads/moved".to_string(),
+                old_target: Some(commit_a.id().clone()),
+                new_target: Some(commit_b.id().clone()),
+            },
+        ]
+    );
+    assert_eq!(preview.failed_refs.len(), 1);
+    assert_eq!(
+        preview.failed_refs[0].name,
+        RefName::LocalBranch("main/sub".to_string())
+    );
+    assert_matches!(
+        preview.failed_refs[0].reason,
+        FailedRefExportReason::ConflictingName
+    );
+
+    // Nothing should have been written to Git
+    assert!(git_repo.find_reference("refs/heads/deleted").is_ok());
+    assert!(git_repo.find_reference("refs/heads/main").is_err());
+    assert_eq!(
+        git_repo
+            .find_reference("refs/heads/moved")
+            .unwrap()
+            .target()
+            .unwrap(),
+        git_id(&commit_a)
+    );
+
+    // The actual export should fail on the same refs for the same reasons
+    let failed = git::export_refs(mut_repo).unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].name, RefName::LocalBranch("main/sub".to_string()));
+    assert_matches!(failed[0].reason, FailedRefExportReason::ConflictingName);
+    let preview = git::preview_export_refs(mut_repo).unwrap();
+    assert_eq!(preview.updated_refs, vec![]);
+}
+
 #[test]
 fn test_export_reexport_transitions() {
     // Test exporting after making changes on the jj side, or the git side, or both
@@ -2513,6 +2820,64 @@ fn test_fetch_no_default_branch() {
     assert_eq!(stats.default_branch, None);
 }
 
+#[test]
+fn test_fetch_with_git_command() {
+    let test_data = GitRepoData::create();
+    let initial_git_commit = empty_git_commit(&test_data.origin_repo, "refs/heads/main", &[]);
+    test_data.origin_repo.set_head("refs/heads/main").unwrap();
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    git::fetch(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        &[StringPattern::everything()],
+        git::RemoteCallbacks::default(),
+        &GitSettings::default(),
+        None,
+    )
+    .unwrap();
+    let new_git_commit = empty_git_commit(
+        &test_data.origin_repo,
+        "refs/heads/main",
+        &[&initial_git_commit],
+    );
+    let negotiation_tips = [jj_id(&initial_git_commit)];
+    let options = git::GitCommandFetchOptions {
+        negotiation_tips: &negotiation_tips,
+        ..Default::default()
+    };
+
+    // Running git must be enabled explicitly
+    let result = git::fetch_with_git_command(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        &[StringPattern::everything()],
+        &options,
+        git::RemoteCallbacks::default(),
+        &GitSettings::default(),
+    );
+    assert_matches!(result, Err(GitFetchError::GitCommandDisabled));
+
+    let git_settings = GitSettings {
+        subprocess: true,
+        ..Default::default()
+    };
+    let stats = git::fetch_with_git_command(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        &[StringPattern::everything()],
+        &options,
+        git::RemoteCallbacks::default(),
+        &git_settings,
+    )
+    .unwrap();
+    // The default branch is reported as with git::fetch()
+    assert_eq!(stats.default_branch, Some("main".to_string()));
+    assert!(tx.repo().view().heads().contains(&jj_id(&new_git_commit)));
+}
+
 #[test]
 fn test_fetch_empty_refspecs() {
     let test_data = GitRepoData::create();
@@ -2877,6 +3242,293 @@ fn test_push_bookmarks_not_fast_forward() {
 // may want to add tests for when a bookmark unexpectedly moved backwards or
 // unexpectedly does not exist for bookmark deletion.
 
+#[test]
+fn test_push_bookmarks_atomic() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let mut setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+    let git_settings = GitSettings {
+        subprocess: true,
+        ..Default::default()
+    };
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![
+            (
+                "main".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: Some(setup.main_commit.id().clone()),
+                    new_target: Some(setup.sideways_commit.id().clone()),
+                },
+            ),
+            (
+                "topic".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: None,
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            ),
+        ],
+    };
+    // Running git must be enabled explicitly
+    let result = git::push_branches_atomic(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        &[],
+        git::RemoteCallbacks::default(),
+        &GitSettings::default(),
+    );
+    assert_eq!(result, Err(GitPushError::GitCommandDisabled));
+
+    let result = git::push_branches_atomic(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        &[],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+    );
+    assert_eq!(result, Ok(()));
+
+    // Check that both refs got updated in the source repo
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let new_target = source_repo
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .target();
+    assert_eq!(new_target, Some(git_id(&setup.sideways_commit)));
+    let new_target = source_repo
+        .find_reference("refs/heads/topic")
+        .unwrap()
+        .target();
+    assert_eq!(new_target, Some(git_id(&setup.child_of_main_commit)));
+
+    // Check that the repo view got updated
+    let view = tx.repo_mut().view();
+    assert_eq!(
+        *view.get_remote_bookmark("main", "origin"),
+        RemoteRef {
+            target: RefTarget::normal(setup.sideways_commit.id().clone()),
+            state: RemoteRefState::Tracking,
+        },
+    );
+    assert_eq!(
+        *view.get_remote_bookmark("topic", "origin"),
+        RemoteRef {
+            target: RefTarget::normal(setup.child_of_main_commit.id().clone()),
+            state: RemoteRefState::Tracking,
+        },
+    );
+
+    // Check that the repo view reflects the changes in the Git repo
+    setup.jj_repo = tx.commit("test");
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+    git::import_refs(tx.repo_mut(), &GitSettings::default()).unwrap();
+    assert!(!tx.repo_mut().has_changes());
+}
+
+#[test]
+fn test_push_bookmarks_atomic_unexpectedly_moved() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+    let git_settings = GitSettings {
+        subprocess: true,
+        ..Default::default()
+    };
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    // The main bookmark isn't where we expect it to be, so the topic bookmark
+    // shouldn't be created either.
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![
+            (
+                "main".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: Some(setup.sideways_commit.id().clone()),
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            ),
+            (
+                "topic".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: None,
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            ),
+        ],
+    };
+    let result = git::push_branches_atomic(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        &[],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+    );
+    assert_eq!(
+        result,
+        Err(GitPushError::RefInUnexpectedLocation(vec![
+            "refs/heads/main".to_owned()
+        ]))
+    );
+
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let target = source_repo
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .target();
+    assert_eq!(target, Some(git_id(&setup.main_commit)));
+    assert!(source_repo.find_reference("refs/heads/topic").is_err());
+}
+
+#[test]
+fn test_push_bookmarks_atomic_moved_forward_on_remote() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+    let git_settings = GitSettings {
+        subprocess: true,
+        ..Default::default()
+    };
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    // The main bookmark moved forward on the remote, but the push is still
+    // allowed since it moves the bookmark further forward, like a push
+    // without --atomic.
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![(
+            "main".to_owned(),
+            BookmarkPushUpdate {
+                old_target: Some(setup.parent_of_main_commit.id().clone()),
+                new_target: Some(setup.child_of_main_commit.id().clone()),
+            },
+        )],
+    };
+    let result = git::push_branches_atomic(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        &[],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+    );
+    assert_eq!(result, Ok(()));
+
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let new_target = source_repo
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .target();
+    assert_eq!(new_target, Some(git_id(&setup.child_of_main_commit)));
+}
+
+#[test]
+fn test_push_bookmarks_atomic_unsupported_by_remote() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+    let git_settings = GitSettings {
+        subprocess: true,
+        ..Default::default()
+    };
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    source_repo
+        .config()
+        .unwrap()
+        .set_bool("receive.advertiseAtomic", false)
+        .unwrap();
+
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![(
+            "main".to_owned(),
+            BookmarkPushUpdate {
+                old_target: Some(setup.main_commit.id().clone()),
+                new_target: Some(setup.child_of_main_commit.id().clone()),
+            },
+        )],
+    };
+    let result = git::push_branches_atomic(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        &[],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+    );
+    assert_eq!(
+        result,
+        Err(GitPushError::AtomicPushNotSupported("origin".to_owned()))
+    );
+
+    // Nothing should be pushed
+    let target = source_repo
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .target();
+    assert_eq!(target, Some(git_id(&setup.main_commit)));
+    assert_eq!(
+        *tx.repo_mut().view().get_remote_bookmark("main", "origin"),
+        RemoteRef {
+            target: RefTarget::normal(setup.main_commit.id().clone()),
+            state: RemoteRefState::Tracking,
+        },
+    );
+}
+
+#[test]
+fn test_push_bookmarks_with_options_unsupported_by_remote() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![(
+            "main".to_owned(),
+            BookmarkPushUpdate {
+                old_target: Some(setup.main_commit.id().clone()),
+                new_target: Some(setup.child_of_main_commit.id().clone()),
+            },
+        )],
+    };
+    // Local remotes don't support push options
+    let result = git::push_branches_with_options(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        &["ci.skip".to_owned()],
+        git::RemoteCallbacks::default(),
+    );
+    assert_eq!(
+        result,
+        Err(GitPushError::PushOptionsNotSupported("origin".to_owned()))
+    );
+
+    // Nothing should be pushed
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let target = source_repo
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .target();
+    assert_eq!(target, Some(git_id(&setup.main_commit)));
+}
+
 #[test]
 fn test_push_updates_unexpectedly_moved_sideways_on_remote() {
     let settings = testutils::user_settings();
@@ -3122,6 +3774,104 @@ fn test_push_updates_invalid_remote() {
     assert!(matches!(result, Err(GitPushError::NoSuchRemote(_))));
 }
 
+#[test]
+fn test_sign_tags() {
+    let temp_dir = testutils::new_temp_dir();
+    let git_repo = git2::Repository::init_bare(temp_dir.path()).unwrap();
+    let commit = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    git_repo
+        .reference("refs/tags/lightweight", commit.id(), false, "")
+        .unwrap();
+    let git_signature = git2::Signature::now("Someone", "someone@example.com").unwrap();
+    let annotated_oid = git_repo
+        .tag(
+            "annotated",
+            commit.as_object(),
+            &git_signature,
+            "Release\n",
+            false,
+        )
+        .unwrap();
+    let tagger = Signature {
+        name: "Test User".to_owned(),
+        email: "test.user@example.com".to_owned(),
+        timestamp: Timestamp {
+            timestamp: MillisSinceEpoch(1_000_000),
+            tz_offset: -90,
+        },
+    };
+    let fake_signature = b"-----BEGIN FAKE SIGNATURE-----\nsigned\n-----END FAKE SIGNATURE-----\n";
+    let tag_names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect_vec();
+
+    // Annotated tags and missing tags are refused, and no tag is replaced
+    assert_matches!(
+        git::sign_tags(
+            &git_repo,
+            &tag_names(&["lightweight", "annotated"]),
+            &tagger,
+            |_| Ok(fake_signature.to_vec()),
+        ),
+        Err(GitSignTagsError::AlreadyAnnotated(name)) if name == "annotated"
+    );
+    assert_matches!(
+        git::sign_tags(
+            &git_repo,
+            &tag_names(&["lightweight", "missing"]),
+            &tagger,
+            |_| Ok(fake_signature.to_vec()),
+        ),
+        Err(GitSignTagsError::NoSuchTag(name)) if name == "missing"
+    );
+    assert_eq!(
+        git_repo.refname_to_id("refs/tags/lightweight").unwrap(),
+        commit.id()
+    );
+
+    // The signature is appended to the tag object that was signed
+    let mut signed_data = vec![];
+    let signed_tags = git::sign_tags(&git_repo, &tag_names(&["lightweight"]), &tagger, |data| {
+        signed_data = data.to_vec();
+        Ok(fake_signature.to_vec())
+    })
+    .unwrap();
+    assert_eq!(signed_tags.len(), 1);
+    assert_eq!(signed_tags[0].name, "lightweight");
+    assert_eq!(signed_tags[0].old_oid, commit.id());
+    assert_eq!(
+        git_repo.refname_to_id("refs/tags/lightweight").unwrap(),
+        signed_tags[0].new_oid
+    );
+    assert_eq!(
+        String::from_utf8(signed_data.clone()).unwrap(),
+        format!(
+            "object {}\ntype commit\ntag lightweight\ntagger Test User <test.user@example.com> \
+             1000 -0130\n\nlightweight\n",
+            commit.id()
+        )
+    );
+    let odb = git_repo.odb().unwrap();
+    let tag_object = odb.read(signed_tags[0].new_oid).unwrap();
+    assert_eq!(tag_object.kind(), git2::ObjectType::Tag);
+    assert_eq!(
+        tag_object.data(),
+        [&signed_data[..], fake_signature].concat()
+    );
+    let tag = git_repo.find_tag(signed_tags[0].new_oid).unwrap();
+    assert_eq!(tag.name(), Some("lightweight"));
+    assert_eq!(tag.target_id(), commit.id());
+    assert_eq!(tag.tagger().unwrap().name(), Some("Test User"));
+
+    // Unsigning points the refs back to the original objects
+    git::unsign_tags(&git_repo, &signed_tags).unwrap();
+    let reference = git_repo.find_reference("refs/tags/lightweight").unwrap();
+    assert_eq!(reference.kind(), Some(git2::ReferenceType::Direct));
+    assert_eq!(reference.target(), Some(commit.id()));
+    assert_eq!(
+        git_repo.refname_to_id("refs/tags/annotated").unwrap(),
+        annotated_oid
+    );
+}
+
 #[test]
 fn test_bulk_update_extra_on_import_refs() {
     let settings = testutils::user_settings();
@@ -22,6 +22,7 @@ use std::time::SystemTime;
 
 use futures::executor::block_on_stream;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::CopyDetectionOptions;
 use jj_lib::backend::CopyRecord;
 use jj_lib::commit::Commit;
 use jj_lib::git_backend::GitBackend;
@@ -64,7 +65,19 @@ fn get_copy_records(
     a: &Commit,
     b: &Commit,
 ) -> HashMap<String, String> {
-    let stream = store.get_copy_records(paths, a.id(), b.id()).unwrap();
+    get_copy_records_with_options(store, paths, a, b, &CopyDetectionOptions::default())
+}
+
+fn get_copy_records_with_options(
+    store: &Store,
+    paths: Option<&[RepoPathBuf]>,
+    a: &Commit,
+    b: &Commit,
+    options: &CopyDetectionOptions,
+) -> HashMap<String, String> {
+    let stream = store
+        .get_copy_records(paths, a.id(), b.id(), options)
+        .unwrap();
     let mut res: HashMap<String, String> = HashMap::new();
     for CopyRecord { target, source, .. } in block_on_stream(stream).filter_map(|r| r.ok()) {
         res.insert(
@@ -237,6 +250,42 @@ fn test_gc() {
     assert_eq!(collect_no_gc_refs(git_repo_path), hashset! {});
 }
 
+#[test]
+fn test_gc_prune_unreachable_objects() {
+    // TODO: Better way to disable the test if git command couldn't be executed
+    if Command::new("git").arg("--version").status().is_err() {
+        eprintln!("Skipping because git command might fail to run");
+        return;
+    }
+
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = test_repo.repo;
+    let git_backend = get_git_backend(&repo);
+    let blob_id = git_backend
+        .git_repo()
+        .write_blob(b"unreachable")
+        .unwrap()
+        .detach();
+    let blob_hex = blob_id.to_string();
+    let loose_object_path = git_backend
+        .git_repo_path()
+        .join("objects")
+        .join(&blob_hex[..2])
+        .join(&blob_hex[2..]);
+    assert!(loose_object_path.exists());
+
+    // Unreachable object is kept if it's newer than the expiration time
+    repo.store()
+        .gc(repo.index(), SystemTime::UNIX_EPOCH)
+        .unwrap();
+    assert!(loose_object_path.exists());
+
+    // Pruned once it's older
+    let now = SystemTime::now() + Duration::from_secs(1);
+    repo.store().gc(repo.index(), now).unwrap();
+    assert!(!loose_object_path.exists());
+}
+
 #[test]
 fn test_copy_detection() {
     let settings = testutils::user_settings();
@@ -295,3 +344,75 @@ fn test_copy_detection() {
         HashMap::default(),
     );
 }
+
+#[test]
+fn test_copy_detection_options() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    let rename_source = RepoPath::from_internal_string("rename-source");
+    let rename_target = RepoPath::from_internal_string("rename-target");
+    let copy_source = RepoPath::from_internal_string("copy-source");
+    let copy_target = RepoPath::from_internal_string("copy-target");
+    // Ten lines, the first `changed` of which differ from the original
+    let lines = |prefix: &str, changed: usize| {
+        (0..10)
+            .map(|i| {
+                if i < changed {
+                    format!("changed {i}\n")
+                } else {
+                    format!("{prefix} {i}\n")
+                }
+            })
+            .collect::<String>()
+    };
+
+    let mut tx = repo.start_transaction(&settings);
+    let commit_a = make_commit(
+        &mut tx,
+        &settings,
+        vec![repo.store().root_commit_id().clone()],
+        &[
+            (rename_source, &lines("rename", 0)),
+            (copy_source, &lines("copy", 0)),
+        ],
+    );
+    // The renamed file is 70% similar to its source
+    let commit_b = make_commit(
+        &mut tx,
+        &settings,
+        vec![commit_a.id().clone()],
+        &[
+            (rename_target, &lines("rename", 3)),
+            (copy_source, &lines("copy", 1)),
+            (copy_target, &lines("copy", 0)),
+        ],
+    );
+
+    let store = repo.store();
+    let get_records = |options: &CopyDetectionOptions| {
+        get_copy_records_with_options(store, None, &commit_a, &commit_b, options)
+    };
+    assert_eq!(
+        get_records(&CopyDetectionOptions::default()),
+        HashMap::from([
+            ("rename-target".to_string(), "rename-source".to_string()),
+            ("copy-target".to_string(), "copy-source".to_string()),
+        ])
+    );
+    assert_eq!(
+        get_records(&CopyDetectionOptions {
+            find_copies: true,
+            similarity_threshold: 0.8,
+        }),
+        HashMap::from([("copy-target".to_string(), "copy-source".to_string())])
+    );
+    assert_eq!(
+        get_records(&CopyDetectionOptions {
+            find_copies: false,
+            similarity_threshold: 0.5,
+        }),
+        HashMap::from([("rename-target".to_string(), "rename-source".to_string())])
+    );
+}
@@ -1079,6 +1079,42 @@ fn test_evaluate_expression_heads() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_heads_hidden() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit1]);
+
+    // Abandoned heads are excluded, and their visible parents become heads
+    mut_repo.record_abandoned_commit(commit3.id().clone());
+    mut_repo.record_abandoned_commit(commit4.id().clone());
+    mut_repo.rebase_descendants(&settings).unwrap();
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "heads(all())"),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "heads(all())"),
+        resolve_commit_ids(mut_repo, "visible_heads()")
+    );
+
+    // Abandoning the remaining head makes its parent the head
+    mut_repo.record_abandoned_commit(commit2.id().clone());
+    mut_repo.rebase_descendants(&settings).unwrap();
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "heads(all())"),
+        vec![commit1.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_roots() {
     let settings = testutils::user_settings();
@@ -2008,17 +2044,41 @@ fn test_evaluate_expression_git_refs() {
 #[test]
 fn test_evaluate_expression_git_head() {
     let settings = testutils::user_settings();
-    let test_repo = TestRepo::init();
-    let repo = &test_repo.repo;
+    let test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+    let workspace = &test_workspace.workspace;
 
     let mut tx = repo.start_transaction(&settings);
     let mut_repo = tx.repo_mut();
 
     let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = write_random_commit(mut_repo, &settings);
 
     // Can get git head when it's not set
-    assert_eq!(resolve_commit_ids(mut_repo, "git_head()"), vec![]);
-    mut_repo.set_git_head_target(RefTarget::normal(commit1.id().clone()));
+    assert_eq!(
+        resolve_commit_ids_in_workspace(mut_repo, "git_head()", workspace, None),
+        vec![]
+    );
+    mut_repo.set_workspace_git_head_target(
+        workspace.workspace_id(),
+        RefTarget::normal(commit1.id().clone()),
+    );
+    assert_eq!(
+        resolve_commit_ids_in_workspace(mut_repo, "git_head()", workspace, None),
+        vec![commit1.id().clone()]
+    );
+
+    // Git HEAD of another workspace isn't included
+    mut_repo.set_workspace_git_head_target(
+        &WorkspaceId::new("other".to_string()),
+        RefTarget::normal(commit2.id().clone()),
+    );
+    assert_eq!(
+        resolve_commit_ids_in_workspace(mut_repo, "git_head()", workspace, None),
+        vec![commit1.id().clone()]
+    );
+
+    // Without workspace context, Git HEAD of the default workspace is used
     assert_eq!(
         resolve_commit_ids(mut_repo, "git_head()"),
         vec![commit1.id().clone()]
@@ -2207,6 +2267,32 @@ fn test_evaluate_expression_remote_bookmarks() {
         ),
         vec![commit1.id().clone()]
     );
+    // Can specify the patterns as keyword arguments
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "remote_bookmarks(remote=origin)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            r#"remote_bookmarks(bookmark=glob:"bookmark*", remote=glob:"*i*")"#
+        ),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            r#"remote_bookmarks(remote=exact:private, bookmark=glob:"*2")"#
+        ),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            r#"remote_bookmarks(bookmark=glob:"*2", remote=origin)"#
+        ),
+        vec![]
+    );
     // Can filter bookmarks by tracked and untracked
     assert_eq!(
         resolve_commit_ids(mut_repo, "tracked_remote_bookmarks()"),
@@ -429,6 +429,24 @@ fn test_merge_views_git_heads() {
         [tx1_head.id().clone(), tx2_head.id().clone()],
     );
     assert_eq!(repo.view().git_head(), &expected_git_head);
+
+    // Git HEADs of different workspaces are merged independently
+    let ws2 = WorkspaceId::new("ws2".to_string());
+    let mut tx3 = repo.start_transaction(&settings);
+    tx3.repo_mut()
+        .set_git_head_target(RefTarget::normal(tx1_head.id().clone()));
+    let mut tx4 = repo.start_transaction(&settings);
+    tx4.repo_mut()
+        .set_workspace_git_head_target(&ws2, RefTarget::normal(tx2_head.id().clone()));
+    let repo = commit_transactions(&settings, vec![tx3, tx4]);
+    assert_eq!(
+        repo.view().git_head(),
+        &RefTarget::normal(tx1_head.id().clone())
+    );
+    assert_eq!(
+        repo.view().workspace_git_head(&ws2),
+        &RefTarget::normal(tx2_head.id().clone())
+    );
 }
 
 #[test]